@@ -273,7 +273,13 @@ impl Camera for ASICamera {
                         max: ccaps.MaxValue as f64,
                         step: 1.0, //TODO
                         num_decimals: 0,
-                        is_exposure_time: ccaps.ControlType == ASI_CONTROL_TYPE_ASI_EXPOSURE
+                        is_exposure_time: ccaps.ControlType == ASI_CONTROL_TYPE_ASI_EXPOSURE,
+                        time_unit: if ccaps.ControlType == ASI_CONTROL_TYPE_ASI_EXPOSURE {
+                            Some(TimeUnit::Microseconds)
+                        } else {
+                            None
+                        },
+                        is_frame_rate: false
                     }));
 
                     control_added = true;
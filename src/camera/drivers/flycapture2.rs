@@ -800,7 +800,17 @@ impl Camera for FlyCapture2Camera {
                 max,
                 step,
                 num_decimals,
-                is_exposure_time: as_property_type(i) as u32 == fc2PropertyType::FC2_SHUTTER as u32
+                is_exposure_time: as_property_type(i) as u32 == fc2PropertyType::FC2_SHUTTER as u32,
+                // absolute shutter values are in milliseconds; raw values have no defined unit
+                time_unit: if as_property_type(i) as u32 == fc2PropertyType::FC2_SHUTTER as u32
+                    && prop_info.absValSupported == TRUE {
+                    Some(TimeUnit::Milliseconds)
+                } else {
+                    None
+                },
+                // absolute frame rate values are in frames per second
+                is_frame_rate: as_property_type(i) as u32 == fc2PropertyType::FC2_FRAME_RATE as u32
+                    && prop_info.absValSupported == TRUE
             };
 
             if i == fc2PropertyType::FC2_SHUTTER as u32 {
@@ -524,7 +524,17 @@ impl Camera for IIDCCamera {
                 max,
                 step,
                 num_decimals,
-                is_exposure_time: feature.id == dc1394feature_t::DC1394_FEATURE_SHUTTER
+                is_exposure_time: feature.id == dc1394feature_t::DC1394_FEATURE_SHUTTER,
+                // absolute shutter values are in seconds; raw values have no defined unit
+                time_unit: if feature.id == dc1394feature_t::DC1394_FEATURE_SHUTTER
+                    && feature.absolute_capable == dc1394bool_t::DC1394_TRUE {
+                    Some(TimeUnit::Seconds)
+                } else {
+                    None
+                },
+                // absolute frame rate values are in frames per second
+                is_frame_rate: feature.id == dc1394feature_t::DC1394_FEATURE_FRAME_RATE
+                    && feature.absolute_capable == dc1394bool_t::DC1394_TRUE
             };
 
             if feature.id == dc1394feature_t::DC1394_FEATURE_SHUTTER {
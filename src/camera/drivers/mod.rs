@@ -26,6 +26,49 @@ use crate::camera::Driver;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// Driver names used in the "disabled drivers" configuration setting.
+pub mod config_names {
+    pub const IIDC: &str = "camera_iidc";
+    pub const V4L2: &str = "camera_v4l2";
+    pub const FLYCAPTURE2: &str = "camera_flycap2";
+    pub const SPINNAKER: &str = "camera_spinnaker";
+    pub const ASI: &str = "camera_asi";
+    pub const SIMULATOR: &str = "simulator";
+}
+
+/// Returns (configuration name, description) of drivers included in this build.
+pub fn available_drivers() -> Vec<(&'static str, &'static str)> {
+    let mut drivers = vec![];
+
+    #[cfg(feature = "camera_iidc")]
+    drivers.push((config_names::IIDC, "IIDC (FireWire, USB) cameras"));
+
+    #[cfg(feature = "camera_v4l2")]
+    drivers.push((config_names::V4L2, "Video4Linux2 cameras"));
+
+    #[cfg(feature = "camera_flycap2")]
+    drivers.push((config_names::FLYCAPTURE2, "FLIR FlyCapture2 cameras"));
+
+    #[cfg(feature = "camera_spinnaker")]
+    drivers.push((config_names::SPINNAKER, "FLIR Spinnaker cameras"));
+
+    #[cfg(feature = "camera_asi")]
+    drivers.push((config_names::ASI, "ZWO ASI cameras"));
+
+    drivers.push((config_names::SIMULATOR, "Camera simulator"));
+
+    drivers
+}
+
+/// Adds `driver` to `drivers` if it has been initialized successfully; otherwise only logs a warning
+/// (a missing or failing camera library shall not prevent the program from running).
+fn add_driver<D: Driver + 'static>(drivers: &mut Vec<Rc<RefCell<Box<dyn Driver>>>>, driver: Option<D>, name: &str) {
+    match driver {
+        Some(driver) => drivers.push(Rc::new(RefCell::new(Box::new(driver)))),
+        None => log::warn!("failed to initialize {} driver", name)
+    }
+}
+
 pub fn init_drivers<'a>(
     disabled_drivers: &[&str],
     simulator_video_file: Option<std::path::PathBuf>
@@ -33,40 +76,48 @@ pub fn init_drivers<'a>(
     let mut drivers: Vec<Rc<RefCell<Box<dyn Driver>>>> = vec![];
 
     #[cfg(feature = "camera_iidc")]
-    if !disabled_drivers.contains(&"camera_iidc") {
+    if !disabled_drivers.contains(&config_names::IIDC) {
         log::info!("initializing IIDC camera driver");
-        drivers.push(Rc::new(RefCell::new(Box::new(iidc::IIDCDriver::new().unwrap()))));
+        add_driver(&mut drivers, iidc::IIDCDriver::new(), "IIDC camera");
     }
 
     #[cfg(feature = "camera_v4l2")]
-    if !disabled_drivers.contains(&"camera_v4l2") {
+    if !disabled_drivers.contains(&config_names::V4L2) {
         log::info!("initializing V4L2 camera driver");
-        drivers.push(Rc::new(RefCell::new(Box::new(v4l2::V4L2Driver::new().unwrap()))));
+        add_driver(&mut drivers, v4l2::V4L2Driver::new(), "V4L2 camera");
     }
 
     #[cfg(feature = "camera_flycap2")]
-    if !disabled_drivers.contains(&"camera_flycap2") {
+    if !disabled_drivers.contains(&config_names::FLYCAPTURE2) {
         log::info!("initializing FlyCapture2 camera driver");
-        drivers.push(Rc::new(RefCell::new(Box::new(flycapture2::FlyCapture2Driver::new().unwrap()))));
+        add_driver(
+            &mut drivers,
+            flycapture2::FlyCapture2Driver::new().map_err(|e| log::warn!("FlyCapture2 error: {:?}", e)).ok(),
+            "FlyCapture2 camera"
+        );
     }
 
     #[cfg(feature = "camera_spinnaker")]
-    if !disabled_drivers.contains(&"camera_spinnaker") {
+    if !disabled_drivers.contains(&config_names::SPINNAKER) {
         log::info!("initializing Spinnaker camera driver");
-        drivers.push(Rc::new(RefCell::new(Box::new(spinnaker::SpinnakerDriver::new().unwrap()))));
+        add_driver(
+            &mut drivers,
+            spinnaker::SpinnakerDriver::new().map_err(|e| log::warn!("Spinnaker error: {:?}", e)).ok(),
+            "Spinnaker camera"
+        );
     }
 
     #[cfg(feature = "camera_asi")]
-    if !disabled_drivers.contains(&"camera_asi") {
+    if !disabled_drivers.contains(&config_names::ASI) {
         log::info!("initializing ZWO ASI camera driver");
-        drivers.push(Rc::new(RefCell::new(Box::new(asi::ASIDriver::new().unwrap()))));
+        add_driver(&mut drivers, asi::ASIDriver::new(), "ZWO ASI camera");
     }
 
     // add more drivers here
 
-    if !disabled_drivers.contains(&"simulator") {
+    if !disabled_drivers.contains(&config_names::SIMULATOR) {
         log::info!("initializing camera simulator driver");
-        drivers.push(Rc::new(RefCell::new(Box::new(simulator::SimDriver::new(simulator_video_file).unwrap()))));
+        add_driver(&mut drivers, simulator::SimDriver::new(simulator_video_file), "camera simulator");
     }
 
     drivers
@@ -30,7 +30,10 @@ mod control_ids {
 
 #[derive(Debug)]
 pub enum SimulatorError {
-    Internal
+    Internal,
+    /// User-specified file (`SimulatorVideoFile`) is not set in the configuration file.
+    NoUserFile,
+    UserFile(String)
 }
 
 impl From<SimulatorError> for CameraError {
@@ -39,6 +42,11 @@ impl From<SimulatorError> for CameraError {
     }
 }
 
+pub const DRIVER_NAME: &str = "Sim";
+
+/// Width and height of the test patterns (see `test_pattern_mono16`, `test_pattern_rgb8`).
+pub const TEST_PATTERN_SIZE: u32 = 256;
+
 pub struct SimDriver {
     user_video: Option<std::path::PathBuf>
 }
@@ -50,7 +58,7 @@ impl SimDriver {
 }
 
 impl Driver for SimDriver {
-    fn name(&self) -> &'static str { "Sim" }
+    fn name(&self) -> &'static str { DRIVER_NAME }
 
     fn enumerate_cameras(&mut self) -> Result<Vec<CameraInfo>, CameraError> {
         Ok(vec![CameraInfo{ id: CameraId{ id1: 1, id2: 1 }, name: "Simulator".to_string() }])
@@ -67,12 +75,16 @@ impl Driver for SimDriver {
             user_video: self.user_video.clone()
         }))
     }
+
+    fn set_simulator_video_file(&mut self, path: Option<std::path::PathBuf>) {
+        self.user_video = path;
+    }
 }
 
 pub struct SimCamera {
     image_shown: ImageShown,
     dummy1: RefCell<f64>,
-    new_img_seq: RefCell<Option<crossbeam::channel::Sender<Box<dyn input::ImageSequence>>>>,
+    new_img_seq: RefCell<Option<crossbeam::channel::Sender<CapturerInput>>>,
     frame_rate: Arc<RwLock<f64>>,
     exposure_time: RefCell<f64>,
     mount_simulator_data: crate::MountSimulatorData,
@@ -85,12 +97,53 @@ enum ImageShown {
     LandscapeMono8,
     LandscapeCFA8,
     Star1,
-    UserVideo
+    SunHAlphaMono8,
+    TestPatternMono16,
+    TestPatternRGB8,
+    /// SER or AVI video or image file specified in the configuration file.
+    UserFile
+}
+
+/// Images to be provided by the frame capturer.
+struct CapturerInput {
+    img_sequence: Box<dyn input::ImageSequence>,
+    /// If true, the images are moved according to the simulated sky rotation and mount slewing.
+    simulate_motion: bool
+}
+
+/// Returns a 16-bit test pattern for validating the capture and recording pipeline; the pixel at (x, y)
+/// has the value `256 * y + x`, i.e., its high byte is the row and its low byte the column.
+pub fn test_pattern_mono16() -> ga_image::Image {
+    let mut image = ga_image::Image::new(
+        TEST_PATTERN_SIZE, TEST_PATTERN_SIZE, None, ga_image::PixelFormat::Mono16, None, false
+    );
+    for y in 0..TEST_PATTERN_SIZE {
+        for (x, value) in image.line_mut::<u16>(y).iter_mut().enumerate() {
+            *value = (TEST_PATTERN_SIZE * y + x as u32) as u16;
+        }
+    }
+    image
+}
+
+/// Returns an 8-bit color test pattern for validating the capture and recording pipeline (incl. the channel
+/// order); the pixel at (x, y) has the value R = x, G = y, B = 255 - x.
+pub fn test_pattern_rgb8() -> ga_image::Image {
+    let mut image = ga_image::Image::new(
+        TEST_PATTERN_SIZE, TEST_PATTERN_SIZE, None, ga_image::PixelFormat::RGB8, None, false
+    );
+    for y in 0..TEST_PATTERN_SIZE {
+        for (x, pixel) in image.line_mut::<u8>(y).chunks_exact_mut(3).enumerate() {
+            pixel[0] = x as u8;
+            pixel[1] = y as u8;
+            pixel[2] = 255 - x as u8;
+        }
+    }
+    image
 }
 
 impl SimCamera {
-    fn create_capturer_input(&self) -> Box<dyn input::ImageSequence> {
-        match &self.image_shown {
+    fn create_capturer_input(&self) -> Result<CapturerInput, CameraError> {
+        let img_sequence = match &self.image_shown {
             ImageShown::LandscapeRGB8 => {
                 input::create_image_list(vec![resources::load_sim_image(resources::SimulatorImage::Landscape).unwrap()])
             },
@@ -109,11 +162,37 @@ impl SimCamera {
                 input::create_image_list(vec![resources::load_sim_image(resources::SimulatorImage::Star1).unwrap()])
             },
 
-            ImageShown::UserVideo => {
-                if self.user_video.is_none() { panic!("user video file must be specified in the configuration file"); }
-                input::open_ser_video(self.user_video.as_ref().unwrap()).unwrap()
-            }
-        }
+            ImageShown::SunHAlphaMono8 => {
+                input::create_image_list(vec![resources::load_sim_image(resources::SimulatorImage::SunHAlphaFullDisk).unwrap()
+                    .convert_pix_fmt(ga_image::PixelFormat::Mono8, None)])
+            },
+
+            ImageShown::UserFile => {
+                let path = self.user_video.as_ref().ok_or(SimulatorError::NoUserFile)?;
+                let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+                if extension.as_deref() == Some("ser") {
+                    input::open_ser_video(path).map_err(|e| SimulatorError::UserFile(e.to_string()))?
+                } else if extension.as_deref() == Some("avi") {
+                    input::open_avi_video(path).map_err(|e| SimulatorError::UserFile(e.to_string()))?
+                } else {
+                    let image = ga_image::Image::load(&path.to_string_lossy(), ga_image::FileType::Auto)
+                        .map_err(|e| SimulatorError::UserFile(format!("{:?}", e)))?;
+                    input::create_image_list(vec![image])
+                }
+            },
+
+            ImageShown::TestPatternMono16 => input::create_image_list(vec![test_pattern_mono16()]),
+
+            ImageShown::TestPatternRGB8 => input::create_image_list(vec![test_pattern_rgb8()])
+        };
+
+        let is_test_pattern = match self.image_shown {
+            ImageShown::TestPatternMono16 | ImageShown::TestPatternRGB8 => true,
+            _ => false
+        };
+
+        // test patterns must be recorded unchanged, so that the output can be compared with them
+        Ok(CapturerInput{ img_sequence, simulate_motion: !is_test_pattern })
     }
 }
 
@@ -145,7 +224,10 @@ impl Camera for SimCamera {
                 "Landscape (mono 8-bit)".to_string(),
                 "Landscape (raw color 8-bit)".to_string(),
                 "Defocused star".to_string(),
-                "User-specified video".to_string()
+                "Sun H-alpha full disk (mono 8-bit)".to_string(),
+                "Test pattern (mono 16-bit)".to_string(),
+                "Test pattern (RGB 8-bit)".to_string(),
+                "User-specified file".to_string()
             ],
             current_idx: ImageShown::iter().enumerate().find(|(_, val)| *val == self.image_shown).unwrap().0
         });
@@ -165,7 +247,9 @@ impl Camera for SimCamera {
             max: 30.0,
             step: 1.0e-6,
             num_decimals: 6,
-            is_exposure_time: true
+            is_exposure_time: true,
+            time_unit: Some(TimeUnit::Seconds),
+            is_frame_rate: false
         });
 
         let dummy_control_1 = CameraControl::Number(NumberControl{
@@ -183,7 +267,9 @@ impl Camera for SimCamera {
             max: 10.0,
             step: 0.1,
             num_decimals: 1,
-            is_exposure_time: false
+            is_exposure_time: false,
+            time_unit: None,
+            is_frame_rate: false
         });
 
         let dummy_control_2 = CameraControl::List(ListControl{
@@ -215,7 +301,9 @@ impl Camera for SimCamera {
             max: 1000.0,
             step: 10.0,
             num_decimals: 0,
-            is_exposure_time: false
+            is_exposure_time: false,
+            time_unit: None,
+            is_frame_rate: true
         });
 
         Ok(vec![
@@ -228,7 +316,7 @@ impl Camera for SimCamera {
     }
 
     fn create_capturer(&self) -> Result<Box<dyn FrameCapturer + Send>, CameraError> {
-        let img_sequence = self.create_capturer_input();
+        let input = self.create_capturer_input()?;
         let (sender, receiver) = crossbeam::channel::unbounded();
         *self.new_img_seq.borrow_mut() = Some(sender);
 
@@ -236,7 +324,8 @@ impl Camera for SimCamera {
         Ok(Box::new(SimFrameCapturer{
             t_last_capture: std::time::Instant::now(),
             img_index: 0,
-            img_sequence,
+            img_sequence: input.img_sequence,
+            simulate_motion: input.simulate_motion,
             new_img_seq: receiver,
             frame_rate: Arc::clone(&self.frame_rate),
             mount_simulator_data: self.mount_simulator_data.clone(),
@@ -268,8 +357,17 @@ impl Camera for SimCamera {
     fn set_list_control(&mut self, id: CameraControlId, option_idx: usize) -> Result<(), CameraError> {
         match id.0 {
             control_ids::IMAGE_SHOWN => {
-                self.image_shown = ImageShown::iter().skip(option_idx).next().unwrap();
-                self.new_img_seq.borrow().as_ref().unwrap().send(self.create_capturer_input()).unwrap();
+                let prev_image_shown = std::mem::replace(
+                    &mut self.image_shown,
+                    ImageShown::iter().skip(option_idx).next().unwrap()
+                );
+                match self.create_capturer_input() {
+                    Ok(input) => self.new_img_seq.borrow().as_ref().unwrap().send(input).unwrap(),
+                    Err(e) => {
+                        self.image_shown = prev_image_shown;
+                        return Err(e);
+                    }
+                }
             },
 
             _ => ()
@@ -325,10 +423,12 @@ pub struct SimFrameCapturer {
     t_last_capture: std::time::Instant,
     img_index: usize,
     img_sequence: Box<dyn input::ImageSequence>,
+    /// See `CapturerInput::simulate_motion`.
+    simulate_motion: bool,
     frame_rate: Arc<RwLock<f64>>,
     mount_simulator_data: crate::MountSimulatorData,
     img_offset: cgmath::Vector2<f64>,
-    new_img_seq: crossbeam::channel::Receiver<Box<dyn input::ImageSequence>>
+    new_img_seq: crossbeam::channel::Receiver<CapturerInput>
 }
 
 impl FrameCapturer for SimFrameCapturer {
@@ -340,9 +440,11 @@ impl FrameCapturer for SimFrameCapturer {
         match self.new_img_seq.try_recv() {
             Err(e) => if e != crossbeam::channel::TryRecvError::Empty { panic!("unexpected receiver error {:?}.", e) },
 
-            Ok(img_seq) => {
+            Ok(input) => {
                 self.img_index = 0;
-                self.img_sequence = img_seq;
+                self.img_sequence = input.img_sequence;
+                self.simulate_motion = input.simulate_motion;
+                if !self.simulate_motion { self.img_offset = cgmath::Vector2::new(0.0, 0.0); }
             }
         }
 
@@ -388,8 +490,10 @@ impl FrameCapturer for SimFrameCapturer {
             primary_axis_slew_dir_in_img_space * msd.primary_axis_speed.load(Ordering::Acquire) as f64 +
             secondary_axis_slew_dir_in_img_space * msd.secondary_axis_speed.load(Ordering::Acquire) as f64;
 
-        self.img_offset = self.img_offset +
-            t_elapsed.as_secs_f64() * (sky_rotation + mount_slew);
+        if self.simulate_motion {
+            self.img_offset = self.img_offset +
+                t_elapsed.as_secs_f64() * (sky_rotation + mount_slew);
+        }
 
         image.resize_and_translate_into(
             dest_image,
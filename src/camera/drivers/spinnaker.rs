@@ -62,6 +62,9 @@ impl From<SpinnakerCameraId> for CameraId  {
 /// Common GenICam camera feature names (Standard Features Naming Convention 1.5.1).
 mod genicam {
     pub const ACQUISITION_FRAME_RATE:                         &'static str = "AcquisitionFrameRate";
+    pub const CHUNK_ENABLE:                                   &'static str = "ChunkEnable";
+    pub const CHUNK_MODE_ACTIVE:                              &'static str = "ChunkModeActive";
+    pub const CHUNK_SELECTOR:                                 &'static str = "ChunkSelector";
     pub const DEVICE_MODEL_NAME:                              &'static str = "DeviceModelName";
     pub const DEVICE_TEMPERATURE:                             &'static str = "DeviceTemperature";
     pub const DEVICE_VENDOR_NAME:                             &'static str = "DeviceVendorName";
@@ -243,6 +246,15 @@ mod spin {
         pub fn data_size(&self) -> Result<size_t, CameraError> {
             read_value(self.handle, spinImageGetBufferSize)
         }
+
+        /// Returns the camera's timestamp (in nanoseconds) of the image.
+        pub fn timestamp(&self) -> Result<u64, CameraError> {
+            read_value(self.handle, spinImageGetTimeStamp)
+        }
+
+        pub fn frame_id(&self) -> Result<u64, CameraError> {
+            read_value(self.handle, spinImageGetFrameID)
+        }
     }
 
     impl Drop for Image {
@@ -402,6 +414,13 @@ mod spin {
             read_string(self.handle, spinNodeToString)
         }
 
+        pub fn set_from_string(&self, value: &str) -> Result<(), CameraError> {
+            let value = std::ffi::CString::new(value)
+                .map_err(|_| SpinnakerError::Error(format!("invalid value for node \"{}\"", self.name)))?;
+            checked_call!(spinNodeFromString(self.handle, value.as_c_str().as_ptr()));
+            Ok(())
+        }
+
         pub fn name(&self) -> &str {
             &self.name
         }
@@ -594,6 +613,10 @@ impl Driver for SpinnakerDriver {
 
         let mut camera_handle = self.cameras.as_ref().unwrap().camera(index)?;
         camera_handle.init()?;
+        // needs to be done before acquisition starts
+        if let Err(e) = enable_chunk_data(&camera_handle) {
+            log::warn!("failed to enable chunk data: {:?}", e);
+        }
         camera_handle.begin_acquisition()?;
 
         let genicam_node_map = camera_handle.genicam_node_map()?;
@@ -612,6 +635,36 @@ impl Driver for SpinnakerDriver {
     }
 }
 
+/// Makes the camera append frame counter and timestamp to each frame.
+fn enable_chunk_data(camera: &spin::Camera) -> Result<(), CameraError> {
+    let node_map = camera.genicam_node_map()?;
+    node_map.node(genicam::CHUNK_MODE_ACTIVE)?.set_bool_value(true)?;
+    for chunk in ["FrameID", "Timestamp"] {
+        node_map.node(genicam::CHUNK_SELECTOR)?.set_from_string(chunk)?;
+        node_map.node(genicam::CHUNK_ENABLE)?.set_bool_value(true)?;
+    }
+
+    Ok(())
+}
+
+/// Converts `node` and all its descendants.
+fn to_raw_node(node: &spin::Node) -> Result<RawNode, CameraError> {
+    let is_category = node.node_type()? == _spinNodeType_CategoryNode;
+
+    let mut children = vec![];
+    for i in 0..node.num_children()? {
+        children.push(to_raw_node(&node.child(i)?)?);
+    }
+
+    Ok(RawNode{
+        name: node.name().to_string(),
+        display_name: node.display_name().unwrap_or(node.name().to_string()),
+        value: if is_category { None } else { node.as_string().ok() },
+        writable: !is_category && node.writable().unwrap_or(false),
+        children
+    })
+}
+
 struct ControlData {
     node: spin::Node,
     enum_entries: Option<Vec<spin::EnumEntry>>
@@ -654,7 +707,10 @@ impl SpinnakerCamera {
                     max: node.max_float()?,
                     step,
                     num_decimals,
-                    is_exposure_time
+                    is_exposure_time,
+                    // GenICam specifies exposure time in microseconds
+                    time_unit: if is_exposure_time { Some(TimeUnit::Microseconds) } else { None },
+                    is_frame_rate: name == genicam::ACQUISITION_FRAME_RATE
                 }));
 
                 control_data.push(ControlData{ node, enum_entries: None });
@@ -954,6 +1010,8 @@ impl Camera for SpinnakerCamera {
         // by the main thread, and the `FrameCapturer`'s instance - by the capture thread.
         Ok(Box::new(SpinnakerFrameCapturer{
             camera_handle: self.camera_handle.clone(),
+            last_timestamp: None,
+            last_frame_id: None
         }))
     }
 
@@ -989,6 +1047,15 @@ impl Camera for SpinnakerCamera {
         self.controls[id.0 as usize].node.float_value()
     }
 
+    fn raw_node_tree(&self) -> Result<Option<RawNode>, CameraError> {
+        let root = self.camera_handle.genicam_node_map()?.node(genicam::ROOT)?;
+        Ok(Some(to_raw_node(&root)?))
+    }
+
+    fn set_raw_node_value(&mut self, name: &str, value: &str) -> Result<(), CameraError> {
+        self.camera_handle.genicam_node_map()?.node(name)?.set_from_string(value)
+    }
+
     fn get_list_control(&self, id: CameraControlId) -> Result<usize, CameraError> {
         let control_data = &self.controls[id.0 as usize];
         let current = control_data.node.current_enum_value_opaque()?;
@@ -1057,7 +1124,9 @@ impl Camera for SpinnakerCamera {
 }
 
 struct SpinnakerFrameCapturer {
-    camera_handle: Arc<spin::Camera>
+    camera_handle: Arc<spin::Camera>,
+    last_timestamp: Option<std::time::Duration>,
+    last_frame_id: Option<u64>
 }
 
 unsafe impl Send for SpinnakerFrameCapturer {}
@@ -1105,6 +1174,9 @@ impl FrameCapturer for SpinnakerFrameCapturer {
 
         let f_pix_fmt = to_pix_format(frame.pixel_format()?)?;
 
+        self.last_timestamp = frame.timestamp().ok().map(std::time::Duration::from_nanos);
+        self.last_frame_id = frame.frame_id().ok();
+
         let frame_pixels: &[u8] = unsafe { std::slice::from_raw_parts(
             frame.data_ptr()? as *const u8,
             frame.data_size()? as usize
@@ -1138,6 +1210,14 @@ impl FrameCapturer for SpinnakerFrameCapturer {
         Ok(())
     }
 
+    fn last_frame_timestamp(&self) -> Option<std::time::Duration> {
+        self.last_timestamp
+    }
+
+    fn last_frame_id(&self) -> Option<u64> {
+        self.last_frame_id
+    }
+
     fn pause(&mut self) -> Result<(), CameraError> {
         self.camera_handle.end_acquisition()
     }
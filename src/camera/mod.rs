@@ -19,6 +19,8 @@ use ga_image::Image;
 pub enum CameraError {
     FrameUnavailable,
     UnableToSetROI(String),
+    /// The operation is not supported by the camera or driver.
+    NotSupported(String),
     SimulatorError(drivers::simulator::SimulatorError),
     #[cfg(feature = "camera_iidc")]
     IIDCError(drivers::iidc::IIDCError),
@@ -32,6 +34,32 @@ pub enum CameraError {
     ASIError(drivers::asi::ASIError),
 }
 
+impl std::fmt::Display for CameraError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            CameraError::FrameUnavailable => write!(f, "frame unavailable (timeout)"),
+            CameraError::UnableToSetROI(msg) => write!(f, "unable to set ROI: {}", msg),
+            CameraError::NotSupported(msg) => write!(f, "not supported: {}", msg),
+            CameraError::SimulatorError(e) => match e {
+                drivers::simulator::SimulatorError::Internal => write!(f, "simulator: internal error"),
+                drivers::simulator::SimulatorError::NoUserFile =>
+                    write!(f, "simulator: video file not specified in the configuration file"),
+                drivers::simulator::SimulatorError::UserFile(msg) => write!(f, "simulator: video file error: {}", msg)
+            },
+            #[cfg(feature = "camera_iidc")]
+            CameraError::IIDCError(e) => write!(f, "IIDC driver error: {}", e),
+            #[cfg(feature = "camera_v4l2")]
+            CameraError::V4L2Error(e) => write!(f, "V4L2 driver error: {:?}", e),
+            #[cfg(feature = "camera_flycap2")]
+            CameraError::FlyCapture2Error(e) => write!(f, "FlyCapture2 driver error: {:?}", e),
+            #[cfg(feature = "camera_spinnaker")]
+            CameraError::SpinnakerError(e) => write!(f, "Spinnaker driver error: {:?}", e),
+            #[cfg(feature = "camera_asi")]
+            CameraError::ASIError(e) => write!(f, "ASI driver error: {:?}", e),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct CameraId {
     pub id1: u64,
@@ -48,6 +76,16 @@ impl CameraInfo {
     pub fn name(&self) -> &str { &self.name }
 }
 
+/// Raw device feature (e.g., a GenICam node); meant for troubleshooting.
+pub struct RawNode {
+    pub name: String,
+    pub display_name: String,
+    /// `None` for categories and unreadable nodes.
+    pub value: Option<String>,
+    pub writable: bool,
+    pub children: Vec<RawNode>
+}
+
 pub trait Camera {
     fn id(&self) -> CameraId;
 
@@ -84,6 +122,14 @@ pub trait Camera {
 
     /// Only implemented by camera simulator.
     fn set_mount_simulator_data(&mut self, _mount_simulator_data: crate::MountSimulatorData) {}
+
+    /// Returns the tree of the camera's raw features; `None` if not supported.
+    fn raw_node_tree(&self) -> Result<Option<RawNode>, CameraError> { Ok(None) }
+
+    /// Sets a raw feature's value from its string representation.
+    fn set_raw_node_value(&mut self, _name: &str, _value: &str) -> Result<(), CameraError> {
+        Err(CameraError::NotSupported("setting raw feature values".to_string()))
+    }
 }
 
 pub trait FrameCapturer {
@@ -93,6 +139,13 @@ pub trait FrameCapturer {
     // TODO: add policy (wait, poll)
     fn capture_frame(&mut self, dest_image: &mut Image) -> Result<(), CameraError>;
 
+    /// Returns the camera's own timestamp of the most recently captured frame (relative to a camera-specific epoch),
+    /// if supported.
+    fn last_frame_timestamp(&self) -> Option<std::time::Duration> { None }
+
+    /// Returns the camera's frame counter value of the most recently captured frame, if supported.
+    fn last_frame_id(&self) -> Option<u64> { None }
+
     fn pause(&mut self) -> Result<(), CameraError>;
 
     fn resume(&mut self) -> Result<(), CameraError>;
@@ -109,6 +162,9 @@ pub trait Driver {
     ///
     /// * `id` - One of IDs returned by the most recent call to `enumerate_cameras`.
     fn open_camera(&mut self, id: CameraId) -> Result<Box<dyn Camera>, CameraError>;
+
+    /// Only implemented by camera simulator; applies to cameras opened afterwards.
+    fn set_simulator_video_file(&mut self, _path: Option<std::path::PathBuf>) {}
 }
 
 #[enum_dispatch]
@@ -157,6 +213,23 @@ pub struct CameraControlBase {
     pub requires_capture_pause: bool
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TimeUnit {
+    Microseconds,
+    Milliseconds,
+    Seconds
+}
+
+impl TimeUnit {
+    pub fn to_seconds(&self, value: f64) -> f64 {
+        match self {
+            TimeUnit::Microseconds => value * 1.0e-6,
+            TimeUnit::Milliseconds => value * 1.0e-3,
+            TimeUnit::Seconds => value
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct NumberControl {
     base: CameraControlBase,
@@ -165,7 +238,11 @@ pub struct NumberControl {
     max: f64,
     step: f64,
     num_decimals: usize,
-    is_exposure_time: bool
+    is_exposure_time: bool,
+    /// Unit of `value`, if known (concerns exposure time controls).
+    time_unit: Option<TimeUnit>,
+    /// True if the control sets the frame rate in frames per second.
+    is_frame_rate: bool
 }
 
 impl NumberControl {
@@ -175,6 +252,8 @@ impl NumberControl {
     pub fn step(&self) -> f64 { self.step }
     pub fn num_decimals(&self) -> usize { self.num_decimals }
     pub fn is_exposure_time(&self) -> bool { self.is_exposure_time }
+    pub fn time_unit(&self) -> Option<TimeUnit> { self.time_unit }
+    pub fn is_frame_rate(&self) -> bool { self.is_frame_rate }
 }
 
 impl BaseProperties for NumberControl {
@@ -11,6 +11,12 @@
 //!
 
 use cgmath::Vector2;
+use crate::devices::{DeviceConnectionDiscriminants, SerialSettings, focuser::FocuserSimulatorParams};
+use crate::MountSimulatorParams;
+use crate::mount;
+use crate::gui::StatusBarField;
+use crate::output::OutputFormat;
+use crate::workers::{histogram, recording};
 use std::path::{Path, PathBuf};
 use strum::IntoEnumIterator;
 
@@ -23,14 +29,39 @@ mod groups {
     pub const FOCUSER: &str = "Focuser";
     pub const MOUNT: &str = "Mount";
     pub const UI: &str = "UI";
+    pub const SHORTCUTS: &str = "Shortcuts";
+    pub const SERIAL: &str = "Serial";
+    pub const RECORDING: &str = "Recording";
 }
 
 mod keys {
     // group: MAIN
+    /// Version of the configuration file format (see `CONFIG_VERSION`).
+    pub const CONFIG_VERSION: &str = "ConfigVersion";
     pub const RECORDING_DEST_PATH: &str = "RecordingDestPath";
     pub const DISABLED_DRIVERS: &str = "DisabledDrivers";
     pub const PREVIEW_FPS_LIMIT: &str = "PreviewFpsLimit";
     pub const SIM_VIDEO_FILE: &str = "SimulatorVideoFile";
+    /// File to which a summary of each program session is appended.
+    pub const SESSION_SUMMARY_FILE: &str = "SessionSummaryFile";
+    /// Interval (in seconds) of automatic configuration saving; 0 disables it.
+    pub const AUTOSAVE_INTERVAL: &str = "ConfigAutosaveInterval";
+    /// Time (in minutes) of inactivity after which the camera and mount are disconnected; 0 disables it.
+    pub const IDLE_DISCONNECT_TIMEOUT: &str = "IdleDisconnectTimeout";
+    /// Number of consecutive failed attempts to capture a frame (e.g., due to a timeout) after which the capture
+    /// is considered failed; 0 means unlimited.
+    pub const MAX_FRAME_UNAVAILABLE_RETRIES: &str = "MaxFrameUnavailableRetries";
+    /// If true, closing the main window during recording has to be confirmed.
+    pub const CONFIRM_QUIT_WHILE_RECORDING: &str = "ConfirmQuitWhileRecording";
+    /// Address (IP:port) at which the live frame feed serves captured frames (see `workers::frame_feed`).
+    pub const FRAME_FEED_ADDRESS: &str = "FrameFeedAddress";
+    /// Address (IP:port) at which the preview is served as an MJPEG stream (see `workers::mjpeg_server`);
+    /// local only by default, use e.g. "0.0.0.0:8090" to serve on all interfaces.
+    pub const MJPEG_SERVER_ADDRESS: &str = "MjpegServerAddress";
+    /// Address (IP:port) at which the remote control HTTP API is served (see `workers::remote_control`).
+    pub const REMOTE_CONTROL_ADDRESS: &str = "RemoteControlAddress";
+    /// Image scale (in arcseconds per pixel) used to express star sizes in angular units; 0 means unknown.
+    pub const IMAGE_SCALE: &str = "ImageScale";
 
     // group: UI
     pub const MAIN_WINDOW_POS_SIZE: &str = "MainWindowPosSize";
@@ -39,8 +70,64 @@ mod keys {
     pub const MAIN_WINDOW_PANED_POS: &str = "MainWindowPanedPos";
     /// Position of the divider between camera controls and histogram.
     pub const CAMERA_CONTROLS_PANED_POS: &str = "CameraControlsPanedPos";
+    /// If false, the preview uses nearest-neighbor interpolation when zoomed in.
+    pub const PREVIEW_SMOOTH_ZOOM: &str = "PreviewSmoothZoom";
+    /// Interval (in seconds) of refreshing the preview image while the preview is paused; 0 means never.
+    pub const PAUSED_PREVIEW_REFRESH_INTERVAL: &str = "PausedPreviewRefreshInterval";
+    /// Number of histogram bins (one of `histogram::NUM_BINS_CHOICES`).
+    pub const HISTOGRAM_NUM_BINS: &str = "HistogramNumBins";
     pub const INFO_OVERLAY_FONT_SIZE: &str = "InfoOverlayFontSize";
+    /// If true, the SNR of the star tracked via centroid is shown in the informational overlay.
+    pub const SHOW_GUIDE_STAR_SNR: &str = "ShowGuideStarSnr";
+    /// RGB components (0.0-1.0) of the informational overlay and reticle color.
+    pub const OVERLAY_COLOR: &str = "OverlayColor";
     pub const TOOLBAR_ICON_SIZE: &str = "ToolbarIconSize";
+    /// Labels of camera controls marked as favorite.
+    pub const FAVORITE_CAMERA_CONTROLS: &str = "FavoriteCameraControls";
+    pub const FAVORITE_CAMERA_CONTROLS_ONLY: &str = "FavoriteCameraControlsOnly";
+    /// If true, buttons in the controls panels are enlarged for use on a touchscreen.
+    pub const LARGE_BUTTONS: &str = "LargeButtons";
+    /// If true, the red-on-black user interface theme is used.
+    pub const NIGHT_MODE: &str = "NightMode";
+    /// Names of the shown status bar fields (see `StatusBarField::config_name`), in display order.
+    pub const STATUS_BAR_FIELDS: &str = "StatusBarFields";
+    /// User interface language code (see `i18n::LANGUAGES`); if empty, determined from the environment.
+    pub const LANGUAGE: &str = "Language";
+
+    /// Width and height of the centroid tracking area created by clicking (instead of selecting an area).
+    pub const CENTROID_AREA_SIZE: &str = "CentroidAreaSize";
+    /// Margin (in pixels) around the tracked target (or its centroid area) used when setting ROI around the target.
+    pub const ROI_AROUND_TARGET_MARGIN: &str = "RoiAroundTargetMargin";
+    /// If the SNR of the star tracked via centroid stays below this value, tracking is stopped; 0 disables the check.
+    pub const MIN_GUIDE_STAR_SNR: &str = "MinGuideStarSnr";
+
+    // group: RECORDING
+    /// If true, recorded frames' timestamps are based on the camera's clock (if supported).
+    pub const CAMERA_TIMESTAMPS: &str = "CameraTimestamps";
+    /// If true, the output is re-opened and checked for missing or unreadable frames after recording.
+    pub const VERIFY_OUTPUT: &str = "VerifyOutput";
+    /// If true, a list of recorded frames' quality is saved next to the output (see `output::quality_log`).
+    pub const QUALITY_LOG: &str = "QualityLog";
+    /// Frames to be written (see `recording::FrameSelection`): "all", "quality" or "best".
+    pub const FRAME_SELECTION: &str = "FrameSelection";
+    /// Minimum quality of written frames (for "quality") or percentage of the best frames (for "best").
+    pub const FRAME_SELECTION_VALUE: &str = "FrameSelectionValue";
+    /// Maximum amount of captured image data waiting to be recorded (see `recording::DEFAULT_MAX_BUFFERED_MIB`).
+    pub const MAX_BUFFERED_MIB: &str = "MaxBufferedMiB";
+    /// Interval (in milliseconds) of updating the amount of buffered data in the recording thread
+    /// (see `recording::DEFAULT_UPDATE_INTERVAL_MS`).
+    pub const UPDATE_INTERVAL_MS: &str = "BufferUpdateIntervalMs";
+    /// Output format selected by default (see `OutputFormat::config_name`).
+    pub const OUTPUT_FORMAT: &str = "OutputFormat";
+    /// Type of the last used recording limit: "duration", "frames" or "forever".
+    pub const LIMIT_TYPE: &str = "LimitType";
+    pub const LIMIT_DURATION_S: &str = "LimitDurationSec";
+    pub const LIMIT_FRAMES: &str = "LimitFrames";
+    /// Number of recordings in a sequence.
+    pub const SEQUENCE_COUNT: &str = "SequenceCount";
+    pub const SEQUENCE_INTERVAL_S: &str = "SequenceIntervalSec";
+    /// Maximum frame rate of recordings (frames captured in excess are not recorded); 0 means no limit.
+    pub const MAX_FRAME_RATE: &str = "MaxFrameRate";
 
     // group FOCUSER
     pub const FOCUSCUBE3_LAST_SERIAL_PORT: &str = "FocusCube3LastSerialPort";
@@ -48,6 +135,38 @@ mod keys {
     pub const FOCUSCUBE3_LAST_NETWORK_PWD: &str = "FocusCube3LastNetworkPwd";
     pub const DREAMFOCUSER_MINI_LAST_SERIAL_PORT: &str = "DreamFocuserMiniLastSerialPort";
     pub const DREAMFOCUSER_MINI_LAST_MAC_ADDR: &str = "DreamFocuserMiniLastMACAddr";
+    /// Comma-separated list of step counts for the relative move buttons.
+    pub const FOCUSER_REL_MOVE_STEPS: &str = "RelativeMoveSteps";
+    /// Comma-separated list of speeds (multiples of the focuser's normal speed) offered in the speed combo box;
+    /// prefixed with `DeviceConnectionDiscriminants::config_name`.
+    pub const FOCUSER_SPEEDS_SUFFIX: &str = "Speeds";
+    /// Focuser backlash compensation: number of steps by which absolute moves overshoot the target before
+    /// approaching it (so that the backlash is always taken up in the same direction); the sign determines
+    /// the direction of the final approach (e.g. a positive value means the target is always approached
+    /// in the positive direction). Zero disables overshooting.
+    pub const FOCUSER_GOTO_OVERSHOOT: &str = "GotoOvershoot";
+    /// Focuser position at the last disconnection; prefixed with `DeviceConnectionDiscriminants::config_name`.
+    pub const FOCUSER_LAST_POSITION_SUFFIX: &str = "LastPosition";
+    /// Focuser simulator's change in position per second at normal speed.
+    pub const FOCUSER_SIM_SPEED: &str = "SimulatorSpeed";
+    /// Focuser simulator's length of travel in steps.
+    pub const FOCUSER_SIM_TRAVEL: &str = "SimulatorTravel";
+    /// Focuser simulator's change of temperature per hour (°C).
+    pub const FOCUSER_SIM_TEMPERATURE_DRIFT: &str = "SimulatorTemperatureDrift";
+    /// If true, the focuser is moved automatically when the temperature changes (see `FOCUSER_TEMP_COMP_DELTA`).
+    pub const FOCUSER_TEMP_COMP_ENABLED: &str = "TemperatureCompensation";
+    /// Change of temperature (°C) since the last focusing which triggers a refocusing move.
+    pub const FOCUSER_TEMP_COMP_DELTA: &str = "TemperatureCompensationDelta";
+    /// Number of steps to move the focuser by per 1 °C of temperature increase.
+    pub const FOCUSER_TEMP_COMP_STEPS_PER_DEG: &str = "TemperatureCompensationStepsPerDegree";
+
+    // group SERIAL (prefixed with `DeviceConnectionDiscriminants::config_name`)
+    pub const SERIAL_BAUD_RATE_SUFFIX: &str = "BaudRate";
+    pub const SERIAL_TIMEOUT_MS_SUFFIX: &str = "TimeoutMs";
+
+    // per-camera group (see `camera_group`)
+    pub const CAMERA_DEFAULT_VIDEO_MODE: &str = "DefaultVideoMode";
+    pub const CAMERA_DEFAULT_ROI: &str = "DefaultROI";
 
     // group MOUNT
     pub const IOPTRON_LAST_DEVICE: &str = "iOptronLastDevice";
@@ -58,10 +177,65 @@ mod keys {
     pub const SIM_PRIMARY_AXIS_SLEW_DIR_IN_IMG_SPACE: &str = "SimulatorPrimaryAxisSlewDirInImgSpace";
     //TODO: orientation of secondary axis' slew direction rel. to primary's (to simulate the usage of a star diagonal)
     pub const SIM_SKY_ROTATION_SPEED_PIX_PER_SEC: &str = "SimulatorSkyRotationSpeedPixelsPerSecond";
+    /// If true, slews started by the user are stopped when the main window loses focus.
+    pub const STOP_SLEW_ON_FOCUS_LOSS: &str = "StopSlewOnFocusLoss";
+    /// Acceleration (in multiples of sidereal rate per second) of slews started by the user, used for mounts
+    /// supporting variable slewing speeds; 0 means slews start and stop immediately.
+    pub const SLEW_ACCELERATION: &str = "SlewAcceleration";
+    /// Duration (in milliseconds) of the extra guide pulse taking up backlash when guiding reverses direction
+    /// on the primary axis; 0 disables compensation.
+    pub const GUIDE_BACKLASH_PRIMARY_MS: &str = "GuideBacklashCompensationPrimaryMs";
+    /// As `GUIDE_BACKLASH_PRIMARY_MS`, for the secondary axis.
+    pub const GUIDE_BACKLASH_SECONDARY_MS: &str = "GuideBacklashCompensationSecondaryMs";
+    /// If true, guiding corrects only the primary (RA) axis; the secondary (Dec) axis receives zero speed.
+    pub const GUIDE_PRIMARY_AXIS_ONLY: &str = "GuidePrimaryAxisOnly";
+    /// Comma-separated list of sidereal rate multiples offered as slewing speeds; prefixed with
+    /// `DeviceConnectionDiscriminants::config_name`.
+    pub const SLEWING_SPEEDS_SUFFIX: &str = "SlewingSpeeds";
 }
 
+/// Current version of the configuration file format; increase it whenever existing groups or keys are renamed
+/// or their values' format changes, and add the corresponding function to `MIGRATIONS`.
+const CONFIG_VERSION: i32 = 1;
+
+/// Element `i` converts configuration from version `i` to `i + 1`. Version 0 denotes files from before
+/// the introduction of versioning.
+const MIGRATIONS: [fn(&glib::KeyFile); CONFIG_VERSION as usize] = [
+    // unversioned files already have the version 1 layout
+    |_| ()
+];
+
 const DEFAULT_PREVIEW_FPS_LIMIT: i32 = 60;
 
+const DEFAULT_AUTOSAVE_INTERVAL_SECS: i32 = 60;
+
+const DEFAULT_CENTROID_AREA_SIZE: i32 = 64;
+
+const DEFAULT_ROI_AROUND_TARGET_MARGIN: i32 = 128;
+
+const DEFAULT_FOCUSER_REL_MOVE_STEPS: [i32; 3] = [10, 100, 1000];
+
+/// Local connections only by default.
+const DEFAULT_FRAME_FEED_ADDRESS: &str = "127.0.0.1:5210";
+/// Listens on all interfaces, so that other devices (e.g., a phone) can connect.
+const DEFAULT_MJPEG_SERVER_ADDRESS: &str = "127.0.0.1:8090";
+/// Accepts local connections only; the API is not authenticated.
+const DEFAULT_REMOTE_CONTROL_ADDRESS: &str = "127.0.0.1:8091";
+
+const DEFAULT_FOCUSER_TEMP_COMP_DELTA: f64 = 1.0;
+
+const DEFAULT_FOCUSER_SPEEDS: [f64; 13] = [
+    1.0 / 64.0, 1.0 / 32.0, 1.0 / 16.0, 1.0 / 8.0, 1.0 / 4.0, 1.0 / 2.0, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0
+];
+
+const DEFAULT_SLEWING_SPEEDS: [f64; 13] = [1.0, 2.0, 4.0, 8.0, 16.0, 20.0, 32.0, 60.0, 64.0, 128.0, 256.0, 512.0, 720.0];
+
+/// Returns name of the configuration group with settings of the specified camera.
+fn camera_group(camera_name: &str) -> String {
+    // brackets are not allowed in group names
+    format!("Camera {}", camera_name.replace(|c| c == '[' || c == ']', "_"))
+}
+
 pub struct Configuration {
     key_file: glib::KeyFile
 }
@@ -72,16 +246,7 @@ impl Configuration {
     }
 
     pub fn new() -> Configuration {
-        let key_file = glib::KeyFile::new();
-        let file_path = config_file_path();
-        if key_file.load_from_file(
-            file_path.clone(),
-            glib::KeyFileFlags::NONE
-        ).is_err() {
-            println!("WARNING: Failed to load configuration from {}.", file_path.to_str().unwrap());
-        }
-
-        Configuration{ key_file }
+        Configuration{ key_file: load_key_file(&config_file_path()) }
     }
 
     #[cfg(feature = "controller")]
@@ -114,6 +279,228 @@ impl Configuration {
         result
     }
 
+    pub fn shortcut(&self, config_key: &str) -> Option<String> {
+        self.key_file.string(groups::SHORTCUTS, config_key).ok().map(|s| s.to_string())
+    }
+
+    pub fn set_shortcut(&self, config_key: &str, accelerator: &str) {
+        self.key_file.set_string(groups::SHORTCUTS, config_key, accelerator);
+    }
+
+    /// Returns a flag selecting the contents of recording settings file (see `rec_gui::SettingsFileContents`).
+    pub fn settings_file_option(&self, config_key: &str) -> Option<bool> {
+        self.key_file.boolean(groups::RECORDING, config_key).ok()
+    }
+
+    pub fn set_settings_file_option(&self, config_key: &str, value: bool) {
+        self.key_file.set_boolean(groups::RECORDING, config_key, value);
+    }
+
+    pub fn camera_timestamps(&self) -> bool {
+        self.key_file.boolean(groups::RECORDING, keys::CAMERA_TIMESTAMPS).unwrap_or(false)
+    }
+
+    pub fn set_camera_timestamps(&self, value: bool) {
+        self.key_file.set_boolean(groups::RECORDING, keys::CAMERA_TIMESTAMPS, value);
+    }
+
+    pub fn verify_output(&self) -> bool {
+        self.key_file.boolean(groups::RECORDING, keys::VERIFY_OUTPUT).unwrap_or(true)
+    }
+
+    pub fn set_verify_output(&self, value: bool) {
+        self.key_file.set_boolean(groups::RECORDING, keys::VERIFY_OUTPUT, value);
+    }
+
+    pub fn quality_log(&self) -> bool {
+        self.key_file.boolean(groups::RECORDING, keys::QUALITY_LOG).unwrap_or(false)
+    }
+
+    pub fn set_quality_log(&self, value: bool) {
+        self.key_file.set_boolean(groups::RECORDING, keys::QUALITY_LOG, value);
+    }
+
+    pub fn recording_frame_selection(&self) -> recording::FrameSelection {
+        let value = self.key_file.double(groups::RECORDING, keys::FRAME_SELECTION_VALUE).unwrap_or(0.0);
+        match self.key_file.string(groups::RECORDING, keys::FRAME_SELECTION) {
+            Ok(kind) => match kind.as_str() {
+                "all" => recording::FrameSelection::All,
+                "quality" if value >= 0.0 => recording::FrameSelection::QualityAbove(value),
+                "best" if value > 0.0 && value <= 100.0 => recording::FrameSelection::BestPercent(value),
+                _ => {
+                    println!(
+                        "WARNING: invalid configuration value for {}/{},{}: {},{}",
+                        groups::RECORDING, keys::FRAME_SELECTION, keys::FRAME_SELECTION_VALUE, kind, value
+                    );
+                    recording::FrameSelection::All
+                }
+            },
+
+            _ => recording::FrameSelection::All
+        }
+    }
+
+    pub fn set_recording_frame_selection(&self, value: recording::FrameSelection) {
+        let (kind, value) = match value {
+            recording::FrameSelection::All => ("all", 0.0),
+            recording::FrameSelection::QualityAbove(value) => ("quality", value),
+            recording::FrameSelection::BestPercent(value) => ("best", value)
+        };
+        self.key_file.set_string(groups::RECORDING, keys::FRAME_SELECTION, kind);
+        self.key_file.set_double(groups::RECORDING, keys::FRAME_SELECTION_VALUE, value);
+    }
+
+    pub fn max_buffered_mib(&self) -> i32 {
+        match self.key_file.integer(groups::RECORDING, keys::MAX_BUFFERED_MIB) {
+            Ok(value) => if value > 0 {
+                value
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::RECORDING, keys::MAX_BUFFERED_MIB, value);
+                recording::DEFAULT_MAX_BUFFERED_MIB
+            },
+
+            _ => recording::DEFAULT_MAX_BUFFERED_MIB
+        }
+    }
+
+    pub fn set_max_buffered_mib(&self, value: i32) {
+        self.key_file.set_integer(groups::RECORDING, keys::MAX_BUFFERED_MIB, value);
+    }
+
+    pub fn recording_update_interval_ms(&self) -> u32 {
+        match self.key_file.integer(groups::RECORDING, keys::UPDATE_INTERVAL_MS) {
+            Ok(value) => if value >= recording::MIN_UPDATE_INTERVAL_MS as i32 {
+                value as u32
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::RECORDING, keys::UPDATE_INTERVAL_MS, value);
+                recording::DEFAULT_UPDATE_INTERVAL_MS
+            },
+
+            _ => recording::DEFAULT_UPDATE_INTERVAL_MS
+        }
+    }
+
+    pub fn set_recording_update_interval_ms(&self, value: u32) {
+        self.key_file.set_integer(groups::RECORDING, keys::UPDATE_INTERVAL_MS, value as i32);
+    }
+
+    pub fn recording_output_format(&self) -> Option<OutputFormat> {
+        let value = self.key_file.string(groups::RECORDING, keys::OUTPUT_FORMAT).ok()?;
+        match OutputFormat::iter().find(|f| f.config_name() == value.as_str()) {
+            Some(output_format) => Some(output_format),
+            None => {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::RECORDING, keys::OUTPUT_FORMAT, value);
+                None
+            }
+        }
+    }
+
+    pub fn set_recording_output_format(&self, value: &OutputFormat) {
+        self.key_file.set_string(groups::RECORDING, keys::OUTPUT_FORMAT, value.config_name());
+    }
+
+    /// Returns the last used limit type ("duration", "frames" or "forever").
+    pub fn recording_limit_type(&self) -> Option<String> {
+        match self.key_file.string(groups::RECORDING, keys::LIMIT_TYPE) {
+            Ok(value) => if ["duration", "frames", "forever"].contains(&value.as_str()) {
+                Some(value.to_string())
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::RECORDING, keys::LIMIT_TYPE, value);
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_recording_limit_type(&self, value: &str) {
+        self.key_file.set_string(groups::RECORDING, keys::LIMIT_TYPE, value);
+    }
+
+    pub fn recording_limit_duration(&self) -> Option<std::time::Duration> {
+        match self.key_file.integer(groups::RECORDING, keys::LIMIT_DURATION_S) {
+            Ok(value) if value >= 0 => Some(std::time::Duration::from_secs(value as u64)),
+            _ => None
+        }
+    }
+
+    pub fn set_recording_limit_duration(&self, value: std::time::Duration) {
+        self.key_file.set_integer(groups::RECORDING, keys::LIMIT_DURATION_S, value.as_secs() as i32);
+    }
+
+    pub fn recording_limit_frames(&self) -> Option<usize> {
+        match self.key_file.integer(groups::RECORDING, keys::LIMIT_FRAMES) {
+            Ok(value) if value > 0 => Some(value as usize),
+            _ => None
+        }
+    }
+
+    pub fn set_recording_limit_frames(&self, value: usize) {
+        self.key_file.set_integer(groups::RECORDING, keys::LIMIT_FRAMES, value as i32);
+    }
+
+    pub fn recording_sequence_count(&self) -> Option<usize> {
+        match self.key_file.integer(groups::RECORDING, keys::SEQUENCE_COUNT) {
+            Ok(value) if value > 0 => Some(value as usize),
+            _ => None
+        }
+    }
+
+    pub fn set_recording_sequence_count(&self, value: usize) {
+        self.key_file.set_integer(groups::RECORDING, keys::SEQUENCE_COUNT, value as i32);
+    }
+
+    pub fn recording_sequence_interval(&self) -> Option<std::time::Duration> {
+        match self.key_file.integer(groups::RECORDING, keys::SEQUENCE_INTERVAL_S) {
+            Ok(value) if value >= 0 => Some(std::time::Duration::from_secs(value as u64)),
+            _ => None
+        }
+    }
+
+    pub fn set_recording_sequence_interval(&self, value: std::time::Duration) {
+        self.key_file.set_integer(groups::RECORDING, keys::SEQUENCE_INTERVAL_S, value.as_secs() as i32);
+    }
+
+    /// Returns `None` if recording frame rate is not limited.
+    pub fn recording_max_frame_rate(&self) -> Option<f64> {
+        match self.key_file.double(groups::RECORDING, keys::MAX_FRAME_RATE) {
+            Ok(value) => if value > 0.0 {
+                Some(value)
+            } else {
+                if value < 0.0 {
+                    println!("WARNING: invalid configuration value for {}/{}: {}", groups::RECORDING, keys::MAX_FRAME_RATE, value);
+                }
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_recording_max_frame_rate(&self, value: Option<f64>) {
+        self.key_file.set_double(groups::RECORDING, keys::MAX_FRAME_RATE, value.unwrap_or(0.0));
+    }
+
+    /// Returns image scale in arcseconds per pixel (`None` if unknown).
+    pub fn image_scale(&self) -> Option<f64> {
+        match self.key_file.double(groups::MAIN, keys::IMAGE_SCALE) {
+            Ok(value) => if value > 0.0 {
+                Some(value)
+            } else {
+                if value < 0.0 {
+                    println!("WARNING: invalid configuration value for {}/{}: {}", groups::MAIN, keys::IMAGE_SCALE, value);
+                }
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_image_scale(&self, value: Option<f64>) {
+        self.key_file.set_double(groups::MAIN, keys::IMAGE_SCALE, value.unwrap_or(0.0));
+    }
+
     pub fn main_window_pos(&self) -> Option<gtk::Rectangle> {
         self.read_rect(groups::UI, keys::MAIN_WINDOW_POS_SIZE)
     }
@@ -146,6 +533,129 @@ impl Configuration {
         self.key_file.set_integer(groups::UI, keys::CAMERA_CONTROLS_PANED_POS, value)
     }
 
+    pub fn confirm_quit_while_recording(&self) -> bool {
+        self.key_file.boolean(groups::MAIN, keys::CONFIRM_QUIT_WHILE_RECORDING).unwrap_or(true)
+    }
+
+    pub fn set_confirm_quit_while_recording(&self, value: bool) {
+        self.key_file.set_boolean(groups::MAIN, keys::CONFIRM_QUIT_WHILE_RECORDING, value);
+    }
+
+    pub fn preview_smooth_zoom(&self) -> bool {
+        self.key_file.boolean(groups::UI, keys::PREVIEW_SMOOTH_ZOOM).unwrap_or(true)
+    }
+
+    pub fn set_preview_smooth_zoom(&self, value: bool) {
+        self.key_file.set_boolean(groups::UI, keys::PREVIEW_SMOOTH_ZOOM, value);
+    }
+
+    /// Returns `None` if the paused preview is not refreshed.
+    pub fn paused_preview_refresh_interval(&self) -> Option<std::time::Duration> {
+        match self.key_file.integer(groups::UI, keys::PAUSED_PREVIEW_REFRESH_INTERVAL) {
+            Ok(value) => if value > 0 {
+                Some(std::time::Duration::from_secs(value as u64))
+            } else {
+                if value < 0 {
+                    println!(
+                        "WARNING: invalid configuration value for {}/{}: {}",
+                        groups::UI, keys::PAUSED_PREVIEW_REFRESH_INTERVAL, value
+                    );
+                }
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_paused_preview_refresh_interval(&self, value: Option<std::time::Duration>) {
+        self.key_file.set_integer(
+            groups::UI,
+            keys::PAUSED_PREVIEW_REFRESH_INTERVAL,
+            value.map(|interval| interval.as_secs() as i32).unwrap_or(0)
+        );
+    }
+
+    pub fn histogram_num_bins(&self) -> usize {
+        match self.key_file.integer(groups::UI, keys::HISTOGRAM_NUM_BINS) {
+            Ok(value) => if histogram::NUM_BINS_CHOICES.contains(&(value as usize)) {
+                value as usize
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::UI, keys::HISTOGRAM_NUM_BINS, value);
+                histogram::DEFAULT_NUM_BINS
+            },
+
+            _ => histogram::DEFAULT_NUM_BINS
+        }
+    }
+
+    pub fn set_histogram_num_bins(&self, value: usize) {
+        self.key_file.set_integer(groups::UI, keys::HISTOGRAM_NUM_BINS, value as i32)
+    }
+
+    pub fn favorite_camera_controls(&self) -> Vec<String> {
+        match self.key_file.string_list(groups::UI, keys::FAVORITE_CAMERA_CONTROLS) {
+            Ok(list) => list.iter().map(|s| s.to_string()).collect(),
+            _ => vec![]
+        }
+    }
+
+    pub fn set_favorite_camera_controls(&self, labels: &[&str]) {
+        self.key_file.set_string_list(groups::UI, keys::FAVORITE_CAMERA_CONTROLS, labels);
+    }
+
+    pub fn favorite_camera_controls_only(&self) -> Option<bool> {
+        self.key_file.boolean(groups::UI, keys::FAVORITE_CAMERA_CONTROLS_ONLY).ok()
+    }
+
+    pub fn set_favorite_camera_controls_only(&self, value: bool) {
+        self.key_file.set_boolean(groups::UI, keys::FAVORITE_CAMERA_CONTROLS_ONLY, value);
+    }
+
+    pub fn large_buttons(&self) -> bool {
+        self.key_file.boolean(groups::UI, keys::LARGE_BUTTONS).unwrap_or(false)
+    }
+
+    pub fn set_large_buttons(&self, value: bool) {
+        self.key_file.set_boolean(groups::UI, keys::LARGE_BUTTONS, value);
+    }
+
+    /// Returns `StatusBarField::defaults()` if not set.
+    pub fn status_bar_fields(&self) -> Vec<StatusBarField> {
+        match self.key_file.string_list(groups::UI, keys::STATUS_BAR_FIELDS) {
+            Ok(list) => list.iter().filter_map(|name| {
+                let field = StatusBarField::from_config_name(name);
+                if field.is_none() {
+                    println!("WARNING: invalid status bar field in configuration: {}", name);
+                }
+                field
+            }).collect(),
+
+            _ => StatusBarField::defaults()
+        }
+    }
+
+    pub fn set_status_bar_fields(&self, fields: &[StatusBarField]) {
+        let names: Vec<&str> = fields.iter().map(|field| field.config_name()).collect();
+        self.key_file.set_string_list(groups::UI, keys::STATUS_BAR_FIELDS, &names);
+    }
+
+    pub fn language(&self) -> String {
+        self.key_file.string(groups::UI, keys::LANGUAGE).map(|s| s.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_language(&self, code: &str) {
+        self.key_file.set_string(groups::UI, keys::LANGUAGE, code);
+    }
+
+    pub fn night_mode(&self) -> bool {
+        self.key_file.boolean(groups::UI, keys::NIGHT_MODE).unwrap_or(false)
+    }
+
+    pub fn set_night_mode(&self, value: bool) {
+        self.key_file.set_boolean(groups::UI, keys::NIGHT_MODE, value);
+    }
+
     pub fn info_overlay_font_size(&self) -> Option<f64> {
         self.key_file.double(groups::UI, keys::INFO_OVERLAY_FONT_SIZE).ok()
     }
@@ -154,6 +664,28 @@ impl Configuration {
         self.key_file.set_double(groups::UI, keys::INFO_OVERLAY_FONT_SIZE, value);
     }
 
+    pub fn show_guide_star_snr(&self) -> bool {
+        self.key_file.boolean(groups::UI, keys::SHOW_GUIDE_STAR_SNR).unwrap_or(true)
+    }
+
+    pub fn set_show_guide_star_snr(&self, value: bool) {
+        self.key_file.set_boolean(groups::UI, keys::SHOW_GUIDE_STAR_SNR, value);
+    }
+
+    pub fn overlay_color(&self) -> Option<[f64; 3]> {
+        let values = self.key_file.double_list(groups::UI, keys::OVERLAY_COLOR).ok()?;
+        if values.len() == 3 && values.iter().all(|v| (0.0..=1.0).contains(v)) {
+            Some([values[0], values[1], values[2]])
+        } else {
+            println!("WARNING: invalid configuration value for {}/{}: {:?}", groups::UI, keys::OVERLAY_COLOR, values);
+            None
+        }
+    }
+
+    pub fn set_overlay_color(&self, value: [f64; 3]) {
+        self.key_file.set_double_list(groups::UI, keys::OVERLAY_COLOR, &value);
+    }
+
     pub fn ascom_last_driver(&self) -> Option<String> {
         self.key_file.string(groups::MOUNT, keys::ASCOM_LAST_DRIVER).ok().map(|s| s.to_string())
     }
@@ -178,14 +710,6 @@ impl Configuration {
         self.key_file.set_string(groups::MOUNT, keys::IOPTRON_LAST_DEVICE, value);
     }
 
-    pub fn mount_simulator_sky_rotation_dir_in_img_space(&self) -> Option<Vector2<i32>> {
-        self.read_vec2(groups::MOUNT, keys::SIM_SKY_ROTATION_DIR_IN_IMG_SPACE)
-    }
-
-    pub fn mount_simulator_primary_axis_slew_dir_in_img_space(&self) -> Option<Vector2<i32>> {
-        self.read_vec2(groups::MOUNT, keys::SIM_PRIMARY_AXIS_SLEW_DIR_IN_IMG_SPACE)
-    }
-
     pub fn zwo_last_device(&self) -> Option<String> {
         self.key_file.string(groups::MOUNT, keys::ZWO_LAST_DEVICE).ok().map(|s| s.to_string())
     }
@@ -194,10 +718,117 @@ impl Configuration {
         self.key_file.set_string(groups::MOUNT, keys::ZWO_LAST_DEVICE, value);
     }
 
-    pub fn mount_simulator_sky_rotation_speed_pix_per_sec(&self) -> Option<u32> {
-        match self.key_file.integer(groups::MOUNT, keys::SIM_SKY_ROTATION_SPEED_PIX_PER_SEC) {
-            Ok(value) => if value >= 0 { Some(value as u32) } else { None },
-            Err(_) => None
+    pub fn stop_slew_on_focus_loss(&self) -> bool {
+        self.key_file.boolean(groups::MOUNT, keys::STOP_SLEW_ON_FOCUS_LOSS).unwrap_or(true)
+    }
+
+    pub fn set_stop_slew_on_focus_loss(&self, value: bool) {
+        self.key_file.set_boolean(groups::MOUNT, keys::STOP_SLEW_ON_FOCUS_LOSS, value);
+    }
+
+    /// Returns `None` if slews are not accelerated gradually.
+    pub fn slew_acceleration(&self) -> Option<f64> {
+        match self.key_file.double(groups::MOUNT, keys::SLEW_ACCELERATION) {
+            Ok(value) => if value > 0.0 {
+                Some(value)
+            } else {
+                if value < 0.0 {
+                    println!("WARNING: invalid configuration value for {}/{}: {}", groups::MOUNT, keys::SLEW_ACCELERATION, value);
+                }
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_slew_acceleration(&self, value: Option<f64>) {
+        self.key_file.set_double(groups::MOUNT, keys::SLEW_ACCELERATION, value.unwrap_or(0.0));
+    }
+
+    pub fn guide_primary_axis_only(&self) -> bool {
+        self.key_file.boolean(groups::MOUNT, keys::GUIDE_PRIMARY_AXIS_ONLY).unwrap_or(false)
+    }
+
+    pub fn set_guide_primary_axis_only(&self, value: bool) {
+        self.key_file.set_boolean(groups::MOUNT, keys::GUIDE_PRIMARY_AXIS_ONLY, value);
+    }
+
+    /// Returns duration of the backlash take-up pulse issued when guiding reverses direction on `axis`
+    /// (zero if compensation is disabled).
+    pub fn guide_backlash_compensation(&self, axis: mount::Axis) -> std::time::Duration {
+        let key = match axis {
+            mount::Axis::Primary => keys::GUIDE_BACKLASH_PRIMARY_MS,
+            mount::Axis::Secondary => keys::GUIDE_BACKLASH_SECONDARY_MS
+        };
+
+        match self.key_file.integer(groups::MOUNT, key) {
+            Ok(value) => if value >= 0 {
+                std::time::Duration::from_millis(value as u64)
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::MOUNT, key, value);
+                std::time::Duration::ZERO
+            },
+
+            _ => std::time::Duration::ZERO
+        }
+    }
+
+    pub fn set_guide_backlash_compensation(&self, axis: mount::Axis, value: std::time::Duration) {
+        let key = match axis {
+            mount::Axis::Primary => keys::GUIDE_BACKLASH_PRIMARY_MS,
+            mount::Axis::Secondary => keys::GUIDE_BACKLASH_SECONDARY_MS
+        };
+        self.key_file.set_integer(groups::MOUNT, key, value.as_millis() as i32);
+    }
+
+    /// Returns the mount simulator parameters; missing or invalid values are replaced with defaults.
+    pub fn mount_simulator_params(&self) -> MountSimulatorParams {
+        let default = MountSimulatorParams::default();
+
+        MountSimulatorParams{
+            sky_rotation_dir_in_img_space: self.read_vec2(groups::MOUNT, keys::SIM_SKY_ROTATION_DIR_IN_IMG_SPACE)
+                .unwrap_or(default.sky_rotation_dir_in_img_space),
+
+            primary_axis_slew_dir_in_img_space: self.read_vec2(groups::MOUNT, keys::SIM_PRIMARY_AXIS_SLEW_DIR_IN_IMG_SPACE)
+                .unwrap_or(default.primary_axis_slew_dir_in_img_space),
+
+            sky_rotation_speed_pix_per_sec: match self.key_file.integer(groups::MOUNT, keys::SIM_SKY_ROTATION_SPEED_PIX_PER_SEC) {
+                Ok(value) if value >= 0 => value as u32,
+                _ => default.sky_rotation_speed_pix_per_sec
+            }
+        }
+    }
+
+    pub fn set_mount_simulator_params(&self, params: &MountSimulatorParams) {
+        self.store_vec2(groups::MOUNT, keys::SIM_SKY_ROTATION_DIR_IN_IMG_SPACE, params.sky_rotation_dir_in_img_space);
+        self.store_vec2(groups::MOUNT, keys::SIM_PRIMARY_AXIS_SLEW_DIR_IN_IMG_SPACE, params.primary_axis_slew_dir_in_img_space);
+        self.key_file.set_integer(
+            groups::MOUNT,
+            keys::SIM_SKY_ROTATION_SPEED_PIX_PER_SEC,
+            params.sky_rotation_speed_pix_per_sec as i32
+        );
+    }
+
+    pub fn camera_default_video_mode(&self, camera_name: &str) -> Option<String> {
+        self.key_file.string(&camera_group(camera_name), keys::CAMERA_DEFAULT_VIDEO_MODE).ok().map(|s| s.to_string())
+    }
+
+    pub fn set_camera_default_video_mode(&self, camera_name: &str, value: Option<&str>) {
+        match value {
+            Some(value) => self.key_file.set_string(&camera_group(camera_name), keys::CAMERA_DEFAULT_VIDEO_MODE, value),
+            None => { let _ = self.key_file.remove_key(&camera_group(camera_name), keys::CAMERA_DEFAULT_VIDEO_MODE); }
+        }
+    }
+
+    pub fn camera_default_roi(&self, camera_name: &str) -> Option<gtk::Rectangle> {
+        self.read_rect(&camera_group(camera_name), keys::CAMERA_DEFAULT_ROI)
+    }
+
+    pub fn set_camera_default_roi(&self, camera_name: &str, value: Option<gtk::Rectangle>) {
+        match value {
+            Some(value) => self.store_rect(&camera_group(camera_name), keys::CAMERA_DEFAULT_ROI, value),
+            None => { let _ = self.key_file.remove_key(&camera_group(camera_name), keys::CAMERA_DEFAULT_ROI); }
         }
     }
 
@@ -231,6 +862,10 @@ impl Configuration {
         Some(gtk::Rectangle{ x: numbers[0], y: numbers[1], width: numbers[2], height: numbers[3] })
     }
 
+    fn store_vec2(&self, group: &str, key: &str, vec2: Vector2<i32>) {
+        self.key_file.set_string(group, key, &format!("{};{}", vec2.x, vec2.y));
+    }
+
     fn read_vec2(&self, group: &str, key: &str) -> Option<Vector2<i32>> {
         let vec2_str = match self.key_file.string(group, key) {
             Ok(s) => s,
@@ -281,6 +916,65 @@ impl Configuration {
             .unwrap_or("".to_string())
     }
 
+    /// Expects driver names from `camera::drivers::config_names`.
+    pub fn set_disabled_drivers(&self, drivers: &[&str]) {
+        self.key_file.set_string(groups::MAIN, keys::DISABLED_DRIVERS, &drivers.join(","));
+    }
+
+    pub fn centroid_area_size(&self) -> u32 {
+        match self.key_file.integer(groups::MAIN, keys::CENTROID_AREA_SIZE) {
+            Ok(value) => if value >= 8 {
+                value as u32
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::MAIN, keys::CENTROID_AREA_SIZE, value);
+                DEFAULT_CENTROID_AREA_SIZE as u32
+            },
+
+            _ => DEFAULT_CENTROID_AREA_SIZE as u32
+        }
+    }
+
+    pub fn set_centroid_area_size(&self, value: u32) {
+        self.key_file.set_integer(groups::MAIN, keys::CENTROID_AREA_SIZE, value as i32);
+    }
+
+    pub fn roi_around_target_margin(&self) -> u32 {
+        match self.key_file.integer(groups::MAIN, keys::ROI_AROUND_TARGET_MARGIN) {
+            Ok(value) => if value >= 0 {
+                value as u32
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::MAIN, keys::ROI_AROUND_TARGET_MARGIN, value);
+                DEFAULT_ROI_AROUND_TARGET_MARGIN as u32
+            },
+
+            _ => DEFAULT_ROI_AROUND_TARGET_MARGIN as u32
+        }
+    }
+
+    pub fn set_roi_around_target_margin(&self, value: u32) {
+        self.key_file.set_integer(groups::MAIN, keys::ROI_AROUND_TARGET_MARGIN, value as i32);
+    }
+
+    /// Returns `None` if lost star detection based on SNR is disabled.
+    pub fn min_guide_star_snr(&self) -> Option<f64> {
+        match self.key_file.double(groups::MAIN, keys::MIN_GUIDE_STAR_SNR) {
+            Ok(value) => if value > 0.0 {
+                Some(value)
+            } else {
+                if value < 0.0 {
+                    println!("WARNING: invalid configuration value for {}/{}: {}", groups::MAIN, keys::MIN_GUIDE_STAR_SNR, value);
+                }
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_min_guide_star_snr(&self, value: Option<f64>) {
+        self.key_file.set_double(groups::MAIN, keys::MIN_GUIDE_STAR_SNR, value.unwrap_or(0.0));
+    }
+
     pub fn preview_fps_limit(&self) -> Option<i32> {
         match self.key_file.integer(groups::MAIN, keys::PREVIEW_FPS_LIMIT) {
             Ok(value) => if value > 0 {
@@ -294,6 +988,66 @@ impl Configuration {
         }
     }
 
+    /// Returns `None` if failing to capture a frame is to be retried indefinitely.
+    pub fn max_frame_unavailable_retries(&self) -> Option<u32> {
+        match self.key_file.integer(groups::MAIN, keys::MAX_FRAME_UNAVAILABLE_RETRIES) {
+            Ok(value) => if value > 0 {
+                Some(value as u32)
+            } else if value == 0 {
+                None
+            } else {
+                println!(
+                    "WARNING: invalid configuration value for {}/{}: {}",
+                    groups::MAIN, keys::MAX_FRAME_UNAVAILABLE_RETRIES, value
+                );
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_max_frame_unavailable_retries(&self, value: Option<u32>) {
+        self.key_file.set_integer(groups::MAIN, keys::MAX_FRAME_UNAVAILABLE_RETRIES, value.unwrap_or(0) as i32);
+    }
+
+    /// Returns `None` if automatic configuration saving is disabled.
+    pub fn autosave_interval(&self) -> Option<std::time::Duration> {
+        match self.key_file.integer(groups::MAIN, keys::AUTOSAVE_INTERVAL) {
+            Ok(value) => if value > 0 {
+                Some(std::time::Duration::from_secs(value as u64))
+            } else if value == 0 {
+                None
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::MAIN, keys::AUTOSAVE_INTERVAL, value);
+                None
+            },
+
+            _ => Some(std::time::Duration::from_secs(DEFAULT_AUTOSAVE_INTERVAL_SECS as u64))
+        }
+    }
+
+    /// Returns `None` if automatic disconnection of idle devices is disabled.
+    pub fn idle_disconnect_timeout(&self) -> Option<std::time::Duration> {
+        match self.key_file.integer(groups::MAIN, keys::IDLE_DISCONNECT_TIMEOUT) {
+            Ok(value) => if value > 0 {
+                Some(std::time::Duration::from_secs(60 * value as u64))
+            } else if value == 0 {
+                None
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::MAIN, keys::IDLE_DISCONNECT_TIMEOUT, value);
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    pub fn set_idle_disconnect_timeout(&self, value: Option<std::time::Duration>) {
+        let minutes = value.map(|t| (t.as_secs() / 60).max(1)).unwrap_or(0);
+        self.key_file.set_integer(groups::MAIN, keys::IDLE_DISCONNECT_TIMEOUT, minutes as i32);
+    }
+
     pub fn simulator_video_file(&self) -> Option<std::path::PathBuf> {
         match self.key_file.string(groups::MAIN, keys::SIM_VIDEO_FILE).ok() {
             Some(s) => Some(std::path::PathBuf::from(s.as_str())),
@@ -301,6 +1055,44 @@ impl Configuration {
         }
     }
 
+    pub fn set_simulator_video_file(&self, value: &std::path::Path) {
+        self.key_file.set_string(groups::MAIN, keys::SIM_VIDEO_FILE, &value.to_string_lossy());
+    }
+
+    pub fn session_summary_file(&self) -> Option<std::path::PathBuf> {
+        self.key_file.string(groups::MAIN, keys::SESSION_SUMMARY_FILE).ok().map(|s| std::path::PathBuf::from(s.as_str()))
+    }
+
+    pub fn frame_feed_address(&self) -> String {
+        self.key_file.string(groups::MAIN, keys::FRAME_FEED_ADDRESS)
+            .map(|s| s.to_string())
+            .unwrap_or(DEFAULT_FRAME_FEED_ADDRESS.to_string())
+    }
+
+    pub fn set_frame_feed_address(&self, value: &str) {
+        self.key_file.set_string(groups::MAIN, keys::FRAME_FEED_ADDRESS, value);
+    }
+
+    pub fn mjpeg_server_address(&self) -> String {
+        self.key_file.string(groups::MAIN, keys::MJPEG_SERVER_ADDRESS)
+            .map(|s| s.to_string())
+            .unwrap_or(DEFAULT_MJPEG_SERVER_ADDRESS.to_string())
+    }
+
+    pub fn set_mjpeg_server_address(&self, value: &str) {
+        self.key_file.set_string(groups::MAIN, keys::MJPEG_SERVER_ADDRESS, value);
+    }
+
+    pub fn remote_control_address(&self) -> String {
+        self.key_file.string(groups::MAIN, keys::REMOTE_CONTROL_ADDRESS)
+            .map(|s| s.to_string())
+            .unwrap_or(DEFAULT_REMOTE_CONTROL_ADDRESS.to_string())
+    }
+
+    pub fn set_remote_control_address(&self, value: &str) {
+        self.key_file.set_string(groups::MAIN, keys::REMOTE_CONTROL_ADDRESS, value);
+    }
+
     pub fn focuscube3_last_serial_port(&self) -> Option<String> {
         self.key_file.string(groups::FOCUSER, keys::FOCUSCUBE3_LAST_SERIAL_PORT).ok().map(|s| s.to_string())
     }
@@ -325,6 +1117,179 @@ impl Configuration {
         self.key_file.set_string(groups::FOCUSER, keys::FOCUSCUBE3_LAST_NETWORK_PWD, value);
     }
 
+    /// Returns `None` if `device` does not use a serial connection.
+    pub fn serial_settings(&self, device: DeviceConnectionDiscriminants) -> Option<SerialSettings> {
+        let mut settings = device.default_serial_settings()?;
+
+        let key = format!("{}{}", device.config_name(), keys::SERIAL_BAUD_RATE_SUFFIX);
+        if let Ok(value) = self.key_file.integer(groups::SERIAL, &key) {
+            if value > 0 {
+                settings.baud_rate = value as u32;
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::SERIAL, key, value);
+            }
+        }
+
+        let key = format!("{}{}", device.config_name(), keys::SERIAL_TIMEOUT_MS_SUFFIX);
+        if let Ok(value) = self.key_file.integer(groups::SERIAL, &key) {
+            if value > 0 {
+                settings.timeout = std::time::Duration::from_millis(value as u64);
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::SERIAL, key, value);
+            }
+        }
+
+        Some(settings)
+    }
+
+    /// Returns sidereal rate multiples offered as slewing speeds for the specified mount type.
+    pub fn slewing_speeds(&self, mount: DeviceConnectionDiscriminants) -> Vec<f64> {
+        let key = format!("{}{}", mount.config_name(), keys::SLEWING_SPEEDS_SUFFIX);
+        match self.key_file.string(groups::MOUNT, &key) {
+            Ok(s) => {
+                let speeds: Result<Vec<f64>, _> = s.split(',').map(|item| item.trim().parse::<f64>()).collect();
+                match speeds {
+                    Ok(speeds) if !speeds.is_empty() && speeds.iter().all(|s| *s > 0.0) => speeds,
+                    _ => {
+                        println!("WARNING: invalid configuration value for {}/{}: {}", groups::MOUNT, key, s);
+                        DEFAULT_SLEWING_SPEEDS.to_vec()
+                    }
+                }
+            },
+
+            _ => DEFAULT_SLEWING_SPEEDS.to_vec()
+        }
+    }
+
+    pub fn set_slewing_speeds(&self, mount: DeviceConnectionDiscriminants, speeds: &[f64]) {
+        let key = format!("{}{}", mount.config_name(), keys::SLEWING_SPEEDS_SUFFIX);
+        let value: Vec<String> = speeds.iter().map(|s| s.to_string()).collect();
+        self.key_file.set_string(groups::MOUNT, &key, &value.join(","));
+    }
+
+    pub fn focuser_rel_move_steps(&self) -> Vec<i32> {
+        match self.key_file.string(groups::FOCUSER, keys::FOCUSER_REL_MOVE_STEPS) {
+            Ok(s) => {
+                let steps: Result<Vec<i32>, _> = s.split(',').map(|item| item.trim().parse::<i32>()).collect();
+                match steps {
+                    Ok(steps) if !steps.is_empty() && steps.iter().all(|s| *s > 0) => steps,
+                    _ => {
+                        println!(
+                            "WARNING: invalid configuration value for {}/{}: {}",
+                            groups::FOCUSER, keys::FOCUSER_REL_MOVE_STEPS, s
+                        );
+                        DEFAULT_FOCUSER_REL_MOVE_STEPS.to_vec()
+                    }
+                }
+            },
+
+            _ => DEFAULT_FOCUSER_REL_MOVE_STEPS.to_vec()
+        }
+    }
+
+    /// Returns speeds (multiples of normal speed) offered for the specified focuser type.
+    pub fn focuser_speeds(&self, focuser: DeviceConnectionDiscriminants) -> Vec<f64> {
+        let key = format!("{}{}", focuser.config_name(), keys::FOCUSER_SPEEDS_SUFFIX);
+        match self.key_file.string(groups::FOCUSER, &key) {
+            Ok(s) => {
+                let speeds: Result<Vec<f64>, _> = s.split(',').map(|item| item.trim().parse::<f64>()).collect();
+                match speeds {
+                    Ok(speeds) if !speeds.is_empty() && speeds.iter().all(|s| *s > 0.0) => speeds,
+                    _ => {
+                        println!("WARNING: invalid configuration value for {}/{}: {}", groups::FOCUSER, key, s);
+                        DEFAULT_FOCUSER_SPEEDS.to_vec()
+                    }
+                }
+            },
+
+            _ => DEFAULT_FOCUSER_SPEEDS.to_vec()
+        }
+    }
+
+    pub fn set_focuser_speeds(&self, focuser: DeviceConnectionDiscriminants, speeds: &[f64]) {
+        let key = format!("{}{}", focuser.config_name(), keys::FOCUSER_SPEEDS_SUFFIX);
+        let value: Vec<String> = speeds.iter().map(|s| s.to_string()).collect();
+        self.key_file.set_string(groups::FOCUSER, &key, &value.join(","));
+    }
+
+    pub fn focuser_goto_overshoot(&self) -> i32 {
+        self.key_file.integer(groups::FOCUSER, keys::FOCUSER_GOTO_OVERSHOOT).unwrap_or(0)
+    }
+
+    pub fn set_focuser_goto_overshoot(&self, value: i32) {
+        self.key_file.set_integer(groups::FOCUSER, keys::FOCUSER_GOTO_OVERSHOOT, value);
+    }
+
+    pub fn focuser_temp_compensation(&self) -> bool {
+        self.key_file.boolean(groups::FOCUSER, keys::FOCUSER_TEMP_COMP_ENABLED).unwrap_or(false)
+    }
+
+    pub fn set_focuser_temp_compensation(&self, value: bool) {
+        self.key_file.set_boolean(groups::FOCUSER, keys::FOCUSER_TEMP_COMP_ENABLED, value);
+    }
+
+    pub fn focuser_temp_compensation_delta(&self) -> f64 {
+        match self.key_file.double(groups::FOCUSER, keys::FOCUSER_TEMP_COMP_DELTA) {
+            Ok(value) => if value > 0.0 {
+                value
+            } else {
+                println!("WARNING: invalid configuration value for {}/{}: {}", groups::FOCUSER, keys::FOCUSER_TEMP_COMP_DELTA, value);
+                DEFAULT_FOCUSER_TEMP_COMP_DELTA
+            },
+
+            _ => DEFAULT_FOCUSER_TEMP_COMP_DELTA
+        }
+    }
+
+    pub fn set_focuser_temp_compensation_delta(&self, value: f64) {
+        self.key_file.set_double(groups::FOCUSER, keys::FOCUSER_TEMP_COMP_DELTA, value);
+    }
+
+    pub fn focuser_temp_compensation_steps_per_deg(&self) -> f64 {
+        self.key_file.double(groups::FOCUSER, keys::FOCUSER_TEMP_COMP_STEPS_PER_DEG).unwrap_or(0.0)
+    }
+
+    pub fn set_focuser_temp_compensation_steps_per_deg(&self, value: f64) {
+        self.key_file.set_double(groups::FOCUSER, keys::FOCUSER_TEMP_COMP_STEPS_PER_DEG, value);
+    }
+
+    pub fn focuser_last_position(&self, focuser: DeviceConnectionDiscriminants) -> Option<i32> {
+        let key = format!("{}{}", focuser.config_name(), keys::FOCUSER_LAST_POSITION_SUFFIX);
+        self.key_file.integer(groups::FOCUSER, &key).ok()
+    }
+
+    pub fn set_focuser_last_position(&self, focuser: DeviceConnectionDiscriminants, value: i32) {
+        let key = format!("{}{}", focuser.config_name(), keys::FOCUSER_LAST_POSITION_SUFFIX);
+        self.key_file.set_integer(groups::FOCUSER, &key, value);
+    }
+
+    /// Returns the focuser simulator parameters; missing or invalid values are replaced with defaults.
+    pub fn focuser_simulator_params(&self) -> FocuserSimulatorParams {
+        let default = FocuserSimulatorParams::default();
+
+        FocuserSimulatorParams{
+            unit_speed: match self.key_file.double(groups::FOCUSER, keys::FOCUSER_SIM_SPEED) {
+                Ok(value) if value > 0.0 => value,
+                _ => default.unit_speed
+            },
+
+            travel: match self.key_file.integer(groups::FOCUSER, keys::FOCUSER_SIM_TRAVEL) {
+                Ok(value) if value > 0 => value,
+                _ => default.travel
+            },
+
+            temperature_drift: self.key_file.double(groups::FOCUSER, keys::FOCUSER_SIM_TEMPERATURE_DRIFT)
+                .map(|value| value as f32)
+                .unwrap_or(default.temperature_drift)
+        }
+    }
+
+    pub fn set_focuser_simulator_params(&self, params: &FocuserSimulatorParams) {
+        self.key_file.set_double(groups::FOCUSER, keys::FOCUSER_SIM_SPEED, params.unit_speed);
+        self.key_file.set_integer(groups::FOCUSER, keys::FOCUSER_SIM_TRAVEL, params.travel);
+        self.key_file.set_double(groups::FOCUSER, keys::FOCUSER_SIM_TEMPERATURE_DRIFT, params.temperature_drift as f64);
+    }
+
     pub fn dreamfocuser_mini_last_serial_port(&self) -> Option<String> {
         self.key_file.string(groups::FOCUSER, keys::DREAMFOCUSER_MINI_LAST_SERIAL_PORT).ok().map(|s| s.to_string())
     }
@@ -342,8 +1307,78 @@ impl Configuration {
     }
 }
 
+/// Converts `key_file` to the current configuration version.
+fn migrate(key_file: &glib::KeyFile) {
+    let version = key_file.integer(groups::MAIN, keys::CONFIG_VERSION).unwrap_or(0);
+
+    if version > CONFIG_VERSION {
+        println!(
+            "WARNING: Configuration file version {} is newer than supported ({}); some settings may be ignored.",
+            version, CONFIG_VERSION
+        );
+        return;
+    }
+
+    for from_version in version.max(0)..CONFIG_VERSION {
+        log::info!("migrating configuration from version {} to {}", from_version, from_version + 1);
+        MIGRATIONS[from_version as usize](key_file);
+    }
+    key_file.set_integer(groups::MAIN, keys::CONFIG_VERSION, CONFIG_VERSION);
+}
+
+/// Loads and migrates the configuration file; if it cannot be loaded, returns an empty configuration
+/// of the current version.
+fn load_key_file(file_path: &Path) -> glib::KeyFile {
+    let key_file = glib::KeyFile::new();
+    if key_file.load_from_file(
+        file_path,
+        glib::KeyFileFlags::NONE
+    ).is_err() {
+        println!("WARNING: Failed to load configuration from {}.", file_path.to_string_lossy());
+        key_file.set_integer(groups::MAIN, keys::CONFIG_VERSION, CONFIG_VERSION);
+    } else {
+        migrate(&key_file);
+    }
+
+    key_file
+}
+
 fn config_file_path() -> PathBuf {
     Path::new(
         &dirs::config_dir().or(Some(Path::new("").to_path_buf())).unwrap()
     ).join("vidoxide.cfg")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_unversioned_config_migration_preserves_settings_and_sets_version() {
+        let key_file = glib::KeyFile::new();
+        key_file.set_integer(groups::MAIN, keys::PREVIEW_FPS_LIMIT, 30);
+
+        migrate(&key_file);
+
+        assert_eq!(CONFIG_VERSION, key_file.integer(groups::MAIN, keys::CONFIG_VERSION).unwrap());
+        assert_eq!(30, key_file.integer(groups::MAIN, keys::PREVIEW_FPS_LIMIT).unwrap());
+    }
+
+    #[test]
+    fn given_newer_config_version_is_left_unchanged() {
+        let key_file = glib::KeyFile::new();
+        key_file.set_integer(groups::MAIN, keys::CONFIG_VERSION, CONFIG_VERSION + 1);
+
+        migrate(&key_file);
+
+        assert_eq!(CONFIG_VERSION + 1, key_file.integer(groups::MAIN, keys::CONFIG_VERSION).unwrap());
+    }
+
+    #[test]
+    fn given_no_config_file_new_config_has_current_version() {
+        let path = std::env::temp_dir().join(format!("vidoxide_missing_config_{}.cfg", std::process::id()));
+        let key_file = load_key_file(&path);
+
+        assert_eq!(CONFIG_VERSION, key_file.integer(groups::MAIN, keys::CONFIG_VERSION).unwrap());
+    }
+}
@@ -11,6 +11,7 @@
 //!
 
 use crate::devices::{
+    SerialSettings,
     focuser::dream_focuser_mini::{CmdExecutor, Command, Position, Speed, State, to_raw_speed},
     utils
 };
@@ -23,13 +24,13 @@ pub struct UsbExecutor {
 }
 
 impl UsbExecutor {
-    pub fn new(device: &str) -> Result<Box<dyn CmdExecutor>, Box<dyn Error>> {
-        let serial_port = serialport::new(device, 115200)
+    pub fn new(device: &str, serial_settings: SerialSettings) -> Result<Box<dyn CmdExecutor>, Box<dyn Error>> {
+        let serial_port = serialport::new(device, serial_settings.baud_rate)
             .data_bits(serialport::DataBits::Eight)
             .flow_control(serialport::FlowControl::None)
             .parity(serialport::Parity::None)
             .stop_bits(serialport::StopBits::One)
-            .timeout(std::time::Duration::from_millis(100))
+            .timeout(serial_settings.timeout)
             .open()?;
 
             Ok(Box::new(UsbExecutor{ serial_port }))
@@ -18,11 +18,12 @@ mod dfmini_usb;
 use dfmini_bluetooth::BluetoothExecutor;
 use dfmini_usb::UsbExecutor;
 
-use crate::devices::focuser::{Focuser, Position, PositionRange, Speed, SpeedRange, State};
+use crate::devices::{SerialSettings, focuser::{Focuser, Position, PositionRange, Speed, SpeedRange, State}};
 use std::error::Error;
 #[cfg(feature = "bluetooth")]
 use std::rc::Rc;
 
+#[derive(Clone)]
 pub enum Connection {
     USB { device: String },
     #[cfg(feature = "bluetooth")]
@@ -69,14 +70,19 @@ fn to_raw_speed(speed: Speed) -> i16 {
 
 impl DreamFocuserMini {
     #[must_use]
+    /// Parameters:
+    ///
+    /// * `serial_settings` - Used only for `Connection::USB`.
+    ///
     pub fn new(
         connection: Connection,
+        serial_settings: SerialSettings,
         #[cfg(feature = "bluetooth")]
         tokio_rt: Rc<tokio::runtime::Runtime>
     ) -> Result<DreamFocuserMini, Box<dyn Error>> {
         match connection {
                 Connection::USB{ ref device } => {
-                    Ok(DreamFocuserMini{ connection_str: device.into(), executor: UsbExecutor::new(device)? })
+                    Ok(DreamFocuserMini{ connection_str: device.into(), executor: UsbExecutor::new(device, serial_settings)? })
                 },
 
                 #[cfg(feature = "bluetooth")]
@@ -11,6 +11,7 @@
 //!
 
 use crate::devices::{
+    SerialSettings,
     focuser::{DegC, Focuser, Position, PositionRange, Speed, SpeedRange, State},
     utils,
     utils::{InvalidResponseTreatment, ResponseType}
@@ -29,6 +30,7 @@ impl RawSpeed {
     }
 }
 
+#[derive(Clone)]
 pub enum Connection {
     Serial{ device: String },
     TcpIp{ address: String, password: String }
@@ -64,15 +66,19 @@ macro_rules! send_cmd {
 //TODO change response expectations to their full contents
 
 impl FocusCube3 {
-    pub fn new(connection: Connection) -> Result<FocusCube3, Box<dyn Error>> {
+    /// Parameters:
+    ///
+    /// * `serial_settings` - Used only for `Connection::Serial`.
+    ///
+    pub fn new(connection: Connection, serial_settings: SerialSettings) -> Result<FocusCube3, Box<dyn Error>> {
         let device = match connection {
             Connection::Serial { ref device } => {
-                Device::Serial(serialport::new(device, 115200)
+                Device::Serial(serialport::new(device, serial_settings.baud_rate)
                     .data_bits(serialport::DataBits::Eight)
                     .flow_control(serialport::FlowControl::None)
                     .parity(serialport::Parity::None)
                     .stop_bits(serialport::StopBits::One)
-                    .timeout(std::time::Duration::from_millis(100))
+                    .timeout(serial_settings.timeout)
                     .open()?)
             },
 
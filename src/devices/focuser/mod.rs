@@ -14,11 +14,12 @@ mod dream_focuser_mini;
 mod focuscube3;
 mod simulator;
 
-use crate::{ProgramData, devices::DeviceConnection};
+use crate::{ProgramData, devices::{DeviceConnection, DeviceConnectionDiscriminants}};
 use std::{cell::RefCell, error::Error, rc::Rc};
 
 pub type DFminiConnection = dream_focuser_mini::Connection;
 pub type FC3Connection = focuscube3::Connection;
+pub type FocuserSimulatorParams = simulator::SimulatorParams;
 
 #[derive(Copy, Clone, Debug)]
 pub struct Position(pub i32);
@@ -57,11 +58,27 @@ impl std::ops::Mul<f64> for Speed {
     }
 }
 
+#[derive(Copy, Clone)]
 pub struct PositionRange {
     pub min: Position,
     pub max: Position
 }
 
+impl PositionRange {
+    /// Returns false if the focuser's travel is unknown (drivers report the whole `i32` range then).
+    pub fn is_known(&self) -> bool {
+        !(self.min.0 == i32::MIN && self.max.0 == i32::MAX)
+    }
+
+    /// Returns position within travel (0.0 at `min`, 1.0 at `max`).
+    pub fn fraction(&self, pos: Position) -> f64 {
+        let length = self.max.0 as f64 - self.min.0 as f64;
+        if length <= 0.0 { return 0.0; }
+        ((pos.0 as f64 - self.min.0 as f64) / length).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Copy, Clone)]
 pub struct SpeedRange {
     pub min: Speed,
     pub max: Speed
@@ -109,9 +126,18 @@ impl FocuserWrapper {
 
     pub fn get_mut(&mut self) -> &mut Box<dyn Focuser> { &mut self.focuser }
 
+    /// Non-blocking; reaching target position can be queried via `Focuser::state`.
+    pub fn move_abs(&mut self, target: Position, speed: Speed) -> Result<(), Box<dyn Error>> {
+        let PositionRange{ min, max } = self.focuser.pos_range()?;
+        self.focuser.move_(Position(target.0.clamp(min.0, max.0)), speed)
+    }
+
     /// Non-blocking; reaching target position can be queried via `Focuser::state`.
     pub fn move_rel(&mut self, rel_pos: RelativePos, speed: Speed) -> Result<(), Box<dyn Error>> {
-        unimplemented!()
+        let current = self.focuser.state()?.pos;
+        let PositionRange{ min, max } = self.focuser.pos_range()?;
+        let target = Position(current.0.saturating_add((rel_pos.0).0).clamp(min.0, max.0));
+        self.focuser.move_(target, speed)
     }
 
     pub fn move_in_dir(&mut self, speed: Speed, dir: FocuserDir) -> Result<(), Box<dyn Error>> {
@@ -124,15 +150,19 @@ pub fn connect_to_focuser(
     connection: DeviceConnection,
     program_data_rc: &Rc<RefCell<ProgramData>>
 ) -> Result<FocuserWrapper, Box<dyn Error>> {
+    let serial_settings = program_data_rc.borrow().config.serial_settings(DeviceConnectionDiscriminants::from(&connection));
+
     match connection {
         DeviceConnection::FocusCube3{ connection } =>
-            Ok(FocuserWrapper::new(Box::new(focuscube3::FocusCube3::new(connection)?))),
+            Ok(FocuserWrapper::new(Box::new(focuscube3::FocusCube3::new(connection, serial_settings.unwrap())?))),
 
-        DeviceConnection::FocuserSimulator => Ok(FocuserWrapper::new(Box::new(simulator::Simulator::new()?))),
+        DeviceConnection::FocuserSimulator{ params } =>
+            Ok(FocuserWrapper::new(Box::new(simulator::Simulator::new(params)?))),
 
         DeviceConnection::DreamFocuserMini{ connection } =>
             Ok(FocuserWrapper::new(Box::new(dream_focuser_mini::DreamFocuserMini::new(
                 connection,
+                serial_settings.unwrap(),
                 #[cfg(feature = "bluetooth")]
                 Rc::clone(&program_data_rc.borrow().tokio_rt)
             )?))),
@@ -140,3 +170,24 @@ pub fn connect_to_focuser(
         _ => unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_near_end_of_travel_gives_fraction_near_one() {
+        let range = PositionRange{ min: Position(-10_000), max: Position(10_000) };
+
+        assert!(range.is_known());
+        assert_eq!(0.0, range.fraction(Position(-10_000)));
+        assert_eq!(0.5, range.fraction(Position(0)));
+        assert_eq!(0.95, range.fraction(Position(9_000)));
+        assert_eq!(1.0, range.fraction(Position(20_000)));
+    }
+
+    #[test]
+    fn full_i32_range_means_unknown_travel() {
+        assert!(!PositionRange{ min: Position(i32::MIN), max: Position(i32::MAX) }.is_known());
+    }
+}
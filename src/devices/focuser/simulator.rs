@@ -13,7 +13,34 @@
 use crate::devices::focuser::{DegC, Focuser, Position, PositionRange, Speed, SpeedRange, State};
 use std::error::Error;
 
-const UNIT_SPEED: f64 = 100.0; // change in position per second for speed = 1.0
+/// Temperature at connection time.
+const INITIAL_TEMPERATURE: f32 = 20.0;
+
+/// Parameters of the simulated focuser.
+#[derive(Copy, Clone, Debug)]
+pub struct SimulatorParams {
+    /// Change in position per second for speed = 1.0.
+    pub unit_speed: f64,
+    /// Length of travel in steps (centered on position 0).
+    pub travel: i32,
+    /// Change of temperature per hour (°C).
+    pub temperature_drift: f32
+}
+
+impl Default for SimulatorParams {
+    fn default() -> SimulatorParams {
+        SimulatorParams{
+            unit_speed: 100.0,
+            travel: 20_000,
+            temperature_drift: -1.0
+        }
+    }
+}
+
+/// Returns simulated temperature after `elapsed` time since connection.
+fn temperature_after(elapsed: std::time::Duration, drift_per_hour: f32) -> DegC {
+    DegC(INITIAL_TEMPERATURE + drift_per_hour * (elapsed.as_secs_f64() / 3600.0) as f32)
+}
 
 #[derive(Debug)]
 struct MoveRequest {
@@ -24,32 +51,42 @@ struct MoveRequest {
 }
 
 pub struct Simulator {
+    params: SimulatorParams,
+    t_connected: std::time::Instant,
     position: Position,
     move_request: Option<MoveRequest>
 }
 
 impl Simulator {
-    pub fn new() -> Result<Simulator, Box<dyn Error>> {
+    pub fn new(params: SimulatorParams) -> Result<Simulator, Box<dyn Error>> {
+        if params.unit_speed <= 0.0 { return Err("speed must be positive".into()); }
+        if params.travel <= 0 { return Err("travel must be positive".into()); }
+
         Ok(Simulator{
+            params,
+            t_connected: std::time::Instant::now(),
             position: Position(0),
             move_request: None
         })
     }
 
     fn update_state(&mut self, new_request: Option<MoveRequest>) {
+        let mut target_reached = false;
         if let Some(prev_req) = &self.move_request {
-            let raw_speed = UNIT_SPEED * prev_req.speed.0;
+            let raw_speed = self.params.unit_speed * prev_req.speed.0;
             let time_to_target =
                 std::time::Duration::from_secs_f64((prev_req.target.0 - prev_req.origin.0).abs() as f64 / raw_speed);
             let dt = prev_req.t.elapsed();
             if dt > time_to_target {
                 self.position = prev_req.target;
+                target_reached = true;
             } else {
                 let sign = (prev_req.target.0 - prev_req.origin.0).signum() as f64;
                 self.position = Position((prev_req.origin.0 as f64 + dt.as_secs_f64() * sign * raw_speed) as i32);
             }
         }
 
+        if target_reached { self.move_request = None; }
         if new_request.is_some() { self.move_request = new_request }
     }
 }
@@ -63,6 +100,7 @@ impl Focuser for Simulator {
         if speed.is_zero() {
             self.stop().unwrap();
         } else {
+            self.update_state(None);
             self.update_state(Some(MoveRequest{ t: std::time::Instant::now(), origin: self.position, target, speed }));
         }
 
@@ -70,7 +108,8 @@ impl Focuser for Simulator {
     }
 
     fn pos_range(&mut self) -> Result<PositionRange, Box<dyn Error>> {
-        Ok(PositionRange{ min: Position(-10_000), max: Position(10_000) })
+        let half_travel = self.params.travel / 2;
+        Ok(PositionRange{ min: Position(-half_travel), max: Position(self.params.travel - half_travel) })
     }
 
     fn speed_range(&mut self) -> Result<SpeedRange, Box<dyn Error>> {
@@ -82,7 +121,7 @@ impl Focuser for Simulator {
         Ok(State{
             pos: self.position,
             moving: Some(self.move_request.is_some()),
-            temperature: Some(DegC(20.0))
+            temperature: Some(temperature_after(self.t_connected.elapsed(), self.params.temperature_drift))
         })
     }
 
@@ -96,3 +135,15 @@ impl Focuser for Simulator {
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperature_drifts_linearly() {
+        assert_eq!(INITIAL_TEMPERATURE, temperature_after(std::time::Duration::ZERO, -1.0).0);
+        assert_eq!(INITIAL_TEMPERATURE - 1.5, temperature_after(std::time::Duration::from_secs(5400), -1.0).0);
+        assert_eq!(INITIAL_TEMPERATURE + 2.0, temperature_after(std::time::Duration::from_secs(3600), 2.0).0);
+    }
+}
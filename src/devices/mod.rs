@@ -17,20 +17,26 @@ pub mod focuser;
 use strum_macros as sm;
 use strum::EnumIter;
 
-#[derive(sm::EnumDiscriminants)]
+#[derive(Clone, sm::EnumDiscriminants)]
 #[strum_discriminants(derive(EnumIter))]
 pub enum DeviceConnection {
-    MountSimulator,
+    MountSimulator{ params: crate::MountSimulatorParams },
     SkyWatcherMountSerial{ device: String },
     IoptronMountSerial{ device: String },
     #[cfg(feature = "mount_ascom")]
     AscomMount{ prog_id: String },
     ZWOMountSerial{ device: String },
-    FocuserSimulator,
+    FocuserSimulator{ params: focuser::FocuserSimulatorParams },
     DreamFocuserMini{ connection: focuser::DFminiConnection },
     FocusCube3{ connection: focuser::FC3Connection },
 }
 
+/// Serial port parameters.
+#[derive(Copy, Clone, Debug)]
+pub struct SerialSettings {
+    pub baud_rate: u32,
+    pub timeout: std::time::Duration
+}
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum DeviceType {
@@ -52,4 +58,36 @@ impl DeviceConnectionDiscriminants {
             DeviceConnectionDiscriminants::FocuserSimulator => DeviceType::Focuser,
         }
     }
+
+    /// Returns `None` if the device does not use a serial connection.
+    pub fn default_serial_settings(&self) -> Option<SerialSettings> {
+        let settings = |baud_rate, timeout_ms| Some(SerialSettings{
+            baud_rate,
+            timeout: std::time::Duration::from_millis(timeout_ms)
+        });
+
+        match self {
+            DeviceConnectionDiscriminants::SkyWatcherMountSerial => settings(9600, 50),
+            DeviceConnectionDiscriminants::IoptronMountSerial => settings(115200, 50),
+            DeviceConnectionDiscriminants::ZWOMountSerial => settings(9600, 50),
+            DeviceConnectionDiscriminants::DreamFocuserMini => settings(115200, 100),
+            DeviceConnectionDiscriminants::FocusCube3 => settings(115200, 100),
+            _ => None
+        }
+    }
+
+    /// Name used for configuration keys.
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            DeviceConnectionDiscriminants::SkyWatcherMountSerial => "SkyWatcher",
+            DeviceConnectionDiscriminants::IoptronMountSerial => "iOptron",
+            #[cfg(feature = "mount_ascom")]
+            DeviceConnectionDiscriminants::AscomMount => "Ascom",
+            DeviceConnectionDiscriminants::MountSimulator => "MountSimulator",
+            DeviceConnectionDiscriminants::ZWOMountSerial => "ZWO",
+            DeviceConnectionDiscriminants::DreamFocuserMini => "DreamFocuserMini",
+            DeviceConnectionDiscriminants::FocusCube3 => "FocusCube3",
+            DeviceConnectionDiscriminants::FocuserSimulator => "FocuserSimulator",
+        }
+    }
 }
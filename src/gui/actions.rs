@@ -16,8 +16,19 @@ pub const PREFIX: &'static str = "vidoxide";
 // action names to be used for constructing `gio::SimpleAction`
 pub const DISCONNECT_CAMERA: &'static str = "disconnect camera";
 pub const TAKE_SNAPSHOT:     &'static str = "take snapshot";
+pub const COPY_TO_CLIPBOARD: &'static str = "copy to clipboard";
+pub const SAVE_HISTOGRAM:    &'static str = "save histogram";
 pub const SET_ROI:           &'static str = "set roi";
+pub const ROI_AROUND_TARGET: &'static str = "roi around target";
+pub const RAW_CAMERA_FEATURES: &'static str = "raw camera features";
+pub const CAMERA_DEFAULTS:   &'static str = "camera connection defaults";
+pub const AUTO_EXPOSURE:     &'static str = "auto exposure";
+pub const START_RECORDING:   &'static str = "start recording";
+pub const STOP_RECORDING:    &'static str = "stop recording";
+pub const STOP_MOUNT:        &'static str = "stop mount";
 pub const UNDOCK_PREVIEW:    &'static str = "undock preview area";
+pub const ENLARGE_CENTROID_AREA: &'static str = "enlarge centroid area";
+pub const SHRINK_CENTROID_AREA: &'static str = "shrink centroid area";
 
 /// Returns prefixed action name to be used with `ActionableExt::set_action_name`.
 pub fn prefixed(s: &str) -> String {
@@ -0,0 +1,307 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Exposure time and gain adjustment to reach the target histogram mean or peak.
+//!
+
+use crate::ProgramData;
+use crate::gui::{
+    camera_gui::{CommonControlWidgets, ControlWidgetBundle, NumberControlWidgets},
+    freezeable::Freezeable,
+    show_message
+};
+use crate::workers::histogram::Histogram;
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Max. allowed difference between histogram mean and target (as fraction of the full range).
+const TOLERANCE: f64 = 0.03;
+
+const MAX_ITERATIONS: usize = 20;
+
+/// Number of histograms to ignore after changing exposure time (they may come from frames captured
+/// with the previous exposure time).
+const HISTOGRAMS_TO_SKIP: usize = 2;
+
+/// Histogram statistic value above which the image is considered saturated (and exposure time is halved).
+const SATURATION_VALUE: f64 = 0.98;
+
+/// Max. factor by which exposure time is increased in a single iteration.
+const MAX_INCREASE: f64 = 10.0;
+
+/// Histogram statistic to be brought to the target value.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Statistic {
+    Mean,
+    /// Value not exceeded by 99.9% of pixels.
+    Peak
+}
+
+impl std::fmt::Display for Statistic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self { Statistic::Mean => "mean", Statistic::Peak => "peak" })
+    }
+}
+
+pub struct AutoExposure {
+    /// Target histogram statistic value as fraction of the full range.
+    target: f64,
+    statistic: Statistic,
+    /// If true, gain is adjusted after exposure time reaches its limit.
+    use_gain: bool,
+    /// Bounds of gain bisection; `Some` once gain adjustment has started.
+    gain_bounds: Option<(f64, f64)>,
+    iteration: usize,
+    histograms_to_skip: usize,
+    /// Time of the last exposure time (or gain) change; histograms of frames captured earlier are ignored.
+    changed_at: std::time::Instant,
+    on_target_reached: Box<dyn Fn(&Rc<RefCell<ProgramData>>)>
+}
+
+type SpinButton = Rc<RefCell<Freezeable<gtk::SpinButton>>>;
+
+/// Returns the spin button and the "auto" check box of the first number control satisfying `predicate`.
+fn number_control_widgets(
+    program_data: &ProgramData,
+    predicate: impl Fn(&CommonControlWidgets, &NumberControlWidgets) -> bool
+) -> Option<(SpinButton, Option<gtk::CheckButton>)> {
+    program_data.gui.as_ref().unwrap().control_widgets.values().find_map(|(common, bundle)| match bundle {
+        ControlWidgetBundle::NumberControl(num_ctrl) if predicate(common, num_ctrl) =>
+            Some((Rc::clone(&num_ctrl.spin_btn), common.auto.clone())),
+        _ => None
+    })
+}
+
+fn exposure_widgets(program_data: &ProgramData) -> Option<(SpinButton, Option<gtk::CheckButton>)> {
+    number_control_widgets(program_data, |_, num_ctrl| num_ctrl.is_exposure_time)
+}
+
+fn gain_widgets(program_data: &ProgramData) -> Option<(SpinButton, Option<gtk::CheckButton>)> {
+    number_control_widgets(
+        program_data,
+        |common, num_ctrl| !num_ctrl.is_exposure_time && common.name.to_lowercase().contains("gain")
+    )
+}
+
+fn disable_auto(auto: Option<gtk::CheckButton>) {
+    if let Some(auto) = auto {
+        if auto.is_active() { auto.set_active(false); }
+    }
+}
+
+/// Disables automatic exposure and sets exposure time to `value`; returns false if there is no exposure time control.
+fn set_exposure(value: impl Fn(&gtk::Adjustment) -> f64, program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    let widgets = exposure_widgets(&program_data_rc.borrow());
+    let (spin_btn, auto) = match widgets {
+        Some(widgets) => widgets,
+        None => {
+            show_message("Camera does not provide an exposure time control.", "Error", gtk::MessageType::Error, program_data_rc);
+            return false;
+        }
+    };
+
+    disable_auto(auto);
+
+    let new_value = value(&spin_btn.borrow().adjustment());
+    spin_btn.borrow().set_value(new_value);
+
+    true
+}
+
+/// Sets the shortest exposure time; returns false if there is no exposure time control.
+pub fn set_min_exposure(program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    set_exposure(|adj| adj.lower(), program_data_rc)
+}
+
+/// Starts adjusting exposure time (and gain, if `use_gain` is true) until histogram `statistic` reaches `target`
+/// (fraction of the full range).
+pub fn start(
+    target: f64,
+    statistic: Statistic,
+    use_gain: bool,
+    on_target_reached: Box<dyn Fn(&Rc<RefCell<ProgramData>>)>,
+    program_data_rc: &Rc<RefCell<ProgramData>>
+) {
+    if !set_exposure(|adj| adj.value(), program_data_rc) { return; }
+    if use_gain {
+        let widgets = gain_widgets(&program_data_rc.borrow());
+        if let Some((_, auto)) = widgets { disable_auto(auto); }
+    }
+
+    log::info!("adjusting exposure time to reach histogram {} of {:.0}%", statistic, target * 100.0);
+    program_data_rc.borrow_mut().gui.as_mut().unwrap().auto_exposure = Some(AutoExposure{
+        target,
+        statistic,
+        use_gain,
+        gain_bounds: None,
+        iteration: 0,
+        histograms_to_skip: HISTOGRAMS_TO_SKIP,
+        changed_at: std::time::Instant::now(),
+        on_target_reached
+    });
+}
+
+pub fn stop(program_data: &mut ProgramData) {
+    if program_data.gui.as_mut().unwrap().auto_exposure.take().is_some() {
+        log::info!("exposure time adjustment stopped");
+    }
+}
+
+/// Returns new exposure time, or `None` if `current` cannot be changed to get closer to the target.
+fn next_exposure(current: f64, min: f64, max: f64, value: f64, target: f64) -> Option<f64> {
+    let factor = if value >= SATURATION_VALUE {
+        0.5
+    } else {
+        (target / value.max(1.0 / 255.0)).min(MAX_INCREASE)
+    };
+
+    let new_value = (current * factor).max(min).min(max);
+    if new_value == current { None } else { Some(new_value) }
+}
+
+/// Returns new gain bisection bounds and new gain, or `None` if the bounds have shrunk to less than `step`.
+///
+/// Gain's unit is not known (it may be logarithmic), so bisection is used instead of proportional change.
+fn next_gain(current: f64, bounds: (f64, f64), step: f64, value: f64, target: f64) -> ((f64, f64), Option<f64>) {
+    let bounds = if value < target { (current, bounds.1) } else { (bounds.0, current) };
+    let new_value = (bounds.0 + bounds.1) / 2.0;
+
+    (bounds, if (new_value - current).abs() < step.max(std::f64::EPSILON) { None } else { Some(new_value) })
+}
+
+/// Called for each new histogram.
+pub fn on_histogram(histogram: &Histogram, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    enum Outcome { Continue(SpinButton, f64), TargetReached, Failed(String) }
+
+    let outcome;
+    {
+        let mut pd = program_data_rc.borrow_mut();
+        let exposure = exposure_widgets(&pd);
+        let gain = gain_widgets(&pd);
+        let auto_exposure = match pd.gui.as_mut().unwrap().auto_exposure.as_mut() {
+            Some(auto_exposure) => auto_exposure,
+            None => return
+        };
+
+        if histogram.captured().map_or(false, |captured| captured < auto_exposure.changed_at) {
+            return;
+        }
+
+        if auto_exposure.histograms_to_skip > 0 {
+            auto_exposure.histograms_to_skip -= 1;
+            return;
+        }
+
+        let value = match auto_exposure.statistic {
+            Statistic::Mean => histogram.mean(),
+            Statistic::Peak => histogram.peak()
+        };
+
+        outcome = match (value, exposure) {
+            (None, _) => Outcome::Failed("histogram is not available for the current pixel format".to_string()),
+
+            (_, None) => Outcome::Failed("exposure time control is not available".to_string()),
+
+            (Some(value), Some((exposure, _))) => if (value - auto_exposure.target).abs() <= TOLERANCE {
+                Outcome::TargetReached
+            } else if auto_exposure.iteration >= MAX_ITERATIONS {
+                Outcome::Failed(format!("no convergence after {} iterations", MAX_ITERATIONS))
+            } else {
+                let limit_reached = format!(
+                    "limit reached at histogram {} of {:.0}%", auto_exposure.statistic, value * 100.0
+                );
+
+                let new_exposure = if auto_exposure.gain_bounds.is_none() {
+                    let adj = exposure.borrow().adjustment();
+                    // avoid getting stuck at zero
+                    let current = adj.value().max(adj.step_increment()).max(adj.lower());
+                    next_exposure(current, adj.lower(), adj.upper(), value, auto_exposure.target)
+                } else {
+                    None
+                };
+
+                match (new_exposure, gain) {
+                    (Some(new_exposure), _) => Outcome::Continue(exposure, new_exposure),
+
+                    (None, Some((gain, _))) if auto_exposure.use_gain => {
+                        let adj = gain.borrow().adjustment();
+                        let bounds = auto_exposure.gain_bounds.unwrap_or((adj.lower(), adj.upper()));
+                        let (new_bounds, new_gain) = next_gain(
+                            adj.value(), bounds, adj.step_increment(), value, auto_exposure.target
+                        );
+                        auto_exposure.gain_bounds = Some(new_bounds);
+                        match new_gain {
+                            Some(new_gain) => Outcome::Continue(gain, new_gain),
+                            None => Outcome::Failed(format!("exposure time and gain {}", limit_reached))
+                        }
+                    },
+
+                    (None, _) => Outcome::Failed(format!("exposure time {}", limit_reached))
+                }
+            }
+        };
+
+        if let Outcome::Continue(..) = outcome {
+            auto_exposure.iteration += 1;
+            auto_exposure.histograms_to_skip = HISTOGRAMS_TO_SKIP;
+            auto_exposure.changed_at = std::time::Instant::now();
+        }
+    }
+
+    match outcome {
+        Outcome::Continue(spin_btn, new_value) => spin_btn.borrow().set_value(new_value),
+
+        Outcome::TargetReached => {
+            let auto_exposure = program_data_rc.borrow_mut().gui.as_mut().unwrap().auto_exposure.take().unwrap();
+            log::info!("exposure adjustment finished");
+            (auto_exposure.on_target_reached)(program_data_rc);
+        },
+
+        Outcome::Failed(message) => {
+            program_data_rc.borrow_mut().gui.as_mut().unwrap().auto_exposure = None;
+            log::warn!("exposure adjustment failed: {}", message);
+            show_message(
+                &format!("Failed to adjust exposure: {}.", message),
+                "Warning",
+                gtk::MessageType::Warning,
+                program_data_rc
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_underexposure_exposure_increases_proportionally() {
+        assert_eq!(Some(20.0), next_exposure(10.0, 1.0, 100.0, 0.25, 0.5));
+    }
+
+    #[test]
+    fn given_saturation_exposure_is_halved() {
+        assert_eq!(Some(5.0), next_exposure(10.0, 1.0, 100.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn given_exposure_at_limit_no_change_is_possible() {
+        assert_eq!(None, next_exposure(100.0, 1.0, 100.0, 0.1, 0.5));
+    }
+
+    #[test]
+    fn given_underexposure_gain_bisection_searches_upper_half() {
+        assert_eq!(((10.0, 20.0), Some(15.0)), next_gain(10.0, (0.0, 20.0), 1.0, 0.25, 0.5));
+    }
+
+    #[test]
+    fn given_narrow_gain_bounds_bisection_stops() {
+        assert_eq!(((10.0, 10.5), None), next_gain(10.0, (0.0, 10.5), 1.0, 0.25, 0.5));
+    }
+}
@@ -0,0 +1,66 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Auto exposure dialog.
+//!
+
+use crate::{ProgramData, gui::{auto_exposure, auto_exposure::Statistic, DialogDestroyer}};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Control padding in pixels.
+const PADDING: u32 = 10;
+
+/// Lets the user choose the target histogram value and starts exposure adjustment.
+pub fn show_auto_exposure_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Auto exposure"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("Start", gtk::ResponseType::Accept), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Accept);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some("Exposure time is adjusted using the histogram of the histogram area (or the whole image).")),
+        false,
+        true,
+        PADDING
+    );
+
+    let target = gtk::SpinButton::new(Some(&gtk::Adjustment::new(50.0, 1.0, 99.0, 1.0, 10.0, 0.0)), 1.0, 0);
+    target.set_activates_default(true);
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(&gtk::Label::new(Some("Target value (% of full range):")), false, false, PADDING);
+    hbox.pack_start(&target, false, false, PADDING);
+    dialog.content_area().pack_start(&hbox, false, true, PADDING);
+
+    let rb_mean = gtk::RadioButton::with_label("histogram mean");
+    let rb_peak = gtk::RadioButton::with_label_from_widget(&rb_mean, "histogram peak");
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(&rb_mean, false, false, PADDING);
+    hbox.pack_start(&rb_peak, false, false, PADDING);
+    dialog.content_area().pack_start(&hbox, false, true, PADDING);
+
+    let use_gain = gtk::CheckButton::with_label("adjust gain when exposure time reaches its limit");
+    dialog.content_area().pack_start(&use_gain, false, true, PADDING);
+
+    dialog.show_all();
+
+    if dialog.run() != gtk::ResponseType::Accept { return; }
+
+    auto_exposure::start(
+        target.value() / 100.0,
+        if rb_peak.is_active() { Statistic::Peak } else { Statistic::Mean },
+        use_gain.is_active(),
+        Box::new(|_: &Rc<RefCell<ProgramData>>| ()),
+        program_data_rc
+    );
+}
@@ -0,0 +1,147 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Camera connection defaults dialog.
+//!
+
+use crate::{ProgramData, camera::{BaseProperties, CameraControl}, gui::{camera_gui, show_message, DialogDestroyer}};
+use glib::clone;
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Control padding in pixels.
+const PADDING: u32 = 10;
+
+/// Lets the user choose the video mode and ROI to be set automatically after connecting to the current camera.
+pub fn show_camera_defaults_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let camera_name = program_data_rc.borrow().camera.as_ref().unwrap().name().to_string();
+
+    let controls = program_data_rc.borrow_mut().camera.as_mut().unwrap().enumerate_controls();
+    let video_modes: Vec<String> = match controls {
+        Ok(controls) => controls.iter().find_map(|control| match control {
+            CameraControl::List(list_ctrl) if list_ctrl.base().label == camera_gui::VIDEO_MODE_CONTROL_LABEL =>
+                Some(list_ctrl.items().clone()),
+            _ => None
+        }).unwrap_or_default(),
+        Err(_) => vec![]
+    };
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Connection defaults"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("OK", gtk::ResponseType::Accept), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Accept);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some(&format!("Settings applied after connecting to {}.", camera_name))),
+        false,
+        true,
+        PADDING
+    );
+
+    let (default_video_mode, default_roi) = {
+        let config = &program_data_rc.borrow().config;
+        (config.camera_default_video_mode(&camera_name), config.camera_default_roi(&camera_name))
+    };
+
+    let video_mode = gtk::ComboBoxText::new();
+    video_mode.append_text("(unchanged)");
+    video_mode.set_active(Some(0));
+    for (idx, mode) in video_modes.iter().enumerate() {
+        video_mode.append_text(mode);
+        if default_video_mode.as_ref() == Some(mode) { video_mode.set_active(Some(idx as u32 + 1)); }
+    }
+    video_mode.set_sensitive(!video_modes.is_empty());
+
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(&gtk::Label::new(Some("Video mode:")), false, false, PADDING);
+    hbox.pack_start(&video_mode, true, true, PADDING);
+    dialog.content_area().pack_start(&hbox, false, true, PADDING);
+
+    let set_roi = gtk::CheckButtonBuilder::new()
+        .label("set ROI")
+        .active(default_roi.is_some())
+        .build();
+    dialog.content_area().pack_start(&set_roi, false, true, PADDING);
+
+    let add_entry = |label: &str, value: i32| -> gtk::Entry {
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        let entry = gtk::EntryBuilder::new()
+            .input_purpose(gtk::InputPurpose::Digits)
+            .text(&format!("{}", value))
+            .activates_default(true)
+            .sensitive(default_roi.is_some())
+            .build();
+        hbox.pack_start(&gtk::Label::new(Some(label)), false, false, PADDING);
+        hbox.pack_start(&entry, true, true, PADDING);
+        dialog.content_area().pack_start(&hbox, false, true, PADDING);
+
+        entry
+    };
+
+    let roi = default_roi.unwrap_or(gtk::Rectangle{ x: 0, y: 0, width: 640, height: 480 });
+    let entry_x_offset = add_entry("X offset:", roi.x);
+    let entry_y_offset = add_entry("Y offset:", roi.y);
+    let entry_width = add_entry("width:", roi.width);
+    let entry_height = add_entry("height:", roi.height);
+
+    set_roi.connect_toggled(clone!(
+        @weak entry_x_offset, @weak entry_y_offset, @weak entry_width, @weak entry_height
+        => @default-panic, move |cb| {
+            for entry in [&entry_x_offset, &entry_y_offset, &entry_width, &entry_height].iter() {
+                entry.set_sensitive(cb.is_active());
+            }
+        }
+    ));
+
+    dialog.show_all();
+
+    loop {
+        if dialog.run() != gtk::ResponseType::Accept { return; }
+
+        let roi = if set_roi.is_active() {
+            let x_offset = entry_x_offset.text().as_str().parse::<u32>();
+            let y_offset = entry_y_offset.text().as_str().parse::<u32>();
+            let width = entry_width.text().as_str().parse::<u32>();
+            let height = entry_height.text().as_str().parse::<u32>();
+
+            match (x_offset, y_offset, width, height) {
+                (Ok(x), Ok(y), Ok(width), Ok(height)) if width > 0 && height > 0 => Some(gtk::Rectangle{
+                    x: x as i32, y: y as i32, width: width as i32, height: height as i32
+                }),
+
+                _ => {
+                    show_message(
+                        "Invalid ROI; expected non-negative integers and non-zero size.",
+                        "Error",
+                        gtk::MessageType::Error,
+                        program_data_rc
+                    );
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        let mode = match video_mode.active() {
+            Some(idx) if idx > 0 => Some(video_modes[idx as usize - 1].as_str()),
+            _ => None
+        };
+
+        let config = &program_data_rc.borrow().config;
+        config.set_camera_default_video_mode(&camera_name, mode);
+        config.set_camera_default_roi(&camera_name, roi);
+
+        return;
+    }
+}
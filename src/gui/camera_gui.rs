@@ -15,9 +15,8 @@ use crate::camera;
 use crate::camera::{BaseProperties, CameraControl, CameraControlId, CameraInfo, ControlAccessMode, Driver};
 use crate::gui::dec_intervals::DecIntervalsWidget;
 use crate::gui::freezeable::Freezeable;
-use crate::gui::{actions, disconnect_camera, on_capture_thread_message, show_message};
-use crate::workers::capture;
-use crate::workers::capture::MainToCaptureThreadMsg;
+use crate::gui::{actions, disconnect_camera, GuiData, initiate_capture_pause_action, on_capture_thread_message, show_message};
+use crate::workers::{capture, frame_feed};
 use enum_dispatch::enum_dispatch;
 use glib::clone;
 use gtk::prelude::*;
@@ -28,6 +27,9 @@ use std::sync::atomic::AtomicBool;
 /// Control padding in pixels.
 const PADDING: u32 = 10;
 
+/// Label of the video mode list control (as provided by IIDC and FlyCapture2 drivers).
+pub const VIDEO_MODE_CONTROL_LABEL: &str = "Video Mode";
+
 /// Delay after the last user modification of a control, after which all controls are refreshed.
 const ALL_CONTROLS_REFRESH_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
 
@@ -39,7 +41,10 @@ pub struct ListControlWidgets {
 pub struct NumberControlWidgets {
     pub slider: Rc<RefCell<Freezeable<gtk::Scale>>>,
     pub spin_btn: Rc<RefCell<Freezeable<gtk::SpinButton>>>,
-    pub intervals: Option<Rc<RefCell<DecIntervalsWidget>>>
+    pub intervals: Option<Rc<RefCell<DecIntervalsWidget>>>,
+    /// Shows the auto-scaled value of an exposure time control (if its unit is known).
+    pub time_label: Option<(gtk::Label, camera::TimeUnit)>,
+    pub is_exposure_time: bool
 }
 
 pub struct BooleanControlWidgets {
@@ -55,7 +60,9 @@ pub struct CommonControlWidgets {
     pub h_box: gtk::Box,
     pub auto: Option<gtk::CheckButton>,
     pub on_off: Option<gtk::CheckButton>,
-    pub access_mode: camera::ControlAccessMode
+    pub access_mode: camera::ControlAccessMode,
+    /// If true, changing the control pauses capture (so it must not be changed during recording).
+    pub requires_capture_pause: bool
 }
 
 #[enum_dispatch]
@@ -103,11 +110,13 @@ pub fn init_camera_menu(
     rescan.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
         disconnect_camera(&program_data_rc, true);
 
-        for cam_item in &program_data_rc.borrow().gui.as_ref().unwrap().camera_menu_items {
-            program_data_rc.borrow().gui.as_ref().unwrap().camera_menu.remove(&cam_item.0);
+        let camera_menu = program_data_rc.borrow().gui.as_ref().unwrap().camera_menu.clone();
+        // remove camera items (and the "no cameras" item, if present) preceding the first separator
+        for item in camera_menu.children() {
+            if item.is::<gtk::SeparatorMenuItem>() { break; }
+            camera_menu.remove(&item);
         }
 
-        let camera_menu = program_data_rc.borrow().gui.as_ref().unwrap().camera_menu.clone();
         program_data_rc.borrow_mut().gui.as_mut().unwrap().camera_menu_items =
             create_camera_menu_items(
                 &camera_menu,
@@ -118,13 +127,82 @@ pub fn init_camera_menu(
     let disconnect_item = gtk::MenuItem::with_label("Disconnect");
     disconnect_item.set_action_name(Some(&actions::prefixed(actions::DISCONNECT_CAMERA)));
 
+    let raw_features_item = gtk::MenuItem::with_label("Raw features...");
+    raw_features_item.set_action_name(Some(&actions::prefixed(actions::RAW_CAMERA_FEATURES)));
+
     menu.append(&rescan);
     menu.append(&disconnect_item);
+    let defaults_item = gtk::MenuItem::with_label("Connection defaults...");
+    defaults_item.set_action_name(Some(&actions::prefixed(actions::CAMERA_DEFAULTS)));
+
+    let auto_exposure_item = gtk::MenuItem::with_label("Auto exposure...");
+    auto_exposure_item.set_action_name(Some(&actions::prefixed(actions::AUTO_EXPOSURE)));
+
+    let drivers_item = gtk::MenuItem::with_label("Drivers...");
+    drivers_item.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        super::drivers_dialog::show_drivers_dialog(&program_data_rc);
+    }));
+
+    let frame_feed_item = gtk::CheckMenuItem::with_label("Live frame feed");
+    frame_feed_item.set_tooltip_text(Some(&format!(
+        "Serve captured frames over TCP to external programs (e.g., for live stacking) at {}",
+        program_data_rc.borrow().config.frame_feed_address()
+    )));
+    frame_feed_item.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        on_toggle_frame_feed(item, &program_data_rc);
+    }));
+
+    menu.append(&gtk::SeparatorMenuItem::new());
+    menu.append(&frame_feed_item);
+    menu.append(&auto_exposure_item);
+    menu.append(&defaults_item);
+    menu.append(&raw_features_item);
+    menu.append(&drivers_item);
 
     (menu, camera_menu_items)
 }
 
+/// Starts or stops the live frame feed (see `workers::frame_feed`).
+fn on_toggle_frame_feed(item: &gtk::CheckMenuItem, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let error = {
+        let mut pd = program_data_rc.borrow_mut();
+        if item.is_active() == pd.frame_feed.is_some() { return; }
+
+        let mut error = None;
+        if item.is_active() {
+            match frame_feed::FrameFeed::start(&pd.config.frame_feed_address()) {
+                Ok(feed) => pd.frame_feed = Some(feed),
+                Err(e) => error = Some(e)
+            }
+        } else {
+            pd.frame_feed = None;
+        }
+
+        let sender = pd.frame_feed.as_ref().map(|feed| feed.sender());
+        if let Some(capture_thread_data) = &pd.capture_thread_data {
+            let _ = capture_thread_data.sender.send(capture::MainToCaptureThreadMsg::SetFrameFeed(sender));
+        }
+
+        error
+    };
+
+    if let Some(e) = error {
+        item.set_active(false);
+        show_message(
+            &format!(
+                "Failed to start live frame feed at {}: {}.",
+                program_data_rc.borrow().config.frame_feed_address(), e
+            ),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
+    }
+}
+
 /// Adds camera items at the beginning of `camera_menu`.
+///
+/// If no cameras (other than the simulator) are found, adds an informative, inactive item.
 fn create_camera_menu_items(
     camera_menu: &gtk::Menu,
     program_data_rc: &Rc<RefCell<ProgramData>>
@@ -132,9 +210,21 @@ fn create_camera_menu_items(
     let mut camera_menu_items = vec![];
 
     let mut item_pos = 0;
+    let mut num_hw_cameras = 0;
     for driver in program_data_rc.borrow().drivers.iter() {
         let drv_name = driver.borrow().name();
-        for camera_info in driver.borrow_mut().enumerate_cameras().unwrap() {
+        let cameras = match driver.borrow_mut().enumerate_cameras() {
+            Ok(cameras) => cameras,
+            Err(e) => {
+                log::warn!("failed to enumerate {} cameras: {:?}", drv_name, e);
+                vec![]
+            }
+        };
+        if drv_name != camera::drivers::simulator::DRIVER_NAME {
+            num_hw_cameras += cameras.len();
+        }
+
+        for camera_info in cameras {
             let cam_menu_item = gtk::CheckMenuItem::with_label(&format!("[{}] {}", drv_name, camera_info.name()));
             cam_menu_item.show();
 
@@ -153,6 +243,14 @@ fn create_camera_menu_items(
         }
     }
 
+    if num_hw_cameras == 0 {
+        log::info!("no cameras found");
+        let no_cameras_item = gtk::MenuItem::with_label("No cameras found (connect a camera and choose \"Rescan\")");
+        no_cameras_item.set_sensitive(false);
+        no_cameras_item.show();
+        camera_menu.insert(&no_cameras_item, 0);
+    }
+
     camera_menu_items
 }
 
@@ -183,6 +281,8 @@ fn on_select_camera(
         let sim_data = program_data_rc.borrow().mount_simulator_data.clone();
         program_data_rc.borrow_mut().camera.as_mut().unwrap().set_mount_simulator_data(sim_data);
 
+        apply_connection_defaults(&mut program_data_rc.borrow_mut());
+
         let fc_result = program_data_rc.borrow_mut().camera.as_mut().unwrap().create_capturer();
         let frame_capturer = match fc_result {
             Ok(capturer) => capturer,
@@ -211,12 +311,29 @@ fn on_select_camera(
         ));
 
         let buffered_kib_clone = program_data.recording_thread_data.buffered_kib.clone();
+        let max_buffered_kib = program_data.recording_thread_data.max_buffered_kib.clone();
 
         let new_preview_wanted = std::sync::Arc::new(AtomicBool::new(true));
 
+        let session_stats = program_data.session_stats.clone();
+
+        let frame_feed = program_data.frame_feed.as_ref().map(|feed| feed.sender());
+
+        let max_frame_unavailable_retries = program_data.config.max_frame_unavailable_retries();
+
         program_data.capture_thread_data = Some(crate::CaptureThreadData {
             join_handle: Some(std::thread::spawn(clone!(@weak new_preview_wanted =>
-                move || capture::capture_thread(frame_capturer, sender_worker, receiver_worker, buffered_kib_clone, new_preview_wanted)
+                move || capture::capture_thread(
+                    frame_capturer,
+                    sender_worker,
+                    receiver_worker,
+                    buffered_kib_clone,
+                    max_buffered_kib,
+                    new_preview_wanted,
+                    session_stats,
+                    frame_feed,
+                    max_frame_unavailable_retries
+                )
             ))),
             sender: sender_main,
             new_preview_wanted
@@ -226,7 +343,12 @@ fn on_select_camera(
             let gui = program_data.gui.as_ref().unwrap();
             gui.rec_widgets.on_connect();
             gui.action_map.get(actions::TAKE_SNAPSHOT).unwrap().set_enabled(true);
+            gui.action_map.get(actions::COPY_TO_CLIPBOARD).unwrap().set_enabled(true);
             gui.action_map.get(actions::SET_ROI).unwrap().set_enabled(true);
+            gui.action_map.get(actions::ROI_AROUND_TARGET).unwrap().set_enabled(true);
+            gui.action_map.get(actions::RAW_CAMERA_FEATURES).unwrap().set_enabled(true);
+            gui.action_map.get(actions::CAMERA_DEFAULTS).unwrap().set_enabled(true);
+            gui.action_map.get(actions::AUTO_EXPOSURE).unwrap().set_enabled(true);
         }
 
         for (cam_item, activate_signal) in &program_data.gui.as_ref().unwrap().camera_menu_items {
@@ -248,6 +370,48 @@ fn on_select_camera(
     Ok(())
 }
 
+/// Returns the menu item of the currently connected camera.
+pub fn current_camera_menu_item(program_data: &ProgramData) -> Option<gtk::CheckMenuItem> {
+    program_data.gui.as_ref().unwrap().camera_menu_items.iter()
+        .find(|(item, _)| item.is_active())
+        .map(|(item, _)| item.clone())
+}
+
+/// Applies the user-configured initial video mode and ROI (if any) to the just-opened camera.
+fn apply_connection_defaults(program_data: &mut ProgramData) {
+    let camera = program_data.camera.as_mut().unwrap();
+    let camera_name = camera.name().to_string();
+
+    if let Some(video_mode) = program_data.config.camera_default_video_mode(&camera_name) {
+        let controls = match camera.enumerate_controls() {
+            Ok(controls) => controls,
+            Err(e) => { log::error!("failed to enumerate camera controls: {:?}", e); vec![] }
+        };
+
+        let mode_option = controls.iter().find_map(|control| match control {
+            CameraControl::List(list_ctrl) if list_ctrl.base().label == VIDEO_MODE_CONTROL_LABEL =>
+                list_ctrl.items().iter().position(|item| *item == video_mode).map(|idx| (list_ctrl.base().id, idx)),
+            _ => None
+        });
+
+        match mode_option {
+            Some((id, idx)) => match camera.set_list_control(id, idx) {
+                Ok(()) => log::info!("set default video mode: {}", video_mode),
+                Err(e) => log::error!("failed to set default video mode \"{}\": {:?}", video_mode, e)
+            },
+            None => log::warn!("default video mode \"{}\" is not available", video_mode)
+        }
+    }
+
+    if let Some(roi) = program_data.config.camera_default_roi(&camera_name) {
+        let _ = camera.unset_roi();
+        match camera.set_roi(roi.x as u32, roi.y as u32, roi.width as u32, roi.height as u32) {
+            Ok(()) => log::info!("set default ROI: {}x{} at ({}, {})", roi.width, roi.height, roi.x, roi.y),
+            Err(e) => log::error!("failed to set default ROI: {:?}", e)
+        }
+    }
+}
+
 pub fn remove_camera_controls(program_data: &mut ProgramData) {
     if let Some(gui) = program_data.gui.as_mut() {
         gui.control_widgets.clear();
@@ -263,9 +427,12 @@ fn init_camera_control_widgets(
     let controls_box = program_data_rc.borrow().gui.as_ref().unwrap().controls_box.clone();
 
     let controls = program_data_rc.borrow_mut().camera.as_mut().unwrap().enumerate_controls().unwrap();
+    update_exposure_fps_note(&controls, program_data_rc.borrow().gui.as_ref().unwrap());
     for control in controls  {
+        let is_favorite = program_data_rc.borrow().gui.as_ref().unwrap().favorite_controls.contains(&control.base().label);
         let h_box = create_control_widgets(
             &control,
+            is_favorite,
             program_data_rc,
             &mut program_data_rc.borrow_mut().gui.as_mut().unwrap().control_widgets
         );
@@ -273,10 +440,87 @@ fn init_camera_control_widgets(
     }
 
     controls_box.show_all();
+    update_controls_visibility(program_data_rc.borrow().gui.as_ref().unwrap());
+    let recording = program_data_rc.borrow().rec_job_active;
+    set_capture_pause_controls_enabled(program_data_rc.borrow().gui.as_ref().unwrap(), !recording);
+}
+
+/// Returns the frame rate achievable with exposure time `exposure_s` (in seconds), if lower than `fps`.
+fn exposure_limited_fps(exposure_s: f64, fps: f64) -> Option<f64> {
+    if exposure_s > 0.0 && fps > 0.0 && exposure_s > 1.0 / fps { Some(1.0 / exposure_s) } else { None }
+}
+
+/// Shows a note if the exposure time is longer than the frame interval (i.e., it will limit the frame rate).
+fn update_exposure_fps_note(controls: &[CameraControl], gui: &GuiData) {
+    let mut exposure_s = None;
+    let mut fps = None;
+    for control in controls {
+        if let CameraControl::Number(number_ctrl) = control {
+            if number_ctrl.is_exposure_time() {
+                exposure_s = number_ctrl.time_unit().map(|unit| unit.to_seconds(number_ctrl.value()));
+            } else if number_ctrl.is_frame_rate() {
+                fps = Some(number_ctrl.value());
+            }
+        }
+    }
+
+    match exposure_s.zip(fps).and_then(|(exposure_s, fps)| exposure_limited_fps(exposure_s, fps)) {
+        Some(limited_fps) => {
+            gui.exposure_fps_note.set_label(&format!(
+                "⚠ Exposure time is longer than the frame interval; frame rate will be limited to {:.1} fps.",
+                limited_fps
+            ));
+            gui.exposure_fps_note.show();
+        },
+        None => gui.exposure_fps_note.hide()
+    }
+}
+
+/// Enables or disables widgets of the camera controls which require capture pause.
+pub fn set_capture_pause_controls_enabled(gui: &GuiData, enabled: bool) {
+    for (common, _) in gui.control_widgets.values() {
+        if common.requires_capture_pause { common.h_box.set_sensitive(enabled); }
+    }
+}
+
+/// Shows or hides camera controls' widgets according to the "favorites only" setting and the filter text.
+pub fn update_controls_visibility(gui: &GuiData) {
+    let favorites_only = gui.favorite_controls_only.is_active();
+    let filter = gui.control_filter.text().to_lowercase();
+    for (common, _) in gui.control_widgets.values() {
+        let matches_filter = filter.is_empty() || common.name.to_lowercase().contains(filter.trim());
+        common.h_box.set_visible(
+            matches_filter && (!favorites_only || gui.favorite_controls.contains(&common.name))
+        );
+    }
+}
+
+fn favorite_label(is_favorite: bool) -> &'static str {
+    if is_favorite { "★" } else { "☆" }
+}
+
+fn on_toggle_favorite(btn: &gtk::ToggleButton, ctrl_label: &str, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    btn.set_label(favorite_label(btn.is_active()));
+
+    let mut pd = program_data_rc.borrow_mut();
+    let pd = &mut *pd;
+    let gui = pd.gui.as_mut().unwrap();
+    if btn.is_active() {
+        gui.favorite_controls.insert(ctrl_label.to_string());
+    } else {
+        gui.favorite_controls.remove(ctrl_label);
+    }
+
+    let mut labels: Vec<&str> = gui.favorite_controls.iter().map(|s| s.as_str()).collect();
+    labels.sort();
+    pd.config.set_favorite_camera_controls(&labels);
+
+    update_controls_visibility(gui);
 }
 
 pub fn create_control_widgets(
     control: &camera::CameraControl,
+    is_favorite: bool,
     program_data_rc: &Rc<RefCell<ProgramData>>,
     control_widgets: &mut std::collections::HashMap<
         camera::CameraControlId,
@@ -284,6 +528,19 @@ pub fn create_control_widgets(
     >
 ) -> gtk::Box {
     let h_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+
+    let btn_favorite = gtk::ToggleButtonBuilder::new()
+        .label(favorite_label(is_favorite))
+        .tooltip_text("Mark as favorite")
+        .relief(gtk::ReliefStyle::None)
+        .active(is_favorite)
+        .build();
+    let ctrl_label = control.base().label.clone();
+    btn_favorite.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |btn| {
+        on_toggle_favorite(btn, &ctrl_label, &program_data_rc);
+    }));
+    h_box.pack_start(&btn_favorite, false, false, 0);
+
     h_box.pack_start(&gtk::Label::new(Some(&control.base().label)), false, false, PADDING);
 
     let ctrl_id = control.base().id;
@@ -360,7 +617,8 @@ pub fn create_control_widgets(
                     h_box: h_box.clone(),
                     auto: cb_auto.clone(),
                     on_off: cb_on_off.clone(),
-                    access_mode: list_ctrl.base().access_mode
+                    access_mode: list_ctrl.base().access_mode,
+                    requires_capture_pause: list_ctrl.base().requires_capture_pause
                 }, widget_bundle)
             );
         },
@@ -380,7 +638,8 @@ pub fn create_control_widgets(
                     h_box: h_box.clone(),
                     auto: cb_auto.clone(),
                     on_off: cb_on_off.clone(),
-                    access_mode: number_ctrl.base().access_mode
+                    access_mode: number_ctrl.base().access_mode,
+                    requires_capture_pause: number_ctrl.base().requires_capture_pause
                 }, widget_bundle)
             );
         },
@@ -404,7 +663,8 @@ pub fn create_control_widgets(
                     h_box: h_box.clone(),
                     auto: cb_auto.clone(),
                     on_off: cb_on_off.clone(),
-                    access_mode: bool_ctrl.base().access_mode
+                    access_mode: bool_ctrl.base().access_mode,
+                    requires_capture_pause: bool_ctrl.base().requires_capture_pause
                 }, widget_bundle)
             );
         }
@@ -534,6 +794,16 @@ fn create_number_control_widgets(
 
     h_box.pack_start(&**spin_btn.borrow(), false, true, PADDING);
 
+    let time_label = match number_ctrl.time_unit() {
+        Some(time_unit) if number_ctrl.is_exposure_time() => {
+            let label = gtk::Label::new(None);
+            update_time_label(&label, time_unit, number_ctrl.value());
+            h_box.pack_start(&label, false, true, PADDING);
+            Some((label, time_unit))
+        },
+        _ => None
+    };
+
     // set up event handlers -----------------------------------------
 
     if let Some(intervals) = &intervals {
@@ -572,7 +842,7 @@ fn create_number_control_widgets(
     }
 
     let signal = slider.borrow().connect_value_changed(clone!(
-        @weak program_data_rc, @weak spin_btn, @strong intervals => @default-panic,
+        @weak program_data_rc, @weak spin_btn, @strong intervals, @strong time_label => @default-panic,
         move |slider| {
             spin_btn.borrow().freeze();
             spin_btn.borrow().set_value(slider.value());
@@ -580,13 +850,16 @@ fn create_number_control_widgets(
             if let Some(intervals) = &intervals {
                 intervals.borrow().set_value(slider.value());
             }
+            if let Some((label, time_unit)) = &time_label {
+                update_time_label(label, *time_unit, slider.value());
+            }
             on_camera_number_control_change(slider.value(), &program_data_rc, ctrl_id, requires_capture_pause);
         }
     ));
     slider.borrow_mut().set_signal(signal);
 
     let signal = spin_btn.borrow().connect_value_changed(clone!(
-        @weak program_data_rc, @weak slider, @strong intervals => @default-panic,
+        @weak program_data_rc, @weak slider, @strong intervals, @strong time_label => @default-panic,
         move |spin_btn| {
             slider.borrow().freeze();
             slider.borrow().set_value(spin_btn.value());
@@ -594,16 +867,41 @@ fn create_number_control_widgets(
             if let Some(intervals) = &intervals {
                 intervals.borrow().set_value(spin_btn.value());
             }
+            if let Some((label, time_unit)) = &time_label {
+                update_time_label(label, *time_unit, spin_btn.value());
+            }
             on_camera_number_control_change(spin_btn.value(), &program_data_rc, ctrl_id, requires_capture_pause);
         }
     ));
     spin_btn.borrow_mut().set_signal(signal);
 
     ControlWidgetBundle::NumberControl(
-        NumberControlWidgets{ slider, spin_btn, intervals }
+        NumberControlWidgets{ slider, spin_btn, intervals, time_label, is_exposure_time: number_ctrl.is_exposure_time() }
     )
 }
 
+pub fn update_time_label(label: &gtk::Label, time_unit: camera::TimeUnit, value: f64) {
+    label.set_text(&format_exposure_time(time_unit.to_seconds(value)));
+}
+
+/// Formats exposure time using an auto-scaled unit (µs, ms or s).
+pub fn format_exposure_time(seconds: f64) -> String {
+    let (value, unit) = if seconds >= 1.0 {
+        (seconds, "s")
+    } else if seconds >= 1.0e-3 {
+        (seconds * 1.0e3, "ms")
+    } else {
+        (seconds * 1.0e6, "µs")
+    };
+
+    let mut value_str = format!("{:.3}", value);
+    if value_str.contains('.') {
+        value_str = value_str.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+
+    format!("{} {}", value_str, unit)
+}
+
 fn fill_combo_for_list_control(
     ctrl: &camera::ListControl,
     combo: &gtk::ComboBoxText,
@@ -649,17 +947,14 @@ fn on_camera_list_control_change(
     requires_capture_pause: bool
 ) {
     if requires_capture_pause {
-        if program_data_rc.borrow_mut().capture_thread_data.as_mut().unwrap().sender.send(
-            MainToCaptureThreadMsg::Pause
-        ).is_err() {
-            crate::on_capture_thread_failure(program_data_rc);
+        let result = initiate_capture_pause_action(
+            OnCapturePauseAction::ControlChange(CameraControlChange{ id: ctrl_id, value: NewControlValue::ListOptionIndex(combo.active().unwrap() as usize) }),
+            &mut program_data_rc.borrow_mut()
+        );
+        if result.is_err() {
+            crate::on_capture_thread_failure(None, program_data_rc);
             return;
         }
-
-        program_data_rc.borrow_mut().on_capture_pause_action = Some(OnCapturePauseAction::ControlChange(CameraControlChange{
-            id: ctrl_id,
-            value: NewControlValue::ListOptionIndex(combo.active().unwrap() as usize)
-        }));
     } else {
         program_data_rc.borrow_mut().camera.as_mut().unwrap().set_list_control(
             ctrl_id,
@@ -677,17 +972,14 @@ fn on_camera_number_control_change(
     requires_capture_pause: bool
 ) {
     if requires_capture_pause {
-        if program_data_rc.borrow_mut().capture_thread_data.as_mut().unwrap().sender.send(
-            MainToCaptureThreadMsg::Pause
-        ).is_err() {
-            crate::on_capture_thread_failure(program_data_rc);
+        let result = initiate_capture_pause_action(
+            OnCapturePauseAction::ControlChange(CameraControlChange{ id: ctrl_id, value: NewControlValue::Numerical(value) }),
+            &mut program_data_rc.borrow_mut()
+        );
+        if result.is_err() {
+            crate::on_capture_thread_failure(None, program_data_rc);
             return;
         }
-
-        program_data_rc.borrow_mut().on_capture_pause_action = Some(OnCapturePauseAction::ControlChange(CameraControlChange{
-            id: ctrl_id,
-            value: NewControlValue::Numerical(value)
-        }));
     } else {
         let result = program_data_rc.borrow_mut().camera.as_mut().unwrap().set_number_control(ctrl_id, value);
         if let Err(error) = result {
@@ -710,17 +1002,14 @@ fn on_camera_boolean_control_change(
     requires_capture_pause: bool
 ) {
     if requires_capture_pause {
-        if program_data_rc.borrow_mut().capture_thread_data.as_mut().unwrap().sender.send(
-            MainToCaptureThreadMsg::Pause
-        ).is_err() {
-            crate::on_capture_thread_failure(program_data_rc);
+        let result = initiate_capture_pause_action(
+            OnCapturePauseAction::ControlChange(CameraControlChange{ id: ctrl_id, value: NewControlValue::Boolean(state_checkbox.is_active()) }),
+            &mut program_data_rc.borrow_mut()
+        );
+        if result.is_err() {
+            crate::on_capture_thread_failure(None, program_data_rc);
             return;
         }
-
-        program_data_rc.borrow_mut().on_capture_pause_action = Some(OnCapturePauseAction::ControlChange(CameraControlChange{
-            id: ctrl_id,
-            value: NewControlValue::Boolean(state_checkbox.is_active())
-        }));
     } else {
         program_data_rc.borrow_mut().camera.as_mut().unwrap().set_boolean_control(
             ctrl_id,
@@ -743,3 +1032,23 @@ fn refresh_all_controls(program_data_rc: &Rc<RefCell<ProgramData>>) {
     if program_data_rc.borrow().camera.is_none() { return; }
     init_camera_control_widgets(program_data_rc);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposure_time_formatting() {
+        assert_eq!("2 s", format_exposure_time(camera::TimeUnit::Microseconds.to_seconds(2_000_000.0)));
+        assert_eq!("1.5 ms", format_exposure_time(camera::TimeUnit::Milliseconds.to_seconds(1.5)));
+        assert_eq!("40 µs", format_exposure_time(40.0e-6));
+        assert_eq!("250 ms", format_exposure_time(0.25));
+    }
+
+    #[test]
+    fn given_exposure_longer_than_frame_interval_fps_is_limited() {
+        assert_eq!(Some(20.0), exposure_limited_fps(0.05, 30.0));
+        assert_eq!(None, exposure_limited_fps(0.02, 30.0));
+        assert_eq!(None, exposure_limited_fps(0.05, 0.0));
+    }
+}
@@ -39,6 +39,10 @@ pub struct DispersionDialog {
     drawing_area: gtk::DrawingArea,
     red_offset_label: gtk::Label,
     blue_offset_label: gtk::Label,
+    /// Shows the separation of red and blue channel centroids.
+    separation_label: gtk::Label,
+    /// If active, the dispersion indicator is also shown in the preview (see `info_overlay`).
+    show_in_preview: gtk::CheckButton,
     state: Rc<RefCell<State>>
 }
 
@@ -71,7 +75,8 @@ impl DispersionDialog {
             avg_blue_offset: Vector2::zero()
         }));
 
-        let (drawing_area, red_offset_label, blue_offset_label) = init_controls(&dialog, program_data_rc, &state);
+        let (drawing_area, red_offset_label, blue_offset_label, separation_label, show_in_preview) =
+            init_controls(&dialog, program_data_rc, &state);
         dialog.show_all();
         dialog.hide();
 
@@ -84,6 +89,8 @@ impl DispersionDialog {
             drawing_area,
             red_offset_label,
             blue_offset_label,
+            separation_label,
+            show_in_preview,
             state
         }
     }
@@ -92,11 +99,22 @@ impl DispersionDialog {
 
     pub fn is_visible(&self) -> bool { self.dialog.is_visible() }
 
+    /// Returns the averaged offsets of red and blue channel centroids relative to the green channel centroid
+    /// if the indicator is to be shown in the preview.
+    pub fn preview_indicator(&self) -> Option<(Vector2<f64>, Vector2<f64>)> {
+        if self.show_in_preview.is_active() {
+            let state = self.state.borrow();
+            Some((state.avg_red_offset, state.avg_blue_offset))
+        } else {
+            None
+        }
+    }
+
     pub fn update(&mut self, image: &ga_image::ImageView) {
-        if !self.dialog.is_visible() { return; }
+        if !self.dialog.is_visible() && !self.show_in_preview.is_active() { return; }
 
         let pix_fmt = image.pixel_format();
-        if !(pix_fmt.num_channels() == 3 /*TODO: explicitly check for RGB instead*/ || pix_fmt.is_cfa()) {
+        if !(pix_fmt == ga_image::PixelFormat::RGB8 || pix_fmt == ga_image::PixelFormat::RGB16 || pix_fmt.is_cfa()) {
             let mut state = self.state.borrow_mut();
             self.num_averaged = 0;
             self.red_offset = Vector2::zero();
@@ -117,6 +135,10 @@ impl DispersionDialog {
             state.avg_blue_offset = self.blue_offset / n;
             self.red_offset_label.set_label(&format!("R: {:.1} px", state.avg_red_offset.magnitude()));
             self.blue_offset_label.set_label(&format!("B: {:.1} px", state.avg_blue_offset.magnitude()));
+            let separation = state.avg_red_offset - state.avg_blue_offset;
+            self.separation_label.set_label(
+                &format!("R-B: {:.2} px (vertical: {:.2} px)", separation.magnitude(), separation.y.abs())
+            );
             self.red_offset = Vector2::zero();
             self.blue_offset = Vector2::zero();
             self.drawing_area.queue_draw();
@@ -129,7 +151,7 @@ fn init_controls(
     dialog: &gtk::Dialog,
     program_data_rc: &Rc<RefCell<ProgramData>>,
     state: &Rc<RefCell<State>>
-) -> (gtk::DrawingArea, gtk::Label, gtk::Label) {
+) -> (gtk::DrawingArea, gtk::Label, gtk::Label, gtk::Label, gtk::CheckButton) {
     //TODO: force draw area background to black (?) via CSS provider
 
     // control padding in pixels
@@ -163,11 +185,26 @@ fn init_controls(
     blue_offset.set_halign(gtk::Align::Start);
     vbox.pack_start(&blue_offset, false, false, PADDING);
 
+    let separation = gtk::Label::new(Some("R-B: 0.00 px (vertical: 0.00 px)"));
+    separation.set_halign(gtk::Align::Start);
+    separation.set_tooltip_text(Some("Color fringing to be minimized when adjusting an atmospheric dispersion corrector"));
+    vbox.pack_start(&separation, false, false, PADDING);
+
+    let show_in_preview = gtk::CheckButton::with_label("Show in preview");
+    show_in_preview.set_tooltip_text(Some(
+        "Show the channel offsets (magnified) and their separation at the centroid tracking area \
+        (or the image center) in the preview; stays active when this dialog is closed"
+    ));
+    show_in_preview.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |_| {
+        program_data_rc.borrow().gui.as_ref().unwrap().preview_area.refresh();
+    }));
+    vbox.pack_start(&show_in_preview, false, false, PADDING);
+
     vbox.pack_start(&drawing_area, true, true, PADDING);
 
     dialog.content_area().pack_start(&vbox, true, true, PADDING);
 
-    (drawing_area, red_offset, blue_offset)
+    (drawing_area, red_offset, blue_offset, separation, show_in_preview)
 }
 
 //TODO: draw arrow heads
@@ -210,45 +247,57 @@ fn draw(ctx: &cairo::Context, widget_size: (i32, i32), state: &Rc<RefCell<State>
 
 // TODO: move it to ga_image?
 fn get_rgb_centroids(image: &ga_image::ImageView) -> [Point2<f64>; 3] {
-    let pix_fmt = image.pixel_format();
-    if !(pix_fmt.is_cfa() && pix_fmt.bytes_per_channel() == 1) {
-        return [Point2::origin(), Point2::origin(), Point2::origin()];
-
-        //TODO: implement it
+    match image.pixel_format().bytes_per_channel() {
+        1 => rgb_centroids::<u8>(image),
+        2 => rgb_centroids::<u16>(image),
+        _ => [Point2::origin(), Point2::origin(), Point2::origin()]
     }
+}
+
+/// Returns centroids of R, G, B channels of a raw color or RGB image.
+fn rgb_centroids<T: 'static + Copy + Default + Into<f64>>(image: &ga_image::ImageView) -> [Point2<f64>; 3] {
+    let pix_fmt = image.pixel_format();
 
     // values below are for (R, G, B) channels
     let mut m00 = [0.0; 3]; // image moment 00, i.e. sum of pixels' brightness
     let mut m10 = [0.0; 3]; // image moment 10
     let mut m01 = [0.0; 3]; // image moment 01
 
-    let r_col_ofs = pix_fmt.cfa_pattern().red_col_ofs();
-    let b_col_ofs = (r_col_ofs + 1) % 2;
-    let r_row_ofs = pix_fmt.cfa_pattern().red_row_ofs();
+    let mut add = |channel: usize, x: usize, y: u32, value: f64| {
+        m00[channel] += value;
+        m10[channel] += x as f64 * value;
+        m01[channel] += y as f64 * value;
+    };
 
-    for y in 0..image.height() {
-        let line = image.line::<u8>(y);
+    if pix_fmt.is_cfa() {
+        let r_col_ofs = pix_fmt.cfa_pattern().red_col_ofs();
+        let b_col_ofs = (r_col_ofs + 1) % 2;
+        let r_row_ofs = pix_fmt.cfa_pattern().red_row_ofs();
 
-        // non-green channel being calculated for the current row
-        let rb_calc_channel = if y % 2 == r_row_ofs as u32 { RED } else { BLUE };
+        for y in 0..image.height() {
+            let line = image.line::<T>(y);
 
-        let rb_col_ofs = if rb_calc_channel == RED { r_col_ofs } else { b_col_ofs };
+            // non-green channel being calculated for the current row
+            let rb_calc_channel = if y % 2 == r_row_ofs as u32 { RED } else { BLUE };
 
-        for x in (0..image.width() - 1).step_by(2) {
-            let rb_x = x as usize + rb_col_ofs;
-            let g_x = x as usize + (rb_col_ofs + 1) % 2;
+            let rb_col_ofs = if rb_calc_channel == RED { r_col_ofs } else { b_col_ofs };
 
-            let rb_value = line[rb_x] as f64;
-            let g_value = line[g_x] as f64;
+            for x in (0..image.width() - 1).step_by(2) {
+                let rb_x = x as usize + rb_col_ofs;
+                let g_x = x as usize + (rb_col_ofs + 1) % 2;
 
-            m00[rb_calc_channel] += rb_value;
-            m00[GREEN] += g_value;
-
-            m10[rb_calc_channel] += rb_x as f64 * rb_value;
-            m10[GREEN] += rb_x as f64 * g_value;
-
-            m01[rb_calc_channel] += y as f64 * rb_value;
-            m01[GREEN] += y as f64 * g_value;
+                add(rb_calc_channel, rb_x, y, line[rb_x].into());
+                add(GREEN, g_x, y, line[g_x].into());
+            }
+        }
+    } else {
+        for y in 0..image.height() {
+            let line = image.line::<T>(y);
+            for x in 0..image.width() as usize {
+                for channel in [RED, GREEN, BLUE] {
+                    add(channel, x, y, line[3 * x + channel].into());
+                }
+            }
         }
     }
 
@@ -264,3 +313,38 @@ fn get_rgb_centroids(image: &ga_image::ImageView) -> [Point2<f64>; 3] {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ga_image::{Image, PixelFormat};
+
+    /// Returns an RGB image containing a 2x2 square in each channel, with the red one shifted by `red_shift`.
+    fn rgb_star(red_shift: Vector2<u32>) -> Image {
+        let mut image = Image::new(16, 16, None, PixelFormat::RGB16, None, true);
+        for y in 6..8 {
+            for x in 6..8 {
+                image.line_mut::<u16>(y)[3 * x as usize + GREEN] = 1000;
+                image.line_mut::<u16>(y)[3 * x as usize + BLUE] = 1000;
+                image.line_mut::<u16>(y + red_shift.y)[3 * (x + red_shift.x) as usize + RED] = 1000;
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn channel_offset_is_detected_in_rgb_image() {
+        let centroids = get_rgb_centroids(&rgb_star(Vector2{ x: 0, y: 3 }).view());
+        assert!((centroids[RED] - centroids[GREEN] - Vector2{ x: 0.0, y: 3.0 }).magnitude() < 1.0e-9);
+        assert!((centroids[BLUE] - centroids[GREEN]).magnitude() < 1.0e-9);
+    }
+
+    #[test]
+    fn raw_color_green_centroid_is_at_green_pixels() {
+        // RGGB pattern; only the green pixel at (1, 0) is lit
+        let mut image = Image::new(2, 2, None, PixelFormat::CfaRGGB8, None, true);
+        image.line_mut::<u8>(0)[1] = 100;
+        let centroids = get_rgb_centroids(&image.view());
+        assert!((centroids[GREEN] - Point2{ x: 1.0, y: 0.0 }).magnitude() < 1.0e-9);
+    }
+}
@@ -0,0 +1,103 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Camera drivers dialog.
+//!
+
+use crate::{camera::drivers, gui::{checked_listbox::CheckedListBox, DialogDestroyer, show_message}, ProgramData};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Control padding in pixels.
+const PADDING: u32 = 10;
+
+/// Lets the user enable/disable camera drivers (changes take effect after program restart) and choose the camera
+/// simulator's user-specified file.
+pub fn show_drivers_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Camera drivers"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("OK", gtk::ResponseType::Ok), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Ok);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    let disabled_drivers = program_data_rc.borrow().config.disabled_drivers();
+    let disabled_drivers: Vec<&str> = disabled_drivers.split(',').collect();
+
+    let available_drivers = drivers::available_drivers();
+    let driver_list = CheckedListBox::new();
+    for (idx, (config_name, description)) in available_drivers.iter().enumerate() {
+        driver_list.add_item(idx as u64, !disabled_drivers.contains(config_name), description);
+    }
+
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some("Enabled camera drivers (changes take effect after restart):")),
+        false,
+        true,
+        PADDING
+    );
+    dialog.content_area().pack_start(&driver_list.widget(), true, true, PADDING);
+
+    let sim_file_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    sim_file_box.pack_start(&gtk::Label::new(Some("Camera simulator file:")), false, false, PADDING);
+    let sim_file = gtk::FileChooserButton::new("Camera simulator file", gtk::FileChooserAction::Open);
+    sim_file.set_tooltip_text(Some(
+        "SER or AVI video or image file shown by the camera simulator as \"User-specified file\" \
+        (applies after reconnecting the simulator)"
+    ));
+    let prev_sim_file = program_data_rc.borrow().config.simulator_video_file();
+    if let Some(path) = &prev_sim_file { sim_file.set_filename(path); }
+    sim_file_box.pack_start(&sim_file, true, true, PADDING);
+    dialog.content_area().pack_start(&sim_file_box, false, true, PADDING);
+
+    dialog.show_all();
+
+    if dialog.run() != gtk::ResponseType::Ok { return; }
+
+    if let Some(new_sim_file) = sim_file.filename() {
+        if prev_sim_file.as_ref() != Some(&new_sim_file) {
+            let pd = program_data_rc.borrow();
+            pd.config.set_simulator_video_file(&new_sim_file);
+            for driver in &pd.drivers {
+                driver.borrow_mut().set_simulator_video_file(Some(new_sim_file.clone()));
+            }
+        }
+    }
+
+    // keep also the names of drivers not present in this build
+    let mut new_disabled: Vec<&str> = disabled_drivers.iter()
+        .filter(|name| !name.is_empty() && !available_drivers.iter().any(|(config_name, _)| config_name == *name))
+        .copied()
+        .collect();
+    for (idx, (config_name, _)) in available_drivers.iter().enumerate() {
+        if !driver_list.is_item_checked(idx as u64) {
+            new_disabled.push(config_name);
+        }
+    }
+
+    let changed = {
+        let mut old_disabled: Vec<&str> = disabled_drivers.iter().filter(|name| !name.is_empty()).copied().collect();
+        old_disabled.sort();
+        let mut new_sorted = new_disabled.clone();
+        new_sorted.sort();
+        old_disabled != new_sorted
+    };
+
+    if changed {
+        program_data_rc.borrow().config.set_disabled_drivers(&new_disabled);
+        show_message(
+            "Restart the program for the changes to take effect.",
+            "Camera drivers",
+            gtk::MessageType::Info,
+            program_data_rc
+        );
+    }
+}
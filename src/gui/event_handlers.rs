@@ -16,17 +16,24 @@ use crate::{
     gui::{
         actions,
         apply_gain,
+        APPLYING_INDICATOR_DURATION,
+        auto_exposure,
         camera_gui,
         CameraError,
+        DialogDestroyer,
         disconnect_camera,
+        focuser_gui,
         gamma_correct,
         histogram_utils,
+        img_view::ImgView,
         mount_gui,
         MouseMode,
         rec_gui,
         roi_dialog,
         ScreenSelection,
         show_message,
+        show_question,
+        unlimited_recording_info,
         update_preview_info,
         update_recording_info,
         update_refreshable_camera_controls,
@@ -41,12 +48,13 @@ use crate::{
     workers::{
         capture::CaptureToMainThreadMsg,
         histogram::{Histogram, HistogramRequest},
+        mjpeg_server::MjpegServer,
         recording::RecordingToMainThreadMsg,
     }
 };
 use ga_image::Rect;
 use glib::clone;
-use gtk::{cairo, prelude::*};
+use gtk::{cairo, gdk, prelude::*};
 use std::{cell::RefCell, sync::atomic::Ordering, path::Path, rc::Rc};
 
 pub fn on_preview_area_button_down(pos: Point2<i32>, program_data_rc: &Rc<RefCell<ProgramData>>) {
@@ -57,6 +65,21 @@ pub fn on_preview_area_button_down(pos: Point2<i32>, program_data_rc: &Rc<RefCel
     }
 }
 
+/// Returns the part of `rect` within an image of `img_size` (if known); `None` if there is no such part.
+fn clip_to_image(rect: Rect, img_size: Option<(i32, i32)>) -> Option<Rect> {
+    let (img_w, img_h) = match img_size {
+        Some(size) => size,
+        None => return Some(rect)
+    };
+    let x0 = rect.x.max(0);
+    let y0 = rect.y.max(0);
+    let x1 = (rect.x + rect.width as i32).min(img_w);
+    let y1 = (rect.y + rect.height as i32).min(img_h);
+    if x1 <= x0 || y1 <= y0 { return None; }
+
+    Some(Rect{ x: x0, y: y0, width: (x1 - x0) as u32, height: (y1 - y0) as u32 })
+}
+
 pub fn on_preview_area_button_up(pos: Point2<i32>, program_data_rc: &Rc<RefCell<ProgramData>>) {
     let preview_img_size = program_data_rc.borrow().gui.as_ref().unwrap().preview_area.image_size();
 
@@ -79,6 +102,7 @@ pub fn on_preview_area_button_up(pos: Point2<i32>, program_data_rc: &Rc<RefCell<
     };
 
     let mut show_crop_error = false;
+    let mut show_guiding_error = false;
     let mut send_to_cap_thread_res = Ok(());
 
     {
@@ -89,9 +113,25 @@ pub fn on_preview_area_button_up(pos: Point2<i32>, program_data_rc: &Rc<RefCell<
                 match program_data.gui.as_ref().unwrap().mouse_mode {
                     MouseMode::SelectCentroidArea =>
                     {
-                        send_to_cap_thread_res =
-                            data.sender.send(MainToCaptureThreadMsg::EnableCentroidTracking(sel_rect));
-                        log::info!("enabled target tracking via centroid");
+                        let area = if sel_rect.width < 2 || sel_rect.height < 2 {
+                            // clicked without selecting; use the default size
+                            let size = program_data.config.centroid_area_size();
+                            clip_to_image(
+                                Rect{ x: pos.x - size as i32 / 2, y: pos.y - size as i32 / 2, width: size, height: size },
+                                preview_img_size
+                            )
+                        } else {
+                            Some(sel_rect)
+                        };
+                        match area {
+                            Some(area) => {
+                                send_to_cap_thread_res = data.sender.send(MainToCaptureThreadMsg::EnableCentroidTracking(
+                                    (area, program_data.config.min_guide_star_snr())
+                                ));
+                                log::info!("enabled target tracking via centroid");
+                            },
+                            None => log::warn!("centroid area outside the image; tracking not enabled")
+                        }
                     },
 
                     MouseMode::SelectCropArea => {
@@ -117,7 +157,7 @@ pub fn on_preview_area_button_up(pos: Point2<i32>, program_data_rc: &Rc<RefCell<
 
                     MouseMode::MeasureDistance => (),
 
-                    MouseMode::None | MouseMode::PlaceTrackingAnchor => ()
+                    MouseMode::None | MouseMode::PlaceTrackingAnchor | MouseMode::PlaceGuidingTarget => ()
                 }
             } else {
                 match program_data.gui.as_ref().unwrap().mouse_mode {
@@ -126,6 +166,15 @@ pub fn on_preview_area_button_up(pos: Point2<i32>, program_data_rc: &Rc<RefCell<
                         log::info!("enabled target tracking via anchor");
                     },
 
+                    MouseMode::PlaceGuidingTarget => {
+                        if program_data.mount_data.guiding_pos.is_some() && !program_data.mount_data.centering {
+                            program_data.mount_data.guiding_pos = Some(pos);
+                            log::info!("guiding target moved to {:?}", pos);
+                        } else {
+                            show_guiding_error = true;
+                        }
+                    },
+
                     _ => ()
                 }
             }
@@ -140,9 +189,11 @@ pub fn on_preview_area_button_up(pos: Point2<i32>, program_data_rc: &Rc<RefCell<
     }
 
     if send_to_cap_thread_res.is_err() {
-        crate::on_capture_thread_failure(program_data_rc);
+        crate::on_capture_thread_failure(None, program_data_rc);
     } else if show_crop_error {
         show_message("Cannot set crop area during recording.", "Error", gtk::MessageType::Error, program_data_rc);
+    } else if show_guiding_error {
+        show_message("Guiding is not active.", "Error", gtk::MessageType::Error, program_data_rc);
     }
 }
 
@@ -157,22 +208,77 @@ pub fn on_preview_area_mouse_move(pos: Point2<i32>, program_data_rc: &Rc<RefCell
 
 fn initiate_set_roi(rect: Rect, program_data: &mut ProgramData)
 -> Result<(), std::sync::mpsc::SendError<crate::workers::capture::MainToCaptureThreadMsg>> {
-    let result = program_data.capture_thread_data.as_mut().unwrap().sender.send(
-        MainToCaptureThreadMsg::Pause
-    );
+    if program_data.rec_job_active {
+        log::warn!("cannot change ROI during recording");
+        return Ok(());
+    }
+
+    super::initiate_capture_pause_action(OnCapturePauseAction::SetROI(rect), program_data)
+}
 
-    if result.is_ok() {
-        program_data.on_capture_pause_action = Some(OnCapturePauseAction::SetROI(rect));
+/// Enlarges (or shrinks, if `delta` is negative) the centroid tracking area by `delta` pixels on each side.
+pub fn on_resize_centroid_area(delta: i32, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let result = {
+        let pd = program_data_rc.borrow();
+        match (&pd.capture_thread_data, &pd.tracking) {
+            (Some(data), Some(crate::TrackingData{ mode: crate::TrackingMode::Centroid(_), .. })) =>
+                data.sender.send(MainToCaptureThreadMsg::ResizeCentroidArea(delta)),
+            _ => Ok(())
+        }
+    };
+
+    if result.is_err() {
+        crate::on_capture_thread_failure(None, program_data_rc);
     }
+}
 
-    result
+/// Sets ROI around the tracked target (extended by the configured margin).
+pub fn on_roi_around_target(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let mut show_tracking_error = false;
+    let mut result = Ok(());
+
+    {
+        let mut pd = program_data_rc.borrow_mut();
+        let img_size = pd.last_displayed_preview_image.as_ref().map(|img| (img.width(), img.height()));
+        let roi = match (&pd.tracking, img_size) {
+            (Some(tracking), Some((img_width, img_height))) => {
+                let (width, height) = match tracking.mode {
+                    crate::TrackingMode::Centroid(area) => (area.width, area.height),
+                    crate::TrackingMode::Anchor(_) => (0, 0)
+                };
+                let target = Rect{
+                    x: tracking.pos.x - width as i32 / 2,
+                    y: tracking.pos.y - height as i32 / 2,
+                    width,
+                    height
+                };
+                Some(super::roi_around_target(target, pd.config.roi_around_target_margin(), img_width, img_height))
+            },
+
+            _ => None
+        };
+
+        match roi {
+            Some(roi) => {
+                log::info!("setting ROI around target: {:?}", roi);
+                result = initiate_set_roi(roi, &mut pd);
+            },
+            None => show_tracking_error = true
+        }
+    } // end borrow of `program_data_rc`
+
+    if result.is_err() {
+        crate::on_capture_thread_failure(None, program_data_rc);
+    } else if show_tracking_error {
+        show_message("Tracking is not enabled.", "Error", gtk::MessageType::Error, program_data_rc);
+    }
 }
 
 pub fn on_set_roi(program_data_rc: &Rc<RefCell<ProgramData>>) {
     if let Some(roi_rect) = roi_dialog::show_roi_dialog(program_data_rc) {
         let result = initiate_set_roi(roi_rect, &mut program_data_rc.borrow_mut());
         if result.is_err() {
-            crate::on_capture_thread_failure(program_data_rc);
+            crate::on_capture_thread_failure(None, program_data_rc);
         }
     }
 }
@@ -201,6 +307,95 @@ pub fn on_snapshot(program_data_rc: &Rc<RefCell<ProgramData>>) {
         .view().save(&dest_path.to_str().unwrap().to_string(), ga_image::FileType::Tiff).unwrap();
 }
 
+pub fn on_copy_to_clipboard(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let program_data = program_data_rc.borrow();
+    let gui_data = program_data.gui.as_ref().unwrap();
+
+    match gui_data.preview_area.image_as_pixbuf() {
+        Some(pixbuf) => gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD).set_image(&pixbuf),
+        None => log::info!("no image displayed yet, nothing to copy")
+    }
+}
+
+/// Saves the preview area contents (including overlays) as PNG.
+pub fn on_export_view(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let preview_area = program_data_rc.borrow().gui.as_ref().unwrap().preview_area.clone();
+    let surface = match preview_area.render_with_overlays() {
+        Some(surface) => surface,
+        None => {
+            show_message("No image displayed yet.", "Error", gtk::MessageType::Error, program_data_rc);
+            return;
+        }
+    };
+
+    let dialog = gtk::FileChooserDialog::with_buttons(
+        Some("Export view"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::FileChooserAction::Save,
+        &[("Save", gtk::ResponseType::Accept), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    let _ddestr = DialogDestroyer::new(dialog.upcast_ref::<gtk::Dialog>());
+    dialog.set_do_overwrite_confirmation(true);
+    dialog.set_current_folder(program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.dest_dir());
+    dialog.set_current_name("view.png");
+
+    if dialog.run() != gtk::ResponseType::Accept { return; }
+    let dest_path = match dialog.filename() {
+        Some(path) => path,
+        None => return
+    };
+
+    let result = std::fs::File::create(&dest_path)
+        .map_err(|e| e.to_string())
+        .and_then(|mut file| surface.write_to_png(&mut file).map_err(|e| e.to_string()));
+
+    if let Err(e) = result {
+        show_message(
+            &format!("Failed to save {}: {}.", dest_path.to_string_lossy(), e),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
+    }
+}
+
+/// Saves the current histogram's bins as CSV.
+pub fn on_save_histogram(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let csv = match program_data_rc.borrow().gui.as_ref().unwrap().histogram_view.histogram_csv() {
+        Some(csv) => csv,
+        None => {
+            show_message("No histogram calculated yet.", "Error", gtk::MessageType::Error, program_data_rc);
+            return;
+        }
+    };
+
+    let dialog = gtk::FileChooserDialog::with_buttons(
+        Some("Save histogram"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::FileChooserAction::Save,
+        &[("Save", gtk::ResponseType::Accept), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    let _ddestr = DialogDestroyer::new(dialog.upcast_ref::<gtk::Dialog>());
+    dialog.set_do_overwrite_confirmation(true);
+    dialog.set_current_folder(program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.dest_dir());
+    dialog.set_current_name("histogram.csv");
+
+    if dialog.run() != gtk::ResponseType::Accept { return; }
+    let dest_path = match dialog.filename() {
+        Some(path) => path,
+        None => return
+    };
+
+    if let Err(e) = std::fs::write(&dest_path, csv) {
+        show_message(
+            &format!("Failed to save {}: {}.", dest_path.to_string_lossy(), e),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
+    }
+}
+
 pub fn on_undock_preview_area(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let preview_wnd = gtk::WindowBuilder::new()
         .type_(gtk::WindowType::Toplevel)
@@ -229,20 +424,90 @@ pub fn on_undock_preview_area(program_data_rc: &Rc<RefCell<ProgramData>>) {
     ));
 }
 
-pub fn on_main_window_delete(
+/// Moves the stabilization position (if stabilization is enabled) or the reticle (if shown) by `direction`;
+/// returns false if neither is active.
+pub fn on_preview_area_arrow_key(direction: Vector2<i32>, program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    let mut pd = program_data_rc.borrow_mut();
+    let gui = pd.gui.as_mut().unwrap();
+
+    if gui.stabilization.toggle_button.is_active() {
+        gui.stabilization.position += direction;
+    } else if gui.reticle.enabled {
+        gui.reticle.offset += direction;
+    } else {
+        return false;
+    }
+
+    gui.preview_area.refresh();
+    true
+}
+
+/// Handles the user-configurable shortcuts (see `shortcuts`).
+pub fn on_main_window_key_press(
     wnd: &gtk::ApplicationWindow,
-    main_wnd_contents: &gtk::Paned,
-    cam_controls_and_histogram: &gtk::Paned,
+    event: &gdk::EventKey,
     program_data_rc: &Rc<RefCell<ProgramData>>
-) {
-    let (x, y) = wnd.position();
-    let (width, height) = wnd.size();
-    let config = &program_data_rc.borrow().config;
+) -> gtk::Inhibit {
+    let modifiers = event.state() & gtk::accelerator_get_default_mod_mask();
+
+    // do not interfere with typing in text fields
+    if modifiers.is_empty() && event.keyval().to_unicode().is_some() {
+        if let Some(focus) = wnd.focus() {
+            if focus.is::<gtk::Entry>() { return gtk::Inhibit(false); }
+        }
+    }
+
+    let action = {
+        let pd = program_data_rc.borrow();
+        let gui = pd.gui.as_ref().unwrap();
+        match gui.shortcuts.find_action(*event.keyval().to_lower(), modifiers) {
+            Some(action_name) => gui.action_map.get(action_name).cloned(),
+            None => None
+        }
+    };
+
+    match action {
+        Some(action) => {
+            if action.is_enabled() { action.activate(None); }
+            gtk::Inhibit(true)
+        },
+        None => gtk::Inhibit(false)
+    }
+}
+
+/// Returns false if the user chose not to close the main window while recording.
+pub fn confirm_main_window_close(program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    let ask = {
+        let pd = program_data_rc.borrow();
+        pd.rec_job_active && pd.config.confirm_quit_while_recording()
+    };
+
+    !ask || show_question(
+        "Recording is in progress. Stop it and quit?\n\nFrames captured so far will be saved.",
+        "Recording in progress",
+        program_data_rc
+    )
+}
+
+pub fn on_main_window_delete(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    store_gui_state(&program_data_rc.borrow());
+}
+
+/// Copies the GUI state to be remembered (window layout, recording settings, etc.) to the configuration
+/// (without saving it to disk).
+fn store_gui_state(program_data: &ProgramData) {
+    let gui = program_data.gui.as_ref().unwrap();
+    let config = &program_data.config;
+    let (x, y) = gui.app_window.position();
+    let (width, height) = gui.app_window.size();
     config.set_main_window_pos(gtk::Rectangle{ x, y, width, height });
-    config.set_main_window_maximized(wnd.is_maximized());
-    config.set_main_window_paned_pos(main_wnd_contents.position());
-    config.set_camera_controls_paned_pos(cam_controls_and_histogram.position());
-    //TODO: encode a `Path` somehow;  config.set_recording_dest_path(&program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.dest_dir());
+    config.set_main_window_maximized(gui.app_window.is_maximized());
+    config.set_main_window_paned_pos(gui.window_contents.position());
+    config.set_camera_controls_paned_pos(gui.cam_controls_and_histogram.position());
+    config.set_histogram_num_bins(gui.histogram_view.num_bins());
+    gui.rec_widgets.store_limit_and_sequence(config);
+    focuser_gui::store_last_position(program_data);
+    //TODO: encode a `Path` somehow;  config.set_recording_dest_path(&gui.rec_widgets.dest_dir());
 }
 
 pub fn on_recording_thread_message(
@@ -258,23 +523,34 @@ pub fn on_recording_thread_message(
 
         RecordingToMainThreadMsg::CaptureThreadEnded => {
             rec_gui::on_stop_recording(program_data_rc);
-            crate::on_capture_thread_failure(program_data_rc);
+            crate::on_capture_thread_failure(None, program_data_rc);
         },
 
         RecordingToMainThreadMsg::Error(err) => {
             rec_gui::on_stop_recording(program_data_rc);
             show_message(
-                &format!("Error during recording:\n{}", err),
+                &format!("Error during recording:\n{}\n\nRecording has been stopped.", err),
                 "Recording error",
                 gtk::MessageType::Error,
                 program_data_rc
             );
-        }
+        },
+
+        RecordingToMainThreadMsg::VerificationFailed(err) => show_message(
+            &format!("Verification of the recorded output failed:\n{}", err),
+            "Recording error",
+            gtk::MessageType::Warning,
+            program_data_rc
+        )
     }
 }
 
 /// Called ca. once per second to update the status bar and refresh any readable camera controls.
 pub fn on_timer(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    mount_gui::update_mount_status(program_data_rc);
+    autosave_config(&mut program_data_rc.borrow_mut());
+    check_idle_disconnect(program_data_rc);
+
     if !program_data_rc.borrow().camera.is_some() { return; }
 
     update_preview_info(program_data_rc);
@@ -282,6 +558,74 @@ pub fn on_timer(program_data_rc: &Rc<RefCell<ProgramData>>) {
     update_recording_info(program_data_rc);
 }
 
+/// Saves the configuration if the autosave interval has elapsed, so that settings survive a crash.
+fn autosave_config(program_data: &mut ProgramData) {
+    let interval = match program_data.config_autosave_interval {
+        Some(interval) => interval,
+        None => return
+    };
+
+    if program_data.t_last_config_save.elapsed() >= interval {
+        store_gui_state(program_data);
+        if let Err(e) = program_data.config.store() {
+            log::warn!("failed to save configuration: {}", e);
+        }
+        program_data.t_last_config_save = std::time::Instant::now();
+    }
+}
+
+/// Disconnects the camera and mount once there has been no user input and no device operation (recording,
+/// guiding, mount motion) for the configured time.
+fn check_idle_disconnect(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let (disconnect_cam, disconnect_mount) = {
+        let mut pd = program_data_rc.borrow_mut();
+        let timeout = match pd.idle_disconnect_timeout {
+            Some(timeout) => timeout,
+            None => return
+        };
+
+        let md = &pd.mount_data;
+        // serving frames to remote clients counts as activity
+        let busy = pd.rec_job_active
+            || pd.frame_feed.is_some()
+            || pd.mjpeg_server.as_ref().map_or(false, |server| server.has_clients())
+            || pd.gui.as_ref().unwrap().rec_widgets.sequence_next_start.is_some()
+            || md.calibration_in_progress()
+            || md.guiding_pos.is_some()
+            || md.guide_slewing
+            || md.sky_tracking_on
+            || md.manually_slewed_axes.iter().any(|slewed| *slewed);
+        if busy {
+            pd.t_last_activity = std::time::Instant::now();
+            return;
+        }
+
+        if pd.t_last_activity.elapsed() < timeout { return; }
+
+        (pd.camera.is_some(), pd.mount_data.mount.is_some())
+    };
+
+    if !disconnect_cam && !disconnect_mount { return; }
+
+    log::info!("no activity for the configured time; disconnecting devices");
+    if disconnect_cam { disconnect_camera(program_data_rc, true); }
+    if disconnect_mount { mount_gui::disconnect(program_data_rc); }
+}
+
+/// Marks the user's interaction with the main window (see `check_idle_disconnect`).
+pub fn on_main_window_event(event: &gdk::Event, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    match event.event_type() {
+        gdk::EventType::ButtonPress
+        | gdk::EventType::KeyPress
+        | gdk::EventType::MotionNotify
+        | gdk::EventType::Scroll => if let Ok(mut pd) = program_data_rc.try_borrow_mut() {
+            pd.t_last_activity = std::time::Instant::now();
+        },
+
+        _ => ()
+    }
+}
+
 fn on_tracking_ended(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let mut reenable_calibration = false;
     {
@@ -334,7 +678,8 @@ fn on_tracking_ended(program_data_rc: &Rc<RefCell<ProgramData>>) {
 fn on_preview_image_ready(
     program_data_rc: &Rc<RefCell<ProgramData>>,
     img: std::sync::Arc<ga_image::Image>,
-    tracking_pos: Option<Point2<i32>>
+    tracking_pos: Option<Point2<i32>>,
+    t_captured: std::time::Instant
 ) {
     let mut program_data = program_data_rc.borrow_mut();
 
@@ -346,6 +691,17 @@ fn on_preview_image_ready(
             }
         }
     }
+    if program_data.preview_paused {
+        let refresh_due = match (
+            program_data.config.paused_preview_refresh_interval(),
+            program_data.last_displayed_preview_image_timestamp
+        ) {
+            (Some(interval), Some(last_preview_ts)) => now - last_preview_ts >= interval,
+            (Some(_), None) => true,
+            (None, _) => false
+        };
+        if !refresh_due { return; }
+    }
     program_data.last_displayed_preview_image_timestamp = Some(now);
 
     if let Some(area) = program_data.histogram_area {
@@ -429,13 +785,21 @@ fn on_preview_image_ready(
     );
     program_data.gui.as_ref().unwrap().preview_area.refresh();
 
+    if let Some(mjpeg_server) = &program_data.mjpeg_server {
+        if mjpeg_server.frame_wanted() {
+            send_mjpeg_frame(mjpeg_server, &program_data.gui.as_ref().unwrap().preview_area);
+        }
+    }
+
     const HISTOGRAM_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
     if program_data.t_last_histogram.is_none() ||
        program_data.t_last_histogram.as_ref().unwrap().elapsed() >= HISTOGRAM_UPDATE_INTERVAL {
 
         program_data.histogram_sender.send(MainToHistogramThreadMsg::CalculateHistogram(HistogramRequest{
             image: (*img).clone(),
-            fragment: program_data.histogram_area.clone()
+            fragment: program_data.histogram_area.clone(),
+            captured: t_captured,
+            num_bins: program_data.gui.as_ref().unwrap().histogram_view.num_bins()
         })).unwrap();
 
         program_data.t_last_histogram = Some(std::time::Instant::now());
@@ -444,13 +808,35 @@ fn on_preview_image_ready(
     program_data.last_displayed_preview_image = Some((*img).clone());
 
     program_data.preview_fps_counter += 1;
+    // measured until the image is handed over for drawing (which happens shortly afterwards)
+    program_data.preview_latency_sum += t_captured.elapsed();
+}
+
+/// Encodes the current preview image as JPEG and sends it to MJPEG stream clients.
+fn send_mjpeg_frame(mjpeg_server: &MjpegServer, preview_area: &ImgView) {
+    const JPEG_QUALITY: &str = "80";
+
+    if let Some(pixbuf) = preview_area.image_as_pixbuf() {
+        match pixbuf.save_to_bufferv("jpeg", &[("quality", JPEG_QUALITY)]) {
+            Ok(jpeg) => mjpeg_server.send_frame(jpeg),
+            Err(e) => log::error!("failed to encode preview image as JPEG: {}", e)
+        }
+    }
 }
 
 fn on_capture_paused(
     program_data_rc: &Rc<RefCell<ProgramData>>
 ) {
     let mut show_error: Option<CameraError> = None;
-    let action = program_data_rc.borrow().on_capture_pause_action;
+    let mut action = program_data_rc.borrow_mut().on_capture_pause_action.take();
+    let rejected = action.is_some() && program_data_rc.borrow().rec_job_active;
+    if rejected {
+        // the controls are disabled during recording; should a change arrive anyway, do not break the recording
+        log::warn!("capture pause action rejected during recording");
+        action = None;
+        camera_gui::schedule_refresh(program_data_rc);
+    }
+
     match action {
         Some(action) => match action {
             OnCapturePauseAction::ControlChange(CameraControlChange{ id, value }) => {
@@ -501,7 +887,12 @@ fn on_capture_paused(
     if program_data_rc.borrow_mut().capture_thread_data.as_mut().unwrap().sender.send(
         MainToCaptureThreadMsg::Resume
     ).is_err() {
-        crate::on_capture_thread_failure(program_data_rc);
+        crate::on_capture_thread_failure(None, program_data_rc);
+    } else {
+        let pd = program_data_rc.borrow();
+        let gui = pd.gui.as_ref().unwrap();
+        let indicator = gui.applying_indicator.clone();
+        gui.applying_indicator_timer.run(APPLYING_INDICATOR_DURATION, true, move || indicator.hide());
     }
 
     if let Some(error) = show_error {
@@ -511,6 +902,13 @@ fn on_capture_paused(
             gtk::MessageType::Error,
             program_data_rc
         );
+    } else if rejected {
+        show_message(
+            "Cannot change this setting during recording; the change has not been applied.",
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
     }
 }
 
@@ -521,9 +919,9 @@ pub fn on_capture_thread_message(
     let mut received_preview_image = false;
 
     loop { match msg {
-        CaptureToMainThreadMsg::PreviewImageReady((img, tracking_pos)) => {
+        CaptureToMainThreadMsg::PreviewImageReady((img, tracking_pos, t_captured)) => {
             received_preview_image = true;
-            on_preview_image_ready(program_data_rc, img, tracking_pos);
+            on_preview_image_ready(program_data_rc, img, tracking_pos, t_captured);
         },
 
         CaptureToMainThreadMsg::TrackingUpdate((tracking, crop_area)) => if program_data_rc.borrow().capture_thread_data.is_some() {
@@ -536,23 +934,33 @@ pub fn on_capture_thread_message(
         CaptureToMainThreadMsg::Paused => on_capture_paused(program_data_rc),
 
         CaptureToMainThreadMsg::CaptureError(error) => {
-            //TODO: show a message box
-            println!("Capture error: {:?}", error);
+            log::error!("capture error: {:?}", error);
             let _ = program_data_rc.borrow_mut().capture_thread_data.take().unwrap().join_handle.take().unwrap().join();
-            disconnect_camera(&program_data_rc, false);
+            crate::on_capture_thread_failure(Some(&error), program_data_rc);
         },
 
         CaptureToMainThreadMsg::RecordingFinished => rec_gui::on_recording_finished(&program_data_rc),
 
         CaptureToMainThreadMsg::Info(info) => {
-            let pd = program_data_rc.borrow();
-            let status_bar = &pd.gui.as_ref().unwrap().status_bar;
+            let mut pd = program_data_rc.borrow_mut();
+            let gui = pd.gui.as_mut().unwrap();
+            let status_bar = &gui.status_bar;
 
             status_bar.capture_fps.set_label(&format!("Capture: {:.1} fps", info.capture_fps));
 
             if let Some(msg) = info.recording_info {
-                status_bar.current_recording_info.set_label(&msg);
+                match &mut gui.rec_widgets.unlimited_recording {
+                    Some((t_start, progress)) => {
+                        status_bar.current_recording_info.set_label(
+                            &unlimited_recording_info(&msg, t_start.elapsed())
+                        );
+                        *progress = msg;
+                    },
+                    None => status_bar.current_recording_info.set_label(&msg)
+                }
             }
+
+            status_bar.frame_info.set_label(info.frame_info.as_deref().unwrap_or(""));
         }
     } break; }
 
@@ -568,5 +976,6 @@ pub fn on_histogram_thread_message(
     msg: Histogram,
     program_data_rc: &Rc<RefCell<ProgramData>>
 ) {
+    auto_exposure::on_histogram(&msg, program_data_rc);
     program_data_rc.borrow_mut().gui.as_mut().unwrap().histogram_view.set_histogram(msg);
 }
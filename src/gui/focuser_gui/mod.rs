@@ -15,23 +15,39 @@ pub mod focuscube3;
 pub mod simulator;
 
 use crate::{
-    devices::{DeviceConnectionDiscriminants, DeviceType, focuser},
-    gui::{device_connection_dialog, show_message},
+    devices::{DeviceConnection, DeviceConnectionDiscriminants, DeviceType, focuser},
+    gui::{device_connection_dialog, show_message, show_speed_list_dialog},
     lim_freq_action::LimitedFreqAction,
     ProgramData,
     timer::Timer
 };
 use glib::clone;
 use gtk::prelude::*;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::{Cell, RefCell}, error::Error, rc::Rc};
 use strum::IntoEnumIterator;
 
 const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
 const REFRESH_DUR_AFTER_STOP: std::time::Duration = std::time::Duration::from_secs(1);
+/// Max. distance (in steps) from the overshoot position at which the final approach is started if the focuser
+/// does not report whether it is moving.
+const OVERSHOOT_POS_TOLERANCE: i32 = 2;
+/// Max. time to wait for the overshoot move to complete before abandoning the "go to".
+const OVERSHOOT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+/// Interval of refreshing the temperature while the focuser is not moving.
+const TEMPERATURE_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
 
 /// Control padding in pixels.
 const PADDING: u32 = 10;
 
+/// Returns label of a speed (multiple of the focuser's normal speed), e.g. "1/8x" or "2x".
+fn speed_label(value: f64) -> String {
+    if value < 1.0 && (1.0 / value).fract() == 0.0 {
+        format!("1/{}x", 1.0 / value)
+    } else {
+        format!("{}x", value)
+    }
+}
+
 struct SpeedDescr {
     speed: focuser::Speed,
     label: String,
@@ -40,12 +56,38 @@ struct SpeedDescr {
 
 pub struct FocuserWidgets {
     wbox: gtk::Box,
+    /// Contains all controls except the status row; insensitive while disconnected.
+    controls: gtk::Box,
     status: gtk::Label,
+    /// Reconnects using the parameters of the last connection; insensitive until the first connection.
+    reconnect: gtk::Button,
     speeds: Rc<RefCell<Vec<SpeedDescr>>>,
     speed_combo: gtk::ComboBox,
+    /// Labels of `speeds`.
+    speed_model: gtk::ListStore,
+    /// Speed range of the connected focuser.
+    speed_range: Cell<focuser::SpeedRange>,
     position: gtk::Label,
+    /// Shows position within travel; hidden if the focuser's travel is unknown.
+    position_bar: gtk::ProgressBar,
+    /// Absolute move waiting for the overshoot to complete (see `focuser_goto`).
+    pending_goto: Cell<Option<PendingGoto>>,
+    /// Moves the focuser to `last_position`; hidden if there is none.
+    btn_return: gtk::Button,
+    /// Position of the connected focuser type at its last disconnection.
+    last_position: Cell<Option<focuser::Position>>,
+    /// Travel of the connected focuser (`None` if unknown).
+    pos_range: Cell<Option<focuser::PositionRange>>,
+    /// Shows whether the focuser is moving.
+    motion: gtk::Label,
+    /// Temperature reported by the focuser (empty if not supported).
+    temperature: gtk::Label,
+    /// Temperature at the last focusing (manual or automatic); temperature compensation refocuses once
+    /// the temperature departs from it by the configured delta.
+    temp_comp_reference: Cell<Option<f64>>,
     refresh_timer: Timer,
-    refresh_stop_timer: Timer
+    refresh_stop_timer: Timer,
+    temperature_timer: Timer
 }
 
 impl FocuserWidgets {
@@ -53,7 +95,8 @@ impl FocuserWidgets {
 
     fn on_connect(&self, focuser: &mut focuser::FocuserWrapper)
     {
-        self.wbox.set_sensitive(true);
+        self.controls.set_sensitive(true);
+        self.reconnect.set_sensitive(true);
         self.status.set_text(&format!("{}", focuser.get().info()));
         let focuser::SpeedRange{ min, max } = match focuser.get_mut().speed_range() {
             Ok(range) => range,
@@ -62,75 +105,169 @@ impl FocuserWidgets {
                 focuser::SpeedRange{ min: focuser::Speed::one(), max: focuser::Speed::one() }
             }
         };
-        let mut speeds = self.speeds.borrow_mut();
-        for speed in speeds.iter_mut() {
-            speed.supported = speed.speed >= min && speed.speed <= max;
+        self.speed_range.set(focuser::SpeedRange{ min, max });
+
+        let pos_range = match focuser.get_mut().pos_range() {
+            Ok(range) => if range.is_known() { Some(range) } else { None },
+            Err(e) => {
+                log::error!("Failed to obtain focuser position range: {}.", e);
+                None
+            }
+        };
+        self.pos_range.set(pos_range);
+        self.position_bar.set_visible(pos_range.is_some());
+    }
+
+    /// Fills the speed combo box with `values` (multiples of normal speed).
+    fn set_speeds(&self, values: &[f64]) {
+        let focuser::SpeedRange{ min, max } = self.speed_range.get();
+        let prev_selected = self.speed_combo.active().map(|idx| self.speeds.borrow()[idx as usize].speed);
+
+        let new_speeds: Vec<SpeedDescr> = values.iter().map(|value| {
+            let speed = focuser::Speed::new(*value);
+            SpeedDescr{ speed, label: speed_label(*value), supported: speed >= min && speed <= max }
+        }).collect();
+
+        let new_selected = prev_selected
+            .and_then(|prev| new_speeds.iter().position(|s| s.speed == prev))
+            .or_else(|| new_speeds.iter().position(|s| s.speed.get() == 1.0))
+            .or_else(|| new_speeds.iter().position(|s| s.supported))
+            .unwrap_or(0);
+
+        self.speed_model.clear();
+        for (idx, speed) in new_speeds.iter().enumerate() {
+            self.speed_model.insert_with_values(Some(idx as u32), &[(0u32, &speed.label)]);
         }
+        *self.speeds.borrow_mut() = new_speeds;
+        self.speed_combo.set_active(Some(new_selected as u32));
     }
 
     fn on_disconnect(&self)
     {
-        self.wbox.set_sensitive(false);
+        self.controls.set_sensitive(false);
         self.status.set_text("disconnected");
+        self.motion.set_text("");
+        self.temperature.set_text("");
+        self.temperature_timer.stop();
+        self.temp_comp_reference.set(None);
+        self.pos_range.set(None);
+        self.position_bar.set_visible(false);
+        self.set_last_position(None);
+        self.pending_goto.set(None);
     }
 
+    fn set_last_position(&self, position: Option<focuser::Position>) {
+        self.last_position.set(position);
+        if let Some(position) = position {
+            self.btn_return.set_label(&format!("return to last position ({})", position.0));
+        }
+        self.btn_return.set_visible(position.is_some());
+    }
+
+    /// Returns the selected speed (normal speed if no focuser has been connected yet).
     pub fn selected_speed(&self) -> focuser::Speed {
-        self.speeds.borrow()[self.speed_combo.active().unwrap() as usize].speed
+        match self.speed_combo.active() {
+            Some(idx) => self.speeds.borrow()[idx as usize].speed,
+            None => focuser::Speed::one()
+        }
     }
 }
 
+/// Connects to the focuser specified by `connection`; returns true on success.
+fn connect(connection: DeviceConnection, program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    let focuser_type = DeviceConnectionDiscriminants::from(&connection);
+    match focuser::connect_to_focuser(connection.clone(), program_data_rc) {
+        Err(e) => {
+            show_message(
+                &format!("Failed to connect to focuser: {:?}.", e),
+                "Error",
+                gtk::MessageType::Error,
+                program_data_rc
+            );
+            false
+        },
+        Ok(mut focuser) => {
+            log::info!("connected to {}", focuser.get().info());
+            let speeds = program_data_rc.borrow().config.focuser_speeds(focuser_type);
+            {
+                let pd = program_data_rc.borrow();
+                let widgets = &pd.gui.as_ref().unwrap().focuser_widgets;
+                widgets.on_connect(&mut focuser);
+                widgets.set_speeds(&speeds);
+                widgets.set_last_position(
+                    pd.config.focuser_last_position(focuser_type).map(|pos| focuser::Position(pos))
+                );
+            }
+            let pd = program_data_rc.borrow();
+            let mut focuser_data = pd.focuser_data.borrow_mut();
+            focuser_data.focuser = Some(focuser);
+            focuser_data.focuser_type = Some(focuser_type);
+            focuser_data.last_connection = Some(connection);
+            drop(focuser_data);
+            drop(pd);
+            on_refresh(program_data_rc);
+            program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.temperature_timer.run(
+                TEMPERATURE_REFRESH_INTERVAL,
+                false,
+                clone!(@weak program_data_rc => @default-panic, move || on_refresh_temperature(&program_data_rc))
+            );
+            true
+        }
+    }
+}
+
+/// Disconnects from the focuser (if connected).
+fn disconnect(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    if program_data_rc.borrow().focuser_data.borrow().focuser.is_none() { return; }
+
+    let pd = program_data_rc.borrow();
+    let focuser_info = pd.focuser_data.borrow().focuser.as_ref().unwrap().get().info();
+    store_last_position(&pd);
+    pd.focuser_data.borrow_mut().focuser = None;
+    pd.focuser_data.borrow_mut().focuser_type = None;
+    pd.gui.as_ref().unwrap().focuser_widgets.on_disconnect();
+    log::info!("disconnected from {}", focuser_info);
+}
+
+/// Disconnects from the focuser (if connected) and connects again using the last successful connection's parameters.
+fn reconnect(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let connection = match program_data_rc.borrow().focuser_data.borrow().last_connection.clone() {
+        Some(connection) => connection,
+        None => return
+    };
+    disconnect(program_data_rc);
+    log::info!("reconnecting to focuser");
+    connect(connection, program_data_rc);
+}
+
 pub fn init_focuser_menu(program_data_rc: &Rc<RefCell<ProgramData>>) -> gtk::Menu {
     let menu = gtk::Menu::new();
 
     let item_disconnect = gtk::MenuItem::with_label("Disconnect");
-    item_disconnect.connect_activate(clone!(@weak program_data_rc => @default-panic, move |menu_item| {
-        {
-            let mut pd = program_data_rc.borrow_mut();
-            let focuser_info = pd.focuser_data.borrow().focuser.as_ref().unwrap().get().info();
-            pd.focuser_data.borrow_mut().focuser = None;
-            pd.gui.as_ref().unwrap().focuser_widgets.on_disconnect();
-            log::info!("disconnected from {}", focuser_info);
-        }
-        menu_item.set_sensitive(false);
+    item_disconnect.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        disconnect(&program_data_rc);
     }));
-    item_disconnect.set_sensitive(false);
 
     let item_connect = gtk::MenuItem::with_label("Connect...");
 
     let focuser_connections: Vec<DeviceConnectionDiscriminants> =
         DeviceConnectionDiscriminants::iter().filter(|d| d.device_type() == DeviceType::Focuser).collect();
 
-
-    item_connect.connect_activate(clone!(
-        @weak program_data_rc,
-        @weak item_disconnect
-        => @default-panic, move |_| {
-            match device_connection_dialog::show_device_connection_dialog(
-                "Connect to focuser",
-                "Focuser type:",
-                &program_data_rc,
-                &focuser_connections
-            ) {
-                Some(connection) => {
-                    match focuser::connect_to_focuser(connection, &program_data_rc) {
-                        Err(e) => show_message(
-                            &format!("Failed to connect to focuser: {:?}.", e),
-                            "Error",
-                            gtk::MessageType::Error,
-                            &program_data_rc
-                        ),
-                        Ok(mut focuser) => {
-                            log::info!("connected to {}", focuser.get().info());
-                            program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.on_connect(&mut focuser);
-                            program_data_rc.borrow_mut().focuser_data.borrow_mut().focuser = Some(focuser);
-                            item_disconnect.set_sensitive(true);
-                        }
-                    }
-                },
-                _ => ()
-            }
+    item_connect.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        if let Some(connection) = device_connection_dialog::show_device_connection_dialog(
+            "Connect to focuser",
+            "Focuser type:",
+            &program_data_rc,
+            &focuser_connections
+        ) {
+            connect(connection, &program_data_rc);
         }
-    ));
+    }));
+
+    // the focuser can also be (re)connected from the focuser panel
+    menu.connect_show(clone!(@weak program_data_rc, @weak item_disconnect => @default-panic, move |_| {
+        item_disconnect.set_sensitive(program_data_rc.borrow().focuser_data.borrow().focuser.is_some());
+    }));
 
     menu.append(&item_connect);
     menu.append(&item_disconnect);
@@ -158,7 +295,289 @@ fn on_refresh(program_data_rc: &Rc<RefCell<ProgramData>>) {
     match result {
         Err(e) => log::error!("failed to get focuser state: {}", e),
         Ok(state) => {
-            program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.position.set_text(&format!("{}", state.pos.0));
+            let pd = program_data_rc.borrow();
+            let widgets = &pd.gui.as_ref().unwrap().focuser_widgets;
+            widgets.position.set_text(&format!("{}", state.pos.0));
+            if let Some(pos_range) = widgets.pos_range.get() {
+                let fraction = pos_range.fraction(state.pos);
+                widgets.position_bar.set_fraction(fraction);
+                widgets.position_bar.set_text(Some(&format!("{:.0}% of travel", fraction * 100.0)));
+            }
+            widgets.motion.set_text(match state.moving {
+                Some(true) => "moving…",
+                Some(false) => "idle",
+                None => ""
+            });
+            set_temperature(&widgets.temperature, &state);
+            let pending_goto = widgets.pending_goto.get();
+            drop(pd);
+
+            if let Some(pending_goto) = pending_goto {
+                let overshoot_done = match state.moving {
+                    Some(moving) => !moving,
+                    None => (state.pos.0 as i64 - pending_goto.overshoot_pos.0 as i64).abs()
+                        <= OVERSHOOT_POS_TOLERANCE as i64
+                };
+                if overshoot_done {
+                    program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.pending_goto.set(None);
+                    let result = program_data_rc.borrow().focuser_data.borrow_mut().focuser.as_mut().unwrap()
+                        .move_abs(pending_goto.target, pending_goto.speed);
+                    if let Err(e) = result {
+                        log::error!("failed to move focuser after overshoot: {}", e);
+                    }
+                } else if pending_goto.t_start.elapsed() > OVERSHOOT_TIMEOUT {
+                    program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.pending_goto.set(None);
+                    log::error!(
+                        "focuser did not reach overshoot position {} (currently at {})",
+                        pending_goto.overshoot_pos.0, state.pos.0
+                    );
+                    show_message(
+                        &format!("Focuser did not reach overshoot position {}; move abandoned.", pending_goto.overshoot_pos.0),
+                        "Error",
+                        gtk::MessageType::Error,
+                        program_data_rc
+                    );
+                }
+                return;
+            }
+
+            if state.moving != Some(true) {
+                schedule_refresh_stop(program_data_rc);
+            }
+        }
+    }
+}
+
+fn set_temperature(label: &gtk::Label, state: &focuser::State) {
+    match &state.temperature {
+        Some(temperature) => label.set_text(&format!("{:.1} °C", temperature.0)),
+        None => label.set_text("")
+    }
+}
+
+fn on_refresh_temperature(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    if program_data_rc.borrow().focuser_data.borrow().focuser.is_none() { return; }
+
+    let result = {
+        program_data_rc.borrow().focuser_data.borrow_mut().focuser.as_mut().unwrap().get_mut().state()
+    };
+
+    match result {
+        Err(e) => log::error!("failed to get focuser state: {}", e),
+        Ok(state) => {
+            {
+                let pd = program_data_rc.borrow();
+                set_temperature(&pd.gui.as_ref().unwrap().focuser_widgets.temperature, &state);
+            }
+            if let Some(temperature) = &state.temperature {
+                if state.moving != Some(true) {
+                    check_temperature_compensation(temperature.0 as f64, program_data_rc);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the number of steps by which to refocus after the temperature has changed from `reference`
+/// to `current` (`None` if the change is smaller than `delta`).
+fn temperature_compensation_steps(reference: f64, current: f64, delta: f64, steps_per_degree: f64) -> Option<i32> {
+    let change = current - reference;
+    if change.abs() < delta {
+        None
+    } else {
+        Some((change * steps_per_degree).round() as i32)
+    }
+}
+
+/// Refocuses (if temperature compensation is enabled) once the temperature has changed sufficiently
+/// since the last focusing.
+fn check_temperature_compensation(temperature: f64, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let (delta, steps_per_degree, speed) = {
+        let pd = program_data_rc.borrow();
+        let widgets = &pd.gui.as_ref().unwrap().focuser_widgets;
+        if !pd.config.focuser_temp_compensation() {
+            widgets.temp_comp_reference.set(None);
+            return;
+        }
+
+        match widgets.temp_comp_reference.get() {
+            None => {
+                widgets.temp_comp_reference.set(Some(temperature));
+                return;
+            },
+            Some(_) => ()
+        }
+
+        (
+            pd.config.focuser_temp_compensation_delta(),
+            pd.config.focuser_temp_compensation_steps_per_deg(),
+            widgets.selected_speed()
+        )
+    };
+
+    let reference = program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.temp_comp_reference.get().unwrap();
+    let steps = match temperature_compensation_steps(reference, temperature, delta, steps_per_degree) {
+        Some(steps) => steps,
+        None => return
+    };
+
+    program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.temp_comp_reference.set(Some(temperature));
+    if steps == 0 { return; }
+
+    log::info!(
+        "temperature changed from {:.1} °C to {:.1} °C; refocusing by {} steps", reference, temperature, steps
+    );
+    let result = program_data_rc.borrow().focuser_data.borrow_mut().focuser.as_mut().unwrap().move_rel(
+        focuser::RelativePos(focuser::Position(steps)),
+        speed
+    );
+    match result {
+        Err(e) => log::error!("failed to move focuser for temperature compensation: {}", e),
+        Ok(()) => start_refresh(program_data_rc)
+    }
+}
+
+fn start_refresh(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let pd = program_data_rc.borrow();
+    let gui = pd.gui.as_ref().unwrap();
+    gui.focuser_widgets.refresh_stop_timer.stop();
+    gui.focuser_widgets.refresh_timer.run(
+        REFRESH_INTERVAL,
+        false,
+        clone!(@weak program_data_rc => @default-panic, move || on_refresh(&program_data_rc))
+    );
+}
+
+/// Moves focuser by `delta` steps (relative to its current position) using the selected speed.
+pub fn focuser_move_rel(delta: i32, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let speed = program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets().selected_speed();
+    // the user is refocusing; temperature compensation will start from the temperature after the move
+    program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.temp_comp_reference.set(None);
+
+    let result = program_data_rc.borrow().focuser_data.borrow_mut().focuser.as_mut().unwrap().move_rel(
+        focuser::RelativePos(focuser::Position(delta)),
+        speed
+    );
+
+    match result {
+        Err(e) => show_message(
+            &format!("Failed to move focuser: {}.", e),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        ),
+
+        Ok(()) => start_refresh(program_data_rc)
+    }
+}
+
+/// Stores the connected focuser's position in configuration (to be offered as "last position" on reconnection).
+pub fn store_last_position(program_data: &ProgramData) {
+    let mut focuser_data = program_data.focuser_data.borrow_mut();
+    let focuser_type = match focuser_data.focuser_type {
+        Some(focuser_type) => focuser_type,
+        None => return
+    };
+
+    match focuser_data.focuser.as_mut().unwrap().get_mut().state() {
+        Ok(state) => {
+            program_data.config.set_focuser_last_position(focuser_type, state.pos.0);
+            log::info!("saved last focuser position: {}", state.pos.0);
+        },
+        Err(e) => log::error!("failed to get focuser position: {}", e)
+    }
+}
+
+/// Moves focuser to the position it had at the last disconnection, using the selected speed.
+fn focuser_return_to_last_position(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let (position, speed) = {
+        let pd = program_data_rc.borrow();
+        let widgets = pd.gui.as_ref().unwrap().focuser_widgets();
+        match widgets.last_position.get() {
+            Some(position) => (position, widgets.selected_speed()),
+            None => return
+        }
+    };
+
+    log::info!("returning focuser to last position: {}", position.0);
+    focuser_goto(position, speed, program_data_rc);
+}
+
+/// Absolute move waiting for the overshoot to complete.
+#[derive(Copy, Clone)]
+struct PendingGoto {
+    /// As reached, i.e. limited to travel.
+    overshoot_pos: focuser::Position,
+    target: focuser::Position,
+    speed: focuser::Speed,
+    t_start: std::time::Instant
+}
+
+/// Returns the position to move to before approaching `target` (see `keys::FOCUSER_GOTO_OVERSHOOT`),
+/// or `None` if the move from `current` already ends in the approach direction.
+fn overshoot_position(current: focuser::Position, target: focuser::Position, overshoot: i32) -> Option<focuser::Position> {
+    let approach_dir_ok = (target.0 as i64 - current.0 as i64) * overshoot.signum() as i64 > 0;
+    if overshoot == 0 || approach_dir_ok {
+        None
+    } else {
+        Some(focuser::Position(target.0.saturating_sub(overshoot)))
+    }
+}
+
+/// Starts moving to `target` or to the overshoot position (returned, limited to travel) if needed.
+fn start_goto(
+    focuser: &mut focuser::FocuserWrapper,
+    target: focuser::Position,
+    speed: focuser::Speed,
+    overshoot: i32
+) -> Result<Option<focuser::Position>, Box<dyn Error>> {
+    let current = focuser.get_mut().state()?.pos;
+    match overshoot_position(current, target, overshoot) {
+        None => {
+            focuser.move_abs(target, speed)?;
+            Ok(None)
+        },
+
+        Some(pos) => {
+            let focuser::PositionRange{ min, max } = focuser.get_mut().pos_range()?;
+            let pos = focuser::Position(pos.0.clamp(min.0, max.0));
+            focuser.move_abs(pos, speed)?;
+            Ok(Some(pos))
+        }
+    }
+}
+
+/// Moves focuser to `target`, overshooting it first if configured, so that the target is always approached
+/// from the same direction.
+pub fn focuser_goto(target: focuser::Position, speed: focuser::Speed, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let overshoot = program_data_rc.borrow().config.focuser_goto_overshoot();
+    program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.temp_comp_reference.set(None);
+
+    let result = start_goto(
+        program_data_rc.borrow().focuser_data.borrow_mut().focuser.as_mut().unwrap(),
+        target,
+        speed,
+        overshoot
+    );
+
+    match result {
+        Err(e) => show_message(
+            &format!("Failed to move focuser: {}.", e),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        ),
+
+        Ok(overshoot_pos) => {
+            program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.pending_goto.set(
+                overshoot_pos.map(|pos| PendingGoto{
+                    overshoot_pos: pos,
+                    target,
+                    speed,
+                    t_start: std::time::Instant::now()
+                })
+            );
+            start_refresh(program_data_rc);
         }
     }
 }
@@ -205,46 +624,35 @@ pub fn focuser_move(
 }
 
 pub fn create_focuser_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> FocuserWidgets {
-    let speeds = Rc::new(RefCell::new(vec![
-        SpeedDescr{ speed: focuser::Speed::new(1.0 / 64.0), label: "1/64x".into(), supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(1.0 / 32.0), label: "1/32x".into(), supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(1.0 / 16.0), label: "1/16x".into(), supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(1.0 / 8.0),  label: "1/8x".into() , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(1.0 / 4.0),  label: "1/4x".into() , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(1.0 / 2.0),  label: "1/2x".into() , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(1.0),        label: "1x".into()   , supported: true  },
-        SpeedDescr{ speed: focuser::Speed::new(2.0),        label: "2x".into()   , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(4.0),        label: "4x".into()   , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(8.0),        label: "8x".into()   , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(16.0),       label: "16x".into()  , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(32.0),       label: "32x".into()  , supported: false },
-        SpeedDescr{ speed: focuser::Speed::new(64.0),       label: "64x".into()  , supported: false },
-    ]));
-
-    let normal_speed_idx = speeds.borrow().iter().enumerate().find(|(_, s)| s.speed.get() == 1.0).unwrap().0;
+    let speeds = Rc::new(RefCell::new(vec![]));
 
     let contents = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
     let upper_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     upper_box.pack_start(&gtk::Label::new(Some("Speed:")), false, false, PADDING);
 
-    let model = gtk::ListStore::new(&[gtk::glib::Type::STRING]);
-    for (idx, speed) in speeds.borrow().iter().enumerate() {
-        model.insert_with_values(Some(idx as u32), &[(0u32, &speed.label)]);
-    }
-    let speed_combo = gtk::ComboBox::with_model(&model);
+    let speed_model = gtk::ListStore::new(&[gtk::glib::Type::STRING]);
+    let speed_combo = gtk::ComboBox::with_model(&speed_model);
     let renderer = gtk::CellRendererText::new();
     speed_combo.pack_start(&renderer, true);
     speed_combo.add_attribute(&renderer, "text", 0);
     speed_combo.set_cell_data_func(&renderer, Some(Box::new(
         clone!(@weak speeds => @default-panic, move |_, cell, model, iter| {
             let path = model.path(iter).unwrap();
-            cell.set_sensitive(speeds.borrow()[path.indices()[0] as usize].supported);
+            cell.set_sensitive(speeds.borrow().get(path.indices()[0] as usize).map(|s| s.supported).unwrap_or(false));
         })
     )));
-    speed_combo.set_active(Some(normal_speed_idx as u32));
     upper_box.pack_start(&speed_combo, false, false, PADDING);
 
+    let btn_edit_speeds = gtk::ButtonBuilder::new()
+        .label("...")
+        .tooltip_text("Edit the list of speeds of the connected focuser type")
+        .build();
+    btn_edit_speeds.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        show_speeds_dialog(&program_data_rc);
+    }));
+    upper_box.pack_start(&btn_edit_speeds, false, false, 0);
+
     let btn_stop = gtk::Button::with_label("stop");
     btn_stop.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| on_stop(&program_data_rc)));
     upper_box.pack_end(&btn_stop, false, false, PADDING);
@@ -277,35 +685,219 @@ pub fn create_focuser_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> Focuser
 
     contents.pack_start(&move_box, false, false, PADDING);
 
+    let rel_move_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    rel_move_box.pack_start(&gtk::Label::new(Some("Move by:")), false, false, PADDING);
+    let rel_move_steps = gtk::SpinButton::with_range(-100_000.0, 100_000.0, 1.0);
+    rel_move_steps.set_digits(0);
+    rel_move_steps.set_value(100.0);
+    rel_move_box.pack_start(&rel_move_steps, false, false, PADDING);
+    let btn_move_rel = gtk::ButtonBuilder::new()
+        .label("move")
+        .tooltip_text("Move focuser by the specified number of steps relative to its current position")
+        .build();
+    btn_move_rel.connect_clicked(clone!(@weak program_data_rc, @weak rel_move_steps => @default-panic, move |_| {
+        focuser_move_rel(rel_move_steps.value_as_int(), &program_data_rc);
+    }));
+    rel_move_box.pack_start(&btn_move_rel, false, false, PADDING);
+    contents.pack_start(&rel_move_box, false, false, PADDING);
+
+    let goto_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    goto_box.pack_start(&gtk::Label::new(Some("Go to:")), false, false, PADDING);
+    let goto_pos = gtk::SpinButton::with_range(i32::MIN as f64, i32::MAX as f64, 1.0);
+    goto_pos.set_digits(0);
+    goto_box.pack_start(&goto_pos, false, false, PADDING);
+    let btn_goto = gtk::ButtonBuilder::new()
+        .label("go")
+        .tooltip_text("Move focuser to the specified absolute position")
+        .build();
+    btn_goto.connect_clicked(clone!(@weak program_data_rc, @weak goto_pos => @default-panic, move |_| {
+        let speed = program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets().selected_speed();
+        focuser_goto(focuser::Position(goto_pos.value_as_int()), speed, &program_data_rc);
+    }));
+    goto_box.pack_start(&btn_goto, false, false, PADDING);
+    goto_box.pack_start(&gtk::Label::new(Some("backlash comp.:")), false, false, PADDING);
+    let goto_overshoot = gtk::SpinButton::with_range(-100_000.0, 100_000.0, 1.0);
+    goto_overshoot.set_digits(0);
+    goto_overshoot.set_value(program_data_rc.borrow().config.focuser_goto_overshoot() as f64);
+    goto_overshoot.set_tooltip_text(Some(
+        "Backlash compensation: steps by which \"go to\" moves overshoot the target, so that it is always \
+        approached from the same direction (set by the sign) with the backlash taken up (0: disabled)"
+    ));
+    goto_overshoot.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |spin| {
+        program_data_rc.borrow().config.set_focuser_goto_overshoot(spin.value_as_int());
+    }));
+    goto_box.pack_start(&goto_overshoot, false, false, PADDING);
+    contents.pack_start(&goto_box, false, false, PADDING);
+
+    let preset_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    let mut preset_steps: Vec<i32> = program_data_rc.borrow().config.focuser_rel_move_steps();
+    preset_steps.sort();
+    let preset_steps: Vec<i32> = preset_steps.iter().rev().map(|s| -s).chain(preset_steps.iter().copied()).collect();
+    for steps in preset_steps {
+        let btn_preset = gtk::ButtonBuilder::new()
+            .label(&format!("{:+}", steps))
+            .tooltip_text(&format!("Move focuser by {} steps", steps))
+            .build();
+        btn_preset.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+            focuser_move_rel(steps, &program_data_rc);
+        }));
+        preset_box.pack_start(&btn_preset, true, true, 0);
+    }
+    contents.pack_start(&preset_box, false, false, PADDING);
+
+    let temp_comp_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    let temp_comp = gtk::CheckButtonBuilder::new()
+        .label("temperature compensation: refocus every")
+        .tooltip_text("Move the focuser automatically when the temperature changes since the last focusing")
+        .active(program_data_rc.borrow().config.focuser_temp_compensation())
+        .build();
+    temp_comp.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |btn| {
+        program_data_rc.borrow().config.set_focuser_temp_compensation(btn.is_active());
+    }));
+    temp_comp_box.pack_start(&temp_comp, false, false, PADDING);
+    let temp_comp_delta = gtk::SpinButton::with_range(0.1, 20.0, 0.1);
+    temp_comp_delta.set_digits(1);
+    temp_comp_delta.set_value(program_data_rc.borrow().config.focuser_temp_compensation_delta());
+    temp_comp_delta.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |spin| {
+        program_data_rc.borrow().config.set_focuser_temp_compensation_delta(spin.value());
+    }));
+    temp_comp_box.pack_start(&temp_comp_delta, false, false, 0);
+    temp_comp_box.pack_start(&gtk::Label::new(Some("°C by")), false, false, PADDING);
+    let temp_comp_steps = gtk::SpinButton::with_range(-10_000.0, 10_000.0, 1.0);
+    temp_comp_steps.set_digits(1);
+    temp_comp_steps.set_value(program_data_rc.borrow().config.focuser_temp_compensation_steps_per_deg());
+    temp_comp_steps.set_tooltip_text(Some("Steps to move per 1 °C of temperature increase (negative: move inwards)"));
+    temp_comp_steps.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |spin| {
+        program_data_rc.borrow().config.set_focuser_temp_compensation_steps_per_deg(spin.value());
+    }));
+    temp_comp_box.pack_start(&temp_comp_steps, false, false, 0);
+    temp_comp_box.pack_start(&gtk::Label::new(Some("steps/°C")), false, false, PADDING);
+    contents.pack_start(&temp_comp_box, false, false, PADDING);
+
     let info_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     info_box.pack_start(&gtk::Label::new(Some("Position:")), false, false, PADDING);
     let position = gtk::Label::new(None);
     info_box.pack_start(&position, false, false, PADDING);
-    // TODO show temperature
+    let motion = gtk::Label::new(None);
+    info_box.pack_start(&motion, false, false, PADDING);
+    let temperature = gtk::LabelBuilder::new().tooltip_text("Focuser temperature").build();
+    info_box.pack_end(&temperature, false, false, PADDING);
     contents.pack_start(&info_box, false, false, PADDING);
 
+    let btn_return = gtk::ButtonBuilder::new()
+        .no_show_all(true)
+        .visible(false)
+        .tooltip_text("Move focuser to the position it had when last disconnected")
+        .build();
+    btn_return.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        focuser_return_to_last_position(&program_data_rc);
+    }));
+    contents.pack_start(&btn_return, false, false, PADDING);
+
+    let position_bar = gtk::ProgressBarBuilder::new()
+        .show_text(true)
+        .no_show_all(true)
+        .visible(false)
+        .tooltip_text("Focuser position within its travel")
+        .build();
+    contents.pack_start(&position_bar, false, false, PADDING);
+
+    contents.set_sensitive(false);
+
+    let wbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    wbox.pack_start(&contents, true, true, 0);
+
     let status_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     let status_label = gtk::LabelBuilder::new().justify(gtk::Justification::Left).label("disconnected").build();
     status_box.pack_start(&status_label, false, false, PADDING);
-    contents.pack_end(&status_box, false, false, PADDING);
-
-    contents.set_sensitive(false);
+    let btn_reconnect = gtk::ButtonBuilder::new()
+        .label("reconnect")
+        .tooltip_text("Disconnect (if connected) and connect again to the last used focuser with the same parameters")
+        .sensitive(false)
+        .build();
+    btn_reconnect.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        reconnect(&program_data_rc);
+    }));
+    status_box.pack_end(&btn_reconnect, false, false, PADDING);
+    wbox.pack_end(&status_box, false, false, PADDING);
 
     FocuserWidgets{
-        wbox: contents,
+        wbox,
+        controls: contents,
         status: status_label,
+        reconnect: btn_reconnect,
         speeds,
         speed_combo,
+        speed_model,
+        speed_range: Cell::new(focuser::SpeedRange{ min: focuser::Speed::one(), max: focuser::Speed::one() }),
         position,
+        position_bar,
+        pending_goto: Cell::new(None),
+        btn_return,
+        last_position: Cell::new(None),
+        pos_range: Cell::new(None),
+        motion,
+        temperature,
+        temp_comp_reference: Cell::new(None),
         refresh_timer: Timer::new(),
-        refresh_stop_timer: Timer::new()
+        refresh_stop_timer: Timer::new(),
+        temperature_timer: Timer::new()
     }
 }
 
 fn on_stop(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    program_data_rc.borrow().gui.as_ref().unwrap().focuser_widgets.pending_goto.set(None);
     if let Err(e) = program_data_rc.borrow_mut().focuser_data.borrow_mut().focuser.as_mut().unwrap().get_mut().stop() {
         log::error!("Failed to stop the focuser: {}.", e);
     }
 
     schedule_refresh_stop(program_data_rc);
 }
+
+/// Lets the user add and remove speeds offered for the connected focuser type.
+fn show_speeds_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let focuser_type = match program_data_rc.borrow().focuser_data.borrow().focuser_type {
+        Some(focuser_type) => focuser_type,
+        None => return
+    };
+
+    let speeds = program_data_rc.borrow().config.focuser_speeds(focuser_type);
+    if let Some(speeds) = show_speed_list_dialog(
+        "Focuser speeds",
+        &format!("Speeds offered for focuser type \"{}\" (multiples of normal speed):", focuser_type.config_name()),
+        speeds,
+        128.0,
+        speed_label,
+        program_data_rc
+    ) {
+        let pd = program_data_rc.borrow();
+        pd.config.set_focuser_speeds(focuser_type, &speeds);
+        pd.gui.as_ref().unwrap().focuser_widgets.set_speeds(&speeds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::focuser::Position;
+
+    #[test]
+    fn target_is_approached_from_overshoot_direction() {
+        // positive overshoot: final approach in the positive direction
+        assert!(overshoot_position(Position(0), Position(500), 100).is_none());
+        assert_eq!(400, overshoot_position(Position(1000), Position(500), 100).unwrap().0);
+
+        // negative overshoot: final approach in the negative direction
+        assert!(overshoot_position(Position(1000), Position(500), -100).is_none());
+        assert_eq!(600, overshoot_position(Position(0), Position(500), -100).unwrap().0);
+
+        assert!(overshoot_position(Position(1000), Position(500), 0).is_none());
+    }
+
+    #[test]
+    fn temperature_drop_triggers_refocus() {
+        assert!(temperature_compensation_steps(10.0, 9.5, 1.0, 20.0).is_none());
+        assert_eq!(Some(-24), temperature_compensation_steps(10.0, 8.8, 1.0, 20.0));
+        assert_eq!(Some(30), temperature_compensation_steps(10.0, 11.5, 1.0, 20.0));
+    }
+}
@@ -10,7 +10,7 @@
 //! Focuser simulator connection GUI.
 //!
 
-use crate::{devices::DeviceConnection, gui::ConnectionCreator};
+use crate::{devices::{DeviceConnection, focuser::FocuserSimulatorParams}, gui::ConnectionCreator};
 use gtk::prelude::*;
 use std::error::Error;
 
@@ -18,11 +18,16 @@ use std::error::Error;
 const PADDING: u32 = 10;
 
 pub struct SimulatorConnectionCreator {
-    dialog_tab: gtk::Box
+    dialog_tab: gtk::Box,
+    unit_speed: gtk::SpinButton,
+    travel: gtk::SpinButton,
+    temperature_drift: gtk::SpinButton
 }
 
 impl SimulatorConnectionCreator {
-    pub fn new(_configuration: &crate::config::Configuration) -> Box<dyn ConnectionCreator> {
+    pub fn new(configuration: &crate::config::Configuration) -> Box<dyn ConnectionCreator> {
+        let params = configuration.focuser_simulator_params();
+
         let dialog_tab = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
         dialog_tab.pack_start(
@@ -32,15 +37,49 @@ impl SimulatorConnectionCreator {
             PADDING
         );
 
-        Box::new(SimulatorConnectionCreator{ dialog_tab })
+        let grid = gtk::GridBuilder::new()
+            .row_spacing(PADDING)
+            .column_spacing(PADDING)
+            .margin(PADDING as i32)
+            .build();
+
+        let mut row = 0;
+        let mut add_spin_btn = |label: &str, value: f64, min: f64, max: f64, step: f64, digits: u32| {
+            let spin_btn = gtk::SpinButton::new(
+                Some(&gtk::Adjustment::new(value, min, max, step, step * 10.0, 0.0)),
+                1.0,
+                digits
+            );
+            grid.attach(&gtk::Label::builder().label(label).halign(gtk::Align::Start).build(), 0, row, 1, 1);
+            grid.attach(&spin_btn, 1, row, 1, 1);
+            row += 1;
+            spin_btn
+        };
+
+        let unit_speed = add_spin_btn("Normal speed (steps/s):", params.unit_speed, 1.0, 100_000.0, 10.0, 0);
+        let travel = add_spin_btn("Travel (steps):", params.travel as f64, 100.0, 1_000_000.0, 100.0, 0);
+        let temperature_drift = add_spin_btn(
+            "Temperature drift (°C/h):", params.temperature_drift as f64, -20.0, 20.0, 0.1, 1
+        );
+
+        dialog_tab.pack_start(&grid, false, false, 0);
+
+        Box::new(SimulatorConnectionCreator{ dialog_tab, unit_speed, travel, temperature_drift })
     }
 }
 
 impl ConnectionCreator for SimulatorConnectionCreator {
     fn controls(&self) -> &gtk::Box { &self.dialog_tab }
 
-    fn create(&self, _configuration: &crate::config::Configuration) -> Result<DeviceConnection, Box<dyn Error>> {
-        Ok(DeviceConnection::FocuserSimulator)
+    fn create(&self, configuration: &crate::config::Configuration) -> Result<DeviceConnection, Box<dyn Error>> {
+        let params = FocuserSimulatorParams{
+            unit_speed: self.unit_speed.value(),
+            travel: self.travel.value_as_int(),
+            temperature_drift: self.temperature_drift.value() as f32
+        };
+
+        configuration.set_focuser_simulator_params(&params);
+        Ok(DeviceConnection::FocuserSimulator{ params })
     }
 
     fn label(&self) -> &'static str { "Simulator" }
@@ -10,7 +10,7 @@
 //! Histogram view widget.
 //!
 
-use crate::workers::histogram::Histogram;
+use crate::workers::histogram::{ChannelStats, Histogram, NUM_BINS_CHOICES};
 use gtk::cairo;
 use glib::clone;
 use gtk::prelude::*;
@@ -23,17 +23,45 @@ const PADDING: u32 = 10;
 pub struct HistogramView {
     top_box: gtk::Box,
     info: gtk::Label,
+    /// Shows the percentages of black- and white-clipped values.
+    clipping: gtk::Label,
+    /// Shows mean, median and standard deviation (per channel for color images).
+    stats: gtk::Label,
+    /// Selects one of `NUM_BINS_CHOICES`.
+    num_bins: gtk::ComboBoxText,
     drawing_area: gtk::DrawingArea,
     histogram: Rc<RefCell<Histogram>>
 }
 
 impl HistogramView {
-    pub fn new() -> HistogramView {
+    pub fn new(num_bins: usize) -> HistogramView {
         let top_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
+        let info_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
         let info = gtk::Label::new(None);
         info.set_xalign(0.1);
-        top_box.pack_start(&info, false, false, PADDING);
+        info_box.pack_start(&info, true, true, PADDING);
+
+        let clipping = gtk::LabelBuilder::new()
+            .tooltip_text("Percentage of pixel values at zero (black) and at maximum (white)")
+            .build();
+        info_box.pack_start(&clipping, false, false, PADDING);
+
+        let num_bins_combo = gtk::ComboBoxTextBuilder::new().tooltip_text("Number of histogram bins").build();
+        for choice in &NUM_BINS_CHOICES {
+            num_bins_combo.append_text(&format!("{} bins", choice));
+        }
+        num_bins_combo.set_active(Some(NUM_BINS_CHOICES.iter().position(|n| *n == num_bins).unwrap_or(0) as u32));
+        info_box.pack_end(&num_bins_combo, false, false, PADDING);
+
+        top_box.pack_start(&info_box, false, false, PADDING);
+
+        let stats = gtk::LabelBuilder::new()
+            .xalign(0.0)
+            .margin_start(PADDING as i32)
+            .tooltip_text("Statistics of the histogram area (as percentage of the full range)")
+            .build();
+        top_box.pack_start(&stats, false, false, 0);
 
         let histogram = Rc::new(RefCell::new(Histogram::new()));
         let drawing_area = gtk::DrawingAreaBuilder::new().app_paintable(true).build();
@@ -46,6 +74,9 @@ impl HistogramView {
         HistogramView{
             top_box: top_box,
             info,
+            clipping,
+            stats,
+            num_bins: num_bins_combo,
             drawing_area,
             histogram
         }
@@ -53,6 +84,11 @@ impl HistogramView {
 
     pub fn top_widget(&self) -> &gtk::Box { &self.top_box }
 
+    /// Returns the selected number of bins (8-bit images use at most 256).
+    pub fn num_bins(&self) -> usize {
+        NUM_BINS_CHOICES[self.num_bins.active().unwrap_or(0) as usize]
+    }
+
     pub fn set_histogram(&mut self, histogram: Histogram) {
 
         let first_nonzero_idx: Option<usize> = histogram.values()
@@ -76,20 +112,50 @@ impl HistogramView {
             self.info.set_text("");
         }
 
+        match histogram.clipping() {
+            Some(clipping) => self.clipping.set_text(&format!(
+                "black clip: {:.2}%  white clip: {:.2}%", clipping.black * 100.0, clipping.white * 100.0
+            )),
+            None => self.clipping.set_text("")
+        }
+
+        let channels: &[(usize, &str)] = if histogram.is_rgb() {
+            &[(0, "R: "), (1, "G: "), (2, "B: ")]
+        } else {
+            &[(0, "")]
+        };
+        let stats_lines: Vec<String> = channels.iter()
+            .filter_map(|(ch, prefix)| histogram.channel_stats(*ch).map(|s| format!("{}{}", prefix, stats_text(&s))))
+            .collect();
+        self.stats.set_text(&stats_lines.join("\n"));
+
         *self.histogram.borrow_mut() = histogram;
         self.refresh();
     }
 
+    /// Returns `None` if no histogram has been calculated yet.
+    pub fn histogram_csv(&self) -> Option<String> {
+        let histogram = self.histogram.borrow();
+        if histogram.captured().is_some() { Some(histogram.to_csv()) } else { None }
+    }
+
     pub fn refresh(&self) {
         self.drawing_area.queue_draw();
     }
 }
 
+fn stats_text(stats: &ChannelStats) -> String {
+    format!(
+        "mean: {:.1}%  median: {:.1}%  σ: {:.2}%",
+        stats.mean * 100.0, stats.median * 100.0, stats.std_dev * 100.0
+    )
+}
+
 fn draw_histogram_rgb_values(
     histogram: &Histogram,
     log_scale: bool,
     ctx: &cairo::Context,
-    log_values: &[[f32; 3]; 256],
+    log_values: &[[f32; 3]],
     width: i32,
     height: i32,
     num_buckets: usize,
@@ -155,7 +221,7 @@ fn draw_histogram_mono_values(
     histogram: &Histogram,
     log_scale: bool,
     ctx: &cairo::Context,
-    log_values: &[[f32; 3]; 256],
+    log_values: &[[f32; 3]],
     width: i32,
     height: i32,
     num_buckets: usize,
@@ -188,9 +254,9 @@ fn draw_histogram(histogram: &Histogram, log_scale: bool, d_area: &gtk::DrawingA
 
     let max_value = *histogram.values().iter().map(|x| x.iter().max().unwrap()).max().unwrap().max(&1);
 
-    let mut log_values = [[0.0f32; 3]; 256];
+    let mut log_values = vec![[0.0f32; 3]; histogram.values().len()];
     if max_value > 1 {
-        for i in 0..256 {
+        for i in 0..log_values.len() {
             for j in 0..3 {
                 log_values[i][j] = (histogram.values()[i][j].max(1) as f32).log2() / (max_value as f32).log2();
             }
@@ -21,15 +21,20 @@ use std::rc::Rc;
 struct State {
     image: Option<cairo::ImageSurface>,
     zoom: f64,
+    /// If false, nearest-neighbor interpolation is used when zoomed in (so that pixels stay crisp).
+    smooth_zoom: bool,
     drag_start_pos: Option<(f64, f64)>,
     stabilization_offset: Vector2<i32>
 }
 
 /// Displays an image (stored in `state`) and handles scrolling with right mouse button and zooming with mouse wheel.
+#[derive(Clone)]
 pub struct ImgView {
     top_widget: gtk::ScrolledWindow,
     drawing_area: gtk::DrawingArea,
-    state: Rc<RefCell<State>>
+    state: Rc<RefCell<State>>,
+    draw_info_overlay: Rc<Box<dyn Fn(&cairo::Context, f64)>>,
+    draw_reticle: Rc<Box<dyn Fn(&cairo::Context, f64)>>
 }
 
 impl ImgView {
@@ -45,14 +50,17 @@ impl ImgView {
     /// * `on_mouse_move` - Called on mouse move; receives image coordinates (scrolling and zoom are applied).
     /// * `draw_info_overlay` - Called after the image is drawn. Receives drawing context and zoom value.
     /// * `draw_reticle` - Called after the image is drawn. Receives drawing context having its origin in the middle
-    ///    of the captured image.
+    ///    of the captured image and zoom value.
+    /// * `on_arrow_key` - Called on arrow key press while the view has focus (it is focused by clicking);
+    ///   receives the direction (one pixel long). Returns true if the key has been handled.
     ///
     pub fn new(
         on_button_down: Box<dyn Fn(Point2<i32>)>,
         on_button_up: Box<dyn Fn(Point2<i32>)>,
         on_mouse_move: Box<dyn Fn(Point2<i32>)>,
         draw_info_overlay: Box<dyn Fn(&cairo::Context, f64)>,
-        draw_reticle: Box<dyn Fn(&cairo::Context)>
+        draw_reticle: Box<dyn Fn(&cairo::Context, f64)>,
+        on_arrow_key: Box<dyn Fn(Vector2<i32>) -> bool>
     ) -> ImgView {
         let top_widget = gtk::ScrolledWindow::new::<gtk::Adjustment, gtk::Adjustment>(None, None);
         let drawing_area = gtk::DrawingAreaBuilder::new().app_paintable(true).build();
@@ -63,6 +71,7 @@ impl ImgView {
         let state = Rc::new(RefCell::new(State{
             image: None,
             zoom: 1.0,
+            smooth_zoom: true,
             drag_start_pos: None,
             stabilization_offset: Vector2::zero()
         }));
@@ -71,10 +80,26 @@ impl ImgView {
             gdk::EventMask::POINTER_MOTION_MASK |
             gdk::EventMask::BUTTON_PRESS_MASK |
             gdk::EventMask::BUTTON_RELEASE_MASK |
-            gdk::EventMask::SCROLL_MASK
+            gdk::EventMask::SCROLL_MASK |
+            gdk::EventMask::KEY_PRESS_MASK
         );
+        evt_box.set_can_focus(true);
+
+        evt_box.connect_key_press_event(move |_, evt| {
+            let direction = match evt.keyval() {
+                gdk::keys::constants::Left => Vector2{ x: -1, y: 0 },
+                gdk::keys::constants::Right => Vector2{ x: 1, y: 0 },
+                gdk::keys::constants::Up => Vector2{ x: 0, y: -1 },
+                gdk::keys::constants::Down => Vector2{ x: 0, y: 1 },
+                _ => return gtk::Inhibit(false)
+            };
+
+            gtk::Inhibit(on_arrow_key(direction))
+        });
+
+        evt_box.connect_button_press_event(clone!(@weak state => @default-panic, move |evt_box, evt| {
+            evt_box.grab_focus();
 
-        evt_box.connect_button_press_event(clone!(@weak state => @default-panic, move |_, evt| {
             if evt.button() == MOUSE_BUTTON_LEFT {
                 let image_pos = {
                     let (pos_x, pos_y) = evt.position();
@@ -147,14 +172,25 @@ impl ImgView {
             }
         ));
 
-        drawing_area.connect_draw(clone!(@weak state, @weak top_widget => @default-panic, move |_, ctx| {
-            on_draw(&state, ctx, &draw_info_overlay, &draw_reticle)
-        }));
+        let draw_info_overlay = Rc::new(draw_info_overlay);
+        let draw_reticle = Rc::new(draw_reticle);
+
+        drawing_area.connect_draw(clone!(
+            @weak state,
+            @strong draw_info_overlay,
+            @strong draw_reticle
+            => @default-panic,
+            move |_, ctx| {
+                on_draw(&state, ctx, &draw_info_overlay, &draw_reticle)
+            }
+        ));
 
         ImgView{
             top_widget,
             drawing_area,
-            state
+            state,
+            draw_info_overlay,
+            draw_reticle
         }
     }
 
@@ -179,6 +215,12 @@ impl ImgView {
         }
     }
 
+    /// Selects smooth (bilinear) or nearest-neighbor interpolation used when zoomed in.
+    pub fn set_smooth_zoom(&self, smooth: bool) {
+        self.state.borrow_mut().smooth_zoom = smooth;
+        self.refresh();
+    }
+
     pub fn change_zoom(&mut self, factor: f64) {
         let zoom = self.state.borrow().zoom;
         self.set_zoom(zoom * factor);
@@ -208,6 +250,40 @@ impl ImgView {
         }
     }
 
+    /// Returns the currently displayed image (without overlays).
+    pub fn image_as_pixbuf(&self) -> Option<gtk::gdk_pixbuf::Pixbuf> {
+        match &self.state.borrow().image {
+            Some(image) => gdk::pixbuf_get_from_surface(image, 0, 0, image.width(), image.height()),
+            None => None
+        }
+    }
+
+    /// Renders the current image (using the current zoom) together with info overlay and reticle.
+    ///
+    /// Must not be called while `ProgramData` is borrowed (the overlay callbacks borrow it).
+    ///
+    pub fn render_with_overlays(&self) -> Option<cairo::ImageSurface> {
+        let (width, height) = {
+            let state = self.state.borrow();
+            match &state.image {
+                Some(image) => (
+                    (image.width() as f64 * state.zoom) as i32,
+                    (image.height() as f64 * state.zoom) as i32
+                ),
+                None => return None
+            }
+        };
+
+        let surface = cairo::ImageSurface::create(cairo::Format::Rgb24, width, height).ok()?;
+        {
+            let ctx = cairo::Context::new(&surface).ok()?;
+            on_draw(&self.state, &ctx, &self.draw_info_overlay, &self.draw_reticle);
+        }
+        surface.flush();
+
+        Some(surface)
+    }
+
     pub fn scroll_pos(&self) -> Point2<i32> {
         Point2{
             x: self.top_widget.hadjustment().value() as i32,
@@ -220,7 +296,7 @@ fn on_draw(
     state: &Rc<RefCell<State>>,
     ctx: &cairo::Context,
     draw_info_overlay: &Box<dyn Fn(&cairo::Context, f64)>,
-    draw_reticle: &Box<dyn Fn(&cairo::Context)>
+    draw_reticle: &Box<dyn Fn(&cairo::Context, f64)>
 ) -> glib::signal::Inhibit {
     let state = state.borrow();
 
@@ -241,7 +317,11 @@ fn on_draw(
                 matrix.scale(1.0 / state.zoom, 1.0 / state.zoom);
                 matrix
             });
-            source.set_filter(cairo::Filter::Bilinear);
+            source.set_filter(if state.zoom > 1.0 && !state.smooth_zoom {
+                cairo::Filter::Nearest
+            } else {
+                cairo::Filter::Bilinear
+            });
             ctx.set_source(&source).unwrap();
             //TODO: redraw only the invalidated areas (use `copy_clip_rectangle_list`)
             ctx.rectangle(
@@ -257,7 +337,7 @@ fn on_draw(
                 state.zoom * surface.width() as f64 / 2.0,
                 state.zoom * surface.height() as f64 / 2.0
            );
-           draw_reticle(ctx);
+           draw_reticle(ctx, state.zoom);
         },
         None => ()
     }
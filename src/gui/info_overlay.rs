@@ -10,7 +10,7 @@
 //! Informational overlay.
 //!
 
-use cgmath::{InnerSpace, Point2};
+use cgmath::{InnerSpace, Point2, Vector2};
 use crate::{gui::MouseMode, MountCalibration, ProgramData, TrackingMode};
 use ga_image::Rect;
 use gtk::cairo;
@@ -20,12 +20,27 @@ const INFO_OVERLAY_LABEL_OFFSET: i32 = 5;
 
 const GUIDING_POS_CIRCLE_R: f64 = 12.0;
 
+/// Magnification of the channel offsets shown by the dispersion indicator.
+const DISPERSION_INDICATOR_SCALE: f64 = 20.0;
+
 const GUIDING_BLINK_ON: std::time::Duration = std::time::Duration::from_millis(200);
 const GUIDING_BLINK_OFF: std::time::Duration = std::time::Duration::from_millis(500);
 
 /// Size (in pixels) of the font used in the informational overlay.
 const DEFAULT_INFO_OVERLAY_FONT_SIZE: f64 = 10.0;
 
+/// RGB color of the informational overlay and reticle.
+pub type OverlayColor = [f64; 3];
+
+pub const DEFAULT_OVERLAY_COLOR: OverlayColor = [1.0, 0.0, 0.0];
+
+/// Overlay color presets (label, color).
+pub const OVERLAY_COLOR_PRESETS: [(&str, OverlayColor); 3] = [
+    ("Red", DEFAULT_OVERLAY_COLOR),
+    ("Green (for use with red-light night vision)", [0.0, 1.0, 0.0]),
+    ("Yellow (high contrast)", [1.0, 1.0, 0.0])
+];
+
 enum SelectionType {
     Line,
     Rectangle,
@@ -51,19 +66,29 @@ pub struct InfoOverlay {
     pub screen_sel: Option<ScreenSelection>,
     last_guiding_blink_change: Option<std::time::Instant>,
     guiding_blink_state: Option<bool>,
+    /// Also used for the reticle.
+    pub color: OverlayColor
 }
 
 impl InfoOverlay {
-    pub fn new() -> InfoOverlay {
+    pub fn new(color: OverlayColor) -> InfoOverlay {
         InfoOverlay{
             enabled: true,
             screen_sel: None,
             last_guiding_blink_change: None,
-            guiding_blink_state: None
+            guiding_blink_state: None,
+            color
         }
     }
 }
 
+pub fn set_overlay_color(color: OverlayColor, program_data: &mut ProgramData) {
+    program_data.config.set_overlay_color(color);
+    let gui = program_data.gui.as_mut().unwrap();
+    gui.info_overlay.color = color;
+    gui.preview_area.refresh();
+}
+
 pub fn draw_info_overlay(
     ctx: &cairo::Context,
     zoom: f64,
@@ -74,17 +99,21 @@ pub fn draw_info_overlay(
     ctx.set_antialias(cairo::Antialias::None);
 
     let font_size = program_data.config.info_overlay_font_size().unwrap_or(DEFAULT_INFO_OVERLAY_FONT_SIZE);
+    let color = program_data.gui.as_ref().unwrap().info_overlay.color;
 
     if let Some(sel) = &program_data.gui.as_ref().unwrap().info_overlay.screen_sel {
         draw_screen_selection(
-            ctx, zoom, sel, SelectionType::from_mouse_mode(program_data.gui.as_ref().unwrap().mouse_mode), font_size
+            ctx, zoom, sel, SelectionType::from_mouse_mode(program_data.gui.as_ref().unwrap().mouse_mode), font_size, color
         );
     }
 
     if let Some(tracking) = &program_data.tracking {
         match tracking.mode {
-            TrackingMode::Centroid(rect) => draw_centroid_rect(ctx, rect, zoom, font_size),
-            TrackingMode::Anchor(pos) => draw_anchor(ctx, pos, zoom)
+            TrackingMode::Centroid(rect) => {
+                let snr = if program_data.config.show_guide_star_snr() { tracking.snr } else { None };
+                draw_centroid_rect(ctx, rect, snr, zoom, font_size, color)
+            },
+            TrackingMode::Anchor(pos) => draw_anchor(ctx, pos, zoom, color)
         }
 
         draw_tracking_target_pos(ctx, zoom, tracking.pos);
@@ -115,25 +144,78 @@ pub fn draw_info_overlay(
                 info_overlay.last_guiding_blink_change = None;
             }
 
-            draw_guiding_info(ctx, zoom, *guiding_pos, tracking.pos, info_overlay.guiding_blink_state.unwrap_or(false));
+            draw_guiding_info(
+                ctx, zoom, *guiding_pos, tracking.pos, info_overlay.guiding_blink_state.unwrap_or(false), color
+            );
         }
 
         if let Some(calibration) = &program_data.mount_data.calibration {
-            draw_calibration(ctx, zoom, calibration, tracking.pos)
+            draw_calibration(ctx, zoom, calibration, tracking.pos, color)
         }
     }
 
     if let Some(rect) = &program_data.crop_area {
-        draw_crop_area(ctx, zoom, font_size, *rect);
+        draw_crop_area(ctx, zoom, font_size, *rect, color);
     }
 
     if let Some(rect) = &program_data.histogram_area {
-        draw_histogram_area(ctx, zoom, font_size, *rect);
+        draw_histogram_area(ctx, zoom, font_size, *rect, color);
+    }
+
+    let gui = program_data.gui.as_ref().unwrap();
+    if let Some((red_offset, blue_offset)) = gui.dispersion_dialog.preview_indicator() {
+        // the dispersion is measured in the centroid tracking area (if any) or in the whole image
+        let pos = match program_data.tracking.as_ref().map(|tracking| &tracking.mode) {
+            Some(TrackingMode::Centroid(rect)) => Some(Point2{
+                x: rect.x as f64 + rect.width as f64 / 2.0,
+                y: rect.y as f64 + rect.height as f64 / 2.0
+            }),
+            _ => gui.preview_area.image_size().map(|(width, height)| Point2{ x: width as f64 / 2.0, y: height as f64 / 2.0 })
+        };
+        if let Some(pos) = pos {
+            draw_dispersion_indicator(ctx, zoom, font_size, pos, red_offset, blue_offset, color);
+        }
+    }
+}
+
+fn draw_dispersion_indicator(
+    ctx: &cairo::Context,
+    zoom: f64,
+    font_size: f64,
+    pos: Point2<f64>,
+    red_offset: Vector2<f64>,
+    blue_offset: Vector2<f64>,
+    color: OverlayColor
+) {
+    let x = pos.x * zoom;
+    let y = pos.y * zoom;
+
+    ctx.set_dash(&[], 0.0);
+    ctx.set_line_width(2.0);
+    for (offset, rgb) in [(red_offset, [1.0, 0.0, 0.0]), (blue_offset, [0.3, 0.3, 1.0])] {
+        ctx.set_source_rgb(rgb[0], rgb[1], rgb[2]);
+        ctx.move_to(x, y);
+        ctx.rel_line_to(offset.x * DISPERSION_INDICATOR_SCALE, offset.y * DISPERSION_INDICATOR_SCALE);
+        ctx.stroke().unwrap();
     }
+
+    let separation = red_offset - blue_offset;
+    ctx.set_source_rgb(color[0], color[1], color[2]);
+    ctx.move_to(x + INFO_OVERLAY_LABEL_OFFSET as f64, y + 2.0 * font_size);
+    ctx.set_font_size(font_size);
+    ctx.show_text(&format!("R-B {:.2} px (vert. {:.2} px)", separation.magnitude(), separation.y.abs())).unwrap();
+    ctx.fill().unwrap();
 }
 
-fn draw_guiding_info(ctx: &cairo::Context, zoom: f64, guiding_pos: Point2<i32>, tracking_pos: Point2<i32>, blink_on: bool) {
-    ctx.set_source_rgb(1.0, 0.0, 0.0);
+fn draw_guiding_info(
+    ctx: &cairo::Context,
+    zoom: f64,
+    guiding_pos: Point2<i32>,
+    tracking_pos: Point2<i32>,
+    blink_on: bool,
+    color: OverlayColor
+) {
+    ctx.set_source_rgb(color[0], color[1], color[2]);
     ctx.set_line_width(1.0);
     ctx.set_dash(&[6.0, 4.0], 0.0);
     ctx.arc(guiding_pos.x as f64 * zoom, guiding_pos.y as f64 * zoom, GUIDING_POS_CIRCLE_R, 0.0, 2.0 * std::f64::consts::PI);
@@ -152,7 +234,8 @@ fn draw_screen_selection(
     zoom: f64,
     sel: &ScreenSelection,
     sel_type: SelectionType,
-    font_size: f64
+    font_size: f64,
+    color: OverlayColor
 ) {
     let x1 = sel.start.x as f64 * zoom;
     let y1 = sel.start.y as f64 * zoom;
@@ -165,7 +248,7 @@ fn draw_screen_selection(
             let pos_y = y1.min(y2);
             let width =  (x1 - x2).abs();
             let height = (y1 - y2).abs();
-            ctx.set_source_rgba(1.0, 0.0, 0.0, 0.5);
+            ctx.set_source_rgba(color[0], color[1], color[2], 0.5);
             ctx.rectangle(pos_x, pos_y, width, height);
             ctx.fill().unwrap();
 
@@ -180,9 +263,9 @@ fn draw_screen_selection(
         SelectionType::Line => {
            ctx.move_to(x1, y1);
            ctx.line_to(x2, y2);
-           ctx.set_source_rgba(1.0, 0.0, 0.0, 0.5);
+           ctx.set_source_rgba(color[0], color[1], color[2], 0.5);
            let _ = ctx.stroke();
-           ctx.set_source_rgba(1.0, 0.0, 0.0, 1.0);
+           ctx.set_source_rgba(color[0], color[1], color[2], 1.0);
            ctx.move_to(x1 + font_size / 2.0, y1 - font_size / 2.0);
            ctx.set_font_size(font_size);
            let dist = (sel.start.cast::<f64>().unwrap() - sel.end.cast::<f64>().unwrap()).magnitude();
@@ -191,11 +274,17 @@ fn draw_screen_selection(
     }
 }
 
-fn draw_calibration(ctx: &cairo::Context, zoom: f64, calibration: &MountCalibration, target_pos: Point2<i32>) {
+fn draw_calibration(
+    ctx: &cairo::Context,
+    zoom: f64,
+    calibration: &MountCalibration,
+    target_pos: Point2<i32>,
+    color: OverlayColor
+) {
     if calibration.primary_dir.is_some() && calibration.secondary_dir.is_some() { return; }
 
     ctx.set_line_width(1.0);
-    ctx.set_source_rgb(1.0, 0.0, 0.0);
+    ctx.set_source_rgb(color[0], color[1], color[2]);
 
     let origin = (calibration.origin.x as f64 * zoom, calibration.origin.y as f64 * zoom);
     ctx.arc(origin.0, origin.1, 2.5, 0.0, 2.0 * std::f64::consts::PI);
@@ -207,9 +296,9 @@ fn draw_calibration(ctx: &cairo::Context, zoom: f64, calibration: &MountCalibrat
     ctx.stroke().unwrap();
 }
 
-fn draw_crop_area(ctx: &cairo::Context, zoom: f64, font_size: f64, area: Rect) {
+fn draw_crop_area(ctx: &cairo::Context, zoom: f64, font_size: f64, area: Rect, color: OverlayColor) {
     ctx.set_line_width(1.0);
-    ctx.set_source_rgb(1.0, 0.0, 0.0);
+    ctx.set_source_rgb(color[0], color[1], color[2]);
     ctx.rectangle(
         area.x as f64 * zoom,
         area.y as f64 * zoom,
@@ -225,9 +314,9 @@ fn draw_crop_area(ctx: &cairo::Context, zoom: f64, font_size: f64, area: Rect) {
     ctx.fill().unwrap();
 }
 
-fn draw_histogram_area(ctx: &cairo::Context, zoom: f64, font_size: f64, area: Rect) {
+fn draw_histogram_area(ctx: &cairo::Context, zoom: f64, font_size: f64, area: Rect, color: OverlayColor) {
     ctx.set_line_width(1.0);
-    ctx.set_source_rgb(1.0, 0.0, 0.0);
+    ctx.set_source_rgb(color[0], color[1], color[2]);
     ctx.rectangle(
         area.x as f64 * zoom,
         area.y as f64 * zoom,
@@ -261,9 +350,9 @@ fn draw_tracking_target_pos(ctx: &cairo::Context, zoom: f64, pos: Point2<i32>) {
     ctx.stroke().unwrap();
 }
 
-fn draw_centroid_rect(ctx: &cairo::Context, rect: Rect, zoom: f64, font_size: f64) {
+fn draw_centroid_rect(ctx: &cairo::Context, rect: Rect, snr: Option<f64>, zoom: f64, font_size: f64, color: OverlayColor) {
     ctx.set_line_width(1.0);
-    ctx.set_source_rgb(1.0, 0.0, 0.0);
+    ctx.set_source_rgb(color[0], color[1], color[2]);
     ctx.rectangle(
         rect.x as f64 * zoom,
         rect.y as f64 * zoom,
@@ -275,13 +364,16 @@ fn draw_centroid_rect(ctx: &cairo::Context, rect: Rect, zoom: f64, font_size: f6
 
     ctx.move_to(rect.x as f64 * zoom, rect.y as f64 * zoom - INFO_OVERLAY_LABEL_OFFSET as f64);
     ctx.set_font_size(font_size);
-    ctx.show_text("CENTROID").unwrap();
+    match snr {
+        Some(snr) => ctx.show_text(&format!("CENTROID  SNR {:.1}", snr)).unwrap(),
+        None => ctx.show_text("CENTROID").unwrap()
+    }
     ctx.fill().unwrap();
 }
 
-fn draw_anchor(ctx: &cairo::Context, pos: Point2<i32>, zoom: f64) {
+fn draw_anchor(ctx: &cairo::Context, pos: Point2<i32>, zoom: f64, color: OverlayColor) {
     ctx.set_line_width(1.0);
-    ctx.set_source_rgb(1.0, 0.0, 0.0);
+    ctx.set_source_rgb(color[0], color[1], color[2]);
     ctx.arc(pos.x as f64 * zoom, pos.y as f64 * zoom, 32.0, 0.0, 6.0);
     ctx.stroke().unwrap();
 }
\ No newline at end of file
@@ -10,14 +10,17 @@
 //! GUI initialization.
 //!
 
-use cgmath::{EuclideanSpace, Point2};
+use cgmath::{EuclideanSpace, Point2, Vector2, Zero};
 use crate::{
     gui::{
         actions,
+        auto_exposure_dialog,
+        camera_defaults_dialog,
         camera_gui,
         create_preview_processing_dialog,
         create_reticle_dialog,
         Decibel,
+        DialogDestroyer,
         disconnect_camera,
         DispersionDialog,
         draw_info_overlay,
@@ -27,25 +30,35 @@ use crate::{
         GuiData,
         HistogramView,
         img_view::ImgView,
+        info_overlay,
         InfoOverlay,
+        initiate_capture_pause_action,
         mount_gui,
         MouseMode,
         PADDING,
         PreviewProcessing,
         PsfDialog,
+        raw_features_dialog,
         rec_gui,
+        remote_control,
         Reticle,
+        shortcuts,
         show_about_dialog,
+        status_bar,
+        style,
         show_custom_zoom_dialog,
         show_message,
         Stabilization,
         StatusBarFields,
         ZOOM_CHANGE_FACTOR,
     },
-    MainToCaptureThreadMsg,
+    i18n,
+    i18n::tr,
     OnCapturePauseAction,
     ProgramData,
     resources,
+    timer::Timer,
+    workers::mjpeg_server,
 };
 #[cfg(feature = "controller")]
 use crate::gui::{ControllerDialog, controller::init_controller_menu};
@@ -59,20 +72,79 @@ mod gtk_signals {
     pub const ACTIVATE: &'static str = "activate";
 }
 
+/// Returns the menu selecting how often the preview is refreshed while paused.
+fn create_paused_preview_refresh_menu(program_data_rc: &Rc<RefCell<ProgramData>>) -> gtk::Menu {
+    const INTERVALS_S: [u64; 4] = [1, 5, 10, 30];
+
+    let current = program_data_rc.borrow().config.paused_preview_refresh_interval();
+
+    let menu = gtk::Menu::new();
+    let never_item = gtk::RadioMenuItem::with_label("Never");
+    menu.append(&never_item);
+    let mut items = vec![(None, never_item.clone())];
+    for interval in INTERVALS_S {
+        let item = gtk::RadioMenuItem::with_label_from_widget(&never_item, Some(&format!("Every {} s", interval)));
+        menu.append(&item);
+        items.push((Some(std::time::Duration::from_secs(interval)), item));
+    }
+
+    for (interval, item) in items {
+        item.set_active(interval == current);
+        item.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+            if !item.is_active() { return; }
+            program_data_rc.borrow().config.set_paused_preview_refresh_interval(interval);
+        }));
+    }
+
+    menu
+}
+
+/// Returns the user interface language selection menu; the change takes effect after restart.
+fn create_language_menu(program_data_rc: &Rc<RefCell<ProgramData>>) -> gtk::Menu {
+    let current = program_data_rc.borrow().config.language();
+
+    let menu = gtk::Menu::new();
+    let automatic_item = gtk::RadioMenuItem::with_label(tr("Automatic"));
+    menu.append(&automatic_item);
+    let mut items = vec![("", automatic_item.clone())];
+    for language in i18n::LANGUAGES {
+        let item = gtk::RadioMenuItem::with_label_from_widget(&automatic_item, Some(language.name));
+        menu.append(&item);
+        items.push((language.code, item));
+    }
+
+    for (code, item) in items {
+        item.set_active(code == current);
+        item.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+            if !item.is_active() { return; }
+            program_data_rc.borrow().config.set_language(code);
+            show_message(
+                tr("Restart the program to change the language."),
+                tr("Language"),
+                gtk::MessageType::Info,
+                &program_data_rc
+            );
+        }));
+    }
+
+    menu
+}
+
 /// Returns (menu bar, camera menu, camera menu items).
 fn init_menu(
     window: &gtk::ApplicationWindow,
+    controls_notebook: &gtk::Notebook,
     program_data_rc: &Rc<RefCell<ProgramData>>
 ) -> (gtk::MenuBar, gtk::Menu, Vec<(gtk::CheckMenuItem, glib::SignalHandlerId)>) {
     let accel_group = gtk::AccelGroup::new();
     window.add_accel_group(&accel_group);
 
-    let about_item = gtk::MenuItem::with_label("About");
+    let about_item = gtk::MenuItem::with_label(tr("About"));
     about_item.connect_activate(
         clone!(@weak program_data_rc => @default-panic, move |_| show_about_dialog(&program_data_rc))
     );
 
-    let quit_item = gtk::MenuItem::with_label("Quit");
+    let quit_item = gtk::MenuItem::with_label(tr("Quit"));
     quit_item.connect_activate(clone!(@weak window => @default-panic, move |_| {
         window.close();
     }));
@@ -82,11 +154,58 @@ fn init_menu(
     let (key, modifier) = gtk::accelerator_parse("<Primary>Q");
     quit_item.add_accelerator(gtk_signals::ACTIVATE, &accel_group, key, modifier, gtk::AccelFlags::VISIBLE);
 
+    let shortcuts_item = gtk::MenuItem::with_label(tr("Keyboard shortcuts..."));
+    shortcuts_item.connect_activate(
+        clone!(@weak program_data_rc => @default-panic, move |_| shortcuts::show_shortcuts_dialog(&program_data_rc))
+    );
+
+    let large_buttons_item = gtk::CheckMenuItem::with_label("Large buttons (touchscreen)");
+    large_buttons_item.set_active(program_data_rc.borrow().config.large_buttons());
+    large_buttons_item.connect_toggled(clone!(
+        @weak program_data_rc,
+        @weak controls_notebook
+        => @default-panic, move |item| {
+            style::set_large_buttons(&controls_notebook, item.is_active());
+            program_data_rc.borrow().config.set_large_buttons(item.is_active());
+        }
+    ));
+
+    let night_mode_item = gtk::CheckMenuItem::with_label("Night mode (red)");
+    night_mode_item.set_active(program_data_rc.borrow().config.night_mode());
+    night_mode_item.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        let pd = program_data_rc.borrow();
+        pd.gui.as_ref().unwrap().style.set_night_mode(item.is_active());
+        pd.config.set_night_mode(item.is_active());
+    }));
+
+    let status_bar_item = gtk::MenuItem::with_label("Status bar...");
+    status_bar_item.connect_activate(
+        clone!(@weak program_data_rc => @default-panic, move |_| status_bar::show_status_bar_dialog(&program_data_rc))
+    );
+
+    let remote_control_item = gtk::CheckMenuItem::with_label("Remote control API");
+    remote_control_item.set_tooltip_text(Some(&format!(
+        "Accept HTTP requests to start/stop recording, take snapshots and query status at http://{}/",
+        program_data_rc.borrow().config.remote_control_address()
+    )));
+    remote_control_item.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        remote_control::on_toggle_remote_control(item, &program_data_rc);
+    }));
+
+    let language_item = gtk::MenuItem::with_label(tr("Language"));
+    language_item.set_submenu(Some(&create_language_menu(program_data_rc)));
+
     let file_menu = gtk::Menu::new();
     file_menu.append(&about_item);
+    file_menu.append(&shortcuts_item);
+    file_menu.append(&status_bar_item);
+    file_menu.append(&large_buttons_item);
+    file_menu.append(&night_mode_item);
+    file_menu.append(&remote_control_item);
+    file_menu.append(&language_item);
     file_menu.append(&quit_item);
 
-    let file_menu_item = gtk::MenuItem::with_label("File");
+    let file_menu_item = gtk::MenuItem::with_label(tr("File"));
     file_menu_item.set_submenu(Some(&file_menu));
 
     let menu_bar = gtk::MenuBar::new();
@@ -136,11 +255,41 @@ fn init_preview_menu(
     let menu = gtk::Menu::new();
 
     let snapshot = gtk::MenuItem::with_label("Take snapshot");
+    // shortcut is handled by `event_handlers::on_main_window_key_press` (see `shortcuts`)
     snapshot.set_action_name(Some(&actions::prefixed(actions::TAKE_SNAPSHOT)));
-    let (key, modifier) = gtk::accelerator_parse("F12");
-    snapshot.add_accelerator(gtk_signals::ACTIVATE, accel_group, key, modifier, gtk::AccelFlags::VISIBLE);
     menu.append(&snapshot);
 
+    let copy_to_clipboard = gtk::MenuItem::with_label("Copy to clipboard");
+    copy_to_clipboard.set_action_name(Some(&actions::prefixed(actions::COPY_TO_CLIPBOARD)));
+    let (key, modifier) = gtk::accelerator_parse("<Primary><Shift>C");
+    copy_to_clipboard.add_accelerator(gtk_signals::ACTIVATE, accel_group, key, modifier, gtk::AccelFlags::VISIBLE);
+    menu.append(&copy_to_clipboard);
+
+    let export_view = gtk::MenuItem::with_label("Export view...");
+    export_view.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        event_handlers::on_export_view(&program_data_rc);
+    }));
+    menu.append(&export_view);
+
+    let save_histogram = gtk::MenuItem::with_label("Save histogram...");
+    save_histogram.set_action_name(Some(&actions::prefixed(actions::SAVE_HISTOGRAM)));
+    menu.append(&save_histogram);
+
+    let pause_preview = gtk::CheckMenuItem::with_label("Pause preview");
+    pause_preview.set_tooltip_text(Some(
+        "Keep showing the current image (capture and recording continue); \
+        measuring and selecting areas remain possible"
+    ));
+    pause_preview.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        program_data_rc.borrow_mut().preview_paused = item.is_active();
+        log::info!("preview {}", if item.is_active() { "paused" } else { "resumed" });
+    }));
+    menu.append(&pause_preview);
+
+    let paused_refresh = gtk::MenuItem::with_label("Refresh paused preview");
+    paused_refresh.set_submenu(Some(&create_paused_preview_refresh_menu(program_data_rc)));
+    menu.append(&paused_refresh);
+
     let demosaic_raw_color = gtk::CheckMenuItem::with_label("Demosaic raw color");
     demosaic_raw_color.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
         program_data_rc.borrow_mut().demosaic_preview ^= true;
@@ -177,6 +326,30 @@ fn init_preview_menu(
     }));
     menu.append(&psf);
 
+    let smooth_zoom = gtk::CheckMenuItem::with_label("Smooth interpolation when zoomed in");
+    smooth_zoom.set_tooltip_text(Some("If disabled, individual pixels are shown as sharp-edged squares when zoomed in"));
+    smooth_zoom.set_active(program_data_rc.borrow().config.preview_smooth_zoom());
+    smooth_zoom.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        let pd = program_data_rc.borrow();
+        pd.gui.as_ref().unwrap().preview_area.set_smooth_zoom(item.is_active());
+        pd.config.set_preview_smooth_zoom(item.is_active());
+    }));
+    menu.append(&smooth_zoom);
+
+    let mjpeg_server = gtk::CheckMenuItem::with_label("Remote preview (MJPEG over HTTP)");
+    mjpeg_server.set_tooltip_text(Some(&format!(
+        "Serve the preview as a video stream viewable in a web browser (e.g., on a phone) at http://{}/",
+        program_data_rc.borrow().config.mjpeg_server_address()
+    )));
+    mjpeg_server.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        on_toggle_mjpeg_server(item, &program_data_rc);
+    }));
+    menu.append(&mjpeg_server);
+
+    let overlay_color = gtk::MenuItem::with_label("Overlay color");
+    overlay_color.set_submenu(Some(&init_overlay_color_menu(program_data_rc)));
+    menu.append(&overlay_color);
+
     let undock = gtk::MenuItem::with_label("Undock preview area");
     undock.set_action_name(Some(&actions::prefixed(actions::UNDOCK_PREVIEW)));
     menu.append(&undock);
@@ -184,12 +357,75 @@ fn init_preview_menu(
     menu
 }
 
-/// Returns "default mouse mode" button.
+/// Starts or stops the MJPEG preview server (see `workers::mjpeg_server`).
+fn on_toggle_mjpeg_server(item: &gtk::CheckMenuItem, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let error = {
+        let mut pd = program_data_rc.borrow_mut();
+        if item.is_active() == pd.mjpeg_server.is_some() { return; }
+
+        if item.is_active() {
+            match mjpeg_server::MjpegServer::start(&pd.config.mjpeg_server_address()) {
+                Ok(server) => { pd.mjpeg_server = Some(server); None },
+                Err(e) => Some(e)
+            }
+        } else {
+            pd.mjpeg_server = None;
+            None
+        }
+    };
+
+    if let Some(e) = error {
+        item.set_active(false);
+        show_message(
+            &format!(
+                "Failed to start remote preview server at {}: {}.",
+                program_data_rc.borrow().config.mjpeg_server_address(), e
+            ),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
+    }
+}
+
+fn init_overlay_color_menu(program_data_rc: &Rc<RefCell<ProgramData>>) -> gtk::Menu {
+    let menu = gtk::Menu::new();
+
+    for (label, color) in info_overlay::OVERLAY_COLOR_PRESETS {
+        let item = gtk::MenuItem::with_label(label);
+        item.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+            info_overlay::set_overlay_color(color, &mut program_data_rc.borrow_mut());
+        }));
+        menu.append(&item);
+    }
+
+    let custom = gtk::MenuItem::with_label("Custom...");
+    custom.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        let dialog = gtk::ColorChooserDialog::new(
+            Some("Overlay color"),
+            Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window)
+        );
+        let _ddestr = DialogDestroyer::new(&dialog);
+        dialog.set_use_alpha(false);
+        let [red, green, blue] = program_data_rc.borrow().gui.as_ref().unwrap().info_overlay.color;
+        dialog.set_rgba(&gtk::gdk::RGBA{ red, green, blue, alpha: 1.0 });
+
+        if dialog.run() == gtk::ResponseType::Ok {
+            let rgba = dialog.rgba();
+            info_overlay::set_overlay_color([rgba.red, rgba.green, rgba.blue], &mut program_data_rc.borrow_mut());
+        }
+    }));
+    menu.append(&custom);
+
+    menu
+}
+
+/// Returns ("default mouse mode" button, "select ROI" button).
 fn create_mouse_mode_tb_buttons(
     toolbar: &gtk::Toolbar,
     program_data_rc: &Rc<RefCell<ProgramData>>,
     icon_size: i32
-) -> gtk::RadioToolButton {
+) -> (gtk::RadioToolButton, gtk::RadioToolButton) {
     let btn_mouse_none = gtk::RadioToolButtonBuilder::new()
         .label("⨉")
         .tooltip_text("Mouse mode: none")
@@ -229,6 +465,18 @@ fn create_mouse_mode_tb_buttons(
     }));
     toolbar.insert(&btn_mouse_anchor, -1);
 
+    let btn_mouse_guiding_target = gtk::RadioToolButtonBuilder::new()
+        .label("⌖")
+        .tooltip_text("Mouse mode: place guiding target (guiding moves the tracked target there and holds it)")
+        .build();
+    btn_mouse_guiding_target.join_group(Some(&btn_mouse_none));
+    btn_mouse_guiding_target.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |btn| {
+        if btn.is_active() {
+            program_data_rc.borrow_mut().gui.as_mut().unwrap().mouse_mode = MouseMode::PlaceGuidingTarget;
+        }
+    }));
+    toolbar.insert(&btn_mouse_guiding_target, -1);
+
     let btn_mouse_crop = gtk::RadioToolButtonBuilder::new()
         .label("✂")
         .tooltip_text("Mouse mode: select recording crop area")
@@ -261,32 +509,47 @@ fn create_mouse_mode_tb_buttons(
     }));
     toolbar.insert(&btn_mouse_measure, -1);
 
-    btn_mouse_none
+    (btn_mouse_none, btn_mouse_roi)
 }
 
-fn create_status_bar() -> (gtk::Frame, StatusBarFields) {
+fn create_status_bar(shown_fields: &[status_bar::StatusBarField]) -> (gtk::Frame, StatusBarFields) {
     let status_bar_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     let preview_fps = gtk::Label::new(None);
     let capture_fps = gtk::Label::new(None);
     let temperature = gtk::Label::new(None);
     let current_recording_info = gtk::LabelBuilder::new().justify(gtk::Justification::Left).build();
     let recording_overview = gtk::LabelBuilder::new().justify(gtk::Justification::Left).build();
-
-    status_bar_box.pack_start(&preview_fps, false, false, PADDING);
-    status_bar_box.pack_start(&gtk::Separator::new(gtk::Orientation::Vertical), false, false, PADDING);
-    status_bar_box.pack_start(&capture_fps, false, false, PADDING);
-    status_bar_box.pack_start(&gtk::Separator::new(gtk::Orientation::Vertical), false, false, PADDING);
-    status_bar_box.pack_start(&temperature, false, false, PADDING);
-    status_bar_box.pack_start(&gtk::Separator::new(gtk::Orientation::Vertical), false, false, PADDING);
-    status_bar_box.pack_start(&current_recording_info, false, false, PADDING);
-    status_bar_box.pack_start(&gtk::Separator::new(gtk::Orientation::Vertical), false, false, PADDING);
-    status_bar_box.pack_start(&recording_overview, false, false, PADDING);
+    let frame_info = gtk::Label::new(None);
+    let preview_latency = gtk::Label::new(None);
+    let buffered_data_label = gtk::Label::new(Some("Buffered: 0 MiB"));
+    let buffered_data_level = gtk::LevelBar::for_interval(0.0, 1.0);
+    buffered_data_level.set_size_request(80, -1);
+    buffered_data_level.set_valign(gtk::Align::Center);
+    buffered_data_level.set_tooltip_text(Some("Captured data waiting to be recorded (relative to the maximum)"));
+    let buffered_data = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    buffered_data.pack_start(&buffered_data_label, false, false, 0);
+    buffered_data.pack_start(&buffered_data_level, false, false, PADDING);
 
     let status_bar_frame = gtk::Frame::new(None);
     status_bar_frame.set_shadow_type(gtk::ShadowType::In);
     status_bar_frame.add(&status_bar_box);
 
-    (status_bar_frame, StatusBarFields{ preview_fps, capture_fps, temperature, current_recording_info, recording_overview })
+    let fields = StatusBarFields{
+        container: status_bar_box,
+        preview_fps,
+        capture_fps,
+        temperature,
+        current_recording_info,
+        recording_overview,
+        frame_info,
+        preview_latency,
+        buffered_data,
+        buffered_data_level,
+        buffered_data_label
+    };
+    status_bar::arrange(&fields, shown_fields);
+
+    (status_bar_frame, fields)
 }
 
 fn set_up_actions(app_window: &gtk::ApplicationWindow, program_data_rc: &Rc<RefCell<ProgramData>>)
@@ -312,6 +575,80 @@ fn set_up_actions(app_window: &gtk::ApplicationWindow, program_data_rc: &Rc<RefC
     action_group.add_action(&snapshot_action);
     action_map.insert(actions::TAKE_SNAPSHOT, snapshot_action);
 
+    // ----------------------------
+    let camera_defaults_action = gtk::gio::SimpleAction::new(actions::CAMERA_DEFAULTS, None);
+    camera_defaults_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        camera_defaults_dialog::show_camera_defaults_dialog(&program_data_rc);
+    }));
+    camera_defaults_action.set_enabled(false);
+    action_group.add_action(&camera_defaults_action);
+    action_map.insert(actions::CAMERA_DEFAULTS, camera_defaults_action);
+
+    // ----------------------------
+    let auto_exposure_action = gtk::gio::SimpleAction::new(actions::AUTO_EXPOSURE, None);
+    auto_exposure_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        auto_exposure_dialog::show_auto_exposure_dialog(&program_data_rc);
+    }));
+    auto_exposure_action.set_enabled(false);
+    action_group.add_action(&auto_exposure_action);
+    action_map.insert(actions::AUTO_EXPOSURE, auto_exposure_action);
+
+    // ----------------------------
+    let raw_features_action = gtk::gio::SimpleAction::new(actions::RAW_CAMERA_FEATURES, None);
+    raw_features_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        raw_features_dialog::show_raw_features_dialog(&program_data_rc);
+    }));
+    raw_features_action.set_enabled(false);
+    action_group.add_action(&raw_features_action);
+    action_map.insert(actions::RAW_CAMERA_FEATURES, raw_features_action);
+
+    // ----------------------------
+    let copy_to_clipboard_action = gtk::gio::SimpleAction::new(actions::COPY_TO_CLIPBOARD, None);
+    copy_to_clipboard_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        event_handlers::on_copy_to_clipboard(&program_data_rc);
+    }));
+    copy_to_clipboard_action.set_enabled(false);
+    action_group.add_action(&copy_to_clipboard_action);
+    action_map.insert(actions::COPY_TO_CLIPBOARD, copy_to_clipboard_action);
+
+    // ----------------------------
+    let start_recording_action = gtk::gio::SimpleAction::new(actions::START_RECORDING, None);
+    start_recording_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        let btn_record = program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.btn_record().clone();
+        if btn_record.is_sensitive() { btn_record.clicked(); }
+    }));
+    action_group.add_action(&start_recording_action);
+    action_map.insert(actions::START_RECORDING, start_recording_action);
+
+    // ----------------------------
+    let stop_recording_action = gtk::gio::SimpleAction::new(actions::STOP_RECORDING, None);
+    stop_recording_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        let btn_stop = program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.btn_stop().clone();
+        if btn_stop.is_sensitive() { btn_stop.clicked(); }
+    }));
+    action_group.add_action(&stop_recording_action);
+    action_map.insert(actions::STOP_RECORDING, stop_recording_action);
+
+    // ----------------------------
+    let stop_mount_action = gtk::gio::SimpleAction::new(actions::STOP_MOUNT, None);
+    stop_mount_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        if program_data_rc.borrow().mount_data.mount.is_some() {
+            mount_gui::on_stop(&program_data_rc);
+        }
+    }));
+    action_group.add_action(&stop_mount_action);
+    action_map.insert(actions::STOP_MOUNT, stop_mount_action);
+
+    // ----------------------------
+    for (action_name, delta) in [(actions::ENLARGE_CENTROID_AREA, 2), (actions::SHRINK_CENTROID_AREA, -2)] {
+        let action = gtk::gio::SimpleAction::new(action_name, None);
+        action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+            event_handlers::on_resize_centroid_area(delta, &program_data_rc);
+        }));
+        action_group.add_action(&action);
+        action_map.insert(action_name, action);
+    }
+
     //-----------------------------
     let set_roi_action = gtk::gio::SimpleAction::new(actions::SET_ROI, None);
     set_roi_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
@@ -321,6 +658,23 @@ fn set_up_actions(app_window: &gtk::ApplicationWindow, program_data_rc: &Rc<RefC
     action_group.add_action(&set_roi_action);
     action_map.insert(actions::SET_ROI, set_roi_action);
 
+    //-----------------------------
+    let roi_around_target_action = gtk::gio::SimpleAction::new(actions::ROI_AROUND_TARGET, None);
+    roi_around_target_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        event_handlers::on_roi_around_target(&program_data_rc);
+    }));
+    roi_around_target_action.set_enabled(false);
+    action_group.add_action(&roi_around_target_action);
+    action_map.insert(actions::ROI_AROUND_TARGET, roi_around_target_action);
+
+    //-----------------------------
+    let save_histogram_action = gtk::gio::SimpleAction::new(actions::SAVE_HISTOGRAM, None);
+    save_histogram_action.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        event_handlers::on_save_histogram(&program_data_rc);
+    }));
+    action_group.add_action(&save_histogram_action);
+    action_map.insert(actions::SAVE_HISTOGRAM, save_histogram_action);
+
     // ----------------------------
     let undock_preview_action = gtk::gio::SimpleAction::new(actions::UNDOCK_PREVIEW, None);
     undock_preview_action.set_enabled(true);
@@ -386,11 +740,11 @@ fn create_preview_tb_buttons(
     toolbar.insert(&btn_zoom_reset, -1);
 }
 
-/// Returns (toolbar, default mouse mode button, stabilization button).
+/// Returns (toolbar, default mouse mode button, stabilization button, ROI-changing buttons).
 fn create_toolbar(
     main_wnd: &gtk::ApplicationWindow,
     program_data_rc: &Rc<RefCell<ProgramData>>
-) -> (gtk::Toolbar, gtk::RadioToolButton, gtk::ToggleToolButton) {
+) -> (gtk::Toolbar, gtk::RadioToolButton, gtk::ToggleToolButton, Vec<gtk::Widget>) {
     let toolbar = gtk::Toolbar::new();
 
     let icon_size = if let Some(s) = program_data_rc.borrow().config.toolbar_icon_size() {
@@ -452,7 +806,7 @@ fn create_toolbar(
 
     toolbar.insert(&gtk::SeparatorToolItem::new(), -1);
 
-    let btn_mouse_none = create_mouse_mode_tb_buttons(&toolbar, program_data_rc,  icon_size);
+    let (btn_mouse_none, btn_mouse_roi) = create_mouse_mode_tb_buttons(&toolbar, program_data_rc,  icon_size);
 
     toolbar.insert(&gtk::SeparatorToolItem::new(), -1);
 
@@ -463,6 +817,13 @@ fn create_toolbar(
     btn_set_roi.set_action_name(Some(&actions::prefixed(actions::SET_ROI)));
     toolbar.insert(&btn_set_roi, -1);
 
+    let btn_roi_around_target = gtk::ToolButtonBuilder::new()
+        .label("ROI⌖")
+        .tooltip_text("Set ROI around the tracked target")
+        .build();
+    btn_roi_around_target.set_action_name(Some(&actions::prefixed(actions::ROI_AROUND_TARGET)));
+    toolbar.insert(&btn_roi_around_target, -1);
+
     let btn_unset_roi = gtk::ToolButton::new(
         Some(&resources::load_svg(resources::ToolbarIcon::RoiOff, icon_size).unwrap()), None
     );
@@ -472,16 +833,15 @@ fn create_toolbar(
 
         {
             let mut pd = program_data_rc.borrow_mut();
-            if pd.capture_thread_data.is_some() {
-                cap_send_result = pd.capture_thread_data.as_mut().unwrap().sender.send(MainToCaptureThreadMsg::Pause);
-                if cap_send_result.is_ok() {
-                    pd.on_capture_pause_action = Some(OnCapturePauseAction::DisableROI);
-                }
+            if pd.rec_job_active {
+                log::warn!("cannot disable ROI during recording");
+            } else if pd.capture_thread_data.is_some() {
+                cap_send_result = initiate_capture_pause_action(OnCapturePauseAction::DisableROI, &mut pd);
             }
         } // end borrow of `program_data_rc`
 
         if cap_send_result.is_err() {
-            crate::on_capture_thread_failure(&program_data_rc);
+            crate::on_capture_thread_failure(None, &program_data_rc);
         }
     }));
     toolbar.insert(&btn_unset_roi, -1);
@@ -493,7 +853,9 @@ fn create_toolbar(
     btn_undock_preview_area.set_action_name(Some(&actions::prefixed(actions::UNDOCK_PREVIEW)));
     toolbar.insert(&btn_undock_preview_area, -1);
 
-    (toolbar, btn_mouse_none, btn_toggle_stabilization)
+    let roi_widgets = vec![btn_mouse_roi.upcast(), btn_unset_roi.upcast()];
+
+    (toolbar, btn_mouse_none, btn_toggle_stabilization, roi_widgets)
 }
 
 pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<ProgramData>>) {
@@ -502,6 +864,9 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
 
     let action_map = set_up_actions(&app_window, program_data_rc);
 
+    let style = style::Style::new();
+    style.set_night_mode(program_data_rc.borrow().config.night_mode());
+
     {
         let config = &program_data_rc.borrow().config;
 
@@ -524,10 +889,14 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
         Box::new(clone!(@weak program_data_rc => @default-panic, move |ctx, zoom| {
             draw_info_overlay(ctx, zoom, &mut program_data_rc.borrow_mut());
         })),
-        Box::new(clone!(@weak program_data_rc => @default-panic, move |ctx| {
-            draw_reticle(ctx, &program_data_rc.borrow());
+        Box::new(clone!(@weak program_data_rc => @default-panic, move |ctx, zoom| {
+            draw_reticle(ctx, zoom, &program_data_rc.borrow());
+        })),
+        Box::new(clone!(@weak program_data_rc => @default-panic, move |direction| {
+            event_handlers::on_preview_area_arrow_key(direction, &program_data_rc)
         })),
     );
+    preview_area.set_smooth_zoom(program_data_rc.borrow().config.preview_smooth_zoom());
 
     let camera_controls_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
     camera_controls_box.set_baseline_position(gtk::BaselinePosition::Top);//?
@@ -535,10 +904,47 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
     let camera_controls_scroller = gtk::ScrolledWindow::new::<gtk::Adjustment, gtk::Adjustment>(None, None);
     camera_controls_scroller.add(&camera_controls_box);
 
-    let histogram_view = HistogramView::new();
+    let favorite_controls_only = gtk::CheckButtonBuilder::new()
+        .label("favorites only")
+        .tooltip_text("Show only the controls marked with ★")
+        .active(program_data_rc.borrow().config.favorite_camera_controls_only().unwrap_or(false))
+        .build();
+    favorite_controls_only.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |cb| {
+        let pd = program_data_rc.borrow();
+        pd.config.set_favorite_camera_controls_only(cb.is_active());
+        camera_gui::update_controls_visibility(pd.gui.as_ref().unwrap());
+    }));
+
+    let control_filter = gtk::SearchEntryBuilder::new()
+        .placeholder_text("filter controls")
+        .tooltip_text("Show only the controls whose names contain this text")
+        .build();
+    control_filter.connect_search_changed(clone!(@weak program_data_rc => @default-panic, move |_| {
+        camera_gui::update_controls_visibility(program_data_rc.borrow().gui.as_ref().unwrap());
+    }));
+
+    let applying_indicator = gtk::LabelBuilder::new()
+        .label("applying…")
+        .tooltip_text("Capture is paused while the change is being applied")
+        .no_show_all(true)
+        .build();
+
+    let camera_controls_header = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    camera_controls_header.pack_start(&control_filter, true, true, PADDING);
+    camera_controls_header.pack_end(&favorite_controls_only, false, false, PADDING);
+    camera_controls_header.pack_end(&applying_indicator, false, false, PADDING);
+
+    let exposure_fps_note = gtk::LabelBuilder::new().wrap(true).xalign(0.0).no_show_all(true).build();
+
+    let camera_controls_vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    camera_controls_vbox.pack_start(&camera_controls_header, false, false, 0);
+    camera_controls_vbox.pack_start(&exposure_fps_note, false, false, PADDING);
+    camera_controls_vbox.pack_start(&camera_controls_scroller, true, true, 0);
+
+    let histogram_view = HistogramView::new(program_data_rc.borrow().config.histogram_num_bins());
 
     let cam_controls_and_histogram = gtk::Paned::new(gtk::Orientation::Vertical);
-    cam_controls_and_histogram.pack1(&camera_controls_scroller, false, false);
+    cam_controls_and_histogram.pack1(&camera_controls_vbox, false, false);
     cam_controls_and_histogram.pack2(histogram_view.top_widget(), true, true);
     if let Some(paned_pos) = program_data_rc.borrow().config.camera_controls_paned_pos() {
         cam_controls_and_histogram.set_position(paned_pos);
@@ -547,6 +953,7 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
     }
 
     let controls_notebook = gtk::Notebook::new();
+    style::set_large_buttons(&controls_notebook, program_data_rc.borrow().config.large_buttons());
 
     controls_notebook.append_page(&cam_controls_and_histogram, Some(&gtk::Label::new(Some("Camera controls"))));
 
@@ -562,7 +969,7 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
     let controls_notebook_scroller = gtk::ScrolledWindow::new::<gtk::Adjustment, gtk::Adjustment>(None, None);
     controls_notebook_scroller.add(&controls_notebook);
 
-    let (menu_bar, camera_menu, camera_menu_items) = init_menu(&app_window, program_data_rc);
+    let (menu_bar, camera_menu, camera_menu_items) = init_menu(&app_window, &controls_notebook, program_data_rc);
 
     let window_contents = gtk::Paned::new(gtk::Orientation::Horizontal);
     window_contents.set_wide_handle(true);
@@ -575,11 +982,12 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
         window_contents.set_position(app_window.size().0 - 400);
     }
 
-    let (status_bar_frame, status_bar) = create_status_bar();
+    let status_bar_fields = program_data_rc.borrow().config.status_bar_fields();
+    let (status_bar_frame, status_bar) = create_status_bar(&status_bar_fields);
 
     let top_lvl_v_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
     top_lvl_v_box.pack_start(&menu_bar, false, false, PADDING);
-    let (toolbar, default_mouse_mode_button, stabilization_button) = create_toolbar(&app_window, &program_data_rc);
+    let (toolbar, default_mouse_mode_button, stabilization_button, roi_widgets) = create_toolbar(&app_window, &program_data_rc);
     top_lvl_v_box.pack_start(&toolbar, false, false, 0);
     top_lvl_v_box.pack_start(&window_contents, true, true, PADDING);
     top_lvl_v_box.pack_start(&status_bar_frame, false, false, PADDING);
@@ -588,19 +996,28 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
 
     app_window.show_all();
 
-    app_window.connect_delete_event(clone!(
-        @weak program_data_rc,
-        @weak window_contents,
-        @weak cam_controls_and_histogram
-        => @default-panic, move |wnd, _| {
-            event_handlers::on_main_window_delete(
-                wnd,
-                &window_contents,
-                &cam_controls_and_histogram,
-                &program_data_rc);
-            gtk::Inhibit(false)
+    app_window.connect_delete_event(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        if !event_handlers::confirm_main_window_close(&program_data_rc) {
+            return gtk::Inhibit(true);
         }
-    ));
+        event_handlers::on_main_window_delete(&program_data_rc);
+        gtk::Inhibit(false)
+    }));
+
+    app_window.connect_key_press_event(clone!(@weak program_data_rc => @default-panic, move |wnd, event| {
+        event_handlers::on_main_window_key_press(wnd, event, &program_data_rc)
+    }));
+
+    app_window.add_events(gtk::gdk::EventMask::POINTER_MOTION_MASK);
+    app_window.connect_event(clone!(@weak program_data_rc => @default-panic, move |_, event| {
+        event_handlers::on_main_window_event(event, &program_data_rc);
+        gtk::Inhibit(false)
+    }));
+
+    app_window.connect_focus_out_event(clone!(@weak program_data_rc => @default-panic, move |_, _| {
+        mount_gui::on_main_window_focus_out(&program_data_rc);
+        gtk::Inhibit(false)
+    }));
 
     let rtc_opacity = 1.0;
     let rtc_diameter = 100.0;
@@ -610,6 +1027,9 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
     let gui = GuiData{
         app_window: app_window.clone(),
         controls_box: camera_controls_box,
+        control_filter,
+        favorite_controls_only,
+        favorite_controls: program_data_rc.borrow().config.favorite_camera_controls().into_iter().collect(),
         status_bar,
         control_widgets: Default::default(),
         camera_menu,
@@ -618,14 +1038,17 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
         rec_widgets,
         focuser_widgets,
         mount_widgets,
-        info_overlay: InfoOverlay::new(),
+        info_overlay: InfoOverlay::new(
+            program_data_rc.borrow().config.overlay_color().unwrap_or(info_overlay::DEFAULT_OVERLAY_COLOR)
+        ),
         reticle: Reticle{
             enabled: false,
             dialog: create_reticle_dialog(&app_window, &program_data_rc, rtc_opacity, rtc_diameter, rtc_step, rtc_line_width),
             diameter: rtc_diameter,
             opacity: rtc_opacity,
             step: rtc_step,
-            line_width: rtc_line_width
+            line_width: rtc_line_width,
+            offset: Vector2::zero()
         },
         stabilization: Stabilization{
             position: Point2::origin(),
@@ -643,9 +1066,17 @@ pub fn init_main_window(app: &gtk::Application, program_data_rc: &Rc<RefCell<Pro
         psf_dialog: PsfDialog::new(&app_window, &program_data_rc),
         mouse_mode: MouseMode::None,
         default_mouse_mode_button,
+        roi_widgets,
+        applying_indicator,
+        applying_indicator_timer: Timer::new(),
+        exposure_fps_note,
         histogram_view,
+        auto_exposure: None,
         action_map,
-        window_contents
+        shortcuts: shortcuts::Shortcuts::load(&program_data_rc.borrow().config),
+        window_contents,
+        cam_controls_and_histogram,
+        style
     };
 
     program_data_rc.borrow_mut().gui = Some(gui);
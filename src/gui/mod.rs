@@ -11,7 +11,10 @@
 //!
 
 mod actions;
+mod auto_exposure;
+mod auto_exposure_dialog;
 mod basic_connection_controls;
+mod camera_defaults_dialog;
 mod camera_gui;
 mod checked_listbox;
 #[cfg(feature = "controller")]
@@ -19,6 +22,7 @@ mod controller;
 mod dec_intervals;
 mod device_connection_dialog;
 mod dispersion_dialog;
+mod drivers_dialog;
 mod event_handlers;
 mod focuser_gui;
 mod freezeable;
@@ -30,9 +34,14 @@ mod info_overlay;
 mod mount_gui;
 mod preview_processing;
 mod psf_dialog;
+mod raw_features_dialog;
 mod rec_gui;
+mod remote_control;
 mod reticle_dialog;
 mod roi_dialog;
+mod shortcuts;
+mod status_bar;
+mod style;
 
 use camera_gui::{
     CommonControlWidgets,
@@ -41,18 +50,21 @@ use camera_gui::{
     NumberControlWidgets,
     BooleanControlWidgets
 };
-use cgmath::Point2;
+use cgmath::{Point2, Vector2};
 #[cfg(feature = "controller")]
 use controller::ControllerDialog;
-use crate::ProgramData;
+use crate::{OnCapturePauseAction, ProgramData};
 use crate::camera;
 use crate::camera::CameraError;
 use crate::devices::DeviceConnectionDiscriminants;
+use crate::timer::Timer;
+use crate::workers::capture::MainToCaptureThreadMsg;
 use dispersion_dialog::DispersionDialog;
 use ga_image;
 use ga_image::Rect;
 use preview_processing::create_preview_processing_dialog;
 use gtk::cairo;
+use glib::clone;
 use gtk::prelude::*;
 use histogram_view::HistogramView;
 use img_view::ImgView;
@@ -79,7 +91,10 @@ pub use event_handlers::{
 };
 pub use focuser_gui::{focuser_move, set_up_focuser_move_action};
 pub use initialization::init_main_window;
+pub use camera_gui::current_camera_menu_item;
 pub use mount_gui::{axis_slew, on_mount_error};
+pub use rec_gui::{pending_sequence_item, resume_sequence};
+pub use status_bar::StatusBarField;
 
 /// Control padding in pixels.
 const PADDING: u32 = 10;
@@ -92,14 +107,28 @@ const MAX_ZOOM: f64 = 20.0;
 
 const ZOOM_CHANGE_FACTOR: f64 = 1.10;
 
+/// Minimum time the "applying" indicator stays visible (so that it does not just flicker).
+const APPLYING_INDICATOR_DURATION: std::time::Duration = std::time::Duration::from_millis(700);
+
 
 
 struct StatusBarFields {
+    /// Contains the shown fields (see `status_bar::arrange`).
+    container: gtk::Box,
     preview_fps: gtk::Label,
     capture_fps: gtk::Label,
     temperature: gtk::Label,
     current_recording_info: gtk::Label,
-    recording_overview: gtk::Label
+    /// Camera-provided frame counter and timestamp.
+    frame_info: gtk::Label,
+    /// Average time from frame capture to preview display.
+    preview_latency: gtk::Label,
+    recording_overview: gtk::Label,
+    /// Contains `buffered_data_level` and `buffered_data_label`.
+    buffered_data: gtk::Box,
+    /// Fraction of the maximum amount of buffered data (see `RecordingThreadData::max_buffered_kib`).
+    buffered_data_level: gtk::LevelBar,
+    buffered_data_label: gtk::Label
 }
 
 pub struct Reticle {
@@ -108,7 +137,10 @@ pub struct Reticle {
     diameter: f64,
     opacity: f64,
     step: f64,
-    line_width: f64
+    line_width: f64,
+    /// Position relative to the image center (in image pixels); changed with arrow keys
+    /// (see `event_handlers::on_preview_area_arrow_key`).
+    offset: Vector2<i32>
 }
 
 #[derive(Copy, Clone)]
@@ -140,6 +172,8 @@ enum MouseMode {
     SelectROI,
     SelectCentroidArea,
     PlaceTrackingAnchor,
+    /// Sets the position where guiding holds the tracked target.
+    PlaceGuidingTarget,
     SelectCropArea,
     SelectHistogramArea,
     MeasureDistance
@@ -155,7 +189,8 @@ impl MouseMode {
             | MouseMode::MeasureDistance => true,
 
             MouseMode::None
-            | MouseMode::PlaceTrackingAnchor => false
+            | MouseMode::PlaceTrackingAnchor
+            | MouseMode::PlaceGuidingTarget => false
         }
     }
 }
@@ -168,6 +203,12 @@ pub struct Stabilization {
 pub struct GuiData {
     app_window: gtk::ApplicationWindow,
     controls_box: gtk::Box,
+    /// Only the camera controls whose labels contain this text are shown.
+    control_filter: gtk::SearchEntry,
+    /// If active, only the controls from `favorite_controls` are shown.
+    favorite_controls_only: gtk::CheckButton,
+    /// Labels of camera controls marked as favorite.
+    favorite_controls: std::collections::HashSet<String>,
     control_widgets: std::collections::HashMap<camera::CameraControlId, (CommonControlWidgets, ControlWidgetBundle)>,
     status_bar: StatusBarFields,
     /// Menu items and their "activate" signals.
@@ -187,11 +228,25 @@ pub struct GuiData {
     mouse_mode: MouseMode,
     info_overlay: InfoOverlay,
     default_mouse_mode_button: gtk::RadioToolButton,
+    /// Shown while a change requiring capture pause is being applied.
+    applying_indicator: gtk::Label,
+    /// Hides `applying_indicator`.
+    applying_indicator_timer: Timer,
+    /// Shown if the exposure time limits the frame rate.
+    exposure_fps_note: gtk::Label,
+    /// Toolbar buttons changing the ROI (in addition to the `SET_ROI` action); disabled during recording.
+    roi_widgets: Vec<gtk::Widget>,
     histogram_view: HistogramView,
+    /// Active exposure time adjustment (if any).
+    auto_exposure: Option<auto_exposure::AutoExposure>,
     // We must store an action map ourselves (and not e.g. reuse `SimpleActionGroup`), because currently (0.14.0) with
     // `gio` one cannot access a group's action in a way allowing to change its enabled state.
     action_map: HashMap<&'static str, gtk::gio::SimpleAction>,
-    window_contents: gtk::Paned
+    shortcuts: shortcuts::Shortcuts,
+    window_contents: gtk::Paned,
+    /// Splits camera controls and histogram.
+    cam_controls_and_histogram: gtk::Paned,
+    style: style::Style
 }
 
 impl GuiData {
@@ -199,6 +254,11 @@ impl GuiData {
 
     pub fn mount_widgets(&self) -> &MountWidgets { &self.mount_widgets }
 
+    /// Returns position of the reticle relative to the image center (zero if the reticle is not shown).
+    pub fn reticle_offset(&self) -> Vector2<i32> {
+        if self.reticle.enabled { self.reticle.offset } else { Vector2{ x: 0, y: 0 } }
+    }
+
     #[cfg(feature = "controller")]
     pub fn controller_dialog(&self) -> &ControllerDialog { &self.controller_dialog }
 
@@ -280,6 +340,24 @@ pub fn show_message(msg: &str, title: &str, msg_type: gtk::MessageType, program_
     dialog.close();
 }
 
+/// Returns `true` if user answered "Yes".
+pub fn show_question(msg: &str, title: &str, program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    let dialog = gtk::MessageDialog::new(
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        gtk::ButtonsType::YesNo,
+        msg
+    );
+    let _ddestr = DialogDestroyer::new(&dialog.clone().upcast());
+
+    dialog.set_title(title);
+    let response = dialog.run();
+    dialog.close();
+
+    response == gtk::ResponseType::Yes
+}
+
 fn show_about_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
     show_message(
         &format!(
@@ -304,6 +382,7 @@ fn update_preview_info(program_data_rc: &Rc<RefCell<ProgramData>>) {
         None => {
             program_data.preview_fps_last_timestamp = Some(std::time::Instant::now());
             program_data.preview_fps_counter = 0;
+            program_data.preview_latency_sum = std::time::Duration::ZERO;
         },
         Some(timestamp) => {
             let fps = program_data.preview_fps_counter as f64 / timestamp.elapsed().as_secs_f64();
@@ -313,15 +392,36 @@ fn update_preview_info(program_data_rc: &Rc<RefCell<ProgramData>>) {
                 None => "".to_string()
             };
 
+            // demosaicing applies only to the preview; make it clear that the recorded data is not affected
+            let is_raw_color = match &program_data.last_displayed_preview_image {
+                Some(image) => image.pixel_format().is_cfa(),
+                None => false
+            };
+            let demosaic_str = if program_data.demosaic_preview && is_raw_color && program_data.rec_job_active {
+                "   (preview debayered, recording raw)"
+            } else {
+                ""
+            };
+
             let zoom = program_data.gui.as_ref().unwrap().preview_area.get_zoom();
             program_data.gui.as_ref().unwrap().status_bar.preview_fps.set_label(&format!(
-                "Preview: {} ({:.1}%)   {:.1} fps",
+                "Preview: {} ({:.1}%)   {:.1} fps{}",
                 img_size_str,
                 zoom * 100.0,
-                fps
+                fps,
+                demosaic_str
             ));
 
+            let latency_str = if program_data.preview_fps_counter > 0 {
+                let avg_latency = program_data.preview_latency_sum / program_data.preview_fps_counter as u32;
+                format!("Latency: {} ms", avg_latency.as_millis())
+            } else {
+                "".to_string()
+            };
+            program_data.gui.as_ref().unwrap().status_bar.preview_latency.set_label(&latency_str);
+
             program_data.preview_fps_counter = 0;
+            program_data.preview_latency_sum = std::time::Duration::ZERO;
             program_data.preview_fps_last_timestamp = Some(std::time::Instant::now());
         }
     }
@@ -348,7 +448,7 @@ fn update_refreshable_camera_controls(program_data_rc: &Rc<RefCell<ProgramData>>
                 },
 
                 ControlWidgetBundle::NumberControl(
-                    NumberControlWidgets{ slider, spin_btn, intervals }
+                    NumberControlWidgets{ slider, spin_btn, intervals, time_label, .. }
                 ) => {
                     let new_value = match program_data.camera.as_ref().unwrap().get_number_control(*c_widget.0) {
                         Ok(value) => value,
@@ -375,6 +475,10 @@ fn update_refreshable_camera_controls(program_data_rc: &Rc<RefCell<ProgramData>>
                         spin_btn.borrow().set_value(new_value);
                         spin_btn.borrow().thaw();
                     }
+
+                    if let Some((label, time_unit)) = time_label {
+                        camera_gui::update_time_label(label, *time_unit, new_value);
+                    }
                 },
 
                 ControlWidgetBundle::BooleanControl(BooleanControlWidgets{ state_checkbox, checkbox_changed_signal }) => {
@@ -402,6 +506,7 @@ fn update_refreshable_camera_controls(program_data_rc: &Rc<RefCell<ProgramData>>
 
 fn update_recording_info(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let program_data = program_data_rc.borrow();
+    update_buffered_data_info(&program_data);
     let rec_widgets = &program_data.gui.as_ref().unwrap().rec_widgets;
     let sequence_next_start = rec_widgets.sequence_next_start;
     let (sequence_count, _) = rec_widgets.sequence();
@@ -421,6 +526,40 @@ fn update_recording_info(program_data_rc: &Rc<RefCell<ProgramData>>) {
         },
         _ => ()
     }
+
+    if let Some((t_start, progress)) = &rec_widgets.unlimited_recording {
+        program_data.gui.as_ref().unwrap().status_bar.current_recording_info.set_label(
+            &unlimited_recording_info(progress, t_start.elapsed())
+        );
+    }
+}
+
+/// Returns the progress message of an unlimited recording extended with its elapsed time.
+fn unlimited_recording_info(progress: &str, elapsed: std::time::Duration) -> String {
+    let total_secs = elapsed.as_secs();
+    format!(
+        "{}, elapsed: {:02}:{:02}:{:02}",
+        if progress.is_empty() { "Recording" } else { progress },
+        total_secs / 3600, (total_secs % 3600) / 60, total_secs % 60
+    )
+}
+
+/// Returns `target` extended by `margin` on each side and limited to an image of `img_width`x`img_height`.
+fn roi_around_target(target: Rect, margin: u32, img_width: u32, img_height: u32) -> Rect {
+    let x0 = (target.x - margin as i32).max(0);
+    let y0 = (target.y - margin as i32).max(0);
+    let x1 = (target.x + (target.width + margin) as i32).min(img_width as i32);
+    let y1 = (target.y + (target.height + margin) as i32).min(img_height as i32);
+
+    Rect{ x: x0, y: y0, width: (x1 - x0).max(0) as u32, height: (y1 - y0).max(0) as u32 }
+}
+
+fn update_buffered_data_info(program_data: &ProgramData) {
+    let buffered_kib = program_data.recording_thread_data.buffered_kib.load(std::sync::atomic::Ordering::Relaxed).max(0);
+    let max_buffered_kib = program_data.recording_thread_data.max_buffered_kib.load(std::sync::atomic::Ordering::Relaxed);
+    let status_bar = &program_data.gui.as_ref().unwrap().status_bar;
+    status_bar.buffered_data_level.set_value((buffered_kib as f64 / max_buffered_kib.max(1) as f64).min(1.0));
+    status_bar.buffered_data_label.set_label(&format!("Buffered: {} MiB", buffered_kib / 1024));
 }
 
 fn apply_gamma_correction<T>(image: &mut ga_image::Image, max_value: T, gamma: f32, fragment: Rect)
@@ -511,25 +650,60 @@ where T: Default + FromPrimitive + AsPrimitive<f32>
     }
 }
 
+/// Pauses capture and schedules `action` to be performed once the capture thread confirms the pause
+/// (see `event_handlers::on_capture_paused`).
+fn initiate_capture_pause_action(action: OnCapturePauseAction, program_data: &mut ProgramData)
+-> Result<(), std::sync::mpsc::SendError<MainToCaptureThreadMsg>> {
+    let result = program_data.capture_thread_data.as_mut().unwrap().sender.send(MainToCaptureThreadMsg::Pause);
+    if result.is_ok() {
+        program_data.on_capture_pause_action = Some(action);
+        let gui = program_data.gui.as_ref().unwrap();
+        gui.applying_indicator_timer.stop();
+        gui.applying_indicator.show();
+    }
+
+    result
+}
+
+/// Enables or disables the controls which must not be changed during recording (ROI, camera controls
+/// requiring capture pause).
+fn set_recording_locked_controls_enabled(gui: &GuiData, enabled: bool) {
+    gui.action_map.get(actions::SET_ROI).unwrap().set_enabled(enabled);
+    gui.action_map.get(actions::ROI_AROUND_TARGET).unwrap().set_enabled(enabled);
+    for widget in &gui.roi_widgets { widget.set_sensitive(enabled); }
+    camera_gui::set_capture_pause_controls_enabled(gui, enabled);
+}
+
 pub fn disconnect_camera(program_data_rc: &Rc<RefCell<ProgramData>>, finish_capture_thread: bool) {
     if finish_capture_thread {
         program_data_rc.borrow_mut().finish_capture_thread();
     }
+    program_data_rc.borrow_mut().gui.as_mut().unwrap().rec_widgets.on_disconnect();
     {
         let pd = program_data_rc.borrow();
         let gui = pd.gui.as_ref().unwrap();
-        gui.rec_widgets.on_disconnect();
         gui.action_map.get(actions::TAKE_SNAPSHOT).unwrap().set_enabled(false);
+        gui.action_map.get(actions::COPY_TO_CLIPBOARD).unwrap().set_enabled(false);
         gui.action_map.get(actions::SET_ROI).unwrap().set_enabled(false);
+        gui.action_map.get(actions::ROI_AROUND_TARGET).unwrap().set_enabled(false);
+        for widget in &gui.roi_widgets { widget.set_sensitive(true); }
+        gui.applying_indicator.hide();
+        gui.exposure_fps_note.hide();
+        gui.action_map.get(actions::RAW_CAMERA_FEATURES).unwrap().set_enabled(false);
+        gui.action_map.get(actions::CAMERA_DEFAULTS).unwrap().set_enabled(false);
+        gui.action_map.get(actions::AUTO_EXPOSURE).unwrap().set_enabled(false);
         gui.stabilization.toggle_button.set_active(false);
     }
 
     let mut pd = program_data_rc.borrow_mut();
+    auto_exposure::stop(&mut pd);
     pd.camera = None;
     if let Some(gui) = pd.gui.as_ref() {
         gui.status_bar.preview_fps.set_label("");
         gui.status_bar.capture_fps.set_label("");
         gui.status_bar.current_recording_info.set_label("");
+        gui.status_bar.frame_info.set_label("");
+        gui.status_bar.preview_latency.set_label("");
         for (cam_item, activate_signal) in &gui.camera_menu_items {
             cam_item.set_sensitive(true);
             cam_item.block_signal(&activate_signal);
@@ -547,6 +721,12 @@ pub fn disconnect_camera(program_data_rc: &Rc<RefCell<ProgramData>>, finish_capt
     log::info!("disconnected from camera");
 }
 
+/// Parses a decimal number entered by the user; accepts both '.' and ',' as the decimal separator
+/// (regardless of the current locale).
+pub fn parse_decimal(text: &str) -> Result<f64, std::num::ParseFloatError> {
+    text.trim().replacen(',', ".", 1).parse::<f64>()
+}
+
 /// Returns new zoom factor chosen by user or `None` if the dialog was canceled or there was an invalid input.
 fn show_custom_zoom_dialog(parent: &gtk::ApplicationWindow, old_value: f64, program_data_rc: &Rc<RefCell<ProgramData>>)
 -> Option<f64> {
@@ -570,7 +750,7 @@ fn show_custom_zoom_dialog(parent: &gtk::ApplicationWindow, old_value: f64, prog
     dialog.show_all();
 
     if dialog.run() == gtk::ResponseType::Accept {
-        if let Ok(value_percent) = entry.text().parse::<f64>() {
+        if let Ok(value_percent) = parse_decimal(&entry.text()) {
             if value_percent >= 100.0 * MIN_ZOOM && value_percent <= 100.0 * MAX_ZOOM {
                 Some(value_percent / 100.0)
             } else {
@@ -596,9 +776,92 @@ fn show_custom_zoom_dialog(parent: &gtk::ApplicationWindow, old_value: f64, prog
     }
 }
 
+/// Lets the user add and remove values of a list of speeds; returns the new list (sorted) if accepted.
+fn show_speed_list_dialog(
+    title: &str,
+    description: &str,
+    speeds: Vec<f64>,
+    initial_new_value: f64,
+    label: fn(f64) -> String,
+    program_data_rc: &Rc<RefCell<ProgramData>>
+) -> Option<Vec<f64>> {
+    let dialog = gtk::Dialog::with_buttons(
+        Some(title),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("OK", gtk::ResponseType::Accept), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Accept);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    let speeds = Rc::new(RefCell::new(speeds));
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::Single);
+    let fill_list = clone!(@weak list, @weak speeds => @default-panic, move || {
+        for child in list.children() { list.remove(&child); }
+        for speed in speeds.borrow().iter() {
+            let row_label = gtk::Label::new(Some(&label(*speed)));
+            row_label.set_halign(gtk::Align::Start);
+            list.add(&row_label);
+        }
+        list.show_all();
+    });
+    fill_list();
+
+    let scroller = gtk::ScrolledWindow::new::<gtk::Adjustment, gtk::Adjustment>(None, None);
+    scroller.set_min_content_height(200);
+    scroller.add(&list);
+
+    let new_speed = gtk::SpinButton::with_range(0.001, 10000.0, 1.0);
+    new_speed.set_digits(3);
+    new_speed.set_value(initial_new_value);
+
+    let btn_add = gtk::Button::with_label("Add");
+    btn_add.connect_clicked(clone!(@weak speeds, @weak new_speed, @strong fill_list => @default-panic, move |_| {
+        let value = new_speed.value();
+        if !speeds.borrow().contains(&value) {
+            speeds.borrow_mut().push(value);
+            speeds.borrow_mut().sort_by(|a, b| a.partial_cmp(b).unwrap());
+            fill_list();
+        }
+    }));
+
+    let btn_remove = gtk::ButtonBuilder::new()
+        .label("Remove")
+        .tooltip_text("Remove the selected speed (at least one must remain)")
+        .build();
+    btn_remove.connect_clicked(clone!(@weak speeds, @weak list, @strong fill_list => @default-panic, move |_| {
+        if let Some(row) = list.selected_row() {
+            if speeds.borrow().len() > 1 {
+                speeds.borrow_mut().remove(row.index() as usize);
+                fill_list();
+            }
+        }
+    }));
+
+    let add_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    add_box.pack_start(&new_speed, true, true, PADDING);
+    add_box.pack_start(&btn_add, false, false, PADDING);
+    add_box.pack_start(&btn_remove, false, false, PADDING);
+
+    dialog.content_area().pack_start(&gtk::Label::new(Some(description)), false, false, PADDING);
+    dialog.content_area().pack_start(&scroller, true, true, PADDING);
+    dialog.content_area().pack_start(&add_box, false, false, PADDING);
+    dialog.show_all();
+
+    if dialog.run() == gtk::ResponseType::Accept {
+        let result = speeds.borrow().clone();
+        Some(result)
+    } else {
+        None
+    }
+}
+
 /// Draws reticle on a context whose (0, 0) is the middle of the captured image.
-fn draw_reticle(ctx: &cairo::Context, program_data: &ProgramData) {
+fn draw_reticle(ctx: &cairo::Context, zoom: f64, program_data: &ProgramData) {
     let reticle = &program_data.gui.as_ref().unwrap().reticle;
+    let color = program_data.gui.as_ref().unwrap().info_overlay.color;
 
     if !reticle.enabled { return; }
 
@@ -606,7 +869,8 @@ fn draw_reticle(ctx: &cairo::Context, program_data: &ProgramData) {
     ctx.set_line_width(reticle.line_width);
     ctx.set_antialias(cairo::Antialias::Default);
 
-    ctx.set_source_rgba(1.0, 0.0, 0.0, reticle.opacity);
+    ctx.set_source_rgba(color[0], color[1], color[2], reticle.opacity);
+    ctx.translate(reticle.offset.x as f64 * zoom, reticle.offset.y as f64 * zoom);
     let mut radius = 10.0;
     while radius < reticle.diameter {
         ctx.arc(0.0, 0.0, radius, 0.0, 2.0 * std::f64::consts::PI);
@@ -614,3 +878,35 @@ fn draw_reticle(ctx: &cairo::Context, program_data: &ProgramData) {
         radius += reticle.step;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_unlimited_recording_info_includes_elapsed_time() {
+        assert_eq!(
+            "Recorded 4500 frames, elapsed: 01:02:05",
+            unlimited_recording_info("Recorded 4500 frames", std::time::Duration::from_secs(3725))
+        );
+        assert_eq!("Recording, elapsed: 00:00:03", unlimited_recording_info("", std::time::Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn given_either_decimal_separator_number_is_parsed() {
+        assert_eq!(Ok(12.5), parse_decimal("12.5"));
+        assert_eq!(Ok(12.5), parse_decimal("12,5"));
+        assert_eq!(Ok(150.0), parse_decimal(" 150 "));
+        assert!(parse_decimal("1,2,5").is_err());
+        assert!(parse_decimal("abc").is_err());
+    }
+
+    #[test]
+    fn given_target_near_image_edge_roi_around_it_is_limited_to_image() {
+        let roi = roi_around_target(Rect{ x: 100, y: 50, width: 20, height: 20 }, 10, 640, 480);
+        assert_eq!((90, 40, 40, 40), (roi.x, roi.y, roi.width, roi.height));
+
+        let roi = roi_around_target(Rect{ x: 10, y: 450, width: 10, height: 10 }, 20, 640, 480);
+        assert_eq!((0, 430, 30, 50), (roi.x, roi.y, roi.width, roi.height));
+    }
+}
@@ -18,8 +18,8 @@ pub mod skywatcher;
 pub mod zwo;
 
 use cgmath::{Point2, Vector2, InnerSpace};
-use crate::{devices::{DeviceConnectionDiscriminants, DeviceType}, MountCalibration, ProgramData};
-use crate::{devices::focuser, gui::{device_connection_dialog, show_message}, guiding, mount, mount::RadPerSec};
+use crate::{devices::{DeviceConnection, DeviceConnectionDiscriminants, DeviceType}, MountCalibration, ProgramData};
+use crate::{devices::focuser, gui::{device_connection_dialog, show_message, show_speed_list_dialog, DialogDestroyer}, guiding, mount, mount::RadPerSec};
 use glib::{clone};
 use gtk::prelude::*;
 use std::{cell::RefCell, error::Error, rc::Rc};
@@ -31,35 +31,39 @@ const PADDING: u32 = 10;
 /// Time of slewing in each axis when calibrating for guiding.
 const CALIBRATION_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
 
-#[derive(Clone)]
+/// Duration of the guide pulse issued with a "nudge" button.
+const NUDGE_DURATION: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Interval of changing the speed of gradually accelerated slews (see `Configuration::slew_acceleration`).
+const SLEW_RAMP_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Minimum interval of querying the mount status (see `update_mount_status`).
+const STATUS_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Minimum interval of querying the mount status while the mount is being slewed or guided.
+const STATUS_POLL_INTERVAL_BUSY: std::time::Duration = std::time::Duration::from_secs(3);
+
+#[derive(Clone, PartialEq)]
 pub enum SiderealMultiple {
     Multiple(f64),
     Max, // max slewing speed supported by mount
 }
 
+impl SiderealMultiple {
+    fn label(&self) -> String {
+        match self {
+            SiderealMultiple::Multiple(m) => format!("{}x", m),
+            SiderealMultiple::Max => "MAX".to_string()
+        }
+    }
+}
+
 #[derive(Clone)]
 struct SlewingSpeed {
     sidereal_multiple: SiderealMultiple,
     label: &'static str
 }
 
-const SLEWING_SPEEDS: &'static [SlewingSpeed] = &[
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(1.0),    label: "1x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(2.0),    label: "2x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(4.0),    label: "4x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(8.0),    label: "8x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(16.0),   label: "16x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(20.0),   label: "20x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(32.0),   label: "32x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(60.0),   label: "60x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(64.0),   label: "64x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(128.0),  label: "128x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(256.0),  label: "256x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(512.0),  label: "512x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(720.0),  label: "720x" },
-    SlewingSpeed{ sidereal_multiple: SiderealMultiple::Max,              label: "MAX" },
-];
-
 const GUIDING_SPEEDS: &'static [SlewingSpeed] = &[
     SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(0.1),  label: "0.1x" },
     SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(0.25), label: "0.25x" },
@@ -69,18 +73,46 @@ const GUIDING_SPEEDS: &'static [SlewingSpeed] = &[
     SlewingSpeed{ sidereal_multiple: SiderealMultiple::Multiple(0.9),  label: "0.9x" }
 ];
 
+struct TrackingRate {
+    /// `None` means a custom rate entered by user.
+    rate: Option<RadPerSec>,
+    label: &'static str
+}
+
+const TRACKING_RATES: &'static [TrackingRate] = &[
+    TrackingRate{ rate: Some(mount::SIDEREAL_RATE), label: "sidereal" },
+    TrackingRate{ rate: Some(mount::SOLAR_RATE),    label: "solar" },
+    TrackingRate{ rate: Some(mount::LUNAR_RATE),    label: "lunar" },
+    TrackingRate{ rate: None,                       label: "custom" }
+];
+
 pub struct MountWidgets {
     wbox: gtk::Box,
+    /// Contains all controls except the status row; insensitive while disconnected.
+    controls: gtk::Box,
     status: gtk::Label,
+    /// Reconnects using the parameters of the last connection; insensitive until the first connection.
+    reconnect: gtk::Button,
     /// Button and its "activate" signal.
     sky_tracking: (gtk::ToggleButton, glib::SignalHandlerId),
     /// Button and its "activate" signal.
     guide: (gtk::ToggleButton, glib::SignalHandlerId),
     calibrate: gtk::Button,
+    /// If active, each calibration adds a point to the mount model instead of replacing it.
+    multi_point_calibration: gtk::CheckButton,
     slew_speed: gtk::ComboBox,
+    /// Labels of `slew_speeds`.
+    slew_speed_model: gtk::ListStore,
+    /// Speeds offered in `slew_speed`; the last one is always `SiderealMultiple::Max`.
+    slew_speeds: Rc<RefCell<Vec<SiderealMultiple>>>,
     guide_speed: gtk::ComboBoxText,
-    /// Elements correspond to `SLEWING_SPEEDS`.
-    slew_speed_supported: Rc<RefCell<[bool; SLEWING_SPEEDS.len()]>>
+    /// If active, guiding corrects only the primary axis.
+    guide_primary_axis_only: gtk::CheckButton,
+    tracking_rate: gtk::ComboBoxText,
+    /// Custom tracking rate in arcseconds per second.
+    custom_tracking_rate: gtk::SpinButton,
+    /// Elements correspond to `slew_speeds`.
+    slew_speed_supported: Rc<RefCell<Vec<bool>>>
 }
 
 impl MountWidgets {
@@ -93,26 +125,48 @@ impl MountWidgets {
         }
     }
 
-    fn on_connect(&self, mount: &Box<dyn mount::Mount>, _tracking_enabled: bool)
+    fn on_connect(&self, mount: &Box<dyn mount::Mount>, slewing_speeds: &[f64], _tracking_enabled: bool)
     {
-        self.wbox.set_sensitive(true);
+        self.controls.set_sensitive(true);
+        self.reconnect.set_sensitive(true);
         self.status.set_text(&format!("{}", mount.get_info()));
-        let mut sss = self.slew_speed_supported.borrow_mut();
+        self.set_slew_speeds(slewing_speeds, mount);
+    }
+
+    /// Fills the slewing speed combo box with `multiples` (of sidereal rate) and the maximum speed.
+    fn set_slew_speeds(&self, multiples: &[f64], mount: &Box<dyn mount::Mount>) {
+        let prev_selected = self.slew_speed.active().map(|idx| self.slew_speeds.borrow()[idx as usize].clone());
+
+        let mut speeds: Vec<SiderealMultiple> = multiples.iter().map(|m| SiderealMultiple::Multiple(*m)).collect();
+        speeds.push(SiderealMultiple::Max);
+
         let mut info = "supported slewing speeds: ".to_string();
-        for (idx, speed) in SLEWING_SPEEDS.iter().enumerate() {
-            if let SiderealMultiple::Multiple(m) = speed.sidereal_multiple {
-                sss[idx] = mount.slewing_speed_supported(m * mount::SIDEREAL_RATE);
-                info += &format!("{:.0}x, ", m);
-            } else {
-                sss[idx] = true;
-            }
-        }
+        let supported: Vec<bool> = speeds.iter().map(|speed| match speed {
+            SiderealMultiple::Multiple(m) => {
+                let supported = mount.slewing_speed_supported(*m * mount::SIDEREAL_RATE);
+                if supported { info += &format!("{}x, ", m); }
+                supported
+            },
+            SiderealMultiple::Max => true
+        }).collect();
         log::info!("{}", info);
+
+        let new_selected = prev_selected
+            .and_then(|prev| speeds.iter().position(|s| *s == prev))
+            .unwrap_or(0);
+
+        *self.slew_speed_supported.borrow_mut() = supported;
+        self.slew_speed_model.clear();
+        for (idx, speed) in speeds.iter().enumerate() {
+            self.slew_speed_model.insert_with_values(Some(idx as u32), &[(0u32, &speed.label())]);
+        }
+        *self.slew_speeds.borrow_mut() = speeds;
+        self.slew_speed.set_active(Some(new_selected as u32));
     }
 
     fn on_disconnect(&self)
     {
-        self.wbox.set_sensitive(false);
+        self.controls.set_sensitive(false);
         self.status.set_text("disconnected");
         self.disable_sky_tracking_btn();
     }
@@ -126,7 +180,30 @@ impl MountWidgets {
 
     /// Returns slewing speed (multiple of sidereal rate) selected in combo box.
     fn slew_speed(&self) -> SiderealMultiple {
-        SLEWING_SPEEDS[self.slew_speed.active().unwrap() as usize].sidereal_multiple.clone()
+        self.slew_speeds.borrow()[self.slew_speed.active().unwrap() as usize].clone()
+    }
+
+    /// Returns slewing speed selected in combo box or, if it is not supported by the mount, the closest supported one.
+    fn supported_slew_speed(&self) -> SiderealMultiple {
+        let selected = self.slew_speed.active().unwrap() as usize;
+        let speeds = self.slew_speeds.borrow();
+        let supported = self.slew_speed_supported.borrow();
+        if supported[selected] { return speeds[selected].clone(); }
+
+        let closest = closest_supported_speed(&speeds, &supported, selected);
+        log::warn!(
+            "slewing speed {} is not supported by the mount; using {} instead",
+            speeds[selected].label(), speeds[closest].label()
+        );
+        speeds[closest].clone()
+    }
+
+    /// Returns tracking rate selected in combo box (or entered by user).
+    fn tracking_rate(&self) -> RadPerSec {
+        match TRACKING_RATES[self.tracking_rate.active().unwrap() as usize].rate {
+            Some(rate) => rate,
+            None => RadPerSec::from_arcsec_per_sec(self.custom_tracking_rate.value())
+        }
     }
 
     /// Returns guiding speed (multiple of sidereal rate) selected in combo box.
@@ -137,6 +214,10 @@ impl MountWidgets {
         }
     }
 
+    pub fn guide_primary_axis_only(&self) -> bool {
+        self.guide_primary_axis_only.is_active()
+    }
+
     pub fn disable_guide(&self) {
         let (btn_guide, signal) = &self.guide;
         btn_guide.block_signal(signal);
@@ -145,7 +226,37 @@ impl MountWidgets {
     }
 }
 
-fn on_stop(program_data_rc: &Rc<RefCell<ProgramData>>) {
+/// Refreshes the status label with the state reported by the mount (if supported).
+///
+/// The status is queried on the main thread, so it is done at most every `STATUS_POLL_INTERVAL`, and less often
+/// (`STATUS_POLL_INTERVAL_BUSY`) while the mount is being slewed or guided (an unresponsive mount would block the UI
+/// and delay slewing commands).
+pub fn update_mount_status(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let mut pd = program_data_rc.borrow_mut();
+
+    let md = &pd.mount_data;
+    let busy = md.guiding_pos.is_some()
+        || md.guide_slewing
+        || md.calibration_in_progress()
+        || md.manually_slewed_axes.iter().any(|slewed| *slewed)
+        || md.slew_ramps.iter().any(|ramp| ramp.is_some());
+    let interval = if busy { STATUS_POLL_INTERVAL_BUSY } else { STATUS_POLL_INTERVAL };
+    if md.t_last_status_poll.map_or(false, |t| t.elapsed() < interval) { return; }
+    pd.mount_data.t_last_status_poll = Some(std::time::Instant::now());
+
+    let text = match pd.mount_data.mount.as_mut() {
+        None => return,
+        Some(mount) => match mount.status() {
+            Ok(Some(status)) => format!("{}\n{}", mount.get_info(), status),
+            Ok(None) => return,
+            Err(e) => format!("{}\nstatus unavailable: {}", mount.get_info(), e)
+        }
+    };
+
+    pd.gui.as_ref().unwrap().mount_widgets.status.set_text(&text);
+}
+
+pub fn on_stop(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let res = program_data_rc.borrow_mut().mount_data.mount.as_mut().unwrap().stop();
     if let Err(e) = &res {
         on_mount_error(e, program_data_rc);
@@ -157,6 +268,9 @@ fn on_stop(program_data_rc: &Rc<RefCell<ProgramData>>) {
     pd.mount_data.guide_slewing = false;
     pd.mount_data.guiding_timer.stop();
     pd.mount_data.guiding_pos = None;
+    pd.mount_data.centering = false;
+    pd.mount_data.slew_ramps = [None; 2];
+    pd.mount_data.slew_ramp_timer.stop();
     pd.gui.as_ref().unwrap().mount_widgets.disable_guide();
 
     if pd.mount_data.calibration_in_progress() {
@@ -197,7 +311,7 @@ fn on_start_calibration(btn: &gtk::Button, program_data_rc: &Rc<RefCell<ProgramD
             origin: pd.tracking.as_ref().unwrap().pos,
             primary_dir: None,
             secondary_dir: None,
-            img_to_mount_axes: None,
+            start_pos: pd.tracking.as_ref().unwrap().pos,
             calibration_slew_speed: selected_multiple * mount::SIDEREAL_RATE
         });
     }
@@ -283,7 +397,16 @@ fn on_calibration_timer(program_data_rc: &Rc<RefCell<ProgramData>>) {
                 );
 
                 match guiding::create_img_to_mount_axes_matrix(primary_dir, secondary_dir) {
-                    Ok(matrix) => { pd.mount_data.calibration.as_mut().unwrap().img_to_mount_axes = Some(matrix); },
+                    Ok(matrix) => {
+                        if !pd.gui.as_ref().unwrap().mount_widgets.multi_point_calibration.is_active() {
+                            pd.mount_data.mount_model.clear();
+                        }
+                        let point = guiding::CalibrationPoint{
+                            pos: pd.mount_data.calibration.as_ref().unwrap().start_pos.cast::<f64>().unwrap(),
+                            img_to_mount_axes: matrix
+                        };
+                        pd.mount_data.mount_model.add_point(point);
+                    },
                     _ => {
                         must_show_error.replace(
                             Some("Mount-axes-to-image transformation matrix is non-invertible.".to_string())
@@ -301,9 +424,44 @@ fn on_calibration_timer(program_data_rc: &Rc<RefCell<ProgramData>>) {
         show_message(&msg, "Error", gtk::MessageType::Error, program_data_rc);
         program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.calibrate.set_sensitive(true);
     } else {
-        let calibration_finished = program_data_rc.borrow().mount_data.calibration.as_ref().unwrap().secondary_dir.is_some();
-        if calibration_finished {
-            show_message("Calibration completed.", "Information", gtk::MessageType::Info, program_data_rc);
+        let (directions, model_summary) = {
+            let pd = program_data_rc.borrow();
+            let calibration = pd.mount_data.calibration.as_ref().unwrap();
+            let model = &pd.mount_data.mount_model;
+            (
+                calibration.primary_dir.zip(calibration.secondary_dir),
+                format!("Mount model: {} point(s), polynomial degree {}.", model.num_points(), model.degree())
+            )
+        };
+        if let Some((primary_dir, secondary_dir)) = directions {
+            let quality = guiding::calibration_quality(primary_dir, secondary_dir);
+            log::info!(
+                "calibration completed; angle between axes: {:.1}°, condition number: {:.2}; {}",
+                quality.axes_angle_deg, quality.condition_number, model_summary
+            );
+            let summary = format!(
+                "Angle between axes: {:.1}° (expected: 90°).\nTransformation matrix condition number: {:.2}.\n{}",
+                quality.axes_angle_deg, quality.condition_number, model_summary
+            );
+            if quality.is_good() {
+                show_message(
+                    &format!("Calibration completed.\n\n{}", summary),
+                    "Information",
+                    gtk::MessageType::Info,
+                    program_data_rc
+                );
+            } else {
+                show_message(
+                    &format!(
+                        "Calibration completed, but the measured axes are nearly parallel; guiding may be unreliable.\n\n{}\n\n\
+                        Make sure the tracked target does not drift during calibration or try a higher slewing speed.",
+                        summary
+                    ),
+                    "Warning",
+                    gtk::MessageType::Warning,
+                    program_data_rc
+                );
+            }
             program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.calibrate.set_sensitive(true);
         }
     }
@@ -315,25 +473,31 @@ pub fn create_mount_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> MountWidg
     let upper_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     upper_box.pack_start(&gtk::Label::new(Some("Slewing speed:")), false, false, PADDING);
 
-    let model = gtk::ListStore::new(&[gtk::glib::Type::STRING]);
-    for (idx, speed) in SLEWING_SPEEDS.iter().enumerate() {
-        model.insert_with_values(Some(idx as u32), &[(0u32, &speed.label)]);
-    }
-    let slew_speed = gtk::ComboBox::with_model(&model);
+    let slew_speed_model = gtk::ListStore::new(&[gtk::glib::Type::STRING]);
+    let slew_speed = gtk::ComboBox::with_model(&slew_speed_model);
     let renderer = gtk::CellRendererText::new();
     slew_speed.pack_start(&renderer, true);
     slew_speed.add_attribute(&renderer, "text", 0);
-    let slew_speed_supported = Rc::new(RefCell::new([false; SLEWING_SPEEDS.len()]));
+    let slew_speed_supported = Rc::new(RefCell::new(vec![]));
     slew_speed.set_cell_data_func(&renderer, Some(Box::new(
         clone!(@weak slew_speed_supported => @default-panic, move |_, cell, model, iter| {
             let path = model.path(iter).unwrap();
-            cell.set_sensitive(slew_speed_supported.borrow()[path.indices()[0] as usize]);
+            let supported = slew_speed_supported.borrow().get(path.indices()[0] as usize).copied().unwrap_or(false);
+            cell.set_sensitive(supported);
         })
     )));
-    slew_speed.set_active(Some(0));
 
     upper_box.pack_start(&slew_speed, false, false, PADDING);
 
+    let btn_edit_speeds = gtk::ButtonBuilder::new()
+        .label("...")
+        .tooltip_text("Edit the list of slewing speeds of the connected mount type")
+        .build();
+    btn_edit_speeds.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        show_slewing_speeds_dialog(&program_data_rc);
+    }));
+    upper_box.pack_start(&btn_edit_speeds, false, false, 0);
+
     let btn_calibrate = gtk::ButtonBuilder::new()
         .label("calibrate")
         .tooltip_text("Calibrate guiding by establishing mount-camera orientation (uses the selected slewing speed)")
@@ -343,6 +507,13 @@ pub fn create_mount_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> MountWidg
     );
     upper_box.pack_end(&btn_calibrate, false, false, PADDING);
 
+    let multi_point_calibration = gtk::CheckButtonBuilder::new()
+        .label("multi-point")
+        .tooltip_text("Add each calibration as a point of the mount model instead of replacing it; \
+            calibrating at several positions across the field improves guiding accuracy of wide-field setups")
+        .build();
+    upper_box.pack_end(&multi_point_calibration, false, false, 0);
+
     let btn_stop = gtk::Button::with_label("stop");
     btn_stop.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| on_stop(&program_data_rc)));
     upper_box.pack_end(&btn_stop, false, false, PADDING);
@@ -360,6 +531,38 @@ pub fn create_mount_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> MountWidg
 
     contents.pack_start(&upper_box, false, false, PADDING);
 
+    let tracking_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    tracking_box.pack_start(&gtk::Label::new(Some("Tracking rate:")), false, false, PADDING);
+
+    let tracking_rate = gtk::ComboBoxText::new();
+    for rate in TRACKING_RATES {
+        tracking_rate.append_text(rate.label);
+    }
+    tracking_rate.set_active(Some(0));
+    tracking_box.pack_start(&tracking_rate, false, false, PADDING);
+
+    let custom_tracking_rate = gtk::SpinButton::with_range(-100.0, 100.0, 0.001);
+    custom_tracking_rate.set_digits(3);
+    custom_tracking_rate.set_value(mount::SIDEREAL_RATE.to_arcsec_per_sec());
+    custom_tracking_rate.set_tooltip_text(Some("Custom primary axis tracking rate (arcseconds per second)"));
+    custom_tracking_rate.set_sensitive(false);
+    custom_tracking_rate.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |_| {
+        on_tracking_rate_changed(&program_data_rc);
+    }));
+    tracking_box.pack_start(&custom_tracking_rate, false, false, 0);
+    tracking_box.pack_start(&gtk::Label::new(Some("″/s")), false, false, PADDING);
+
+    tracking_rate.connect_changed(clone!(
+        @weak program_data_rc,
+        @weak custom_tracking_rate
+        => @default-panic, move |combo| {
+            custom_tracking_rate.set_sensitive(TRACKING_RATES[combo.active().unwrap() as usize].rate.is_none());
+            on_tracking_rate_changed(&program_data_rc);
+        }
+    ));
+
+    contents.pack_start(&tracking_box, false, false, PADDING);
+
     let (primary_neg, secondary_pos, secondary_neg, primary_pos) = create_direction_buttons(program_data_rc);
 
     let dir_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
@@ -369,6 +572,26 @@ pub fn create_mount_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> MountWidg
     dir_box.pack_start(&primary_pos, true, true, 0);
     contents.pack_start(&dir_box, false, false, PADDING);
 
+    let nudge_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    nudge_box.pack_start(&gtk::Label::new(Some("Nudge:")), false, false, PADDING);
+    for (label, axis, positive) in [
+        ("←", mount::Axis::Primary, false),
+        ("↑", mount::Axis::Secondary, true),
+        ("↓", mount::Axis::Secondary, false),
+        ("→", mount::Axis::Primary, true)
+    ] {
+        let btn = gtk::Button::with_label(label);
+        btn.set_tooltip_text(Some(&format!(
+            "Single guide pulse ({} ms at the guiding speed) for small framing corrections",
+            NUDGE_DURATION.as_millis()
+        )));
+        btn.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+            nudge(axis, positive, &program_data_rc);
+        }));
+        nudge_box.pack_start(&btn, true, true, 0);
+    }
+    contents.pack_start(&nudge_box, false, false, PADDING);
+
     let lower_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
 
     lower_box.pack_start(&gtk::Label::new(Some("Guiding speed:")), false, false, PADDING);
@@ -380,6 +603,17 @@ pub fn create_mount_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> MountWidg
     guide_speed.set_active(Some(3));
     lower_box.pack_start(&guide_speed, false, false, PADDING);
 
+    let guide_primary_axis_only = gtk::CheckButtonBuilder::new()
+        .label("primary axis only")
+        .tooltip_text("Guide only around the primary (RA) axis and leave the secondary (Dec) axis uncorrected \
+            (e.g., for mounts with declination backlash)")
+        .active(program_data_rc.borrow().config.guide_primary_axis_only())
+        .build();
+    guide_primary_axis_only.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |btn| {
+        program_data_rc.borrow().config.set_guide_primary_axis_only(btn.is_active());
+    }));
+    lower_box.pack_start(&guide_primary_axis_only, false, false, PADDING);
+
     let btn_guide = gtk::ToggleButtonBuilder::new()
         .label("guide")
         .tooltip_text("Enable guiding")
@@ -395,23 +629,76 @@ pub fn create_mount_box(program_data_rc: &Rc<RefCell<ProgramData>>) -> MountWidg
     }));
     lower_box.pack_start(&btn_guide, false, false, PADDING);
 
+    let btn_center = gtk::ButtonBuilder::new()
+        .label("center")
+        .tooltip_text(
+            "Move the tracked target to the image center or to the reticle center, if shown \
+            (uses calibration and the selected guiding speed)"
+        )
+        .build();
+    btn_center.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        guiding::center_target(&program_data_rc);
+    }));
+    lower_box.pack_start(&btn_center, false, false, PADDING);
+
     contents.pack_start(&lower_box, false, false, PADDING);
 
+    let backlash_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    backlash_box.pack_start(&gtk::Label::new(Some("Backlash compensation (ms):")), false, false, PADDING);
+    for (axis, label) in [(mount::Axis::Primary, "primary"), (mount::Axis::Secondary, "secondary")] {
+        backlash_box.pack_start(&gtk::Label::new(Some(label)), false, false, PADDING);
+        let backlash = gtk::SpinButton::with_range(0.0, 10_000.0, 10.0);
+        backlash.set_digits(0);
+        backlash.set_value(program_data_rc.borrow().config.guide_backlash_compensation(axis).as_millis() as f64);
+        backlash.set_tooltip_text(Some(
+            "Duration of the extra guide pulse taking up backlash when guiding reverses direction on the axis \
+            (0: disabled)"
+        ));
+        backlash.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |spin| {
+            program_data_rc.borrow().config.set_guide_backlash_compensation(
+                axis,
+                std::time::Duration::from_millis(spin.value_as_int() as u64)
+            );
+        }));
+        backlash_box.pack_start(&backlash, false, false, PADDING);
+    }
+    contents.pack_start(&backlash_box, false, false, PADDING);
+
+    contents.set_sensitive(false);
+
+    let wbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    wbox.pack_start(&contents, true, true, 0);
+
     let status_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     let status_label = gtk::LabelBuilder::new().justify(gtk::Justification::Left).label("disconnected").build();
     status_box.pack_start(&status_label, false, false, PADDING);
-    contents.pack_end(&status_box, false, false, PADDING);
-
-    contents.set_sensitive(false);
+    let btn_reconnect = gtk::ButtonBuilder::new()
+        .label("reconnect")
+        .tooltip_text("Disconnect (if connected) and connect again to the last used mount with the same parameters")
+        .sensitive(false)
+        .build();
+    btn_reconnect.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        reconnect(&program_data_rc);
+    }));
+    status_box.pack_end(&btn_reconnect, false, false, PADDING);
+    wbox.pack_end(&status_box, false, false, PADDING);
 
     MountWidgets{
-        wbox: contents,
+        wbox,
+        controls: contents,
         status: status_label,
+        reconnect: btn_reconnect,
         sky_tracking: (btn_sky_tracking, signal_sky_tracking),
         guide: (btn_guide, signal_guide),
         calibrate: btn_calibrate,
+        multi_point_calibration,
         slew_speed,
+        slew_speed_model,
+        slew_speeds: Rc::new(RefCell::new(vec![])),
         guide_speed,
+        guide_primary_axis_only,
+        tracking_rate,
+        custom_tracking_rate,
         slew_speed_supported
     }
 }
@@ -447,6 +734,19 @@ fn on_toggle_sky_tracking(btn: &gtk::ToggleButton, program_data_rc: &Rc<RefCell<
     log::info!("sky tracking {}", if btn.is_active() { "enabled" } else { "disabled" });
 }
 
+fn on_tracking_rate_changed(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let rate = program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.tracking_rate();
+    let res = match program_data_rc.borrow_mut().mount_data.mount.as_mut() {
+        Some(mount) => mount.set_tracking_rate(rate),
+        None => return
+    };
+
+    match res {
+        Ok(()) => log::info!("tracking rate set to {:.3}″/s", rate.to_arcsec_per_sec()),
+        Err(e) => on_mount_error(&e, program_data_rc)
+    }
+}
+
 /// Returns slewing buttons: (Primary-, Secondary+, Secondary-, Primary+).
 fn create_direction_buttons(program_data_rc: &Rc<RefCell<ProgramData>>)
 -> (gtk::Button, gtk::Button, gtk::Button, gtk::Button) {
@@ -491,87 +791,381 @@ fn create_direction_buttons(program_data_rc: &Rc<RefCell<ProgramData>>)
 
 
 
+/// Connects to the mount specified by `connection`; returns true on success.
+fn connect(connection: DeviceConnection, program_data_rc: &Rc<RefCell<ProgramData>>) -> bool {
+    let mount_type = DeviceConnectionDiscriminants::from(&connection);
+    let result = mount::connect_to_mount(connection.clone(), &program_data_rc.borrow().config);
+    match result {
+        Err(e) => {
+            show_message(
+                &format!("Failed to connect to mount: {:?}.", e),
+                "Error",
+                gtk::MessageType::Error,
+                program_data_rc
+            );
+            false
+        },
+        Ok(mut mount) => {
+            log::info!("connected to {}", mount.get_info());
+            let target_tracking_enabled = program_data_rc.borrow().tracking.is_some();
+            let slewing_speeds = program_data_rc.borrow().config.slewing_speeds(mount_type);
+            program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.on_connect(
+                &mount,
+                &slewing_speeds,
+                target_tracking_enabled
+            );
+            mount.set_mount_simulator_data(program_data_rc.borrow().mount_simulator_data.clone());
+            program_data_rc.borrow_mut().mount_data.mount = Some(mount);
+            program_data_rc.borrow_mut().mount_data.mount_type = Some(mount_type);
+            program_data_rc.borrow_mut().mount_data.last_connection = Some(connection);
+            program_data_rc.borrow_mut().mount_data.calibration = None;
+            program_data_rc.borrow_mut().mount_data.mount_model.clear();
+            on_tracking_rate_changed(program_data_rc);
+            true
+        }
+    }
+}
+
+/// Disconnects from the mount (if connected).
+pub fn disconnect(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    if program_data_rc.borrow().mount_data.mount.is_none() { return; }
+
+    program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.on_disconnect();
+    let mut pd = program_data_rc.borrow_mut();
+    let mount_info = pd.mount_data.mount.as_ref().unwrap().get_info();
+
+    // stop guiding and calibration first, so that their timers do not access the mount after it is dropped
+    pd.mount_data.guide_slewing = false;
+    pd.mount_data.guiding_timer.stop();
+    pd.mount_data.guiding_pos = None;
+    pd.mount_data.centering = false;
+    pd.mount_data.calibration_timer.stop();
+    pd.mount_data.nudge_timer.stop();
+    pd.gui.as_ref().unwrap().mount_widgets.disable_guide();
+    pd.gui.as_ref().unwrap().mount_widgets.calibrate.set_sensitive(true);
+
+    pd.mount_data.mount = None;
+    pd.mount_data.mount_type = None;
+    pd.mount_data.sky_tracking_on = false;
+    pd.mount_data.calibration = None;
+    pd.mount_data.mount_model.clear();
+    pd.mount_data.manually_slewed_axes = [false; 2];
+    pd.mount_data.slew_ramps = [None; 2];
+    pd.mount_data.slew_ramp_timer.stop();
+    log::info!("disconnected from {}", mount_info);
+}
+
+/// Disconnects from the mount (if connected) and connects again using the last successful connection's parameters.
+fn reconnect(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let connection = match program_data_rc.borrow().mount_data.last_connection.clone() {
+        Some(connection) => connection,
+        None => return
+    };
+    disconnect(program_data_rc);
+    log::info!("reconnecting to mount");
+    connect(connection, program_data_rc);
+}
+
 pub fn init_mount_menu(program_data_rc: &Rc<RefCell<ProgramData>>) -> gtk::Menu {
     let menu = gtk::Menu::new();
 
     let item_disconnect = gtk::MenuItem::with_label("Disconnect");
-    item_disconnect.connect_activate(clone!(@weak program_data_rc => @default-panic, move |menu_item| {
-        program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.on_disconnect();
-        {
-            let mut pd = program_data_rc.borrow_mut();
-            let mount_info = pd.mount_data.mount.as_ref().unwrap().get_info();
-            pd.mount_data.mount = None;
-            pd.mount_data.sky_tracking_on = false;
-            pd.mount_data.calibration = None;
-            pd.gui.as_ref().unwrap().mount_widgets.on_disconnect();
-            log::info!("disconnected from {}", mount_info);
-        }
-        menu_item.set_sensitive(false);
+    item_disconnect.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        disconnect(&program_data_rc);
     }));
-    item_disconnect.set_sensitive(false);
 
     let item_connect = gtk::MenuItem::with_label("Connect...");
 
     let mount_connections: Vec<DeviceConnectionDiscriminants> =
         DeviceConnectionDiscriminants::iter().filter(|d| d.device_type() == DeviceType::Mount).collect();
 
-    item_connect.connect_activate(clone!(
-        @weak program_data_rc,
-        @weak item_disconnect
-        => @default-panic, move |_| {
-            match device_connection_dialog::show_device_connection_dialog(
-                "Connect to mount",
-                "Mount type:",
-                &program_data_rc,
-                &mount_connections
-            ) {
-                Some(connection) => {
-                    match mount::connect_to_mount(connection) {
-                        Err(e) => show_message(
-                            &format!("Failed to connect to mount: {:?}.", e),
-                            "Error",
-                            gtk::MessageType::Error,
-                            &program_data_rc
-                        ),
-                        Ok(mut mount) => {
-                            log::info!("connected to {}", mount.get_info());
-                            let target_tracking_enabled = program_data_rc.borrow().tracking.is_some();
-                            program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.on_connect(
-                                &mount,
-                                target_tracking_enabled
-                            );
-                            mount.set_mount_simulator_data(program_data_rc.borrow().mount_simulator_data.clone());
-                            program_data_rc.borrow_mut().mount_data.mount = Some(mount);
-                            program_data_rc.borrow_mut().mount_data.calibration = None;
-                            item_disconnect.set_sensitive(true);
-                        }
-                    }
-                },
-                _ => ()
-            }
+    item_connect.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        if let Some(connection) = device_connection_dialog::show_device_connection_dialog(
+            "Connect to mount",
+            "Mount type:",
+            &program_data_rc,
+            &mount_connections
+        ) {
+            connect(connection, &program_data_rc);
         }
+    }));
+
+    // the mount can also be (re)connected or disconnected from the mount panel
+    menu.connect_show(clone!(@weak program_data_rc, @weak item_disconnect => @default-panic, move |_| {
+        item_disconnect.set_sensitive(program_data_rc.borrow().mount_data.mount.is_some());
+    }));
+
+    let item_stop_on_focus_loss = gtk::CheckMenuItem::with_label("Stop slewing when window loses focus");
+    item_stop_on_focus_loss.set_tooltip_text(Some(
+        "Stops slews started with direction buttons or keys when switching to another window (which may prevent \
+        the button release from being noticed)"
     ));
+    item_stop_on_focus_loss.set_active(program_data_rc.borrow().config.stop_slew_on_focus_loss());
+    item_stop_on_focus_loss.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |item| {
+        program_data_rc.borrow().config.set_stop_slew_on_focus_loss(item.is_active());
+    }));
+
+    let item_slew_acceleration = gtk::MenuItem::with_label("Slew acceleration...");
+    item_slew_acceleration.connect_activate(clone!(@weak program_data_rc => @default-panic, move |_| {
+        show_slew_acceleration_dialog(&program_data_rc);
+    }));
 
     menu.append(&item_connect);
     menu.append(&item_disconnect);
+    menu.append(&gtk::SeparatorMenuItem::new());
+    menu.append(&item_stop_on_focus_loss);
+    menu.append(&item_slew_acceleration);
 
     menu
 }
 
+/// Lets the user set the acceleration of slews started with direction buttons, keys or controller.
+fn show_slew_acceleration_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Slew acceleration"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("OK", gtk::ResponseType::Ok), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Ok);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    let config_acceleration = program_data_rc.borrow().config.slew_acceleration();
+    let enabled = gtk::CheckButton::with_label("Change slewing speed gradually (sidereal rate multiples per second):");
+    enabled.set_active(config_acceleration.is_some());
+    let acceleration = gtk::SpinButton::new(
+        Some(&gtk::Adjustment::new(config_acceleration.unwrap_or(200.0), 1.0, 10_000.0, 10.0, 100.0, 0.0)),
+        1.0,
+        0
+    );
+    acceleration.set_sensitive(enabled.is_active());
+    enabled.connect_toggled(clone!(@weak acceleration => @default-panic, move |cb| {
+        acceleration.set_sensitive(cb.is_active());
+    }));
+
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(&enabled, false, false, PADDING);
+    hbox.pack_start(&acceleration, false, false, PADDING);
+    dialog.content_area().pack_start(&hbox, false, false, PADDING);
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some("Applies to mounts supporting variable slewing speeds (Sky-Watcher, ASCOM, simulator).")),
+        false, false, PADDING
+    );
+    dialog.show_all();
+
+    if dialog.run() == gtk::ResponseType::Ok {
+        program_data_rc.borrow().config.set_slew_acceleration(
+            if enabled.is_active() { Some(acceleration.value()) } else { None }
+        );
+    }
+}
+
+/// Lets the user add and remove slewing speeds offered for the connected mount type.
+fn show_slewing_speeds_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let mount_type = match program_data_rc.borrow().mount_data.mount_type {
+        Some(mount_type) => mount_type,
+        None => return
+    };
+
+    let speeds = program_data_rc.borrow().config.slewing_speeds(mount_type);
+    if let Some(speeds) = show_speed_list_dialog(
+        "Slewing speeds",
+        &format!("Speeds offered for mount type \"{}\" (multiples of sidereal rate):", mount_type.config_name()),
+        speeds,
+        1000.0,
+        |value| format!("{}x", value),
+        program_data_rc
+    ) {
+        let pd = program_data_rc.borrow();
+        pd.config.set_slewing_speeds(mount_type, &speeds);
+        if let Some(mount) = pd.mount_data.mount.as_ref() {
+            pd.gui.as_ref().unwrap().mount_widgets.set_slew_speeds(&speeds, mount);
+        }
+    }
+}
+
+/// Returns index of the supported speed closest to `speeds[idx]`; `SiderealMultiple::Max` (always supported)
+/// is used if there are no supported multiples.
+fn closest_supported_speed(speeds: &[SiderealMultiple], supported: &[bool], idx: usize) -> usize {
+    let max_idx = speeds.iter().position(|s| *s == SiderealMultiple::Max).unwrap();
+    let target = match speeds[idx] {
+        SiderealMultiple::Multiple(m) => m,
+        SiderealMultiple::Max => return max_idx
+    };
+
+    speeds.iter().enumerate()
+        .filter(|(i, _)| supported[*i])
+        .filter_map(|(i, s)| if let SiderealMultiple::Multiple(m) = s { Some((i, (m - target).abs())) } else { None })
+        .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+        .map(|(i, _)| i)
+        .unwrap_or(max_idx)
+}
+
 pub fn axis_slew(axis: mount::Axis, positive: bool, enable: bool, program_data_rc: &Rc<RefCell<ProgramData>>) -> Result<(), ()> {
-    let speed = program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.slew_speed();
-    let res = program_data_rc.borrow_mut().mount_data.mount.as_mut().unwrap().slew(
-        axis,
-        if enable {
-            match speed {
-                SiderealMultiple::Multiple(s) => mount::SlewSpeed::Specific(if positive { 1.0 } else { -1.0 } * s * mount::SIDEREAL_RATE),
-                SiderealMultiple::Max => mount::SlewSpeed::Max(positive)
-            }
-        } else {
-            mount::SlewSpeed::zero()
+    let slew_speed = if enable {
+        match program_data_rc.borrow().gui.as_ref().unwrap().mount_widgets.supported_slew_speed() {
+            SiderealMultiple::Multiple(s) => mount::SlewSpeed::Specific(if positive { 1.0 } else { -1.0 } * s * mount::SIDEREAL_RATE),
+            SiderealMultiple::Max => mount::SlewSpeed::Max(positive)
         }
-    );
+    } else {
+        mount::SlewSpeed::zero()
+    };
+
+    // (max. speed change per `SLEW_RAMP_INTERVAL`, max. speed)
+    let ramp_params = {
+        let pd = program_data_rc.borrow();
+        match (pd.config.slew_acceleration(), pd.mount_data.mount.as_ref().unwrap().max_variable_slewing_speed()) {
+            (Some(acceleration), Some(max_speed)) =>
+                Some((acceleration * SLEW_RAMP_INTERVAL.as_secs_f64() * mount::SIDEREAL_RATE, max_speed)),
+            _ => None
+        }
+    };
+
+    let res = match (ramp_params, slew_speed) {
+        (Some((max_change, max_speed)), mount::SlewSpeed::Specific(target)) if target.abs() <= max_speed =>
+            start_slew_ramp(axis, target, max_change, program_data_rc),
+
+        _ => {
+            // speeds above the ramp's maximum are set directly
+            let mut pd = program_data_rc.borrow_mut();
+            pd.mount_data.slew_ramps[axis as usize] = None;
+            pd.mount_data.mount.as_mut().unwrap().slew(axis, slew_speed)
+        }
+    };
+    program_data_rc.borrow_mut().mount_data.manually_slewed_axes[axis as usize] = enable && res.is_ok();
     if let Err(e) = &res { on_mount_error(e, program_data_rc) }
 
     res.map_err(|_| ())
 }
+
+/// Starts changing the slewing speed of `axis` gradually (by `max_change` per `SLEW_RAMP_INTERVAL`) to `target`;
+/// the first change is made immediately.
+fn start_slew_ramp(
+    axis: mount::Axis,
+    target: RadPerSec,
+    max_change: RadPerSec,
+    program_data_rc: &Rc<RefCell<ProgramData>>
+) -> Result<(), Box<dyn Error>> {
+    let mut pd = program_data_rc.borrow_mut();
+    let md = &mut pd.mount_data;
+
+    let mut ramp = md.slew_ramps[axis as usize].unwrap_or(mount::SlewRamp{ current: RadPerSec(0.0), target });
+    ramp.target = target;
+    let speed = ramp.step(max_change);
+    if let Err(e) = md.mount.as_mut().unwrap().slew(axis, mount::SlewSpeed::Specific(speed)) {
+        md.slew_ramps[axis as usize] = None;
+        return Err(e);
+    }
+    md.slew_ramps[axis as usize] = if ramp.at_target() && target.is_zero() { None } else { Some(ramp) };
+
+    md.slew_ramp_timer.run(SLEW_RAMP_INTERVAL, false, clone!(@weak program_data_rc => @default-panic, move || {
+        on_slew_ramp_timer(max_change, &program_data_rc);
+    }));
+
+    Ok(())
+}
+
+fn on_slew_ramp_timer(max_change: RadPerSec, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let result = {
+        let mut pd = program_data_rc.borrow_mut();
+        let md = &mut pd.mount_data;
+        let mount = match md.mount.as_mut() {
+            Some(mount) => mount,
+            None => return
+        };
+
+        let mut result = Ok(());
+        for axis in [mount::Axis::Primary, mount::Axis::Secondary] {
+            let ramp = match md.slew_ramps[axis as usize].as_mut() {
+                Some(ramp) if !ramp.at_target() => ramp,
+                _ => continue
+            };
+            let speed = ramp.step(max_change);
+            if ramp.at_target() && ramp.target.is_zero() { md.slew_ramps[axis as usize] = None; }
+
+            result = mount.slew(axis, mount::SlewSpeed::Specific(speed));
+            if result.is_err() { break; }
+        }
+
+        if result.is_err() || md.slew_ramps.iter().all(|ramp| ramp.map_or(true, |ramp| ramp.at_target())) {
+            md.slew_ramp_timer.stop();
+        }
+
+        result
+    };
+
+    if let Err(e) = &result { on_mount_error(e, program_data_rc); }
+}
+
+/// Issues a single guide pulse (see `NUDGE_DURATION`) at the selected guiding speed.
+fn nudge(axis: mount::Axis, positive: bool, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let result = {
+        let mut pd = program_data_rc.borrow_mut();
+        if pd.mount_data.guiding_pos.is_some() || pd.mount_data.calibration_in_progress() {
+            log::warn!("cannot nudge the mount during guiding or calibration");
+            return;
+        }
+
+        let speed = pd.gui.as_ref().unwrap().mount_widgets().guide_speed() * mount::SIDEREAL_RATE
+            * if positive { 1.0 } else { -1.0 };
+        let (axis1_speed, axis2_speed) = match axis {
+            mount::Axis::Primary => (speed, RadPerSec(0.0)),
+            mount::Axis::Secondary => (RadPerSec(0.0), speed)
+        };
+
+        let result = pd.mount_data.mount.as_mut().unwrap().guide(axis1_speed, axis2_speed);
+        if result.is_ok() {
+            log::info!("nudging mount: {} {}",
+                match axis { mount::Axis::Primary => "primary axis", mount::Axis::Secondary => "secondary axis" },
+                if positive { "positive" } else { "negative" }
+            );
+            pd.mount_data.nudge_timer.run(NUDGE_DURATION, true, clone!(@weak program_data_rc => @default-panic, move || {
+                let result = match program_data_rc.borrow_mut().mount_data.mount.as_mut() {
+                    Some(mount) => mount.guide(RadPerSec(0.0), RadPerSec(0.0)),
+                    None => Ok(())
+                };
+                if let Err(e) = &result { on_mount_error(e, &program_data_rc); }
+            }));
+        }
+
+        result
+    };
+
+    if let Err(e) = &result { on_mount_error(e, program_data_rc); }
+}
+
+/// Stops user-initiated slews (if enabled in configuration); the direction button release may not be received
+/// after the main window loses focus.
+pub fn on_main_window_focus_out(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let slewed_axes = {
+        let pd = program_data_rc.borrow();
+        if !pd.config.stop_slew_on_focus_loss() || pd.mount_data.mount.is_none() { return; }
+        pd.mount_data.manually_slewed_axes
+    };
+
+    for axis in [mount::Axis::Primary, mount::Axis::Secondary] {
+        if slewed_axes[axis as usize] {
+            log::info!("main window lost focus; stopping slew");
+            let _ = axis_slew(axis, true, false, program_data_rc);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_unsupported_speed_closest_supported_one_is_chosen() {
+        let speeds = [
+            SiderealMultiple::Multiple(8.0),
+            SiderealMultiple::Multiple(16.0),
+            SiderealMultiple::Multiple(64.0),
+            SiderealMultiple::Max
+        ];
+
+        assert_eq!(0, closest_supported_speed(&speeds, &[true, false, true, true], 1));
+        assert_eq!(2, closest_supported_speed(&speeds, &[false, false, true, true], 1));
+        assert_eq!(3, closest_supported_speed(&speeds, &[false, false, false, true], 1));
+    }
+}
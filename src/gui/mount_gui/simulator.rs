@@ -10,37 +10,107 @@
 //! Mount simulator connection GUI.
 //!
 
-use crate::{devices::DeviceConnection, gui::ConnectionCreator};
+use crate::{devices::DeviceConnection, gui::ConnectionCreator, MountSimulatorParams};
 use gtk::prelude::*;
 use std::error::Error;
 
 /// Control padding in pixels.
 const PADDING: u32 = 10;
 
+/// Spin buttons for the X and Y components of a direction.
+struct DirWidgets {
+    x: gtk::SpinButton,
+    y: gtk::SpinButton
+}
+
+impl DirWidgets {
+    fn new(dir: cgmath::Vector2<i32>) -> DirWidgets {
+        let new_spin_btn = |value| gtk::SpinButton::new(
+            Some(&gtk::Adjustment::new(value as f64, -100.0, 100.0, 1.0, 10.0, 0.0)), 1.0, 0
+        );
+
+        DirWidgets{ x: new_spin_btn(dir.x), y: new_spin_btn(dir.y) }
+    }
+
+    fn value(&self) -> cgmath::Vector2<i32> {
+        cgmath::Vector2::new(self.x.value_as_int(), self.y.value_as_int())
+    }
+
+    fn attach(&self, grid: &gtk::Grid, label: &str, row: i32) {
+        grid.attach(&gtk::Label::builder().label(label).halign(gtk::Align::Start).build(), 0, row, 1, 1);
+        grid.attach(&gtk::Label::new(Some("X:")), 1, row, 1, 1);
+        grid.attach(&self.x, 2, row, 1, 1);
+        grid.attach(&gtk::Label::new(Some("Y:")), 3, row, 1, 1);
+        grid.attach(&self.y, 4, row, 1, 1);
+    }
+}
+
 pub struct SimulatorConnectionCreator {
-    dialog_tab: gtk::Box
+    dialog_tab: gtk::Box,
+    sky_rotation_dir: DirWidgets,
+    primary_axis_slew_dir: DirWidgets,
+    sky_rotation_speed: gtk::SpinButton
 }
 
 impl SimulatorConnectionCreator {
-    pub fn new(_configuration: &crate::config::Configuration) -> Box<dyn ConnectionCreator> {
+    pub fn new(configuration: &crate::config::Configuration) -> Box<dyn ConnectionCreator> {
+        let params = configuration.mount_simulator_params();
+
         let dialog_tab = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
         dialog_tab.pack_start(
-            &gtk::Label::new(Some("Mount simulator.")),
+            &gtk::Label::new(Some("Mount simulator. Directions are in the camera simulator's image space.")),
             false,
             false,
             PADDING
         );
 
-        Box::new(SimulatorConnectionCreator{ dialog_tab })
+        let grid = gtk::GridBuilder::new()
+            .row_spacing(PADDING)
+            .column_spacing(PADDING)
+            .margin(PADDING as i32)
+            .build();
+
+        let sky_rotation_dir = DirWidgets::new(params.sky_rotation_dir_in_img_space);
+        sky_rotation_dir.attach(&grid, "Sky rotation direction:", 0);
+
+        let primary_axis_slew_dir = DirWidgets::new(params.primary_axis_slew_dir_in_img_space);
+        primary_axis_slew_dir.attach(&grid, "Primary axis slew direction:", 1);
+
+        let sky_rotation_speed = gtk::SpinButton::new(
+            Some(&gtk::Adjustment::new(params.sky_rotation_speed_pix_per_sec as f64, 0.0, 1000.0, 1.0, 10.0, 0.0)),
+            1.0,
+            0
+        );
+        grid.attach(
+            &gtk::Label::builder().label("Sky rotation speed (pixels/s):").halign(gtk::Align::Start).build(),
+            0, 2, 1, 1
+        );
+        grid.attach(&sky_rotation_speed, 2, 2, 1, 1);
+
+        dialog_tab.pack_start(&grid, false, false, 0);
+
+        Box::new(SimulatorConnectionCreator{ dialog_tab, sky_rotation_dir, primary_axis_slew_dir, sky_rotation_speed })
     }
 }
 
 impl ConnectionCreator for SimulatorConnectionCreator {
     fn controls(&self) -> &gtk::Box { &self.dialog_tab }
 
-    fn create(&self, _configuration: &crate::config::Configuration) -> Result<DeviceConnection, Box<dyn Error>> {
-        Ok(DeviceConnection::MountSimulator)
+    fn create(&self, configuration: &crate::config::Configuration) -> Result<DeviceConnection, Box<dyn Error>> {
+        let params = MountSimulatorParams{
+            sky_rotation_dir_in_img_space: self.sky_rotation_dir.value(),
+            primary_axis_slew_dir_in_img_space: self.primary_axis_slew_dir.value(),
+            sky_rotation_speed_pix_per_sec: self.sky_rotation_speed.value_as_int() as u32
+        };
+
+        if params.sky_rotation_dir_in_img_space == cgmath::Vector2::new(0, 0)
+            || params.primary_axis_slew_dir_in_img_space == cgmath::Vector2::new(0, 0) {
+            return Err("direction must be non-zero".into());
+        }
+
+        configuration.set_mount_simulator_params(&params);
+        Ok(DeviceConnection::MountSimulator{ params })
     }
 
     fn label(&self) -> &'static str { "Simulator" }
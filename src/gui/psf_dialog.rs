@@ -10,7 +10,7 @@
 //! Point Spread Function (collimation helper) dialog.
 //!
 
-use cgmath::{EuclideanSpace, Point2};
+use cgmath::{EuclideanSpace, InnerSpace, Point2, Vector2};
 use crate::ProgramData;
 use ga_image::{ImageView, Image, PixelFormat};
 use glib::clone;
@@ -20,12 +20,25 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 struct State {
-    image: Option<cairo::ImageSurface>
+    image: Option<cairo::ImageSurface>,
+    /// Star size measured in the most recent averaged image.
+    measurement: Option<PsfMeasurement>
+}
+
+/// Star size measurements (in pixels).
+#[derive(Copy, Clone, Debug)]
+struct PsfMeasurement {
+    /// Full width at half maximum.
+    fwhm: f64,
+    /// Half flux diameter.
+    hfd: f64
 }
 
 pub struct PsfDialog {
     dialog: gtk::Dialog,
     drawing_area: gtk::DrawingArea,
+    readout: gtk::Label,
+    image_scale: gtk::SpinButton,
     num_averaged: usize,
     num_to_average: usize,
     psf_size: u32,
@@ -57,10 +70,11 @@ impl PsfDialog {
         });
 
         let state = Rc::new(RefCell::new(State{
-            image: None
+            image: None,
+            measurement: None
         }));
 
-        let drawing_area = init_controls(&dialog, &state);
+        let (drawing_area, readout, image_scale) = init_controls(&dialog, program_data_rc, &state);
         dialog.show_all();
         dialog.hide();
 
@@ -68,6 +82,8 @@ impl PsfDialog {
         PsfDialog{
             dialog,
             drawing_area,
+            readout,
+            image_scale,
             state,
             num_averaged: 0,
             num_to_average: 10,
@@ -117,6 +133,10 @@ impl PsfDialog {
                 }
             }
 
+            let measurement = measure_psf(&self.averaged_img);
+            self.state.borrow_mut().measurement = measurement;
+            update_readout(&self.readout, measurement, image_scale(&self.image_scale));
+
             let img_bgra24 = self.averaged_img.convert_pix_fmt(ga_image::PixelFormat::BGRA8, None);
             let stride = img_bgra24.bytes_per_line() as i32;
             let (width, height) = (img_bgra24.width() as i32, img_bgra24.height() as i32);
@@ -137,10 +157,102 @@ impl PsfDialog {
     }
 }
 
+/// Returns the image scale (arcseconds per pixel) entered by user (`None` if unknown).
+fn image_scale(spin_button: &gtk::SpinButton) -> Option<f64> {
+    let value = spin_button.value();
+    if value > 0.0 { Some(value) } else { None }
+}
+
+fn format_size(value_px: f64, image_scale: Option<f64>) -> String {
+    match image_scale {
+        Some(scale) => format!("{:.2} px ({:.2}″)", value_px, value_px * scale),
+        None => format!("{:.2} px", value_px)
+    }
+}
+
+fn update_readout(label: &gtk::Label, measurement: Option<PsfMeasurement>, image_scale: Option<f64>) {
+    match measurement {
+        Some(m) => label.set_text(&format!(
+            "FWHM: {}    HFD: {}", format_size(m.fwhm, image_scale), format_size(m.hfd, image_scale)
+        )),
+        None => label.set_text("FWHM: -    HFD: -")
+    }
+}
+
+/// Measures the star in `image` (Mono32f), which is expected to be centered on the star.
+///
+/// Background level is estimated from the image border and subtracted first.
+fn measure_psf(image: &Image) -> Option<PsfMeasurement> {
+    assert!(image.pixel_format() == PixelFormat::Mono32f);
+
+    let (width, height) = (image.width(), image.height());
+    if width < 3 || height < 3 { return None; }
+
+    let mut border_sum = 0.0;
+    let mut border_count = 0;
+    for y in 0..height {
+        let line = image.line::<f32>(y);
+        if y == 0 || y == height - 1 {
+            border_sum += line.iter().map(|v| *v as f64).sum::<f64>();
+            border_count += width as usize;
+        } else {
+            border_sum += line[0] as f64 + line[width as usize - 1] as f64;
+            border_count += 2;
+        }
+    }
+    let background = border_sum / border_count as f64;
+
+    let mut total_flux = 0.0;
+    let mut max_value = 0.0;
+    let mut moment = Vector2{ x: 0.0, y: 0.0 };
+    for y in 0..height {
+        for (x, value) in image.line::<f32>(y).iter().enumerate() {
+            let value = (*value as f64 - background).max(0.0);
+            total_flux += value;
+            moment += value * Vector2{ x: x as f64, y: y as f64 };
+            if value > max_value { max_value = value; }
+        }
+    }
+    if total_flux <= 0.0 { return None; }
+    let centroid = Point2::from_vec(moment / total_flux);
+
+    // FWHM: diameter of a circle whose area equals the number of pixels at or above half of the maximum
+    let mut num_above_half_max = 0;
+    let mut pixels: Vec<(f64, f64)> = vec![]; // (distance from centroid, value)
+    for y in 0..height {
+        for (x, value) in image.line::<f32>(y).iter().enumerate() {
+            let value = (*value as f64 - background).max(0.0);
+            if value >= max_value / 2.0 { num_above_half_max += 1; }
+            if value > 0.0 {
+                pixels.push(((Point2{ x: x as f64, y: y as f64 } - centroid).magnitude(), value));
+            }
+        }
+    }
+    let fwhm = 2.0 * (num_above_half_max as f64 / std::f64::consts::PI).sqrt();
+
+    // HFD: diameter of the circle enclosing half of the total flux
+    pixels.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut enclosed = 0.0;
+    let mut prev_radius = 0.0;
+    let mut half_flux_radius = 0.0;
+    for (radius, value) in &pixels {
+        if enclosed + value >= total_flux / 2.0 {
+            let fraction = (total_flux / 2.0 - enclosed) / value;
+            half_flux_radius = prev_radius + fraction * (radius - prev_radius);
+            break;
+        }
+        enclosed += value;
+        prev_radius = *radius;
+    }
+
+    Some(PsfMeasurement{ fwhm, hfd: 2.0 * half_flux_radius })
+}
+
 fn init_controls(
     dialog: &gtk::Dialog,
+    program_data_rc: &Rc<RefCell<ProgramData>>,
     state: &Rc<RefCell<State>>
-) -> gtk::DrawingArea {
+) -> (gtk::DrawingArea, gtk::Label, gtk::SpinButton) {
 
     // control padding in pixels
     const PADDING: u32 = 10;
@@ -153,11 +265,32 @@ fn init_controls(
         gtk::Inhibit(true)
     }));
 
+    let readout = gtk::Label::new(None);
+    readout.set_halign(gtk::Align::Start);
+    update_readout(&readout, None, None);
+    vbox.pack_start(&readout, false, false, PADDING);
+
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(&gtk::Label::new(Some("Image scale (″/px):")), false, false, PADDING);
+    let image_scale_btn = gtk::SpinButton::with_range(0.0, 100.0, 0.01);
+    image_scale_btn.set_digits(2);
+    image_scale_btn.set_tooltip_text(Some("Used to show star sizes in arcseconds; 0 means unknown"));
+    image_scale_btn.set_value(program_data_rc.borrow().config.image_scale().unwrap_or(0.0));
+    image_scale_btn.connect_value_changed(clone!(
+        @weak program_data_rc, @weak state, @weak readout => @default-panic, move |btn| {
+            let scale = image_scale(btn);
+            program_data_rc.borrow().config.set_image_scale(scale);
+            update_readout(&readout, state.borrow().measurement, scale);
+        }
+    ));
+    hbox.pack_start(&image_scale_btn, false, false, PADDING);
+    vbox.pack_start(&hbox, false, true, PADDING);
+
     vbox.pack_start(&drawing_area, true, true, PADDING);
 
     dialog.content_area().pack_start(&vbox, true, true, PADDING);
 
-    drawing_area
+    (drawing_area, readout, image_scale_btn)
 }
 
 fn draw(ctx: &cairo::Context, width: f64, height: f64, state: &Rc<RefCell<State>>) {
@@ -180,3 +313,35 @@ fn draw(ctx: &cairo::Context, width: f64, height: f64, state: &Rc<RefCell<State>
     ctx.rectangle(0.0, 0.0, width / scale_factor, height / scale_factor);
     ctx.fill().unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_gaussian_star() {
+        const SIZE: u32 = 64;
+        const SIGMA: f64 = 3.0;
+        const BACKGROUND: f32 = 10.0;
+
+        let mut image = Image::new(SIZE, SIZE, None, PixelFormat::Mono32f, None, true);
+        for y in 0..SIZE {
+            for (x, value) in image.line_mut::<f32>(y).iter_mut().enumerate() {
+                let r2 = (x as f64 - 32.0).powi(2) + (y as f64 - 32.0).powi(2);
+                *value = BACKGROUND + (1000.0 * (-r2 / (2.0 * SIGMA * SIGMA)).exp()) as f32;
+            }
+        }
+
+        let measurement = measure_psf(&image).unwrap();
+        // for a Gaussian, both FWHM and HFD equal 2*sqrt(2*ln(2))*sigma
+        let expected = 2.0 * (2.0 * 2f64.ln()).sqrt() * SIGMA;
+        assert!((measurement.fwhm - expected).abs() < 0.3);
+        assert!((measurement.hfd - expected).abs() < 0.3);
+    }
+
+    #[test]
+    fn blank_image_has_no_star() {
+        let image = Image::new(16, 16, None, PixelFormat::Mono32f, None, true);
+        assert!(measure_psf(&image).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Raw camera features dialog.
+//!
+
+use crate::{ProgramData, camera::RawNode, gui::{show_message, DialogDestroyer}};
+use glib::clone;
+use gtk::prelude::*;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// Control padding in pixels.
+const PADDING: u32 = 10;
+
+mod column {
+    pub const DISPLAY_NAME: u32 = 0;
+    pub const NAME: u32 = 1;
+    pub const VALUE: u32 = 2;
+    pub const WRITABLE: u32 = 3;
+}
+
+fn add_nodes(store: &gtk::TreeStore, parent: Option<&gtk::TreeIter>, node: &RawNode) {
+    let iter = store.insert_with_values(parent, None, &[
+        (column::DISPLAY_NAME, &node.display_name),
+        (column::NAME, &node.name),
+        (column::VALUE, &node.value.clone().unwrap_or_default()),
+        (column::WRITABLE, &node.writable)
+    ]);
+
+    for child in &node.children {
+        add_nodes(store, Some(&iter), child);
+    }
+}
+
+fn collect_values(node: &RawNode, values: &mut HashMap<String, String>) {
+    if let Some(value) = &node.value {
+        values.insert(node.name.clone(), value.clone());
+    }
+    for child in &node.children {
+        collect_values(child, values);
+    }
+}
+
+/// Re-reads all values from camera (setting one node may affect others).
+fn refresh_values(store: &gtk::TreeStore, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let result = program_data_rc.borrow().camera.as_ref().unwrap().raw_node_tree();
+    match result {
+        Ok(Some(root)) => {
+            let mut values = HashMap::new();
+            collect_values(&root, &mut values);
+            store.foreach(|model, _, iter| {
+                let name = model.value(iter, column::NAME as i32).get::<String>().unwrap();
+                if let Some(value) = values.get(&name) {
+                    store.set_value(iter, column::VALUE, &value.to_value());
+                }
+                false
+            });
+        },
+
+        Ok(None) => (),
+
+        Err(e) => show_message(
+            &format!("Failed to read camera features:\n{:?}", e),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        )
+    }
+}
+
+pub fn show_raw_features_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let result = program_data_rc.borrow().camera.as_ref().unwrap().raw_node_tree();
+    let root = match result {
+        Ok(Some(root)) => root,
+
+        Ok(None) => {
+            show_message("The camera does not provide raw features.", "Information", gtk::MessageType::Info, program_data_rc);
+            return;
+        },
+
+        Err(e) => {
+            show_message(
+                &format!("Failed to read camera features:\n{:?}", e),
+                "Error",
+                gtk::MessageType::Error,
+                program_data_rc
+            );
+            return;
+        }
+    };
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Raw camera features"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("Close", gtk::ResponseType::Close)]
+    );
+    let _ddestr = DialogDestroyer::new(&dialog);
+    dialog.set_default_size(600, 600);
+
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some("Double-click a value to change it. Changing some features may disrupt capture.")),
+        false,
+        true,
+        PADDING
+    );
+
+    let store = gtk::TreeStore::new(&[glib::Type::STRING, glib::Type::STRING, glib::Type::STRING, glib::Type::BOOL]);
+    for child in &root.children {
+        add_nodes(&store, None, child);
+    }
+
+    let tree_view = gtk::TreeView::with_model(&store);
+
+    let add_column = |title: &str, col_idx: u32| -> gtk::CellRendererText {
+        let renderer = gtk::CellRendererText::new();
+        let column = gtk::TreeViewColumn::new();
+        column.set_title(title);
+        column.set_resizable(true);
+        column.pack_start(&renderer, true);
+        column.add_attribute(&renderer, "text", col_idx as i32);
+        tree_view.append_column(&column);
+        renderer
+    };
+
+    add_column("Feature", column::DISPLAY_NAME);
+    add_column("Name", column::NAME);
+    let value_renderer = add_column("Value", column::VALUE);
+    tree_view.column(2).unwrap().add_attribute(&value_renderer, "editable", column::WRITABLE as i32);
+
+    value_renderer.connect_edited(clone!(@weak program_data_rc, @weak store => @default-panic, move |_, path, new_text| {
+        let iter = match store.iter(&path) { Some(iter) => iter, None => return };
+        let name = store.value(&iter, column::NAME as i32).get::<String>().unwrap();
+
+        let result = program_data_rc.borrow_mut().camera.as_mut().unwrap().set_raw_node_value(&name, new_text);
+        match result {
+            Ok(()) => log::info!("set camera feature {} to \"{}\"", name, new_text),
+            Err(e) => show_message(
+                &format!("Failed to set camera feature {}:\n{:?}", name, e),
+                "Error",
+                gtk::MessageType::Error,
+                &program_data_rc
+            )
+        }
+
+        refresh_values(&store, &program_data_rc);
+    }));
+
+    let scroller = gtk::ScrolledWindow::new::<gtk::Adjustment, gtk::Adjustment>(None, None);
+    scroller.add(&tree_view);
+    dialog.content_area().pack_start(&scroller, true, true, PADDING);
+
+    dialog.show_all();
+    dialog.run();
+}
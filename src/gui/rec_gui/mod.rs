@@ -14,6 +14,8 @@ mod time_widget;
 
 use crate::gui::camera_gui::{ControlWidgetBundle};
 use crate::gui::actions;
+use crate::gui::auto_exposure;
+use crate::i18n::tr;
 use crate::output;
 use crate::output::{OutputFormat};
 use crate::ProgramData;
@@ -27,6 +29,7 @@ use std::cell::RefCell;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::atomic::Ordering;
 use strum::IntoEnumIterator;
 use super::show_message;
 use time_widget::TimeWidget;
@@ -34,26 +37,78 @@ use time_widget::TimeWidget;
 /// Control padding in pixels.
 const PADDING: u32 = 10;
 
+/// Maximum width and height of the thumbnail saved alongside each recording.
+const THUMBNAIL_MAX_SIZE: i32 = 320;
+
+/// Delay between setting the shortest exposure time and starting recording of bias frames.
+const BIAS_RECORDING_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Kind of calibration frames being recorded.
+#[derive(Copy, Clone, PartialEq)]
+enum CalibrationFrames {
+    Bias,
+    Flat
+}
+
+/// Selects what is written to the settings file saved along with each recording.
+#[derive(Copy, Clone)]
+pub struct SettingsFileContents {
+    pub date_time: bool,
+    pub mount_info: bool,
+    pub camera_temperature: bool,
+    /// Controls in "auto" mode.
+    pub auto_controls: bool,
+    /// Controls hidden in the camera controls panel (by the favorites setting or filter).
+    pub hidden_controls: bool
+}
+
 pub struct RecWidgets {
     btn_record: gtk::Button,
     btn_stop: gtk::Button,
+    btn_pause: gtk::ToggleButton,
+    btn_pause_toggled: glib::SignalHandlerId,
     btn_nocrop: gtk::Button,
     name_prefix: gtk::Entry,
     dest_dir: gtk::FileChooserButton,
+    camera_timestamps: gtk::CheckButton,
     output_fmt_getter: Box<dyn Fn() -> output::OutputFormat>,
+    tiff_compression_getter: Box<dyn Fn() -> output::tiff::Compression>,
+    ser_color_id_getter: Box<dyn Fn() -> output::ser::ColorId>,
+    frame_selection_getter: Box<dyn Fn() -> recording::FrameSelection>,
     rec_limit_getter: Box<dyn Fn() -> recording::Limit>,
+    /// Returns the maximum recording frame rate (`None` if not limited).
+    max_frame_rate_getter: Box<dyn Fn() -> Option<f64>>,
     /// Returns (sequence count, sequence interval).
     sequence_getter: Box<dyn Fn() -> (usize, std::time::Duration)>,
+    /// Stores values of the limit and sequence widgets in configuration.
+    limit_and_sequence_storer: Box<dyn Fn(&crate::config::Configuration)>,
+    settings_file_getter: Box<dyn Fn() -> SettingsFileContents>,
     pub sequence_idx: usize,
     pub sequence_next_start: Option<std::time::Instant>,
+    /// Start time and latest progress message of the current unlimited recording (its elapsed time is shown
+    /// as a running clock, see `gui::update_recording_info`).
+    pub unlimited_recording: Option<(std::time::Instant, String)>,
     sequence_timer: Timer,
+    /// If `Some`, recordings are named accordingly.
+    calibration_frames: Option<CalibrationFrames>,
+    calibration_timer: Timer,
+    /// Destination of the current recording's thumbnail.
+    thumbnail_path: Option<PathBuf>,
     others: gtk::Box,
 }
 
 impl RecWidgets {
-    pub fn on_disconnect(&self) {
+    pub fn store_limit_and_sequence(&self, config: &crate::config::Configuration) {
+        (*self.limit_and_sequence_storer)(config);
+    }
+
+    pub fn on_disconnect(&mut self) {
+        self.abort_sequence();
+        self.calibration_timer.stop();
+        self.calibration_frames = None;
         self.btn_record.set_sensitive(false);
         self.btn_stop.set_sensitive(false);
+        self.reset_pause();
         self.btn_nocrop.set_sensitive(false);
         self.others.set_sensitive(false);
     }
@@ -61,6 +116,7 @@ impl RecWidgets {
     pub fn on_connect(&self) {
         self.btn_record.set_sensitive(true);
         self.btn_stop.set_sensitive(false);
+        self.reset_pause();
         self.btn_nocrop.set_sensitive(true); //TODO: only if crop enabled
         self.others.set_sensitive(true);
     }
@@ -68,25 +124,53 @@ impl RecWidgets {
     pub fn on_start_recording(&self) {
         self.btn_record.set_sensitive(false);
         self.btn_stop.set_sensitive(true);
+        self.btn_pause.set_sensitive(true);
         self.btn_nocrop.set_sensitive(false);
         self.others.set_sensitive(false);
     }
 
     pub fn on_stop_recording(&mut self) {
-        self.sequence_timer.stop();
+        self.abort_sequence();
+        self.unlimited_recording = None;
+        self.calibration_frames = None;
         self.btn_record.set_sensitive(true);
         self.btn_stop.set_sensitive(false);
+        self.reset_pause();
         self.btn_nocrop.set_sensitive(true); //TODO: only if crop enabled
         self.others.set_sensitive(true);
     }
 
-    pub fn on_recording_ended(&self) {
+    /// Releases and disables the pause button (without notifying the capture thread).
+    fn reset_pause(&self) {
+        self.btn_pause.block_signal(&self.btn_pause_toggled);
+        self.btn_pause.set_active(false);
+        self.btn_pause.unblock_signal(&self.btn_pause_toggled);
+        self.btn_pause.set_sensitive(false);
+    }
+
+    /// Cancels the scheduled start of the next recording in sequence (if any).
+    fn abort_sequence(&mut self) {
+        self.sequence_timer.stop();
+        if self.sequence_next_start.is_some() {
+            log::info!("recording sequence aborted");
+        }
+        self.sequence_idx = 0;
+        self.sequence_next_start = None;
+    }
+
+    pub fn on_recording_ended(&mut self) {
+        self.calibration_frames = None;
         self.btn_record.set_sensitive(true);
         self.btn_stop.set_sensitive(false);
+        self.reset_pause();
         self.btn_nocrop.set_sensitive(true); //TODO: only if crop enabled
         self.others.set_sensitive(true);
     }
 
+    pub fn btn_record(&self) -> &gtk::Button { &self.btn_record }
+
+    pub fn btn_stop(&self) -> &gtk::Button { &self.btn_stop }
+
     pub fn rec_limit(&self) -> recording::Limit {
         (*self.rec_limit_getter)()
     }
@@ -96,13 +180,22 @@ impl RecWidgets {
     }
 
     pub fn name_prefix(&self) -> String {
-        self.name_prefix.text().as_str().to_string()
+        let prefix = self.name_prefix.text().as_str().to_string();
+        match self.calibration_frames {
+            Some(CalibrationFrames::Bias) => prefix + "_bias",
+            Some(CalibrationFrames::Flat) => prefix + "_flat",
+            None => prefix
+        }
     }
 
     /// Returns (sequence count, sequence interval).
     pub fn sequence(&self) -> (usize, std::time::Duration) {
         (*self.sequence_getter)()
     }
+
+    pub fn settings_file_contents(&self) -> SettingsFileContents {
+        (*self.settings_file_getter)()
+    }
 }
 
 fn append_to_fstem(p: &Path, suffix: &str) -> PathBuf {
@@ -115,21 +208,73 @@ fn append_to_fstem(p: &Path, suffix: &str) -> PathBuf {
     new_p
 }
 
+/// Returns the path of a file accompanying a recording, named after the recording's `dest_path` with `suffix`
+/// and `extension`. For image sequences (where `dest_path` is the destination directory) the file is placed inside
+/// the directory and named after `recording_name` instead.
+fn accompanying_file_path(
+    dest_path: &Path,
+    output_fmt: &OutputFormat,
+    recording_name: &str,
+    suffix: &str,
+    extension: &str
+) -> PathBuf {
+    if output_fmt.is_image_sequence() {
+        dest_path.join(format!("{}{}.{}", recording_name, suffix, extension))
+    } else {
+        append_to_fstem(dest_path, suffix).with_extension(extension)
+    }
+}
+
+/// Returns the frame selection chosen with widgets created in `create_recording_panel`.
+fn frame_selection(kinds: &gtk::ComboBoxText, value: &gtk::SpinButton) -> recording::FrameSelection {
+    match kinds.active() {
+        Some(1) => recording::FrameSelection::QualityAbove(value.value()),
+        Some(2) => recording::FrameSelection::BestPercent(value.value()),
+        _ => recording::FrameSelection::All
+    }
+}
+
+/// Adjusts the frame selection value widget to the frame selection kind (index in the list of kinds).
+fn set_frame_selection_value_range(value: &gtk::SpinButton, kind: u32) {
+    match kind {
+        1 => {
+            value.set_adjustment(&gtk::Adjustment::new(0.01, 0.0, 100.0, 0.0001, 0.01, 0.0));
+            value.set_digits(4);
+        },
+        2 => {
+            value.set_adjustment(&gtk::Adjustment::new(25.0, 1.0, 100.0, 1.0, 10.0, 0.0));
+            value.set_digits(0);
+        },
+        _ => ()
+    }
+    value.set_sensitive(kind != 0);
+}
+
 fn on_start_recording(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let mut dest_path;
     let rec_limit;
     let output_fmt;
     let name_prefix;
     let sequence_suffix;
+    let tiff_compression;
+    let ser_color_id;
+    let camera_name;
+    let camera_timestamps;
+    let max_frame_rate;
 
     {
         let mut program_data = program_data_rc.borrow_mut();
+        camera_name = program_data.camera.as_ref().unwrap().name().to_string();
         let rec_widgets = &mut program_data.gui.as_mut().unwrap().rec_widgets;
         let (sequence_count, _) = rec_widgets.sequence();
         sequence_suffix = if sequence_count > 1 { format!("_{:05}", rec_widgets.sequence_idx + 1) } else { "".to_string() };
         name_prefix = rec_widgets.name_prefix();
 
         output_fmt = (*rec_widgets.output_fmt_getter)();
+        tiff_compression = (*rec_widgets.tiff_compression_getter)();
+        ser_color_id = (*rec_widgets.ser_color_id_getter)();
+        camera_timestamps = rec_widgets.camera_timestamps.is_active();
+        max_frame_rate = (*rec_widgets.max_frame_rate_getter)();
 
         dest_path = {
             match output_fmt {
@@ -138,7 +283,7 @@ fn on_start_recording(program_data_rc: &Rc<RefCell<ProgramData>>) {
                     dest_fname.set_extension(if output_fmt == OutputFormat::AviVideo { "avi" } else { "ser" });
                     Path::new(&rec_widgets.dest_dir()).join(&dest_fname).into()
                 },
-                OutputFormat::BmpSequence | OutputFormat::TiffSequence => {
+                OutputFormat::BmpSequence | OutputFormat::TiffSequence | OutputFormat::DngSequence => {
                     rec_widgets.dest_dir()
                 }
             }
@@ -156,6 +301,8 @@ fn on_start_recording(program_data_rc: &Rc<RefCell<ProgramData>>) {
 
 
 
+    let recording_name = name_prefix + &sequence_suffix;
+
     let writer: Box<dyn output::OutputWriter> = match output_fmt {
         OutputFormat::AviVideo | OutputFormat::SerVideo => {
             match std::fs::OpenOptions::new().read(false).write(true).create(true).open(&dest_path) {
@@ -171,15 +318,9 @@ fn on_start_recording(program_data_rc: &Rc<RefCell<ProgramData>>) {
 
                 Ok(file) => {
                     if output_fmt == OutputFormat::AviVideo {
-                        show_message(
-                            "Recording as AVI not implemented yet.",
-                            "Error",
-                            gtk::MessageType::Error,
-                            program_data_rc
-                        );
-                        return;
+                        Box::new(output::avi::AviVideo::new(file, &dest_path))
                     } else {
-                        Box::new(output::ser::SerVideo::new(file))
+                        Box::new(output::ser::SerVideo::new(file, &dest_path, ser_color_id))
                     }
                 }
             }
@@ -187,35 +328,109 @@ fn on_start_recording(program_data_rc: &Rc<RefCell<ProgramData>>) {
 
         OutputFormat::BmpSequence | OutputFormat::TiffSequence => {
             Box::new(output::file_seq::FileSequence::new(
-                &dest_path, &(name_prefix + &sequence_suffix), output_fmt.file_type()
+                &dest_path, &recording_name, output_fmt.file_type(), tiff_compression
             ))
+        },
+
+        OutputFormat::DngSequence => {
+            Box::new(output::dng::DngSequence::new(&dest_path, &recording_name, &camera_name))
         }
     };
 
+    let frame_limit = match rec_limit {
+        recording::Limit::FrameCount(count) => Some(count),
+        _ => None
+    };
+    let unlimited = matches!(rec_limit, recording::Limit::Forever);
+
     let (rec_sender, rec_receiver) = crossbeam::channel::unbounded();
 
     if program_data_rc.borrow_mut().capture_thread_data.as_ref().unwrap().sender.send(
-        MainToCaptureThreadMsg::StartRecording((rec_sender, rec_limit))
+        MainToCaptureThreadMsg::StartRecording((rec_sender, rec_limit, camera_timestamps, max_frame_rate))
     ).is_err() {
-        crate::on_capture_thread_failure(program_data_rc);
+        crate::on_capture_thread_failure(None, program_data_rc);
         return;
     }
 
-    let new_job = recording::Job::new(rec_receiver, writer);
+    let verify_output = program_data_rc.borrow().config.verify_output();
+    let frame_selection = (*program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.frame_selection_getter)();
+
+    let quality_log = if program_data_rc.borrow().config.quality_log() {
+        let quality_log_path = accompanying_file_path(&dest_path, &output_fmt, &recording_name, "_quality", "csv");
+        match output::quality_log::QualityLog::create(&quality_log_path) {
+            Ok(quality_log) => Some(quality_log),
+            Err(e) => {
+                log::error!("failed to create quality log {}: {}", quality_log_path.to_string_lossy(), e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let new_job = recording::Job::new(rec_receiver, writer, verify_output, quality_log, frame_selection, frame_limit);
 
     let mut program_data = program_data_rc.borrow_mut();
     program_data.recording_thread_data.jobs.push(new_job);
     program_data.rec_job_active = true;
+    program_data.session_stats.add_recording();
     program_data.recording_thread_data.sender.send(MainToRecordingThreadMsg::CheckJobQueue).unwrap();
 
     program_data.gui.as_ref().unwrap().rec_widgets.on_start_recording();
+    program_data.gui.as_mut().unwrap().rec_widgets.unlimited_recording =
+        if unlimited { Some((std::time::Instant::now(), String::new())) } else { None };
+    super::set_recording_locked_controls_enabled(program_data.gui.as_ref().unwrap(), false);
+
+    program_data.gui.as_mut().unwrap().rec_widgets.thumbnail_path =
+        Some(accompanying_file_path(&dest_path, &output_fmt, &recording_name, "_thumbnail", "png"));
+
+    save_camera_controls_state(&dest_path, &mut program_data);
+}
+
+/// Saves a downsized copy of the currently displayed preview image as the thumbnail of the recording
+/// which has just ended.
+fn save_thumbnail(program_data: &mut ProgramData) {
+    let gui = program_data.gui.as_mut().unwrap();
+    let thumbnail_path = match gui.rec_widgets.thumbnail_path.take() {
+        Some(path) => path,
+        None => return
+    };
+
+    let pixbuf = match gui.preview_area.image_as_pixbuf() {
+        Some(pixbuf) => pixbuf,
+        None => { log::warn!("no image displayed, recording thumbnail not saved"); return; }
+    };
+
+    let (width, height) = thumbnail_size(pixbuf.width(), pixbuf.height());
+    let thumbnail = match pixbuf.scale_simple(width, height, gtk::gdk_pixbuf::InterpType::Bilinear) {
+        Some(thumbnail) => thumbnail,
+        None => { log::warn!("failed to scale recording thumbnail"); return; }
+    };
+
+    if let Err(e) = thumbnail.savev(&thumbnail_path, "png", &[]) {
+        log::warn!("failed to save thumbnail \"{}\": {}", thumbnail_path.to_string_lossy(), e);
+    }
+}
 
-    save_camera_controls_state(&dest_path, &program_data);
+/// Returns size of a thumbnail fitting within `THUMBNAIL_MAX_SIZE` and preserving the aspect ratio
+/// (images smaller than that are not enlarged).
+fn thumbnail_size(width: i32, height: i32) -> (i32, i32) {
+    let max_dim = width.max(height);
+    if max_dim <= THUMBNAIL_MAX_SIZE {
+        (width, height)
+    } else {
+        (
+            (width * THUMBNAIL_MAX_SIZE / max_dim).max(1),
+            (height * THUMBNAIL_MAX_SIZE / max_dim).max(1)
+        )
+    }
 }
 
-/// Saves current data & time, camera name and camera controls' state to a text file
-/// at the same directory as `rec_dest_path`.
-fn save_camera_controls_state(rec_dest_path: &Path, program_data: &ProgramData) {
+/// Saves camera name, camera controls' state and (optionally) current date & time, mount info
+/// and camera temperature to a text file at the same directory as `rec_dest_path`.
+fn save_camera_controls_state(rec_dest_path: &Path, program_data: &mut ProgramData) {
+    let contents = program_data.gui.as_ref().unwrap().rec_widgets.settings_file_contents();
+
     let settings_fstem = append_to_fstem(rec_dest_path, "_settings");
     let settings_path = rec_dest_path.parent().unwrap().join(settings_fstem).with_extension("txt");
     let mut file = std::fs::OpenOptions::new()
@@ -227,24 +442,42 @@ fn save_camera_controls_state(rec_dest_path: &Path, program_data: &ProgramData)
         .unwrap();
 
     writeln!(file, "Recorded with Vidoxide\n").unwrap(); //TODO: print Vidoxide version
-    writeln!(
-        file,
-        "{} ({} UTC)",
-        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
-    ).unwrap();
-    writeln!(file, "{}\n", program_data.camera.as_ref().unwrap().name()).unwrap();
+    if contents.date_time {
+        writeln!(
+            file,
+            "{} ({} UTC)",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
+        ).unwrap();
+    }
+    writeln!(file, "{}", program_data.camera.as_ref().unwrap().name()).unwrap();
+    if contents.camera_temperature {
+        if let Some(temp) = program_data.camera.as_ref().unwrap().temperature() {
+            writeln!(file, "Temperature: {:.1} °C", temp).unwrap();
+        }
+    }
+    if contents.mount_info {
+        if let Some(mount) = program_data.mount_data.mount.as_mut() {
+            write!(file, "Mount: {}", mount.get_info()).unwrap();
+            if let Ok(Some(status)) = mount.status() { write!(file, " ({})", status).unwrap(); }
+            write!(file, "\n").unwrap();
+        }
+    }
+    write!(file, "\n").unwrap();
 
     for ctrl_widgets in &program_data.gui.as_ref().unwrap().control_widgets {
-        if !(ctrl_widgets.1).0.h_box.get_visible() {
+        if !contents.hidden_controls && !(ctrl_widgets.1).0.h_box.get_visible() {
+            continue;
+        }
+
+        let auto_active = (ctrl_widgets.1).0.auto.as_ref().map_or(false, |auto| auto.is_active());
+        if !contents.auto_controls && auto_active {
             continue;
         }
 
         write!(file, "{}: ", (ctrl_widgets.1).0.name).unwrap();
 
-        if let Some(auto) = &(ctrl_widgets.1).0.auto {
-            if auto.is_active() { write!(file, "auto, ").unwrap(); }
-        }
+        if auto_active { write!(file, "auto, ").unwrap(); }
 
         if let Some(on_off) = &(ctrl_widgets.1).0.on_off {
             write!(file, "{}, ", if on_off.is_active() { "on" } else { "off" }).unwrap();
@@ -266,40 +499,99 @@ fn save_camera_controls_state(rec_dest_path: &Path, program_data: &ProgramData)
 
 }
 
+fn start_calibration_recording(kind: CalibrationFrames, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    {
+        let mut pd = program_data_rc.borrow_mut();
+        let rec_widgets = &mut pd.gui.as_mut().unwrap().rec_widgets;
+        rec_widgets.calibration_frames = Some(kind);
+        rec_widgets.sequence_idx = 0;
+    }
+    on_start_recording(program_data_rc);
+}
+
+/// Sets the shortest exposure time and records bias frames (with the current recording limit and sequence settings).
+fn on_capture_bias(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    if !auto_exposure::set_min_exposure(program_data_rc) { return; }
+
+    program_data_rc.borrow().gui.as_ref().unwrap().rec_widgets.calibration_timer.run(
+        BIAS_RECORDING_DELAY,
+        true,
+        clone!(@weak program_data_rc => @default-panic, move || {
+            start_calibration_recording(CalibrationFrames::Bias, &program_data_rc)
+        })
+    );
+}
+
+/// Adjusts exposure time to reach `target` histogram mean (fraction of the full range) and records flat frames
+/// (with the current recording limit and sequence settings).
+fn on_capture_flats(target: f64, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    auto_exposure::start(
+        target,
+        auto_exposure::Statistic::Mean,
+        false,
+        Box::new(|program_data_rc: &Rc<RefCell<ProgramData>>| {
+            start_calibration_recording(CalibrationFrames::Flat, program_data_rc)
+        }),
+        program_data_rc
+    );
+}
+
 pub fn on_stop_recording(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let mut program_data = program_data_rc.borrow_mut();
 
     let _ = program_data.capture_thread_data.as_ref().unwrap().sender.send(MainToCaptureThreadMsg::StopRecording);
     program_data.rec_job_active = false;
+    save_thumbnail(&mut program_data);
 
     let pd_gui = program_data.gui.as_mut().unwrap();
     pd_gui.status_bar.current_recording_info.set_label(&"");
     pd_gui.rec_widgets.on_stop_recording();
-    pd_gui.rec_widgets.sequence_next_start = None;
+    super::set_recording_locked_controls_enabled(pd_gui, true);
 }
 
 /// Returns (top-level box, RecWidgets).
 pub fn create_recording_panel(program_data_rc: &Rc<RefCell<ProgramData>>) -> (gtk::Box, RecWidgets) {
     let btn_record = gtk::Button::with_label("⏺");
-    btn_record.set_tooltip_text(Some("Start recording"));
+    btn_record.set_tooltip_text(Some(tr("Start recording")));
     btn_record.set_sensitive(false);
     btn_record.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
-        program_data_rc.borrow_mut().gui.as_mut().unwrap().rec_widgets.sequence_idx = 0;
+        {
+            let mut pd = program_data_rc.borrow_mut();
+            let rec_widgets = &mut pd.gui.as_mut().unwrap().rec_widgets;
+            rec_widgets.sequence_idx = 0;
+            rec_widgets.calibration_frames = None;
+        }
         on_start_recording(&program_data_rc)
     }));
 
     let btn_stop = gtk::Button::with_label("⏹");
-    btn_stop.set_tooltip_text(Some("Stop recording"));
+    btn_stop.set_tooltip_text(Some(tr("Stop recording")));
     btn_stop.set_sensitive(false);
     btn_stop.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| on_stop_recording(&program_data_rc)));
 
+    let btn_pause = gtk::ToggleButton::with_label("⏸");
+    btn_pause.set_tooltip_text(Some(tr("Pause recording")));
+    btn_pause.set_sensitive(false);
+    let btn_pause_toggled = btn_pause.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |btn| {
+        let msg = if btn.is_active() {
+            log::info!("recording paused");
+            MainToCaptureThreadMsg::PauseRecording
+        } else {
+            log::info!("recording resumed");
+            MainToCaptureThreadMsg::ResumeRecording
+        };
+        if let Some(capture_thread_data) = program_data_rc.borrow().capture_thread_data.as_ref() {
+            let _ = capture_thread_data.sender.send(msg);
+        }
+    }));
+
     let btn_snapshot = gtk::Button::with_label("✷");
-    btn_snapshot.set_tooltip_text(Some("Take snapshot"));
+    btn_snapshot.set_tooltip_text(Some(tr("Take snapshot")));
     btn_snapshot.set_action_name(Some(&actions::prefixed(actions::TAKE_SNAPSHOT)));
 
     let btn_nocrop = gtk::Button::with_label("✂");
     btn_nocrop.set_sensitive(false);
-    btn_nocrop.set_tooltip_text(Some("Disable recording crop"));
+    btn_nocrop.set_tooltip_text(Some(tr("Disable recording crop")));
     btn_nocrop.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
         let mut pd = program_data_rc.borrow_mut();
         pd.capture_thread_data.as_ref().unwrap().sender.send(MainToCaptureThreadMsg::DisableRecordingCrop).unwrap();
@@ -309,6 +601,7 @@ pub fn create_recording_panel(program_data_rc: &Rc<RefCell<ProgramData>>) -> (gt
     let btn_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     btn_box.pack_start(&btn_record, false, false, PADDING);
     btn_box.pack_start(&btn_stop, false, false, PADDING);
+    btn_box.pack_start(&btn_pause, false, false, PADDING);
 
     btn_box.pack_end(&btn_nocrop, false, false, PADDING);
     btn_box.pack_end(&btn_snapshot, false, false, PADDING);
@@ -316,7 +609,7 @@ pub fn create_recording_panel(program_data_rc: &Rc<RefCell<ProgramData>>) -> (gt
     let others = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
     let dest_dir_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    let dest_dir = gtk::FileChooserButton::new("Destination directory", gtk::FileChooserAction::SelectFolder);
+    let dest_dir = gtk::FileChooserButton::new(tr("Destination directory"), gtk::FileChooserAction::SelectFolder);
 
     // TODO encode a `Path` somehow
     // if let Some(prev_dest_dir) = program_data_rc.borrow().config.recording_dest_path() {
@@ -325,85 +618,395 @@ pub fn create_recording_panel(program_data_rc: &Rc<RefCell<ProgramData>>) -> (gt
     //     dest_dir.set_filename(".");
     // }
 
-    dest_dir_box.pack_start(&gtk::Label::new(Some("Dest. directory:")), false, false, PADDING);
+    dest_dir_box.pack_start(&gtk::Label::new(Some(tr("Dest. directory:"))), false, false, PADDING);
     dest_dir_box.pack_start(&dest_dir, false, false, PADDING);
     others.pack_start(&dest_dir_box, false, false, PADDING);
 
     let prefix_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     let name_prefix = gtk::EntryBuilder::new().text("rec").build();
-    prefix_box.pack_start(&gtk::Label::new(Some("Name prefix:")), false, false, PADDING);
+    prefix_box.pack_start(&gtk::Label::new(Some(tr("Name prefix:"))), false, false, PADDING);
     prefix_box.pack_start(&name_prefix, false, false, PADDING);
     others.pack_start(&prefix_box, false, false, PADDING);
 
     let output_fmt_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    output_fmt_box.pack_start(&gtk::Label::new(Some("Output format:")), false, false, PADDING);
+    output_fmt_box.pack_start(&gtk::Label::new(Some(tr("Output format:"))), false, false, PADDING);
     let output_formats = gtk::ComboBoxText::new();
     for ofmt in OutputFormat::iter() {
         output_formats.append_text(&format!("{}", ofmt));
     }
-    output_formats.set_active(Some(0));
 
     output_fmt_box.pack_start(&output_formats, false, false, PADDING);
     others.pack_start(&output_fmt_box, false, false, PADDING);
 
-    let limit_frame = gtk::Frame::new(Some("Limit"));
+    let tiff_compression_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    tiff_compression_box.pack_start(&gtk::Label::new(Some(tr("TIFF compression:"))), false, false, PADDING);
+    let tiff_compressions = gtk::ComboBoxText::new();
+    for compression in output::tiff::Compression::iter() {
+        tiff_compressions.append_text(&format!("{}", compression));
+    }
+    tiff_compressions.set_active(Some(0));
+    tiff_compression_box.pack_start(&tiff_compressions, false, false, PADDING);
+    tiff_compression_box.set_sensitive(false);
+    others.pack_start(&tiff_compression_box, false, false, PADDING);
+
+    let ser_color_id_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    ser_color_id_box.pack_start(&gtk::Label::new(Some("SER color ID:")), false, false, PADDING);
+    let ser_color_ids = gtk::ComboBoxText::new();
+    for color_id in output::ser::ColorId::iter() {
+        ser_color_ids.append_text(&format!("{}", color_id));
+    }
+    ser_color_ids.set_active(Some(0));
+    ser_color_ids.set_tooltip_text(Some(tr(
+        "Pixel layout declared in the SER header; players debayer raw color videos according to it.\n\
+        \"Automatic\" uses the camera's pixel format; override it if the camera reports raw color data as mono \
+        or with a wrong Bayer pattern. Not applicable to RGB data."
+    )));
+    ser_color_id_box.pack_start(&ser_color_ids, false, false, PADDING);
+    ser_color_id_box.set_sensitive(false);
+    others.pack_start(&ser_color_id_box, false, false, PADDING);
+
+    let camera_timestamps = gtk::CheckButtonBuilder::new()
+        .label("Use camera timestamps (if supported)")
+        .tooltip_text(tr(
+            "Base frame timestamps (e.g., in SER file trailer) on the camera's clock instead of the computer's"
+        ))
+        .active(program_data_rc.borrow().config.camera_timestamps())
+        .build();
+    camera_timestamps.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |cb| {
+        program_data_rc.borrow().config.set_camera_timestamps(cb.is_active());
+    }));
+    let camera_timestamps_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    camera_timestamps_box.pack_start(&camera_timestamps, false, false, PADDING);
+    others.pack_start(&camera_timestamps_box, false, false, PADDING);
+
+    let verify_output = gtk::CheckButtonBuilder::new()
+        .label("Verify output after recording")
+        .tooltip_text(tr("Re-open the recorded file(s) after each recording and report missing or unreadable frames"))
+        .active(program_data_rc.borrow().config.verify_output())
+        .build();
+    verify_output.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |cb| {
+        program_data_rc.borrow().config.set_verify_output(cb.is_active());
+    }));
+    let verify_output_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    verify_output_box.pack_start(&verify_output, false, false, PADDING);
+    others.pack_start(&verify_output_box, false, false, PADDING);
+
+    let quality_log = gtk::CheckButtonBuilder::new()
+        .label("Save per-frame quality list")
+        .tooltip_text(tr(
+            "Save a CSV file with a sharpness measure of each recorded frame (for selecting the best frames)"
+        ))
+        .active(program_data_rc.borrow().config.quality_log())
+        .build();
+    quality_log.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |cb| {
+        program_data_rc.borrow().config.set_quality_log(cb.is_active());
+    }));
+    let quality_log_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    quality_log_box.pack_start(&quality_log, false, false, PADDING);
+    others.pack_start(&quality_log_box, false, false, PADDING);
+
+    let frame_selection_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    frame_selection_box.pack_start(&gtk::Label::new(Some("Save:")), false, false, PADDING);
+    let frame_selection_kinds = gtk::ComboBoxText::new();
+    for kind in &["All frames", "Frames with quality at least", "Best frames (%)"] {
+        frame_selection_kinds.append_text(kind);
+    }
+    frame_selection_kinds.set_tooltip_text(Some(tr(
+        "Frame quality is a sharpness measure (see the per-frame quality list).\n\
+        Best frames are kept in memory until the recording ends; if they exceed the maximum amount of buffered data,\n\
+        the recording is stopped."
+    )));
+    let frame_selection_value = gtk::SpinButton::new(None::<&gtk::Adjustment>, 1.0, 0);
+    let config_frame_selection = program_data_rc.borrow().config.recording_frame_selection();
+    frame_selection_kinds.set_active(Some(match config_frame_selection {
+        recording::FrameSelection::All => 0,
+        recording::FrameSelection::QualityAbove(_) => 1,
+        recording::FrameSelection::BestPercent(_) => 2
+    }));
+    set_frame_selection_value_range(&frame_selection_value, frame_selection_kinds.active().unwrap());
+    match config_frame_selection {
+        recording::FrameSelection::QualityAbove(value) | recording::FrameSelection::BestPercent(value) =>
+            frame_selection_value.set_value(value),
+        recording::FrameSelection::All => ()
+    }
+    frame_selection_kinds.connect_changed(clone!(
+        @weak program_data_rc, @weak frame_selection_value => @default-panic, move |kinds| {
+            set_frame_selection_value_range(&frame_selection_value, kinds.active().unwrap());
+            program_data_rc.borrow().config.set_recording_frame_selection(
+                frame_selection(kinds, &frame_selection_value)
+            );
+        }
+    ));
+    frame_selection_value.connect_value_changed(clone!(
+        @weak program_data_rc, @weak frame_selection_kinds => @default-panic, move |sb| {
+            program_data_rc.borrow().config.set_recording_frame_selection(frame_selection(&frame_selection_kinds, sb));
+        }
+    ));
+    frame_selection_box.pack_start(&frame_selection_kinds, false, false, PADDING);
+    frame_selection_box.pack_start(&frame_selection_value, false, false, PADDING);
+    others.pack_start(&frame_selection_box, false, false, PADDING);
+
+    let max_frame_rate_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    let config_max_frame_rate = program_data_rc.borrow().config.recording_max_frame_rate();
+    let max_frame_rate_enabled = gtk::CheckButtonBuilder::new()
+        .label("Limit frame rate to (fps):")
+        .tooltip_text(tr(
+            "Record at most the given number of frames per second; excess frames are skipped.\n\
+            Useful for cameras which always stream at maximum speed. Does not affect the preview."
+        ))
+        .active(config_max_frame_rate.is_some())
+        .build();
+    let max_frame_rate = gtk::SpinButton::new(
+        Some(&gtk::Adjustment::new(config_max_frame_rate.unwrap_or(30.0), 0.1, 10_000.0, 1.0, 10.0, 0.0)),
+        1.0,
+        1
+    );
+    max_frame_rate.set_sensitive(max_frame_rate_enabled.is_active());
+    max_frame_rate_enabled.connect_toggled(clone!(
+        @weak program_data_rc, @weak max_frame_rate => @default-panic, move |cb| {
+            max_frame_rate.set_sensitive(cb.is_active());
+            program_data_rc.borrow().config.set_recording_max_frame_rate(
+                if cb.is_active() { Some(max_frame_rate.value()) } else { None }
+            );
+        }
+    ));
+    max_frame_rate.connect_value_changed(clone!(
+        @weak program_data_rc, @weak max_frame_rate_enabled => @default-panic, move |sb| {
+            if max_frame_rate_enabled.is_active() {
+                program_data_rc.borrow().config.set_recording_max_frame_rate(Some(sb.value()));
+            }
+        }
+    ));
+    max_frame_rate_box.pack_start(&max_frame_rate_enabled, false, false, PADDING);
+    max_frame_rate_box.pack_start(&max_frame_rate, false, false, PADDING);
+    others.pack_start(&max_frame_rate_box, false, false, PADDING);
+
+    let max_buffered_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    max_buffered_box.pack_start(&gtk::Label::new(Some("Max. buffered data (MiB):")), false, false, PADDING);
+    let max_buffered = gtk::SpinButton::new(
+        Some(&gtk::Adjustment::new(
+            program_data_rc.borrow().config.max_buffered_mib() as f64, 16.0, 1_000_000.0, 16.0, 256.0, 0.0
+        )),
+        1.0,
+        0
+    );
+    max_buffered.set_tooltip_text(Some(tr(
+        "Captured frames waiting to be saved; when exceeded, new frames are dropped.\n\
+        Higher values reduce drops during I/O slowdowns, at the cost of more memory."
+    )));
+    max_buffered.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |sb| {
+        let pd = program_data_rc.borrow();
+        pd.recording_thread_data.max_buffered_kib.store(sb.value_as_int() as isize * 1024, Ordering::Relaxed);
+        pd.config.set_max_buffered_mib(sb.value_as_int());
+    }));
+    max_buffered_box.pack_start(&max_buffered, false, false, PADDING);
+    others.pack_start(&max_buffered_box, false, false, PADDING);
+
+    let update_interval_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    update_interval_box.pack_start(&gtk::Label::new(Some("Buffer update interval (ms):")), false, false, PADDING);
+    let update_interval = gtk::SpinButton::new(
+        Some(&gtk::Adjustment::new(
+            program_data_rc.borrow().config.recording_update_interval_ms() as f64,
+            recording::MIN_UPDATE_INTERVAL_MS as f64,
+            10_000.0,
+            50.0,
+            500.0,
+            0.0
+        )),
+        1.0,
+        0
+    );
+    update_interval.set_tooltip_text(Some(tr(
+        "How often the amount of buffered data is decreased by what has been saved (and the saving rate reported).\n\
+        Lower values make the buffered amount follow the actual state more closely, at a small cost in throughput."
+    )));
+    update_interval.connect_value_changed(clone!(@weak program_data_rc => @default-panic, move |sb| {
+        let pd = program_data_rc.borrow();
+        pd.recording_thread_data.update_interval_ms.store(sb.value_as_int() as u32, Ordering::Relaxed);
+        pd.config.set_recording_update_interval_ms(sb.value_as_int() as u32);
+    }));
+    update_interval_box.pack_start(&update_interval, false, false, PADDING);
+    others.pack_start(&update_interval_box, false, false, PADDING);
+
+    output_formats.connect_changed(clone!(
+        @weak program_data_rc, @weak tiff_compression_box, @weak ser_color_id_box => @default-panic, move |output_formats| {
+            let output_fmt = OutputFormat::iter().skip(output_formats.active().unwrap() as usize).next().unwrap();
+            tiff_compression_box.set_sensitive(output_fmt == OutputFormat::TiffSequence);
+            ser_color_id_box.set_sensitive(output_fmt == OutputFormat::SerVideo);
+            program_data_rc.borrow().config.set_recording_output_format(&output_fmt);
+        }
+    ));
+    let default_output_fmt = program_data_rc.borrow().config.recording_output_format();
+    output_formats.set_active(Some(
+        default_output_fmt.and_then(|d| OutputFormat::iter().position(|f| f == d)).unwrap_or(0) as u32
+    ));
+
+    let config_limit_type = program_data_rc.borrow().config.recording_limit_type();
+    let config_limit_duration = program_data_rc.borrow().config.recording_limit_duration();
+    let config_limit_frames = program_data_rc.borrow().config.recording_limit_frames();
+    let config_sequence_count = program_data_rc.borrow().config.recording_sequence_count();
+    let config_sequence_interval = program_data_rc.borrow().config.recording_sequence_interval();
+
+    let limit_frame = gtk::Frame::new(Some(tr("Limit")));
     let frame_contents = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
     let box_limit_duration = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    let rb_limit_duration = gtk::RadioButton::with_label("duration:");
+    let rb_limit_duration = gtk::RadioButton::with_label(tr("duration:"));
     box_limit_duration.pack_start(&rb_limit_duration, false, false, PADDING);
-    let duration_widget = TimeWidget::new_with_value(0, 0, 10);
+    let duration_widget = TimeWidget::new_with_duration(
+        config_limit_duration.unwrap_or(std::time::Duration::from_secs(10))
+    );
     box_limit_duration.pack_start(duration_widget.get(), false, false, PADDING);
     frame_contents.pack_start(&box_limit_duration, false, false, PADDING);
 
     let box_limit_frames = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    let rb_limit_frames = gtk::RadioButton::with_label_from_widget(&rb_limit_duration, "frames:");
+    let rb_limit_frames = gtk::RadioButton::with_label_from_widget(&rb_limit_duration, tr("frames:"));
     box_limit_frames.pack_start(&rb_limit_frames, false, false, PADDING);
     let sb_limit_frames = gtk::SpinButton::new(
-        Some(&gtk::Adjustment::new(100.0, 1.0, 1_000_000.0, 1.0, 10.0, 0.0)), 1.1, 0
+        Some(&gtk::Adjustment::new(config_limit_frames.unwrap_or(100) as f64, 1.0, 1_000_000.0, 1.0, 10.0, 0.0)),
+        1.1,
+        0
     );
     box_limit_frames.pack_start(&sb_limit_frames, false, false, PADDING);
     frame_contents.pack_start(&box_limit_frames, false, false, PADDING);
 
     // put it in a box to match margins of the previous radio buttons
     let box_limit_forever = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    let rb_limit_forever = gtk::RadioButton::with_label_from_widget(&rb_limit_duration, "record forever");
+    let rb_limit_forever = gtk::RadioButton::with_label_from_widget(&rb_limit_duration, tr("record forever"));
     box_limit_forever.pack_start(&rb_limit_forever, false, false, PADDING);
     frame_contents.pack_start(&box_limit_forever, false, false, PADDING);
 
+    match config_limit_type.as_deref() {
+        Some("frames") => rb_limit_frames.set_active(true),
+        Some("forever") => rb_limit_forever.set_active(true),
+        _ => rb_limit_duration.set_active(true)
+    }
+
     limit_frame.add(&frame_contents);
 
     others.pack_start(&limit_frame, false, false, PADDING);
 
     let box_sequence = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    box_sequence.pack_start(&gtk::Label::new(Some("Record")), false, false, PADDING);
+    box_sequence.pack_start(&gtk::Label::new(Some(tr("Record"))), false, false, PADDING);
     let btn_rec_count = gtk::SpinButton::new(
-        Some(&gtk::Adjustment::new(1.0, 1.0, 1_000_000.0, 1.0, 10.0, 0.0)), 1.0, 0
+        Some(&gtk::Adjustment::new(config_sequence_count.unwrap_or(1) as f64, 1.0, 1_000_000.0, 1.0, 10.0, 0.0)),
+        1.0,
+        0
     );
     btn_rec_count.set_orientation(gtk::Orientation::Vertical);
     box_sequence.pack_start(&btn_rec_count, false, false, PADDING);
-    box_sequence.pack_start(&gtk::Label::new(Some("time(s) with interval")), false, false, PADDING);
-    let sequence_interval = TimeWidget::new_with_value(0, 0, 10);
+    box_sequence.pack_start(&gtk::Label::new(Some(tr("time(s) with interval"))), false, false, PADDING);
+    let sequence_interval = TimeWidget::new_with_duration(
+        config_sequence_interval.unwrap_or(std::time::Duration::from_secs(10))
+    );
     box_sequence.pack_start(sequence_interval.get(), false, false, PADDING);
 
     others.pack_start(&box_sequence, false, false, PADDING);
 
+    let settings_file_frame = gtk::Frame::new(Some(tr("Settings file")));
+    let settings_file_box = gtk::BoxBuilder::new().orientation(gtk::Orientation::Vertical).margin(PADDING as i32).build();
+    let add_option = |label: &str, config_key: &'static str| -> gtk::CheckButton {
+        let cb = gtk::CheckButtonBuilder::new()
+            .label(label)
+            .active(program_data_rc.borrow().config.settings_file_option(config_key).unwrap_or(true))
+            .build();
+        cb.connect_toggled(clone!(@weak program_data_rc => @default-panic, move |cb| {
+            program_data_rc.borrow().config.set_settings_file_option(config_key, cb.is_active());
+        }));
+        settings_file_box.pack_start(&cb, false, false, 0);
+        cb
+    };
+    let cb_date_time = add_option("date & time", "DateTime");
+    let cb_mount_info = add_option("mount info", "MountInfo");
+    let cb_camera_temperature = add_option("camera temperature", "CameraTemperature");
+    let cb_auto_controls = add_option("controls in auto mode", "AutoControls");
+    let cb_hidden_controls = add_option("controls hidden in the controls panel", "HiddenControls");
+    settings_file_frame.add(&settings_file_box);
+
+    others.pack_start(&settings_file_frame, false, false, PADDING);
+
+    let calibration_frame = gtk::Frame::new(Some(tr("Calibration frames")));
+    let calibration_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+
+    let btn_bias = gtk::Button::with_label(tr("Capture bias"));
+    btn_bias.set_tooltip_text(Some(tr("Set the shortest exposure time and start recording")));
+    btn_bias.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| on_capture_bias(&program_data_rc)));
+    let box_bias = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    box_bias.pack_start(&btn_bias, false, false, PADDING);
+    calibration_box.pack_start(&box_bias, false, false, PADDING);
+
+    let flat_target = gtk::SpinButton::new(
+        Some(&gtk::Adjustment::new(50.0, 1.0, 99.0, 1.0, 10.0, 0.0)), 1.0, 0
+    );
+    let btn_flats = gtk::Button::with_label(tr("Capture flats"));
+    btn_flats.set_tooltip_text(Some(tr(
+        "Adjust exposure time to reach the target histogram mean and start recording"
+    )));
+    btn_flats.connect_clicked(clone!(@weak program_data_rc, @weak flat_target => @default-panic, move |_| {
+        on_capture_flats(flat_target.value() / 100.0, &program_data_rc)
+    }));
+    let box_flats = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    box_flats.pack_start(&btn_flats, false, false, PADDING);
+    box_flats.pack_start(&gtk::Label::new(Some("target mean (%):")), false, false, PADDING);
+    box_flats.pack_start(&flat_target, false, false, PADDING);
+    calibration_box.pack_start(&box_flats, false, false, PADDING);
+
+    calibration_frame.add(&calibration_box);
+    others.pack_start(&calibration_frame, false, false, PADDING);
+
     let box_all = gtk::Box::new(gtk::Orientation::Vertical, 0);
     box_all.pack_start(&btn_box, false, false, PADDING);
     box_all.pack_start(&others, false, false, PADDING);
 
+    let limit_and_sequence_storer = {
+        let rb_limit_frames = rb_limit_frames.clone();
+        let rb_limit_forever = rb_limit_forever.clone();
+        let duration_widget = duration_widget.clone();
+        let sb_limit_frames = sb_limit_frames.clone();
+        let btn_rec_count = btn_rec_count.clone();
+        let sequence_interval = sequence_interval.clone();
+        Box::new(move |config: &crate::config::Configuration| {
+            config.set_recording_limit_type(if rb_limit_frames.is_active() {
+                "frames"
+            } else if rb_limit_forever.is_active() {
+                "forever"
+            } else {
+                "duration"
+            });
+            config.set_recording_limit_duration(duration_widget.duration());
+            config.set_recording_limit_frames(sb_limit_frames.value() as usize);
+            config.set_recording_sequence_count(btn_rec_count.value() as usize);
+            config.set_recording_sequence_interval(sequence_interval.duration());
+        })
+    };
+
     (box_all, RecWidgets{
         btn_record,
         btn_stop,
+        btn_pause,
+        btn_pause_toggled,
         btn_nocrop,
         name_prefix,
         dest_dir,
+        thumbnail_path: None,
         others,
+        camera_timestamps,
         output_fmt_getter: Box::new(
             move || {
                 OutputFormat::iter().skip(output_formats.active().unwrap() as usize).next().unwrap()
             }
         ),
+        tiff_compression_getter: Box::new(
+            move || {
+                output::tiff::Compression::iter().skip(tiff_compressions.active().unwrap() as usize).next().unwrap()
+            }
+        ),
+        ser_color_id_getter: Box::new(
+            move || {
+                output::ser::ColorId::iter().skip(ser_color_ids.active().unwrap() as usize).next().unwrap()
+            }
+        ),
+        frame_selection_getter: Box::new(move || frame_selection(&frame_selection_kinds, &frame_selection_value)),
         rec_limit_getter: Box::new(
             move || {
                 if rb_limit_duration.is_active() {
@@ -415,17 +1018,57 @@ pub fn create_recording_panel(program_data_rc: &Rc<RefCell<ProgramData>>) -> (gt
                 }
             }
         ),
+        max_frame_rate_getter: Box::new(
+            move || if max_frame_rate_enabled.is_active() { Some(max_frame_rate.value()) } else { None }
+        ),
         sequence_getter: Box::new(move || (btn_rec_count.value() as usize, sequence_interval.duration())),
+        limit_and_sequence_storer,
+        settings_file_getter: Box::new(move || SettingsFileContents{
+            date_time: cb_date_time.is_active(),
+            mount_info: cb_mount_info.is_active(),
+            camera_temperature: cb_camera_temperature.is_active(),
+            auto_controls: cb_auto_controls.is_active(),
+            hidden_controls: cb_hidden_controls.is_active()
+        }),
         sequence_idx: 0,
         sequence_next_start: None,
-        sequence_timer: Timer::new()
+        unlimited_recording: None,
+        sequence_timer: Timer::new(),
+        calibration_frames: None,
+        calibration_timer: Timer::new()
     })
 }
 
+/// Returns (index of the next item, item count) if a recording sequence is in progress.
+pub fn pending_sequence_item(program_data: &ProgramData) -> Option<(usize, usize)> {
+    let rec_widgets = &program_data.gui.as_ref().unwrap().rec_widgets;
+    let (sequence_count, _) = rec_widgets.sequence();
+
+    let next_idx = if program_data.rec_job_active {
+        rec_widgets.sequence_idx + 1
+    } else if rec_widgets.sequence_next_start.is_some() {
+        rec_widgets.sequence_idx
+    } else {
+        return None;
+    };
+
+    if sequence_count > 1 && next_idx < sequence_count { Some((next_idx, sequence_count)) } else { None }
+}
+
+/// Starts recording of the specified sequence item (and the remaining ones).
+pub fn resume_sequence(item_idx: usize, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    program_data_rc.borrow_mut().gui.as_mut().unwrap().rec_widgets.sequence_idx = item_idx;
+    on_start_recording(program_data_rc);
+}
+
 pub fn on_recording_finished(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let mut pd = program_data_rc.borrow_mut();
+    // recording has been stopped by user in the meantime; do not continue the sequence
+    if !pd.rec_job_active { return; }
     pd.rec_job_active = false;
+    save_thumbnail(&mut pd);
     let pd_gui = pd.gui.as_mut().unwrap();
+    pd_gui.rec_widgets.unlimited_recording = None;
     pd_gui.rec_widgets.sequence_idx += 1;
     let (sequence_count, sequence_interval) = pd_gui.rec_widgets.sequence();
     if pd_gui.rec_widgets.sequence_idx < sequence_count {
@@ -440,5 +1083,6 @@ pub fn on_recording_finished(program_data_rc: &Rc<RefCell<ProgramData>>) {
     } else {
         pd_gui.rec_widgets.on_recording_ended();
         pd_gui.status_bar.current_recording_info.set_label(&"");
+        super::set_recording_locked_controls_enabled(pd_gui, true);
     }
 }
@@ -15,6 +15,7 @@ use gtk::prelude::*;
 /// Control padding in pixels.
 const PADDING: u32 = 10;
 
+#[derive(Clone)]
 pub struct TimeWidget {
     gtkbox: gtk::Box,
     btn_hours: gtk::SpinButton,
@@ -53,6 +54,12 @@ impl TimeWidget {
         TimeWidget { gtkbox, btn_hours, btn_minutes, btn_seconds }
     }
 
+    /// Durations exceeding the widget's range are clamped.
+    pub fn new_with_duration(duration: std::time::Duration) -> TimeWidget {
+        let secs = duration.as_secs();
+        TimeWidget::new_with_value((secs / 3600).min(59) as u32, ((secs % 3600) / 60) as u32, (secs % 60) as u32)
+    }
+
     pub fn get(&self) -> &gtk::Box { &self.gtkbox }
 
     pub fn duration(&self) -> std::time::Duration {
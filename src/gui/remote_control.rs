@@ -0,0 +1,144 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Remote control API handling.
+//!
+
+use crate::{
+    gui::{actions, show_message},
+    ProgramData,
+    workers::remote_control::{
+        Command,
+        json_escaped,
+        RemoteControl,
+        RemoteToMainThreadMsg,
+        Response,
+        ResponseStatus
+    }
+};
+use glib::clone;
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Starts or stops the remote control API server (see `workers::remote_control`).
+pub fn on_toggle_remote_control(item: &gtk::CheckMenuItem, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let error = {
+        let mut pd = program_data_rc.borrow_mut();
+        if item.is_active() == pd.remote_control.is_some() { return; }
+
+        if item.is_active() {
+            let (sender_worker, receiver_main) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
+            // the receiver is detached once the server thread ends (dropping its sender)
+            receiver_main.attach(None, clone!(@weak program_data_rc => @default-panic, move |msg| {
+                // commands still queued after stopping the API are not performed
+                if program_data_rc.borrow().remote_control.is_some() {
+                    on_remote_command(msg, &program_data_rc);
+                }
+                glib::Continue(true)
+            }));
+
+            match RemoteControl::start(&pd.config.remote_control_address(), sender_worker) {
+                Ok(server) => { pd.remote_control = Some(server); None },
+                Err(e) => Some(e)
+            }
+        } else {
+            pd.remote_control = None;
+            None
+        }
+    };
+
+    if let Some(e) = error {
+        item.set_active(false);
+        show_message(
+            &format!(
+                "Failed to start remote control API at {}: {}.",
+                program_data_rc.borrow().config.remote_control_address(), e
+            ),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
+    }
+}
+
+fn on_remote_command(msg: RemoteToMainThreadMsg, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    log::info!("remote control API: {:?}", msg.command);
+    program_data_rc.borrow_mut().t_last_activity = std::time::Instant::now();
+
+    let response = match msg.command {
+        Command::Status => status(program_data_rc),
+
+        Command::StartRecording => {
+            let startable = {
+                let pd = program_data_rc.borrow();
+                !pd.rec_job_active && pd.gui.as_ref().unwrap().rec_widgets.btn_record().is_sensitive()
+            };
+            if !startable {
+                Response::error(ResponseStatus::Conflict, "recording cannot be started now")
+            } else {
+                activate_action(actions::START_RECORDING, program_data_rc);
+                if program_data_rc.borrow().rec_job_active {
+                    status(program_data_rc)
+                } else {
+                    Response::error(ResponseStatus::Conflict, "recording has not started")
+                }
+            }
+        },
+
+        Command::StopRecording => {
+            if !program_data_rc.borrow().rec_job_active {
+                Response::error(ResponseStatus::Conflict, "not recording")
+            } else {
+                activate_action(actions::STOP_RECORDING, program_data_rc);
+                status(program_data_rc)
+            }
+        },
+
+        Command::TakeSnapshot => {
+            let possible = {
+                let pd = program_data_rc.borrow();
+                pd.last_displayed_preview_image.is_some() &&
+                    pd.gui.as_ref().unwrap().action_map.get(actions::TAKE_SNAPSHOT).unwrap().is_enabled()
+            };
+            if !possible {
+                Response::error(ResponseStatus::Conflict, "no image to take a snapshot of")
+            } else {
+                activate_action(actions::TAKE_SNAPSHOT, program_data_rc);
+                status(program_data_rc)
+            }
+        }
+    };
+
+    // the server thread may have given up waiting
+    let _ = msg.reply.send(response);
+}
+
+/// Activates a GUI action; must not be called while `ProgramData` is borrowed.
+fn activate_action(name: &str, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let action = program_data_rc.borrow().gui.as_ref().unwrap().action_map.get(name).unwrap().clone();
+    action.activate(None);
+}
+
+fn status(program_data_rc: &Rc<RefCell<ProgramData>>) -> Response {
+    let pd = program_data_rc.borrow();
+    let camera = match &pd.camera {
+        Some(camera) => format!("\"{}\"", json_escaped(camera.name())),
+        None => "null".to_string()
+    };
+
+    Response{
+        status: ResponseStatus::Ok,
+        body: format!(
+            "{{\"camera\":{},\"capturing\":{},\"recording\":{}}}",
+            camera,
+            pd.capture_thread_data.is_some(),
+            pd.rec_job_active
+        )
+    }
+}
@@ -77,4 +77,18 @@ fn init_controls(
     add_slider("Step:", 10.0, 100.0, 0.5, step, |reticle, new_value| { reticle.step = new_value; });
 
     add_slider("Line width:", 1.0, 8.0, 0.2, line_width, |reticle, new_value| { reticle.line_width = new_value; });
+
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(
+        &gtk::Label::new(Some("Click the preview and use arrow keys to move the reticle by 1 pixel.")),
+        false, false, PADDING
+    );
+    let btn_center = gtk::Button::with_label("Center");
+    btn_center.connect_clicked(clone!(@weak program_data_rc => @default-panic, move |_| {
+        let mut pd = program_data_rc.borrow_mut();
+        pd.gui.as_mut().unwrap().reticle.offset = cgmath::Vector2{ x: 0, y: 0 };
+        pd.gui.as_ref().unwrap().preview_area.refresh();
+    }));
+    hbox.pack_end(&btn_center, false, false, PADDING);
+    dialog.content_area().pack_start(&hbox, false, false, PADDING);
 }
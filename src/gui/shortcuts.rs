@@ -0,0 +1,188 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Configurable keyboard shortcuts.
+//!
+
+use crate::{ProgramData, config::Configuration, gui::{actions, DialogDestroyer}};
+use glib::clone;
+use gtk::{gdk, prelude::*};
+use std::{cell::{Cell, RefCell}, rc::Rc};
+
+/// Control padding in pixels.
+const PADDING: u32 = 10;
+
+pub struct Shortcut {
+    pub action: &'static str,
+    pub config_key: &'static str,
+    pub description: &'static str,
+    /// Accelerator in the format accepted by `gtk::accelerator_parse`; empty if none.
+    pub default: &'static str
+}
+
+pub const SHORTCUTS: &[Shortcut] = &[
+    Shortcut{ action: actions::TAKE_SNAPSHOT,   config_key: "TakeSnapshot",   description: "Take snapshot",   default: "F12" },
+    Shortcut{ action: actions::SAVE_HISTOGRAM,  config_key: "SaveHistogram",  description: "Save histogram",  default: "" },
+    Shortcut{ action: actions::SET_ROI,         config_key: "SetROI",         description: "Set ROI",         default: "" },
+    Shortcut{
+        action: actions::ROI_AROUND_TARGET,
+        config_key: "RoiAroundTarget",
+        description: "Set ROI around tracked target",
+        default: ""
+    },
+    Shortcut{ action: actions::START_RECORDING, config_key: "StartRecording", description: "Start recording", default: "" },
+    Shortcut{ action: actions::STOP_RECORDING,  config_key: "StopRecording",  description: "Stop recording",  default: "" },
+    Shortcut{ action: actions::STOP_MOUNT,      config_key: "StopMount",      description: "Stop mount",      default: "" },
+    Shortcut{
+        action: actions::ENLARGE_CENTROID_AREA,
+        config_key: "EnlargeCentroidArea",
+        description: "Enlarge centroid tracking area",
+        default: "<Control>bracketright"
+    },
+    Shortcut{
+        action: actions::SHRINK_CENTROID_AREA,
+        config_key: "ShrinkCentroidArea",
+        description: "Shrink centroid tracking area",
+        default: "<Control>bracketleft"
+    },
+];
+
+/// Currently assigned accelerators.
+pub struct Shortcuts {
+    /// Contains as many elements as `SHORTCUTS`.
+    accelerators: Vec<String>
+}
+
+impl Shortcuts {
+    pub fn load(config: &Configuration) -> Shortcuts {
+        Shortcuts{
+            accelerators: SHORTCUTS.iter()
+                .map(|s| config.shortcut(s.config_key).unwrap_or(s.default.to_string()))
+                .collect()
+        }
+    }
+
+    pub fn store(&self, config: &Configuration) {
+        for (shortcut, accelerator) in SHORTCUTS.iter().zip(self.accelerators.iter()) {
+            config.set_shortcut(shortcut.config_key, accelerator);
+        }
+    }
+
+    /// Returns the action assigned to the specified key combination.
+    pub fn find_action(&self, key: u32, modifiers: gdk::ModifierType) -> Option<&'static str> {
+        let modifiers = modifiers & gtk::accelerator_get_default_mod_mask();
+
+        for (shortcut, accelerator) in SHORTCUTS.iter().zip(self.accelerators.iter()) {
+            if accelerator.is_empty() { continue; }
+            let (acc_key, acc_modifiers) = gtk::accelerator_parse(accelerator);
+            if acc_key != 0 && acc_key == key && acc_modifiers == modifiers {
+                return Some(shortcut.action);
+            }
+        }
+
+        None
+    }
+}
+
+fn accelerator_label(accelerator: &str) -> String {
+    if accelerator.is_empty() { return "(none)".to_string(); }
+
+    let (key, modifiers) = gtk::accelerator_parse(accelerator);
+    match gtk::accelerator_get_label(key, modifiers) {
+        Some(label) => label.to_string(),
+        None => accelerator.to_string()
+    }
+}
+
+pub fn show_shortcuts_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Keyboard shortcuts"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("OK", gtk::ResponseType::Accept), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Accept);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    let accelerators = Rc::new(RefCell::new(
+        program_data_rc.borrow().gui.as_ref().unwrap().shortcuts.accelerators.clone()
+    ));
+    // index of the shortcut for which a new key combination is being captured
+    let capturing = Rc::new(Cell::new(None::<usize>));
+
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some("Click a button and press the new key combination (Esc: cancel).")),
+        false,
+        true,
+        PADDING
+    );
+
+    let grid = gtk::GridBuilder::new().row_spacing(PADDING).column_spacing(PADDING).margin(PADDING as i32).build();
+    let mut buttons = vec![];
+    for (idx, shortcut) in SHORTCUTS.iter().enumerate() {
+        grid.attach(&gtk::LabelBuilder::new().label(shortcut.description).xalign(0.0).build(), 0, idx as i32, 1, 1);
+
+        let btn_assign = gtk::Button::with_label(&accelerator_label(&accelerators.borrow()[idx]));
+        btn_assign.connect_clicked(clone!(@strong capturing => @default-panic, move |btn| {
+            capturing.set(Some(idx));
+            btn.set_label("press a key...");
+        }));
+        grid.attach(&btn_assign, 1, idx as i32, 1, 1);
+
+        let btn_clear = gtk::Button::with_label("clear");
+        btn_clear.connect_clicked(clone!(@strong accelerators, @weak btn_assign => @default-panic, move |_| {
+            accelerators.borrow_mut()[idx].clear();
+            btn_assign.set_label(&accelerator_label(""));
+        }));
+        grid.attach(&btn_clear, 2, idx as i32, 1, 1);
+
+        buttons.push(btn_assign);
+    }
+    dialog.content_area().pack_start(&grid, true, true, PADDING);
+
+    dialog.connect_key_press_event(clone!(@strong capturing, @strong accelerators => @default-panic, move |_, event| {
+        let idx = match capturing.get() {
+            Some(idx) => idx,
+            None => return gtk::Inhibit(false)
+        };
+
+        if event.is_modifier() { return gtk::Inhibit(true); }
+
+        capturing.set(None);
+
+        if event.keyval() != gdk::keys::constants::Escape {
+            let modifiers = event.state() & gtk::accelerator_get_default_mod_mask();
+            if let Some(accelerator) = gtk::accelerator_name(*event.keyval().to_lower(), modifiers) {
+                let mut accelerators = accelerators.borrow_mut();
+                // a key combination can be assigned to only one action
+                for (other_idx, other) in accelerators.iter_mut().enumerate() {
+                    if other_idx != idx && *other == accelerator.as_str() {
+                        other.clear();
+                        buttons[other_idx].set_label(&accelerator_label(""));
+                    }
+                }
+                accelerators[idx] = accelerator.to_string();
+            }
+        }
+
+        buttons[idx].set_label(&accelerator_label(&accelerators.borrow()[idx]));
+
+        gtk::Inhibit(true)
+    }));
+
+    dialog.show_all();
+
+    if dialog.run() == gtk::ResponseType::Accept {
+        let mut pd = program_data_rc.borrow_mut();
+        let pd = &mut *pd;
+        let shortcuts = &mut pd.gui.as_mut().unwrap().shortcuts;
+        shortcuts.accelerators = accelerators.borrow().clone();
+        shortcuts.store(&pd.config);
+    }
+}
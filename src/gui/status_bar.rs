@@ -0,0 +1,182 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Status bar fields selection.
+//!
+
+use crate::{ProgramData, gui::{DialogDestroyer, StatusBarFields}};
+use glib::clone;
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+use strum::IntoEnumIterator;
+use strum_macros as sm;
+
+/// Control padding in pixels.
+const PADDING: u32 = 10;
+
+#[derive(Copy, Clone, PartialEq, sm::EnumIter)]
+pub enum StatusBarField {
+    PreviewFps,
+    CaptureFps,
+    Temperature,
+    CurrentRecordingInfo,
+    RecordingOverview,
+    /// Amount of captured data waiting to be recorded.
+    BufferedData,
+    /// Diagnostic; hidden by default.
+    FrameInfo,
+    /// Diagnostic; hidden by default.
+    PreviewLatency
+}
+
+impl StatusBarField {
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            StatusBarField::PreviewFps => "PreviewFps",
+            StatusBarField::CaptureFps => "CaptureFps",
+            StatusBarField::Temperature => "Temperature",
+            StatusBarField::CurrentRecordingInfo => "CurrentRecordingInfo",
+            StatusBarField::RecordingOverview => "RecordingOverview",
+            StatusBarField::BufferedData => "BufferedData",
+            StatusBarField::FrameInfo => "FrameInfo",
+            StatusBarField::PreviewLatency => "PreviewLatency"
+        }
+    }
+
+    /// Fields shown if not configured otherwise.
+    pub fn defaults() -> Vec<StatusBarField> {
+        StatusBarField::iter()
+            .filter(|field| ![StatusBarField::FrameInfo, StatusBarField::PreviewLatency].contains(field))
+            .collect()
+    }
+
+    pub fn from_config_name(name: &str) -> Option<StatusBarField> {
+        StatusBarField::iter().find(|field| field.config_name() == name)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            StatusBarField::PreviewFps => "Preview frame rate",
+            StatusBarField::CaptureFps => "Capture frame rate",
+            StatusBarField::Temperature => "Camera temperature",
+            StatusBarField::CurrentRecordingInfo => "Current recording",
+            StatusBarField::RecordingOverview => "Recording overview",
+            StatusBarField::BufferedData => "Buffered recording data",
+            StatusBarField::FrameInfo => "Camera frame counter & timestamp (diagnostic)",
+            StatusBarField::PreviewLatency => "Capture-to-preview latency (diagnostic)"
+        }
+    }
+}
+
+fn field_widget(fields: &StatusBarFields, field: StatusBarField) -> gtk::Widget {
+    match field {
+        StatusBarField::PreviewFps => fields.preview_fps.clone().upcast(),
+        StatusBarField::CaptureFps => fields.capture_fps.clone().upcast(),
+        StatusBarField::Temperature => fields.temperature.clone().upcast(),
+        StatusBarField::CurrentRecordingInfo => fields.current_recording_info.clone().upcast(),
+        StatusBarField::RecordingOverview => fields.recording_overview.clone().upcast(),
+        StatusBarField::BufferedData => fields.buffered_data.clone().upcast(),
+        StatusBarField::FrameInfo => fields.frame_info.clone().upcast(),
+        StatusBarField::PreviewLatency => fields.preview_latency.clone().upcast()
+    }
+}
+
+/// Shows only the `shown` fields (in the given order) in the status bar.
+pub fn arrange(fields: &StatusBarFields, shown: &[StatusBarField]) {
+    for child in fields.container.children() {
+        fields.container.remove(&child);
+    }
+
+    for (idx, field) in shown.iter().enumerate() {
+        if idx > 0 {
+            fields.container.pack_start(&gtk::Separator::new(gtk::Orientation::Vertical), false, false, PADDING);
+        }
+        fields.container.pack_start(&field_widget(fields, *field), false, false, PADDING);
+    }
+
+    fields.container.show_all();
+}
+
+/// Lets the user choose the shown status bar fields and their order.
+pub fn show_status_bar_dialog(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Status bar"),
+        Some(&program_data_rc.borrow().gui.as_ref().unwrap().app_window),
+        gtk::DialogFlags::MODAL,
+        &[("OK", gtk::ResponseType::Ok), ("Cancel", gtk::ResponseType::Cancel)]
+    );
+    dialog.set_default_response(gtk::ResponseType::Ok);
+    let _ddestr = DialogDestroyer::new(&dialog);
+
+    let shown = program_data_rc.borrow().config.status_bar_fields();
+    // shown fields first, in their current order
+    let mut all_fields = shown.clone();
+    all_fields.extend(StatusBarField::iter().filter(|field| !shown.contains(field)));
+
+    let list_box = gtk::ListBox::new();
+    list_box.set_selection_mode(gtk::SelectionMode::Single);
+    let rows: Rc<RefCell<Vec<(StatusBarField, gtk::CheckButton)>>> = Rc::new(RefCell::new(vec![]));
+    for field in all_fields {
+        let check_button = gtk::CheckButton::with_label(field.label());
+        check_button.set_active(shown.contains(&field));
+        let row = gtk::ListBoxRow::new();
+        row.add(&check_button);
+        list_box.add(&row);
+        rows.borrow_mut().push((field, check_button));
+    }
+
+    // moves the selected row by `offset` positions
+    let move_selected = Rc::new(clone!(@weak list_box, @strong rows => @default-panic, move |offset: i32| {
+        let selected = match list_box.selected_row() {
+            Some(row) => row,
+            None => return
+        };
+        let idx = selected.index();
+        let new_idx = idx + offset;
+        if new_idx < 0 || new_idx as usize >= rows.borrow().len() { return; }
+
+        rows.borrow_mut().swap(idx as usize, new_idx as usize);
+        list_box.remove(&selected);
+        list_box.insert(&selected, new_idx);
+        list_box.select_row(Some(&selected));
+    }));
+
+    let btn_up = gtk::Button::with_label("Move up");
+    btn_up.connect_clicked(clone!(@strong move_selected => @default-panic, move |_| move_selected(-1)));
+    let btn_down = gtk::Button::with_label("Move down");
+    btn_down.connect_clicked(move |_| move_selected(1));
+
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    hbox.pack_start(&list_box, true, true, PADDING);
+    let buttons_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    buttons_box.pack_start(&btn_up, false, false, PADDING);
+    buttons_box.pack_start(&btn_down, false, false, PADDING);
+    hbox.pack_start(&buttons_box, false, false, PADDING);
+
+    dialog.content_area().pack_start(
+        &gtk::Label::new(Some("Select status bar fields and their order:")),
+        false,
+        true,
+        PADDING
+    );
+    dialog.content_area().pack_start(&hbox, true, true, PADDING);
+
+    dialog.show_all();
+
+    if dialog.run() != gtk::ResponseType::Ok { return; }
+
+    let new_shown: Vec<StatusBarField> = rows.borrow().iter()
+        .filter(|(_, check_button)| check_button.is_active())
+        .map(|(field, _)| *field)
+        .collect();
+
+    let pd = program_data_rc.borrow();
+    pd.config.set_status_bar_fields(&new_shown);
+    arrange(&pd.gui.as_ref().unwrap().status_bar, &new_shown);
+}
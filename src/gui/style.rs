@@ -0,0 +1,111 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Application-wide widget styling.
+//!
+
+use gtk::prelude::*;
+
+/// Style class of containers whose buttons are enlarged for use on a touchscreen.
+const LARGE_BUTTONS_CLASS: &str = "vidoxide-large-buttons";
+
+const CSS: &str = "
+.vidoxide-large-buttons button {
+    min-width: 56px;
+    min-height: 56px;
+    font-size: 130%;
+}
+";
+
+/// Red-on-black theme preserving dark adaptation; overrides the current GTK theme when active.
+const NIGHT_MODE_CSS: &str = "
+* {
+    color: #d00000;
+    background-color: #000000;
+    background-image: none;
+    border-color: #500000;
+    outline-color: #500000;
+    box-shadow: none;
+    text-shadow: none;
+    -gtk-icon-shadow: none;
+    -gtk-icon-effect: none;
+}
+
+*:disabled {
+    color: #600000;
+}
+
+*:hover {
+    background-color: #200000;
+}
+
+*:selected, *:checked, *:active {
+    color: #000000;
+    background-color: #a00000;
+}
+
+tooltip, tooltip * {
+    background-color: #100000;
+}
+";
+
+pub struct Style {
+    screen: Option<gtk::gdk::Screen>,
+    night_mode: gtk::CssProvider
+}
+
+impl Style {
+    /// Installs the application's style sheet; needs to be called after GTK initialization.
+    pub fn new() -> Style {
+        let provider = gtk::CssProvider::new();
+        let night_mode = gtk::CssProvider::new();
+        for (provider, css) in [(&provider, CSS), (&night_mode, NIGHT_MODE_CSS)] {
+            if let Err(e) = provider.load_from_data(css.as_bytes()) {
+                log::warn!("failed to load style sheet: {}", e);
+            }
+        }
+
+        let screen = gtk::gdk::Screen::default();
+        match &screen {
+            Some(screen) => gtk::StyleContext::add_provider_for_screen(
+                screen,
+                &provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION
+            ),
+            None => log::warn!("no default screen; style sheet not installed")
+        }
+
+        Style{ screen, night_mode }
+    }
+
+    /// Switches the whole user interface to (or from) red-on-black colors.
+    pub fn set_night_mode(&self, enabled: bool) {
+        let screen = match &self.screen {
+            Some(screen) => screen,
+            None => return
+        };
+
+        if enabled {
+            // user priority, so that it takes precedence also over the theme's and application's style sheets
+            gtk::StyleContext::add_provider_for_screen(screen, &self.night_mode, gtk::STYLE_PROVIDER_PRIORITY_USER);
+        } else {
+            gtk::StyleContext::remove_provider_for_screen(screen, &self.night_mode);
+        }
+    }
+}
+
+/// Enlarges (or restores the default size of) all buttons inside `container`.
+pub fn set_large_buttons(container: &impl IsA<gtk::Widget>, enabled: bool) {
+    let style_context = container.style_context();
+    if enabled {
+        style_context.add_class(LARGE_BUTTONS_CLASS);
+    } else {
+        style_context.remove_class(LARGE_BUTTONS_CLASS);
+    }
+}
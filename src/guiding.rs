@@ -10,7 +10,7 @@
 //! Guiding.
 //!
 
-use cgmath::{InnerSpace, Point2, SquareMatrix, Matrix2, Vector2};
+use cgmath::{EuclideanSpace, InnerSpace, Point2, SquareMatrix, Matrix2, Vector2};
 use crate::ProgramData;
 use crate::gui::show_message;
 use crate::mount;
@@ -18,6 +18,12 @@ use crate::mount::RadPerSec;
 use glib::clone;
 use std::{cell::RefCell, error::Error, rc::Rc};
 
+/// Min. angle between primary and secondary axis directions (in image space) of an acceptable calibration.
+const MIN_GOOD_AXES_ANGLE_DEG: f64 = 60.0;
+
+/// Min. number of calibration points for fitting a linear and quadratic mount model, respectively.
+const MIN_POINTS_FOR_DEGREE: [usize; 2] = [3, 6];
+
 //TODO: set it from GUI
 const GUIDE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(2000);
 
@@ -25,8 +31,22 @@ pub fn start_guiding(program_data_rc: &Rc<RefCell<ProgramData>>) {
     let failed: bool = if program_data_rc.borrow().tracking.is_none() {
         show_message("Target tracking is not enabled.", "Error", gtk::MessageType::Error, program_data_rc);
         true
-    } else if program_data_rc.borrow().mount_data.calibration.is_none() {
-        show_message("Calibration has not been performed.", "Error", gtk::MessageType::Error, program_data_rc);
+    } else if program_data_rc.borrow().mount_data.calibration_in_progress() {
+        show_message(
+            "Calibration is in progress; please wait until it finishes.",
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
+        true
+    } else if program_data_rc.borrow().mount_data.mount_model.is_empty() {
+
+        show_message(
+            "Mount has not been calibrated; please calibrate first.",
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        );
         true
     } else if !program_data_rc.borrow().mount_data.sky_tracking_on {
         show_message("Sky tracking is not enabled.", "Error", gtk::MessageType::Error, program_data_rc);
@@ -42,6 +62,8 @@ pub fn start_guiding(program_data_rc: &Rc<RefCell<ProgramData>>) {
 
     let mut pd = program_data_rc.borrow_mut();
     pd.mount_data.guiding_pos = Some(pd.tracking.as_ref().unwrap().pos);
+    pd.mount_data.centering = false;
+    pd.mount_data.backlash = Default::default();
     pd.mount_data.guiding_timer.run(
         GUIDE_CHECK_INTERVAL,
         true,
@@ -51,12 +73,58 @@ pub fn start_guiding(program_data_rc: &Rc<RefCell<ProgramData>>) {
     log::info!("guiding enabled");
 }
 
+/// Moves the mount (using the guiding speed) so that the tracked target gets to the image center (where the reticle
+/// is shown); guiding stops afterwards.
+pub fn center_target(program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let error_msg = {
+        let pd = program_data_rc.borrow();
+        if pd.tracking.is_none() {
+            Some("Target tracking is not enabled.")
+        } else if pd.mount_data.calibration_in_progress() {
+            Some("Calibration is in progress; please wait until it finishes.")
+        } else if pd.mount_data.mount_model.is_empty() {
+            Some("Mount has not been calibrated; please calibrate first.")
+        } else if !pd.mount_data.sky_tracking_on {
+            Some("Sky tracking is not enabled.")
+        } else if pd.mount_data.guiding_pos.is_some() && !pd.mount_data.centering {
+            Some("Guiding is active.")
+        } else if pd.last_displayed_preview_image.is_none() {
+            Some("No image has been captured yet.")
+        } else {
+            None
+        }
+    };
+
+    if let Some(msg) = error_msg {
+        show_message(msg, "Error", gtk::MessageType::Error, program_data_rc);
+        return;
+    }
+
+    {
+        let mut pd = program_data_rc.borrow_mut();
+        let image = pd.last_displayed_preview_image.as_ref().unwrap();
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        // the target is moved to the reticle center (if shown)
+        let offset = pd.gui.as_ref().unwrap().reticle_offset();
+        let center = Point2{
+            x: (width / 2 + offset.x).clamp(0, width - 1),
+            y: (height / 2 + offset.y).clamp(0, height - 1)
+        };
+        pd.mount_data.guiding_pos = Some(center);
+        pd.mount_data.centering = true;
+        log::info!("centering target from {:?} to {:?}", pd.tracking.as_ref().unwrap().pos, center);
+    }
+
+    guiding_step(program_data_rc);
+}
+
 pub fn stop_guiding(program_data_rc: &Rc<RefCell<ProgramData>>) -> Result<(), Box<dyn Error>> {
     {
         let mut pd = program_data_rc.borrow_mut();
         pd.mount_data.guiding_timer.stop();
         pd.mount_data.guide_slewing = false;
         pd.mount_data.guiding_pos = None;
+        pd.mount_data.centering = false;
     }
 
     log::info!("guiding disabled");
@@ -66,7 +134,7 @@ pub fn stop_guiding(program_data_rc: &Rc<RefCell<ProgramData>>) -> Result<(), Bo
 
 pub fn guiding_step(program_data_rc: &Rc<RefCell<ProgramData>>) {
     /// Max acceptable X and Y difference between current and desired tracking position at the end of a guiding slew.
-    const GUIDE_POS_MARGIN: i32 = 5;
+    const GUIDE_POS_MARGIN: f64 = 5.0;
 
     const GUIDE_DIR_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1000);
 
@@ -75,23 +143,69 @@ pub fn guiding_step(program_data_rc: &Rc<RefCell<ProgramData>>) {
     'block: {
         let mut pd = program_data_rc.borrow_mut();
 
-        let dpos = *pd.mount_data.guiding_pos.as_ref().unwrap() - pd.tracking.as_ref().unwrap().pos;
+        let dpos = pd.mount_data.guiding_pos.as_ref().unwrap().cast::<f64>().unwrap()
+            - pd.tracking.as_ref().unwrap().subpixel_pos;
         let st_on = pd.mount_data.sky_tracking_on;
 
-        if dpos.x.abs() > GUIDE_POS_MARGIN || dpos.y.abs() > GUIDE_POS_MARGIN {
-            let guide_dir_axis_space = guiding_direction(
-                pd.mount_data.calibration.as_ref().unwrap().img_to_mount_axes.as_ref().unwrap(),
-                dpos.cast::<f64>().unwrap()
-            );
+        let img_to_mount_axes =
+            pd.mount_data.mount_model.img_to_mount_axes(pd.tracking.as_ref().unwrap().subpixel_pos).unwrap();
+        // if guiding is limited to the primary axis, the offset along the secondary one is left uncorrected
+        let primary_axis_only = pd.gui.as_ref().unwrap().mount_widgets().guide_primary_axis_only();
+        let primary_offset = primary_axis_offset(&img_to_mount_axes, dpos);
+        let off_target = if primary_axis_only {
+            primary_offset.abs() > GUIDE_POS_MARGIN
+        } else {
+            dpos.x.abs() > GUIDE_POS_MARGIN || dpos.y.abs() > GUIDE_POS_MARGIN
+        };
+
+        if off_target {
+            let guide_dir_axis_space = if primary_axis_only {
+                Vector2{ x: primary_offset.signum(), y: 0.0 }
+            } else {
+                guiding_direction(&img_to_mount_axes, dpos)
+            };
 
             let speed = pd.gui.as_ref().unwrap().mount_widgets().guide_speed() * mount::SIDEREAL_RATE;
 
             let x_speed = speed * guide_dir_axis_space.x;
             let y_speed = speed * guide_dir_axis_space.y;
 
+            let reversed = pd.mount_data.backlash.update([x_speed, y_speed]);
+            let compensation = [
+                pd.config.guide_backlash_compensation(mount::Axis::Primary),
+                pd.config.guide_backlash_compensation(mount::Axis::Secondary)
+            ];
+            let take_up_duration = (0..2).filter(|i| reversed[*i]).map(|i| compensation[i]).max().unwrap_or_default();
+            if take_up_duration > std::time::Duration::ZERO {
+                // drive only the reversed axes; the target is not expected to move until backlash is taken up
+                let take_up_speed = |i: usize, axis_speed: RadPerSec| {
+                    if reversed[i] && compensation[i] > std::time::Duration::ZERO {
+                        speed * axis_speed.0.signum()
+                    } else {
+                        RadPerSec(0.0)
+                    }
+                };
+                log::info!(
+                    "guiding direction reversed; taking up backlash for {} ms",
+                    take_up_duration.as_millis()
+                );
+                error = pd.mount_data.mount.as_mut().unwrap().guide(take_up_speed(0, x_speed), take_up_speed(1, y_speed));
+                if error.is_err() { break 'block; }
+
+                pd.mount_data.guide_slewing = true;
+                pd.mount_data.guiding_timer.run(
+                    take_up_duration,
+                    true,
+                    clone!(@weak program_data_rc => @default-panic, move || guiding_step(&program_data_rc))
+                );
+                break 'block;
+            }
+
             log::info!(
-                "off target by [{}, {}] pix; sending guide cmd [{:.2}, {:.2}] · sidereal",
-                dpos.x, dpos.y, x_speed.0 / mount::SIDEREAL_RATE.0, y_speed.0 / mount::SIDEREAL_RATE.0
+                "off target by [{:.2}, {:.2}] pix (frame captured at {}); sending guide cmd [{:.2}, {:.2}] · sidereal",
+                dpos.x, dpos.y,
+                chrono::DateTime::<chrono::Local>::from(pd.tracking.as_ref().unwrap().captured).format("%H:%M:%S%.3f"),
+                x_speed.0 / mount::SIDEREAL_RATE.0, y_speed.0 / mount::SIDEREAL_RATE.0
             );
             error = pd.mount_data.mount.as_mut().unwrap().guide(x_speed, y_speed);
 
@@ -109,6 +223,14 @@ pub fn guiding_step(program_data_rc: &Rc<RefCell<ProgramData>>) {
             if error.is_err() { break 'block; }
 
             pd.mount_data.guide_slewing = false;
+
+            if pd.mount_data.centering {
+                pd.mount_data.centering = false;
+                pd.mount_data.guiding_pos = None;
+                log::info!("target centered");
+                break 'block;
+            }
+
             log::info!("back on target");
 
             pd.mount_data.guiding_timer.run(
@@ -133,6 +255,12 @@ fn guiding_direction(img_to_mount_axes_matrix: &Matrix2<f64>, target_offset: Vec
     guide_dir_axis_space.normalize()
 }
 
+/// Returns offset (in pixels) along the primary axis' slewing direction corresponding to `target_offset`
+/// in image space.
+fn primary_axis_offset(img_to_mount_axes_matrix: &Matrix2<f64>, target_offset: Vector2<f64>) -> f64 {
+    (img_to_mount_axes_matrix * target_offset).x
+}
+
 /// Creates a matrix transforming image-space vectors to mount-axes-space.
 ///
 /// # Parameters
@@ -153,6 +281,207 @@ pub fn create_img_to_mount_axes_matrix(primary_dir: Vector2<f64>, secondary_dir:
     }
 }
 
+/// Calibration performed at a single position in the image.
+#[derive(Copy, Clone, Debug)]
+pub struct CalibrationPoint {
+    /// Position of the tracked target at the start of calibration.
+    pub pos: Point2<f64>,
+    /// Image-space-to-mount-axes-space slewing dir transformation matrix.
+    pub img_to_mount_axes: Matrix2<f64>
+}
+
+/// Image-space-to-mount-axes-space transformation which may vary across the field (e.g., due to optical distortion
+/// of a wide-field setup).
+///
+/// With fewer than 3 calibration points, the transformation is the average of the points' matrices. Otherwise each
+/// matrix element is a polynomial of image position (linear for 3-5 points, quadratic for 6 and more) fitted
+/// with least squares.
+///
+#[derive(Default)]
+pub struct MountModel {
+    points: Vec<CalibrationPoint>,
+    /// Image position corresponding to (0, 0) in polynomial coordinates.
+    center: Point2<f64>,
+    /// Image distance corresponding to 1 in polynomial coordinates.
+    scale: f64,
+    /// Polynomial coefficients (see `polynomial_terms`) of the matrix elements (in column-major order).
+    coeffs: Vec<[f64; 4]>
+}
+
+impl MountModel {
+    pub fn is_empty(&self) -> bool { self.points.is_empty() }
+
+    pub fn num_points(&self) -> usize { self.points.len() }
+
+    /// Returns degree of the fitted polynomial.
+    pub fn degree(&self) -> usize {
+        match self.coeffs.len() {
+            6 => 2,
+            3 => 1,
+            _ => 0
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = MountModel::default();
+    }
+
+    pub fn add_point(&mut self, point: CalibrationPoint) {
+        self.points.push(point);
+        self.fit();
+    }
+
+    /// Returns the image-space-to-mount-axes-space transformation at `pos` (`None` if there are no calibration points).
+    pub fn img_to_mount_axes(&self, pos: Point2<f64>) -> Option<Matrix2<f64>> {
+        if self.points.is_empty() { return None; }
+
+        let terms = polynomial_terms(self.normalized(pos), self.degree());
+        let mut elements = [0.0; 4];
+        for (term, coeffs) in terms.iter().zip(self.coeffs.iter()) {
+            for i in 0..4 { elements[i] += term * coeffs[i]; }
+        }
+
+        Some(Matrix2::new(elements[0], elements[1], elements[2], elements[3]))
+    }
+
+    fn normalized(&self, pos: Point2<f64>) -> Point2<f64> {
+        Point2::from_vec((pos - self.center) / self.scale)
+    }
+
+    /// Fits the highest-degree polynomial allowed by the number of points (and their non-degenerate placement).
+    fn fit(&mut self) {
+        let n = self.points.len() as f64;
+        self.center = Point2::from_vec(self.points.iter().fold(Vector2::new(0.0, 0.0), |sum, p| sum + p.pos.to_vec()) / n);
+        self.scale = self.points.iter().map(|p| (p.pos - self.center).magnitude()).fold(0.0, f64::max).max(1.0);
+
+        let max_degree = MIN_POINTS_FOR_DEGREE.iter().filter(|min| self.points.len() >= **min).count();
+        for degree in (0..=max_degree).rev() {
+            if let Some(coeffs) = self.fit_with_degree(degree) {
+                self.coeffs = coeffs;
+                return;
+            }
+        }
+        unreachable!();
+    }
+
+    /// Returns least-squares polynomial coefficients of the matrix elements (`None` if points' placement
+    /// is degenerate for the given degree, e.g., collinear).
+    fn fit_with_degree(&self, degree: usize) -> Option<Vec<[f64; 4]>> {
+        let num_terms = polynomial_terms(Point2::new(0.0, 0.0), degree).len();
+
+        // normal equations
+        let mut a = vec![vec![0.0; num_terms]; num_terms];
+        let mut b = vec![[0.0; 4]; num_terms];
+        for point in &self.points {
+            let terms = polynomial_terms(self.normalized(point.pos), degree);
+            let m = &point.img_to_mount_axes;
+            let elements = [m.x.x, m.x.y, m.y.x, m.y.y];
+            for i in 0..num_terms {
+                for j in 0..num_terms { a[i][j] += terms[i] * terms[j]; }
+                for k in 0..4 { b[i][k] += terms[i] * elements[k]; }
+            }
+        }
+
+        solve_linear_system(a, b)
+    }
+}
+
+/// Returns values of polynomial terms of the given degree (at most 2) at `pos`.
+fn polynomial_terms(pos: Point2<f64>, degree: usize) -> Vec<f64> {
+    let (x, y) = (pos.x, pos.y);
+    match degree {
+        0 => vec![1.0],
+        1 => vec![1.0, x, y],
+        2 => vec![1.0, x, y, x * x, x * y, y * y],
+        _ => panic!("unsupported polynomial degree: {}", degree)
+    }
+}
+
+/// Solves the linear system `a`·x = `b` (for 4 right-hand sides at once) using Gaussian elimination with partial
+/// pivoting. Returns `None` if `a` is (nearly) singular.
+fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<[f64; 4]>) -> Option<Vec<[f64; 4]>> {
+    const MIN_PIVOT: f64 = 1.0e-9;
+
+    let n = a.len();
+    for col in 0..n {
+        let pivot_row = (col..n).max_by(|r1, r2| a[*r1][col].abs().partial_cmp(&a[*r2][col].abs()).unwrap()).unwrap();
+        if a[pivot_row][col].abs() < MIN_PIVOT { return None; }
+        a.swap(col, pivot_row);
+        b.swap(col, pivot_row);
+
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            for j in col..n { a[row][j] -= factor * a[col][j]; }
+            for k in 0..4 { b[row][k] -= factor * b[col][k]; }
+        }
+    }
+
+    let mut x = vec![[0.0; 4]; n];
+    for row in (0..n).rev() {
+        for k in 0..4 {
+            let sum: f64 = (row + 1..n).map(|j| a[row][j] * x[j][k]).sum();
+            x[row][k] = (b[row][k] - sum) / a[row][row];
+        }
+    }
+
+    Some(x)
+}
+
+/// Detects reversals of guiding direction on mount axes (which require taking up the backlash of the gears
+/// before the target starts moving).
+#[derive(Default)]
+pub struct BacklashCompensation {
+    /// Sign of the most recent non-zero guiding speed on each axis (indexed by `mount::Axis as usize`);
+    /// 0 if there was none yet.
+    last_dir: [f64; 2]
+}
+
+impl BacklashCompensation {
+    /// Records guiding speeds of both axes; returns which axes have reversed direction.
+    pub fn update(&mut self, speeds: [RadPerSec; 2]) -> [bool; 2] {
+        let mut reversed = [false; 2];
+        for i in 0..2 {
+            if speeds[i].0 != 0.0 {
+                let dir = speeds[i].0.signum();
+                reversed[i] = self.last_dir[i] != 0.0 && dir != self.last_dir[i];
+                self.last_dir[i] = dir;
+            }
+        }
+        reversed
+    }
+}
+
+/// Guiding calibration quality.
+pub struct CalibrationQuality {
+    /// Angle between primary and secondary axis directions in image space (0°-180°).
+    pub axes_angle_deg: f64,
+    /// Condition number of the image-to-mount-axes matrix (1 for perpendicular axes).
+    pub condition_number: f64
+}
+
+impl CalibrationQuality {
+    /// Returns false if the axis directions are nearly (anti-)parallel; guiding may then be erratic.
+    pub fn is_good(&self) -> bool {
+        self.axes_angle_deg.min(180.0 - self.axes_angle_deg) >= MIN_GOOD_AXES_ANGLE_DEG
+    }
+}
+
+/// Evaluates calibration using the measured image-space directions of slews around the primary and secondary axis.
+pub fn calibration_quality(primary_dir: Vector2<f64>, secondary_dir: Vector2<f64>) -> CalibrationQuality {
+    let axes_angle_deg = primary_dir.angle(secondary_dir).0.to_degrees();
+
+    // singular values of the axes-to-image matrix are square roots of eigenvalues of its Gram matrix;
+    // the inverse matrix has the same condition number
+    let (a, b, c) = (primary_dir.magnitude2(), primary_dir.dot(secondary_dir), secondary_dir.magnitude2());
+    let half_trace = (a + c) / 2.0;
+    let det = a * c - b * b;
+    let discriminant = (half_trace * half_trace - det).max(0.0).sqrt();
+    let (eigenval_max, eigenval_min) = (half_trace + discriminant, half_trace - discriminant);
+    let condition_number = if eigenval_min > 0.0 { (eigenval_max / eigenval_min).sqrt() } else { f64::INFINITY };
+
+    CalibrationQuality{ axes_angle_deg, condition_number }
+}
+
 mod tests {
     use super::*;
 
@@ -200,4 +529,107 @@ mod tests {
         assert_almost_eq!(v2(s2, s2), guiding_direction(&mat(v2(1.0, -1.0), v2(1.0, 1.0)), v2(1.0, 0.0)));
         assert_almost_eq!(v2(s2, -s2), guiding_direction(&mat(v2(1.0, -1.0), v2(-1.0, -1.0)), v2(1.0, 0.0)));
     }
+
+    #[test]
+    fn given_perpendicular_axes_calibration_quality_is_good() {
+        let quality = calibration_quality(Vector2{ x: 1.0, y: 0.0 }, Vector2{ x: 0.0, y: -1.0 });
+        assert!((quality.axes_angle_deg - 90.0).abs() < 1.0e-9);
+        assert!((quality.condition_number - 1.0).abs() < 1.0e-9);
+        assert!(quality.is_good());
+    }
+
+    #[test]
+    fn given_nearly_parallel_axes_calibration_quality_is_poor() {
+        let s2 = 1.0 / 2.0f64.sqrt();
+        let quality = calibration_quality(Vector2{ x: 1.0, y: 0.0 }, Vector2{ x: -0.99, y: 0.1411 });
+        assert!(!quality.is_good());
+        assert!(quality.condition_number > 10.0);
+
+        let quality = calibration_quality(Vector2{ x: 1.0, y: 0.0 }, Vector2{ x: s2, y: s2 });
+        assert!((quality.axes_angle_deg - 45.0).abs() < 1.0e-9);
+        assert!(!quality.is_good());
+    }
+
+    #[test]
+    fn primary_axis_offset_ignores_secondary_axis_component() {
+        let mat = create_img_to_mount_axes_matrix(Vector2{ x: 0.0, y: 1.0 }, Vector2{ x: 1.0, y: 0.0 }).unwrap();
+        assert!((10.0 - primary_axis_offset(&mat, Vector2{ x: 3.0, y: 10.0 })).abs() < 1.0e-9);
+        assert!(primary_axis_offset(&mat, Vector2{ x: 30.0, y: 0.0 }).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn backlash_compensation_detects_direction_reversals() {
+        let mut backlash = BacklashCompensation::default();
+        let r = |x| RadPerSec(x);
+
+        assert_eq!([false, false], backlash.update([r(1.0), r(-1.0)]));
+        assert_eq!([false, false], backlash.update([r(0.5), r(0.0)]));
+        assert_eq!([true, false], backlash.update([r(-1.0), r(0.0)]));
+        assert_eq!([false, true], backlash.update([r(-1.0), r(1.0)]));
+        assert_eq!([false, false], backlash.update([r(0.0), r(0.0)]));
+        assert_eq!([true, true], backlash.update([r(1.0), r(-1.0)]));
+    }
+
+    /// Returns the true image-to-mount-axes transformation at `pos` in a simulated 1000x1000 field, where the
+    /// orientation of mount axes in the image changes by 20° between opposite edges.
+    fn field_img_to_mount_axes(pos: Point2<f64>) -> Matrix2<f64> {
+        let angle = (10.0 * (pos.x - 500.0) / 500.0 + 5.0 * (pos.y - 500.0) / 500.0).to_radians();
+        let primary_dir = Vector2{ x: angle.cos(), y: angle.sin() };
+        let secondary_dir = Vector2{ x: -angle.sin(), y: angle.cos() };
+        create_img_to_mount_axes_matrix(primary_dir, secondary_dir).unwrap()
+    }
+
+    /// Returns angle (in degrees) between guiding directions obtained with the true transformation at `pos`
+    /// and with `img_to_mount_axes`.
+    fn guiding_error_deg(img_to_mount_axes: &Matrix2<f64>, pos: Point2<f64>) -> f64 {
+        let target_offset = Vector2{ x: 1.0, y: 0.0 };
+        let expected = guiding_direction(&field_img_to_mount_axes(pos), target_offset);
+        let actual = guiding_direction(img_to_mount_axes, target_offset);
+        expected.angle(actual).0.abs().to_degrees()
+    }
+
+    fn calibration_point(x: f64, y: f64) -> CalibrationPoint {
+        let pos = Point2{ x, y };
+        CalibrationPoint{ pos, img_to_mount_axes: field_img_to_mount_axes(pos) }
+    }
+
+    #[test]
+    fn given_single_point_mount_model_is_constant() {
+        let mut model = MountModel::default();
+        assert!(model.img_to_mount_axes(Point2{ x: 0.0, y: 0.0 }).is_none());
+
+        let point = calibration_point(500.0, 500.0);
+        model.add_point(point);
+        assert_eq!(0, model.degree());
+        let matrix = model.img_to_mount_axes(Point2{ x: 0.0, y: 1000.0 }).unwrap();
+        assert!((matrix.x - point.img_to_mount_axes.x).magnitude() < 1.0e-9);
+        assert!((matrix.y - point.img_to_mount_axes.y).magnitude() < 1.0e-9);
+    }
+
+    #[test]
+    fn given_collinear_points_mount_model_falls_back_to_lower_degree() {
+        let mut model = MountModel::default();
+        for x in [100.0, 500.0, 900.0] { model.add_point(calibration_point(x, 500.0)); }
+        assert_eq!(0, model.degree());
+    }
+
+    #[test]
+    fn multi_point_mount_model_reduces_guiding_error_at_frame_edges() {
+        let mut single_point = MountModel::default();
+        single_point.add_point(calibration_point(500.0, 500.0));
+
+        let mut multi_point = MountModel::default();
+        for (x, y) in [(500.0, 500.0), (100.0, 100.0), (900.0, 100.0), (100.0, 900.0), (900.0, 900.0), (500.0, 100.0)] {
+            multi_point.add_point(calibration_point(x, y));
+        }
+        assert_eq!(2, multi_point.degree());
+
+        for (x, y) in [(0.0, 0.0), (1000.0, 0.0), (0.0, 1000.0), (1000.0, 1000.0), (500.0, 0.0), (0.0, 500.0)] {
+            let pos = Point2{ x, y };
+            let single_error = guiding_error_deg(&single_point.img_to_mount_axes(pos).unwrap(), pos);
+            let multi_error = guiding_error_deg(&multi_point.img_to_mount_axes(pos).unwrap(), pos);
+            assert!(single_error > 4.0);
+            assert!(multi_error < single_error / 4.0, "multi-point error {} vs single-point {}", multi_error, single_error);
+        }
+    }
 }
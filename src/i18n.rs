@@ -0,0 +1,176 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! User interface translations.
+//!
+//! Translatable strings are passed through `tr`; as with gettext, the English text serves as the lookup key.
+//!
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct Language {
+    /// ISO 639-1 code.
+    pub code: &'static str,
+    /// Name of the language in that language.
+    pub name: &'static str,
+    /// Pairs of (English text, translation).
+    strings: &'static [(&'static str, &'static str)]
+}
+
+pub const LANGUAGES: &[Language] = &[
+    Language{ code: "en", name: "English", strings: &[] },
+    Language{ code: "pl", name: "Polski", strings: POLISH }
+];
+
+/// Index in `LANGUAGES`.
+static CURRENT_LANGUAGE: AtomicUsize = AtomicUsize::new(0);
+
+const POLISH: &[(&str, &str)] = &[
+    ("About", "O programie"),
+    (
+        "Adjust exposure time to reach the target histogram mean and start recording",
+        "Dostosuj czas ekspozycji do docelowej średniej histogramu i rozpocznij nagrywanie"
+    ),
+    ("Automatic", "Automatycznie"),
+    (
+        "Base frame timestamps (e.g., in SER file trailer) on the camera's clock instead of the computer's",
+        "Opieraj znaczniki czasu klatek (np. w stopce pliku SER) na zegarze kamery zamiast komputera"
+    ),
+    ("Calibration frames", "Klatki kalibracyjne"),
+    ("Capture bias", "Nagraj klatki bias"),
+    ("Capture flats", "Nagraj klatki flat"),
+    (
+        "Captured frames waiting to be saved; when exceeded, new frames are dropped.\n\
+        Higher values reduce drops during I/O slowdowns, at the cost of more memory.",
+        "Przechwycone klatki oczekujące na zapis; po przekroczeniu nowe klatki są odrzucane.\n\
+        Wyższe wartości ograniczają utratę klatek podczas spowolnień zapisu kosztem większego zużycia pamięci."
+    ),
+    ("Dest. directory:", "Katalog docelowy:"),
+    ("Destination directory", "Katalog docelowy"),
+    ("Disable recording crop", "Wyłącz kadrowanie nagrania"),
+    ("duration:", "czas trwania:"),
+    ("File", "Plik"),
+    (
+        "Frame quality is a sharpness measure (see the per-frame quality list).\n\
+        Best frames are kept in memory until the recording ends; if they exceed the maximum amount of buffered data,\n\
+        the recording is stopped.",
+        "Jakość klatki to miara ostrości (zob. listę jakości klatek).\n\
+        Najlepsze klatki są przechowywane w pamięci do końca nagrania; jeśli przekroczą maksymalną ilość buforowanych \
+        danych,\nnagrywanie zostaje zatrzymane."
+    ),
+    ("frames:", "klatki:"),
+    (
+        "How often the amount of buffered data is decreased by what has been saved (and the saving rate reported).\n\
+        Lower values make the buffered amount follow the actual state more closely, at a small cost in throughput.",
+        "Jak często ilość buforowanych danych jest pomniejszana o to, co zostało zapisane (i raportowana szybkość \
+        zapisu).\nNiższe wartości sprawiają, że ilość buforowanych danych dokładniej odzwierciedla rzeczywisty stan, \
+        kosztem niewielkiego spadku przepustowości."
+    ),
+    ("Keyboard shortcuts...", "Skróty klawiszowe..."),
+    ("Language", "Język"),
+    ("Limit", "Limit"),
+    ("Name prefix:", "Przedrostek nazwy:"),
+    ("Output format:", "Format wyjściowy:"),
+    ("Pause recording", "Wstrzymaj nagrywanie"),
+    (
+        "Pixel layout declared in the SER header; players debayer raw color videos according to it.\n\
+        \"Automatic\" uses the camera's pixel format; override it if the camera reports raw color data as mono \
+        or with a wrong Bayer pattern. Not applicable to RGB data.",
+        "Układ pikseli deklarowany w nagłówku SER; odtwarzacze wykonują według niego debayering surowych nagrań \
+        kolorowych.\n\"Automatycznie\" używa formatu pikseli kamery; należy go zmienić, jeśli kamera zgłasza surowe \
+        dane kolorowe jako mono lub z błędnym wzorem Bayera. Nie dotyczy danych RGB."
+    ),
+    ("Quit", "Zakończ"),
+    (
+        "Re-open the recorded file(s) after each recording and report missing or unreadable frames",
+        "Po każdym nagraniu otwórz ponownie nagrane pliki i zgłoś brakujące lub nieczytelne klatki"
+    ),
+    ("Record", "Nagraj"),
+    (
+        "Record at most the given number of frames per second; excess frames are skipped.\n\
+        Useful for cameras which always stream at maximum speed. Does not affect the preview.",
+        "Nagrywaj co najwyżej podaną liczbę klatek na sekundę; nadmiarowe klatki są pomijane.\n\
+        Przydatne dla kamer, które zawsze przesyłają obraz z maksymalną szybkością. Nie wpływa na podgląd."
+    ),
+    ("record forever", "nagrywaj bez limitu"),
+    ("Restart the program to change the language.", "Uruchom program ponownie, aby zmienić język."),
+    (
+        "Save a CSV file with a sharpness measure of each recorded frame (for selecting the best frames)",
+        "Zapisz plik CSV z miarą ostrości każdej nagranej klatki (do wyboru najlepszych klatek)"
+    ),
+    ("Set the shortest exposure time and start recording", "Ustaw najkrótszy czas ekspozycji i rozpocznij nagrywanie"),
+    ("Settings file", "Plik ustawień"),
+    ("Start recording", "Rozpocznij nagrywanie"),
+    ("Stop recording", "Zatrzymaj nagrywanie"),
+    ("Take snapshot", "Zrób zdjęcie"),
+    ("TIFF compression:", "Kompresja TIFF:"),
+    ("time(s) with interval", "raz(y) z odstępem")
+];
+
+/// Selects the user interface language by its code; if empty or unknown, the language is determined
+/// from the environment (`LC_ALL`, `LC_MESSAGES`, `LANG`), falling back to English.
+///
+/// Needs to be called before creating the user interface.
+pub fn init(code: &str) {
+    let index = language_index(code)
+        .or_else(|| ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| language_index(&locale))
+        )
+        .unwrap_or(0);
+
+    log::info!("user interface language: {}", LANGUAGES[index].code);
+    CURRENT_LANGUAGE.store(index, Ordering::Relaxed);
+}
+
+/// Returns the translation of `text` into the current language (or `text` itself if there is none).
+pub fn tr(text: &'static str) -> &'static str {
+    translate(CURRENT_LANGUAGE.load(Ordering::Relaxed), text)
+}
+
+fn translate(language_index: usize, text: &'static str) -> &'static str {
+    LANGUAGES[language_index].strings.iter()
+        .find(|(key, _)| *key == text)
+        .map(|(_, translation)| *translation)
+        .unwrap_or(text)
+}
+
+/// Returns index in `LANGUAGES` of the language of `locale` (e.g., "pl", "pl_PL.UTF-8").
+fn language_index(locale: &str) -> Option<usize> {
+    let code = locale.split(|c| c == '_' || c == '.' || c == '@').next().unwrap_or("");
+    LANGUAGES.iter().position(|language| language.code == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_locale_language_is_found() {
+        assert_eq!(Some(1), language_index("pl"));
+        assert_eq!(Some(1), language_index("pl_PL.UTF-8"));
+        assert_eq!(Some(0), language_index("en_US"));
+        assert_eq!(None, language_index("C"));
+        assert_eq!(None, language_index(""));
+    }
+
+    #[test]
+    fn given_untranslated_text_key_is_returned() {
+        assert_eq!("Rozpocznij nagrywanie", translate(1, "Start recording"));
+        assert_eq!("Start recording", translate(0, "Start recording"));
+        assert_eq!("Some other text", translate(1, "Some other text"));
+    }
+
+    #[test]
+    fn given_multiline_text_translation_is_found() {
+        assert!(translate(1, "Captured frames waiting to be saved; when exceeded, new frames are dropped.\n\
+            Higher values reduce drops during I/O slowdowns, at the cost of more memory.").starts_with("Przechwycone"));
+    }
+}
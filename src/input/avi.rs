@@ -0,0 +1,228 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Video input: uncompressed AVI video (in the formats written by `output::avi`).
+//!
+
+use crate::input::{ImageSequence, ImgSeqError};
+use crate::output::avi::AviFrameLayout;
+use ga_image::{Image, PixelFormat};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+
+pub fn open_avi_video(path: &std::path::Path) -> Result<Box<dyn ImageSequence>, ImgSeqError> {
+    Ok(Box::new(AviVideo::open(path)?))
+}
+
+struct AviVideo {
+    file: File,
+    layout: AviFrameLayout,
+    pix_fmt: PixelFormat,
+    width: u32,
+    height: u32,
+    /// Stored rows are in bottom-up order.
+    bottom_up: bool,
+    /// Positions of frame data.
+    frames: Vec<u64>
+}
+
+/// Returns the FourCC and size of the chunk at `position`.
+fn read_chunk_header(file: &mut File, position: u64) -> Result<([u8; 4], u32), ImgSeqError> {
+    let mut header = [0u8; 8];
+    file.seek(SeekFrom::Start(position))?;
+    file.read_exact(&mut header)?;
+    Ok(([header[0], header[1], header[2], header[3]], u32::from_le_bytes([header[4], header[5], header[6], header[7]])))
+}
+
+fn read_fourcc(file: &mut File, position: u64) -> Result<[u8; 4], ImgSeqError> {
+    let mut fourcc = [0u8; 4];
+    file.seek(SeekFrom::Start(position))?;
+    file.read_exact(&mut fourcc)?;
+    Ok(fourcc)
+}
+
+/// Frame format read from the `strf` chunk.
+struct FrameFormat {
+    layout: AviFrameLayout,
+    width: u32,
+    height: u32,
+    bottom_up: bool
+}
+
+fn parse_bitmap_info_header(header: &[u8]) -> Result<FrameFormat, ImgSeqError> {
+    if header.len() < 40 {
+        return Err(ImgSeqError::new("invalid stream format".to_string()));
+    }
+    let width = i32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let height = i32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    let bit_count = u16::from_le_bytes([header[14], header[15]]);
+    let compression = [header[16], header[17], header[18], header[19]];
+
+    let layout = match (bit_count, &compression) {
+        (8, [0, 0, 0, 0]) => AviFrameLayout::Mono8,
+        (24, [0, 0, 0, 0]) => AviFrameLayout::Bgr24,
+        (16, b"Y16 ") => AviFrameLayout::Mono16,
+        (48, b"b48r") => AviFrameLayout::Rgb48,
+        _ => return Err(ImgSeqError::new(format!(
+            "unsupported frame format: {} bits per pixel, compression {:?}",
+            bit_count, String::from_utf8_lossy(&compression)
+        )))
+    };
+
+    if width <= 0 || height == 0 {
+        return Err(ImgSeqError::new(format!("invalid frame size {}x{}", width, height)));
+    }
+
+    Ok(FrameFormat{
+        layout,
+        width: width as u32,
+        height: height.unsigned_abs(),
+        // for uncompressed DIBs, a negative height denotes top-down rows
+        bottom_up: layout.is_bottom_up() && height > 0
+    })
+}
+
+impl AviVideo {
+    fn open(file_name: &std::path::Path) -> Result<AviVideo, ImgSeqError> {
+        let mut file = OpenOptions::new().read(true).write(false).open(file_name)?;
+        let file_len = file.metadata()?.len();
+
+        let (riff, _) = read_chunk_header(&mut file, 0)?;
+        if &riff != b"RIFF" || &read_fourcc(&mut file, 8)? != b"AVI " {
+            return Err(ImgSeqError::new("not an AVI file".to_string()));
+        }
+
+        let mut format = None;
+        let mut frames = vec![];
+
+        // top level: "RIFF AVI " followed by "RIFF AVIX" lists (OpenDML)
+        let mut position = 0;
+        while position + 12 <= file_len {
+            let (fourcc, size) = read_chunk_header(&mut file, position)?;
+            if &fourcc == b"RIFF" {
+                let end = (position + 8 + size as u64).min(file_len);
+                read_riff_list(&mut file, position + 12, end, &mut format, &mut frames)?;
+            }
+            position += 8 + size as u64 + (size as u64 & 1);
+        }
+
+        let format = format.ok_or_else(|| ImgSeqError::new("video stream format not found".to_string()))?;
+        let pix_fmt = match format.layout {
+            AviFrameLayout::Mono8 => PixelFormat::Mono8,
+            AviFrameLayout::Bgr24 => PixelFormat::RGB8,
+            AviFrameLayout::Mono16 => PixelFormat::Mono16,
+            AviFrameLayout::Rgb48 => PixelFormat::RGB16
+        };
+
+        // skip chunks too small to contain a frame (e.g., dropped frames)
+        let frame_size = (format.layout.stride(format.width) * format.height as usize) as u64;
+        let mut valid_frames = vec![];
+        for (frame_pos, size) in frames {
+            if size as u64 >= frame_size { valid_frames.push(frame_pos); }
+        }
+
+        Ok(AviVideo{
+            file,
+            layout: format.layout,
+            pix_fmt,
+            width: format.width,
+            height: format.height,
+            bottom_up: format.bottom_up,
+            frames: valid_frames
+        })
+    }
+}
+
+/// Reads the contents (from `start` to `end`) of a RIFF list; stores the stream format (if found) and positions
+/// and sizes of frame data.
+fn read_riff_list(
+    file: &mut File,
+    start: u64,
+    end: u64,
+    format: &mut Option<FrameFormat>,
+    frames: &mut Vec<(u64, u32)>
+) -> Result<(), ImgSeqError> {
+    let mut position = start;
+    while position + 8 <= end {
+        let (fourcc, size) = read_chunk_header(file, position)?;
+        let contents_end = (position + 8 + size as u64).min(end);
+
+        if &fourcc == b"LIST" && position + 12 <= end {
+            match &read_fourcc(file, position + 8)? {
+                b"hdrl" | b"strl" => read_riff_list(file, position + 12, contents_end, format, frames)?,
+                b"movi" | b"rec " => read_movi_list(file, position + 12, contents_end, frames)?,
+                _ => ()
+            }
+        } else if &fourcc == b"strf" && format.is_none() {
+            let mut header = vec![0u8; (size as usize).min(40)];
+            file.read_exact(&mut header)?;
+            *format = Some(parse_bitmap_info_header(&header)?);
+        }
+
+        position += 8 + size as u64 + (size as u64 & 1);
+    }
+
+    Ok(())
+}
+
+fn read_movi_list(file: &mut File, start: u64, end: u64, frames: &mut Vec<(u64, u32)>) -> Result<(), ImgSeqError> {
+    let mut position = start;
+    while position + 8 <= end {
+        let (fourcc, size) = read_chunk_header(file, position)?;
+        if &fourcc == b"LIST" {
+            read_movi_list(file, position + 12, (position + 8 + size as u64).min(end), frames)?;
+        } else if &fourcc == b"00db" || &fourcc == b"00dc" {
+            frames.push((position + 8, size));
+        }
+        position += 8 + size as u64 + (size as u64 & 1);
+    }
+
+    Ok(())
+}
+
+impl ImageSequence for AviVideo {
+    fn get_image(&mut self, index: usize) -> Result<ga_image::Image, ImgSeqError> {
+        let position = *self.frames.get(index)
+            .ok_or_else(|| ImgSeqError::new(format!("frame index {} out of range", index)))?;
+
+        let stride = self.layout.stride(self.width);
+        let mut data = vec![0u8; stride * self.height as usize];
+        self.file.seek(SeekFrom::Start(position))?;
+        self.file.read_exact(&mut data)?;
+
+        let mut img = Image::new(self.width, self.height, None, self.pix_fmt, None, false);
+        for y in 0..self.height {
+            let src_y = if self.bottom_up { self.height - 1 - y } else { y };
+            let src = &data[src_y as usize * stride..(src_y as usize + 1) * stride];
+            match self.layout {
+                AviFrameLayout::Mono8 => {
+                    let line = img.line_raw_mut(y);
+                    let len = line.len();
+                    line.copy_from_slice(&src[..len]);
+                },
+
+                AviFrameLayout::Bgr24 => for (dest, src) in img.line_mut::<u8>(y).chunks_exact_mut(3).zip(src.chunks_exact(3)) {
+                    dest.copy_from_slice(&[src[2], src[1], src[0]]);
+                },
+
+                AviFrameLayout::Mono16 => for (dest, src) in img.line_mut::<u16>(y).iter_mut().zip(src.chunks_exact(2)) {
+                    *dest = u16::from_le_bytes([src[0], src[1]]);
+                },
+
+                AviFrameLayout::Rgb48 => for (dest, src) in img.line_mut::<u16>(y).iter_mut().zip(src.chunks_exact(2)) {
+                    *dest = u16::from_be_bytes([src[0], src[1]]);
+                }
+            }
+        }
+
+        Ok(img)
+    }
+
+    fn num_images(&self) -> usize { self.frames.len() }
+}
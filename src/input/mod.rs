@@ -10,9 +10,11 @@
 //! Video input module.
 //!
 
+mod avi;
 mod image_list;
 mod ser;
 
+pub use avi::open_avi_video;
 pub use image_list::create_image_list;
 pub use ser::open_ser_video;
 
@@ -18,11 +18,14 @@ mod controller;
 mod devices;
 mod gui;
 mod guiding;
+mod i18n;
 mod input;
 mod lim_freq_action;
 mod mount;
 mod output;
+mod quality;
 mod resources;
+mod session_stats;
 mod timer;
 mod tracking;
 mod workers;
@@ -38,7 +41,7 @@ use ga_image::Rect;
 use gtk::gio::prelude::*;
 use glib::clone;
 use mount::RadPerSec;
-use std::{cell::RefCell, collections::HashMap, sync::{atomic::{AtomicBool, AtomicIsize}, Arc}, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, sync::{atomic::{AtomicBool, AtomicIsize, AtomicU32}, Arc}, rc::Rc};
 use timer::Timer;
 use workers::capture::MainToCaptureThreadMsg;
 use workers::histogram::MainToHistogramThreadMsg;
@@ -59,7 +62,11 @@ pub struct RecordingThreadData {
     /// Approximate amount of image data currently buffered for recording.
     ///
     /// Increased after each captured frame, but decreased at a lower frequency. May be negative at times.
-    pub buffered_kib: Arc<AtomicIsize>
+    pub buffered_kib: Arc<AtomicIsize>,
+    /// If `buffered_kib` exceeds this value, captured frames are not recorded (dropped).
+    pub max_buffered_kib: Arc<AtomicIsize>,
+    /// Interval of decreasing `buffered_kib` by the amount written in the meantime.
+    pub update_interval_ms: Arc<AtomicU32>
 }
 
 #[derive(Copy, Clone)]
@@ -82,21 +89,41 @@ pub struct MountCalibration {
     primary_dir: Option<Vector2<f64>>,
     /// Image-space unit vector corresponding to positive slew around secondary axis.
     secondary_dir: Option<Vector2<f64>>,
-    /// Image-space-to-mount-axes-space slewing dir transformation matrix.
-    img_to_mount_axes: Option<cgmath::Matrix2<f64>>,
+    /// Tracked target position at the start of calibration.
+    start_pos: Point2<i32>,
     calibration_slew_speed: RadPerSec
 }
 
 pub struct MountData {
     mount: Option<Box<dyn mount::Mount>>,
+    /// Type of the connected mount.
+    mount_type: Option<devices::DeviceConnectionDiscriminants>,
+    /// Parameters of the last successful connection (used for reconnecting).
+    last_connection: Option<devices::DeviceConnection>,
     sky_tracking_on: bool,
     /// Desired tracking position. If `Some`, guiding is active and the mount will be slewed so that
     /// `ProgramData::tracking.pos` reaches this value.
     guiding_pos: Option<Point2<i32>>,
     guiding_timer: Timer,
     guide_slewing: bool,
+    /// If true, guiding stops once `guiding_pos` is reached (see `guiding::center_target`).
+    centering: bool,
+    /// Calibration in progress or the most recently completed one.
     calibration: Option<MountCalibration>,
-    calibration_timer: Timer
+    calibration_timer: Timer,
+    /// Built from completed calibrations; used for guiding.
+    mount_model: guiding::MountModel,
+    /// Detects guiding direction reversals requiring backlash compensation.
+    backlash: guiding::BacklashCompensation,
+    /// Axes (indexed by `mount::Axis as usize`) being slewed by the user with direction buttons, keys or controller.
+    manually_slewed_axes: [bool; 2],
+    /// Ends the guide pulse started with a "nudge" button.
+    nudge_timer: Timer,
+    /// Speed changes of user-initiated slews being accelerated gradually (indexed by `mount::Axis as usize`).
+    slew_ramps: [Option<mount::SlewRamp>; 2],
+    slew_ramp_timer: Timer,
+    /// Time of the last mount status query (see `gui::mount_gui::update_mount_status`).
+    t_last_status_poll: Option<std::time::Instant>
 }
 
 impl MountData {
@@ -111,7 +138,25 @@ impl MountData {
 
 mod sim_data {
     use std::sync::atomic::{AtomicBool};
-    use std::sync::Arc;
+    use std::sync::{Arc, RwLock};
+
+    /// Parameters of the simulated sky and mount motion (as seen by the camera simulator).
+    #[derive(Copy, Clone, Debug)]
+    pub struct MountSimulatorParams {
+        pub sky_rotation_dir_in_img_space: cgmath::Vector2<i32>,
+        pub primary_axis_slew_dir_in_img_space: cgmath::Vector2<i32>,
+        pub sky_rotation_speed_pix_per_sec: u32
+    }
+
+    impl Default for MountSimulatorParams {
+        fn default() -> MountSimulatorParams {
+            MountSimulatorParams{
+                sky_rotation_dir_in_img_space: cgmath::Vector2::new(1, 0),
+                primary_axis_slew_dir_in_img_space: cgmath::Vector2::new(1, 0),
+                sky_rotation_speed_pix_per_sec: 10
+            }
+        }
+    }
 
     /// Data shared between camera and mount simulators.
     #[derive(Clone)]
@@ -121,9 +166,8 @@ mod sim_data {
         pub primary_axis_speed: Arc<atomic_float::AtomicF32>,
         /// Value in camera simulator's pixels per second.
         pub secondary_axis_speed: Arc<atomic_float::AtomicF32>,
-        sky_rotation_dir_in_img_space: cgmath::Vector2<i32>,
-        primary_axis_slew_dir_in_img_space: cgmath::Vector2<i32>,
-        sky_rotation_speed_pix_per_sec: u32
+        /// May be changed when connecting the mount simulator.
+        params: Arc<RwLock<MountSimulatorParams>>
         //primary_axis_slewing_speed: Arc<Atomic
     }
 
@@ -133,44 +177,44 @@ mod sim_data {
                 mount_connected: Arc::new(AtomicBool::new(false)),
                 primary_axis_speed: Arc::new(atomic_float::AtomicF32::new(0.0)),
                 secondary_axis_speed: Arc::new(atomic_float::AtomicF32::new(0.0)),
-                sky_rotation_dir_in_img_space: cgmath::Vector2::new(1, 0),
-                primary_axis_slew_dir_in_img_space: cgmath::Vector2::new(-1, 0),
-                sky_rotation_speed_pix_per_sec: 10
+                params: Arc::new(RwLock::new(Default::default()))
             }
         }
     }
 
     impl MountSimulatorData {
-        pub fn new(
-            sky_rotation_dir_in_img_space: cgmath::Vector2<i32>,
-            primary_axis_slew_dir_in_img_space: cgmath::Vector2<i32>,
-            sky_rotation_speed_pix_per_sec: u32
-        ) -> MountSimulatorData {
+        pub fn new(params: MountSimulatorParams) -> MountSimulatorData {
             MountSimulatorData{
-                sky_rotation_dir_in_img_space,
-                primary_axis_slew_dir_in_img_space,
-                sky_rotation_speed_pix_per_sec,
+                params: Arc::new(RwLock::new(params)),
                 ..Default::default()
             }
         }
 
+        pub fn set_params(&self, params: MountSimulatorParams) {
+            *self.params.write().unwrap() = params;
+        }
+
         pub fn sky_rotation_dir_in_img_space(&self) -> cgmath::Vector2<i32> {
-            self.sky_rotation_dir_in_img_space
+            self.params.read().unwrap().sky_rotation_dir_in_img_space
         }
 
         pub fn primary_axis_slew_dir_in_img_space(&self) -> cgmath::Vector2<i32> {
-            self.primary_axis_slew_dir_in_img_space
+            self.params.read().unwrap().primary_axis_slew_dir_in_img_space
         }
 
         pub fn sky_rotation_speed_pix_per_sec(&self) -> u32 {
-            self.sky_rotation_speed_pix_per_sec
+            self.params.read().unwrap().sky_rotation_speed_pix_per_sec
         }
     }
 }
-pub use sim_data::MountSimulatorData;
+pub use sim_data::{MountSimulatorData, MountSimulatorParams};
 
 pub struct FocuserData {
-    focuser: Option<devices::focuser::FocuserWrapper>
+    focuser: Option<devices::focuser::FocuserWrapper>,
+    /// Type of the connected focuser.
+    focuser_type: Option<devices::DeviceConnectionDiscriminants>,
+    /// Parameters of the last successful connection (used for reconnecting).
+    last_connection: Option<devices::DeviceConnection>
 }
 
 #[derive(Debug)]
@@ -182,7 +226,13 @@ pub enum TrackingMode {
 #[derive(Debug)]
 pub struct TrackingData {
     pos: Point2<i32>,
-    mode: TrackingMode
+    /// Tracked position with subpixel precision (in centroid mode); used for guiding.
+    subpixel_pos: Point2<f64>,
+    /// Signal-to-noise ratio of the tracked star (in centroid mode).
+    snr: Option<f64>,
+    mode: TrackingMode,
+    /// Capture time of the frame in which the position has been determined.
+    captured: std::time::SystemTime
 }
 
 #[derive(Copy, Clone)]
@@ -202,6 +252,8 @@ pub struct ProgramData {
     on_capture_pause_action: Option<OnCapturePauseAction>,
     preview_fps_counter: usize,
     preview_fps_last_timestamp: Option<std::time::Instant>,
+    /// Sum of capture-to-display latencies of the preview images counted in `preview_fps_counter`.
+    preview_latency_sum: std::time::Duration,
     focuser_data: Rc<RefCell<FocuserData>>,
     /// Non-empty after the main window creation.
     gui: Option<gui::GuiData>,
@@ -214,8 +266,11 @@ pub struct ProgramData {
     /// True if the capture thread is sending images to a recording job.
     rec_job_active: bool,
     t_last_histogram: Option<std::time::Instant>,
-    /// If true, raw color images are demosaiced for preview.
+    /// If true, raw color images are demosaiced for preview (recorded data always stays raw).
     demosaic_preview: bool,
+    /// If true, the preview keeps showing the last displayed image (refreshed according to
+    /// `Configuration::paused_preview_refresh_interval`); overlays stay interactive.
+    preview_paused: bool,
     preview_fps_limit: Option<i32>,
     last_displayed_preview_image_timestamp: Option<std::time::Instant>,
     last_displayed_preview_image: Option<ga_image::Image>,
@@ -232,7 +287,22 @@ pub struct ProgramData {
     #[cfg(feature = "bluetooth")]
     tokio_rt: Rc<tokio::runtime::Runtime>,
     /// Non-empty after program initialization.
-    focuser_move_action: Option<lim_freq_action::LimitedFreqAction<(focuser::Speed, focuser::FocuserDir)>>
+    focuser_move_action: Option<lim_freq_action::LimitedFreqAction<(focuser::Speed, focuser::FocuserDir)>>,
+    session_stats: Arc<session_stats::SessionStats>,
+    t_session_start: std::time::Instant,
+    /// `None` if automatic configuration saving is disabled.
+    config_autosave_interval: Option<std::time::Duration>,
+    t_last_config_save: std::time::Instant,
+    /// `None` if automatic disconnection of idle devices is disabled.
+    idle_disconnect_timeout: Option<std::time::Duration>,
+    /// Time of the last user input, remote command or device operation (see `gui::event_handlers::check_idle_disconnect`).
+    t_last_activity: std::time::Instant,
+    /// Serves captured frames to external consumers (if enabled).
+    frame_feed: Option<workers::frame_feed::FrameFeed>,
+    /// Serves the preview as an MJPEG stream over HTTP (if enabled).
+    mjpeg_server: Option<workers::mjpeg_server::MjpegServer>,
+    /// Serves the remote control HTTP API (if enabled).
+    remote_control: Option<workers::remote_control::RemoteControl>
 }
 
 impl ProgramData {
@@ -277,8 +347,10 @@ fn main() {
 
     let (rec_sender_worker, rec_receiver_main) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
     let buffered_kib = Arc::new(AtomicIsize::new(0));
+    let session_stats = Arc::new(session_stats::SessionStats::default());
 
     let config = Configuration::new();
+    i18n::init(&config.language());
     let disabled_drivers_str = config.disabled_drivers();
     let disabled_drivers: Vec<&str> = if disabled_drivers_str.is_empty() {
         vec![]
@@ -290,11 +362,13 @@ fn main() {
 
     let preview_fps_limit = config.preview_fps_limit();
 
-    let mount_simulator_data = MountSimulatorData::new(
-        config.mount_simulator_sky_rotation_dir_in_img_space().unwrap_or(cgmath::Vector2::new(1, 0)),
-        config.mount_simulator_primary_axis_slew_dir_in_img_space().unwrap_or(cgmath::Vector2::new(1, 0)),
-        config.mount_simulator_sky_rotation_speed_pix_per_sec().unwrap_or(10)
-    );
+    let max_buffered_kib = Arc::new(AtomicIsize::new(config.max_buffered_mib() as isize * 1024));
+    let rec_update_interval_ms = Arc::new(AtomicU32::new(config.recording_update_interval_ms()));
+
+    let config_autosave_interval = config.autosave_interval();
+    let idle_disconnect_timeout = config.idle_disconnect_timeout();
+
+    let mount_simulator_data = MountSimulatorData::new(config.mount_simulator_params());
 
     #[cfg(feature = "controller")]
     let ctrl_actions = config.controller_actions();
@@ -308,26 +382,52 @@ fn main() {
         recording_thread_data: RecordingThreadData {
             jobs: rec_jobs.clone(),
             join_handle: Some(std::thread::spawn(
-                clone!(@weak buffered_kib => @default-panic,
-                    move || workers::recording::recording_thread(rec_jobs, rec_sender_worker, rec_recv_worker, buffered_kib)
+                clone!(@weak buffered_kib, @strong max_buffered_kib, @strong rec_update_interval_ms, @strong session_stats
+                    => @default-panic,
+                    move || workers::recording::recording_thread(
+                        rec_jobs,
+                        rec_sender_worker,
+                        rec_recv_worker,
+                        buffered_kib,
+                        max_buffered_kib,
+                        rec_update_interval_ms,
+                        session_stats
+                    )
                 )
             )),
             sender: rec_sender_main,
-            buffered_kib
+            buffered_kib,
+            max_buffered_kib,
+            update_interval_ms: rec_update_interval_ms
         },
         on_capture_pause_action: None,
         preview_fps_counter: 0,
         preview_fps_last_timestamp: None,
-        focuser_data: Rc::new(RefCell::new(FocuserData{ focuser: None })),
+        preview_latency_sum: std::time::Duration::ZERO,
+        focuser_data: Rc::new(RefCell::new(FocuserData{
+            focuser: None,
+            focuser_type: None,
+            last_connection: None
+        })),
         gui: None,
         mount_data: MountData{
             mount: None,
+            mount_type: None,
+            last_connection: None,
             sky_tracking_on: false,
             guiding_pos: None,
             guiding_timer: Timer::new(),
+            centering: false,
             guide_slewing: false,
             calibration: None,
-            calibration_timer: Timer::new()
+            calibration_timer: Timer::new(),
+            mount_model: Default::default(),
+            backlash: Default::default(),
+            manually_slewed_axes: [false; 2],
+            nudge_timer: Timer::new(),
+            slew_ramps: [None; 2],
+            slew_ramp_timer: Timer::new(),
+            t_last_status_poll: None
         },
         tracking: None,
         crop_area: None,
@@ -335,6 +435,7 @@ fn main() {
         rec_job_active: false,
         t_last_histogram: None,
         demosaic_preview: false,
+        preview_paused: false,
         preview_fps_limit,
         last_displayed_preview_image_timestamp: None,
         last_displayed_preview_image: None,
@@ -349,7 +450,16 @@ fn main() {
         ctrl_names: HashMap::new(),
         #[cfg(feature = "bluetooth")]
         tokio_rt: Rc::new(tokio::runtime::Runtime::new().unwrap()),
-        focuser_move_action: None
+        focuser_move_action: None,
+        session_stats,
+        t_session_start: std::time::Instant::now(),
+        config_autosave_interval,
+        t_last_config_save: std::time::Instant::now(),
+        idle_disconnect_timeout,
+        t_last_activity: std::time::Instant::now(),
+        frame_feed: None,
+        mjpeg_server: None,
+        remote_control: None
     }));
 
     gui::set_up_focuser_move_action(&program_data_rc);
@@ -398,11 +508,30 @@ fn main() {
     program_data_rc.borrow_mut().finish_recording_thread();
     program_data_rc.borrow_mut().camera = None; // make sure the camera is dropped before the drivers are
 
+    log_session_summary(&program_data_rc.borrow());
+
     if program_data_rc.borrow().config.store().is_err() {
         println!("WARNING: Failed to save configuration.");
     }
 }
 
+/// Writes the session summary to the log and (if configured) appends it to the session summary file.
+fn log_session_summary(program_data: &ProgramData) {
+    let summary = program_data.session_stats.summary(program_data.t_session_start.elapsed());
+    log::info!("session summary: {}", summary);
+
+    if let Some(path) = program_data.config.session_summary_file() {
+        use std::io::Write;
+
+        let result = std::fs::OpenOptions::new().create(true).append(true).open(&path).and_then(|mut file|
+            writeln!(file, "{} {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), summary)
+        );
+        if let Err(e) = result {
+            println!("WARNING: Failed to write session summary to {}: {}.", path.to_string_lossy(), e);
+        }
+    }
+}
+
 fn init_timer(timer_step: std::time::Duration, program_data_rc: &Rc<RefCell<ProgramData>>) {
     let (sender_worker, receiver_main) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
     receiver_main.attach(None, clone!(@weak program_data_rc => @default-panic, move |_| {
@@ -420,14 +549,47 @@ fn init_timer(timer_step: std::time::Duration, program_data_rc: &Rc<RefCell<Prog
     // no need to demand and wait for timer thread's termination; let the runtime end it when the program ends
 }
 
-fn on_capture_thread_failure(program_data_rc: &Rc<RefCell<ProgramData>>) {
-    gui::show_message(
-        "Capture thread ended with error. Try reconnecting to the camera.",
-        "Error",
-        gtk::MessageType::Error,
-        program_data_rc
-    );
+/// Handles the capture thread's end due to an error; `error` is `None` if the cause is unknown (e.g., the thread
+/// has been found to be no longer receiving messages).
+fn on_capture_thread_failure(error: Option<&camera::CameraError>, program_data_rc: &Rc<RefCell<ProgramData>>) {
+    let cause = match error {
+        Some(error) => format!("Capture thread ended with error:\n{}", error),
+        None => "Capture thread ended with error.".to_string()
+    };
+
+    let pending_sequence_item = gui::pending_sequence_item(&program_data_rc.borrow());
+    let camera_menu_item = gui::current_camera_menu_item(&program_data_rc.borrow());
+
     gui::disconnect_camera(program_data_rc, true);
+    program_data_rc.borrow_mut().rec_job_active = false;
+
+    match (pending_sequence_item, camera_menu_item) {
+        (Some((item_idx, item_count)), Some(camera_menu_item)) => {
+            let resume = gui::show_question(
+                &format!(
+                    "{}\n\nThis happened during a recording sequence. \
+                    Reconnect to the camera and resume the sequence from recording {} of {}?",
+                    cause, item_idx + 1, item_count
+                ),
+                "Error",
+                program_data_rc
+            );
+            if resume {
+                camera_menu_item.activate();
+                if program_data_rc.borrow().camera.is_some() {
+                    log::info!("resuming recording sequence from item {} of {}", item_idx + 1, item_count);
+                    gui::resume_sequence(item_idx, program_data_rc);
+                }
+            }
+        },
+
+        _ => gui::show_message(
+            &format!("{}\n\nTry reconnecting to the camera.", cause),
+            "Error",
+            gtk::MessageType::Error,
+            program_data_rc
+        )
+    }
 }
 
 #[cfg(feature = "controller")]
@@ -189,7 +189,8 @@ impl std::ops::Deref for ITelescope {
 pub struct Ascom {
     telescope: *mut ITelescope,
     driver: String,
-    tracking: bool
+    tracking: bool,
+    tracking_rate: RadPerSec
 }
 
 impl Drop for Ascom {
@@ -250,7 +251,7 @@ impl Ascom {
 
         checked_call!(unsafe { ((*(*telescope).lpVtbl).Unpark)(telescope) });
 
-        Ok(Ascom{ telescope, driver: progid.to_string(), tracking: false })
+        Ok(Ascom{ telescope, driver: progid.to_string(), tracking: false, tracking_rate: SIDEREAL_RATE })
     }
 
     fn set_motion(&mut self, axis: Axis, speed: RadPerSec) -> Result<(), Box<dyn Error>> {
@@ -278,13 +279,22 @@ impl Mount for Ascom {
 
     fn set_tracking(&mut self, enabled: bool) -> Result<(), Box<dyn Error>> {
         self.tracking = enabled;
-        self.set_motion(Axis::Primary, if enabled { SIDEREAL_RATE } else { RadPerSec(0.0) })
+        self.set_motion(Axis::Primary, if enabled { self.tracking_rate } else { RadPerSec(0.0) })
+    }
+
+    fn set_tracking_rate(&mut self, rate: RadPerSec) -> Result<(), Box<dyn Error>> {
+        self.tracking_rate = rate;
+        if self.tracking {
+            self.set_motion(Axis::Primary, rate)
+        } else {
+            Ok(())
+        }
     }
 
     fn guide(&mut self, axis1_speed: RadPerSec, axis2_speed: RadPerSec) -> Result<(), Box<dyn Error>> {
         if !self.tracking { return Err("cannot guide when tracking disabled".into()); }
 
-        self.set_motion(Axis::Primary, axis1_speed + if self.tracking { SIDEREAL_RATE } else { RadPerSec(0.0) })?;
+        self.set_motion(Axis::Primary, axis1_speed + if self.tracking { self.tracking_rate } else { RadPerSec(0.0) })?;
         self.set_motion(Axis::Secondary, axis2_speed)?;
 
         Ok(())
@@ -297,7 +307,7 @@ impl Mount for Ascom {
         };
 
         match axis {
-            Axis::Primary => self.set_motion(axis, speed + if self.tracking { SIDEREAL_RATE } else { RadPerSec(0.0) })?,
+            Axis::Primary => self.set_motion(axis, speed + if self.tracking { self.tracking_rate } else { RadPerSec(0.0) })?,
             Axis::Secondary => self.set_motion(axis, speed)?
         }
 
@@ -308,6 +318,10 @@ impl Mount for Ascom {
         speed <= MAX_SPEED
     }
 
+    fn max_variable_slewing_speed(&self) -> Option<RadPerSec> {
+        Some(MAX_SPEED)
+    }
+
     fn stop(&mut self) -> Result<(), Box<dyn Error>> {
         self.set_motion(Axis::Primary, RadPerSec(0.0))?;
         self.set_motion(Axis::Secondary, RadPerSec(0.0))
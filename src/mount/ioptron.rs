@@ -12,6 +12,7 @@
 //! Based on "iOptron® Mount RS-232 Command Language" (v. 3.10 2021-01-04).
 //!
 
+use crate::devices::SerialSettings;
 use crate::mount::{Axis, Mount, SlewSpeed, RadPerSec, SIDEREAL_RATE};
 use std::error::Error;
 
@@ -78,15 +79,16 @@ impl Ioptron {
     ///
     /// * `device` - System device name to use for connecting to the mount,
     ///     e.g., "COM3" on Windows or "/dev/ttyUSB0" on Linux.
+    /// * `serial_settings` - Baud rate and timeout.
     ///
     #[must_use]
-    pub fn new(device: &str) -> Result<Ioptron, Box<dyn Error>> {
-        let mut serial_port = serialport::new(device, 115200)
+    pub fn new(device: &str, serial_settings: SerialSettings) -> Result<Ioptron, Box<dyn Error>> {
+        let mut serial_port = serialport::new(device, serial_settings.baud_rate)
             .data_bits(serialport::DataBits::Eight)
             .flow_control(serialport::FlowControl::None)
             .parity(serialport::Parity::None)
             .stop_bits(serialport::StopBits::One)
-            .timeout(std::time::Duration::from_millis(50))
+            .timeout(serial_settings.timeout)
             .open()?;
 
         let model = if let Ok(chars) = send_cmd_and_get_reply(
@@ -17,7 +17,7 @@ mod simulator;
 mod skywatcher;
 mod zwo;
 
-use crate::devices::DeviceConnection;
+use crate::{config::Configuration, devices::{DeviceConnection, DeviceConnectionDiscriminants}};
 use std::error::Error;
 
 #[derive(Copy, Clone)]
@@ -32,6 +32,10 @@ impl RadPerSec {
     pub fn is_zero(&self) -> bool { self.0 == 0.0 }
 
     pub fn abs(&self) -> RadPerSec { RadPerSec(self.0.abs()) }
+
+    pub fn from_arcsec_per_sec(value: f64) -> RadPerSec { RadPerSec((value / 3600.0).to_radians()) }
+
+    pub fn to_arcsec_per_sec(&self) -> f64 { self.0.to_degrees() * 3600.0 }
 }
 
 impl std::ops::Mul<f64> for RadPerSec {
@@ -80,6 +84,12 @@ impl std::ops::DivAssign<f64> for RadPerSec {
 
 pub const SIDEREAL_RATE: RadPerSec = RadPerSec(2.0 * std::f64::consts::PI / SECONDS_PER_DAY);
 
+/// Mean apparent rate of the Sun.
+pub const SOLAR_RATE: RadPerSec = RadPerSec(2.0 * std::f64::consts::PI / 86400.0);
+
+/// Mean apparent rate of the Moon (14.685″/s, as used by ASCOM).
+pub const LUNAR_RATE: RadPerSec = RadPerSec(14.685 / 3600.0 * std::f64::consts::PI / 180.0);
+
 pub enum SlewSpeed {
     Specific(RadPerSec),
     Max(bool) // `true` means positive direction, `false` - negative
@@ -98,6 +108,49 @@ impl SlewSpeed {
     }
 }
 
+/// Gradually changes the slewing speed of an axis (for mounts which support variable speeds, see
+/// `Mount::max_variable_slewing_speed`).
+#[derive(Copy, Clone)]
+pub struct SlewRamp {
+    pub current: RadPerSec,
+    pub target: RadPerSec
+}
+
+impl SlewRamp {
+    pub fn at_target(&self) -> bool { self.current == self.target }
+
+    /// Changes `current` towards `target` by at most `max_change`; returns the new current speed.
+    pub fn step(&mut self, max_change: RadPerSec) -> RadPerSec {
+        let delta = self.target.0 - self.current.0;
+        self.current = if delta.abs() <= max_change.0 {
+            self.target
+        } else {
+            RadPerSec(self.current.0 + max_change.0 * delta.signum())
+        };
+        self.current
+    }
+}
+
+/// Mount state as reported by the mount itself.
+#[derive(Clone, Default)]
+pub struct MountStatus {
+    /// `None` if not reported by the mount.
+    pub tracking: Option<bool>,
+    /// `None` if not reported by the mount.
+    pub slewing: Option<bool>,
+    /// Error condition reported by the mount (if any).
+    pub error: Option<String>
+}
+
+impl std::fmt::Display for MountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let on_off = |value: Option<bool>| match value { Some(true) => "on", Some(false) => "off", None => "?" };
+        write!(f, "tracking: {}, slewing: {}", on_off(self.tracking), on_off(self.slewing))?;
+        if let Some(error) = &self.error { write!(f, "; error: {}", error)?; }
+        Ok(())
+    }
+}
+
 pub trait Mount {
     #[must_use]
     fn get_info(&self) -> String;
@@ -105,6 +158,23 @@ pub trait Mount {
     #[must_use]
     fn set_tracking(&mut self, enabled: bool) -> Result<(), Box<dyn Error>>;
 
+    /// Sets the primary axis rate used when tracking is enabled (default: `SIDEREAL_RATE`).
+    ///
+    /// Default implementation supports only the sidereal rate.
+    ///
+    #[must_use]
+    fn set_tracking_rate(&mut self, rate: RadPerSec) -> Result<(), Box<dyn Error>> {
+        if rate == SIDEREAL_RATE {
+            Ok(())
+        } else {
+            Err("custom tracking rate not supported by this mount".into())
+        }
+    }
+
+    /// Starts guiding with the given speeds (relative to tracking); specify zero speeds to stop.
+    ///
+    /// Backlash compensation on direction reversals (see `guiding::BacklashCompensation`) is performed by the caller.
+    ///
     #[must_use]
     fn guide(&mut self, axis1_speed: RadPerSec, axis2_speed: RadPerSec) -> Result<(), Box<dyn Error>>;
 
@@ -115,24 +185,37 @@ pub trait Mount {
     #[must_use]
     fn slewing_speed_supported(&self, speed: RadPerSec) -> bool;
 
+    /// Returns the maximum speed up to which the mount accepts any slewing speed and can change it without stopping
+    /// (which allows slews to accelerate gradually, see `SlewRamp`; faster slews start without a ramp); returns `None`
+    /// if only a few fixed speeds are supported.
+    fn max_variable_slewing_speed(&self) -> Option<RadPerSec> { None }
+
     fn stop(&mut self) -> Result<(), Box<dyn Error>>;
 
+    /// Queries the mount's current status; returns `None` if not supported.
+    fn status(&mut self) -> Result<Option<MountStatus>, Box<dyn Error>> { Ok(None) }
+
     /// Only implemented by mount simulator.
     fn set_mount_simulator_data(&mut self, _mount_simulator_data: crate::MountSimulatorData) {}
 }
 
-pub fn connect_to_mount(connection: DeviceConnection) -> Result<Box<dyn Mount>, Box<dyn Error>> {
+pub fn connect_to_mount(
+    connection: DeviceConnection,
+    config: &Configuration
+) -> Result<Box<dyn Mount>, Box<dyn Error>> {
+    let serial_settings = config.serial_settings(DeviceConnectionDiscriminants::from(&connection));
+
     match connection {
         DeviceConnection::SkyWatcherMountSerial{device} => {
-            Ok(Box::new(skywatcher::SkyWatcher::new(&device)?))
+            Ok(Box::new(skywatcher::SkyWatcher::new(&device, serial_settings.unwrap())?))
         },
 
         DeviceConnection::IoptronMountSerial{device} => {
-            Ok(Box::new(ioptron::Ioptron::new(&device)?))
+            Ok(Box::new(ioptron::Ioptron::new(&device, serial_settings.unwrap())?))
         },
 
         DeviceConnection::ZWOMountSerial{device} => {
-            Ok(Box::new(zwo::ZWO::new(&device)?))
+            Ok(Box::new(zwo::ZWO::new(&device, serial_settings.unwrap())?))
         },
 
         #[cfg(feature = "mount_ascom")]
@@ -140,10 +223,25 @@ pub fn connect_to_mount(connection: DeviceConnection) -> Result<Box<dyn Mount>,
             Ok(Box::new(ascom::Ascom::new(&prog_id)?))
         },
 
-        DeviceConnection::MountSimulator => {
-            Ok(Box::new(simulator::Simulator::new()))
+        DeviceConnection::MountSimulator{params} => {
+            Ok(Box::new(simulator::Simulator::new(params)))
         },
 
         _ => unreachable!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slew_ramp_reaches_target_gradually() {
+        let mut ramp = SlewRamp{ current: RadPerSec(0.0), target: RadPerSec(-2.5) };
+        assert!(ramp.step(RadPerSec(1.0)) == RadPerSec(-1.0));
+        assert!(ramp.step(RadPerSec(1.0)) == RadPerSec(-2.0));
+        assert!(!ramp.at_target());
+        assert!(ramp.step(RadPerSec(1.0)) == RadPerSec(-2.5));
+        assert!(ramp.at_target());
+    }
+}
@@ -10,20 +10,27 @@
 //! Mount simulator.
 //!
 
-use crate::mount::{Axis, Mount, SlewSpeed, RadPerSec, SIDEREAL_RATE};
+use crate::mount::{Axis, Mount, MountStatus, SlewSpeed, RadPerSec, SIDEREAL_RATE};
 use std::error::Error;
 use std::sync::atomic::Ordering;
 
+const MAX_SPEED: RadPerSec = RadPerSec(512.0 * SIDEREAL_RATE.0);
+
 pub struct Simulator {
     tracking: bool,
-    data: crate::MountSimulatorData
+    tracking_rate: RadPerSec,
+    data: crate::MountSimulatorData,
+    /// Applied to `data` once it is provided.
+    params: crate::MountSimulatorParams
 }
 
 impl Simulator {
-    pub fn new() -> Simulator {
+    pub fn new(params: crate::MountSimulatorParams) -> Simulator {
         Simulator{
             tracking: false,
-            data: Default::default()
+            tracking_rate: SIDEREAL_RATE,
+            data: Default::default(),
+            params
         }
     }
 
@@ -51,19 +58,26 @@ impl Mount for Simulator {
 
     fn set_mount_simulator_data(&mut self, mount_simulator_data: crate::MountSimulatorData) {
         self.data = mount_simulator_data;
+        self.data.set_params(self.params);
         self.data.mount_connected.store(true, Ordering::Release);
     }
 
     fn set_tracking(&mut self, enabled: bool) -> Result<(), Box<dyn Error>> {
         self.tracking = enabled;
-        self.motion(Axis::Primary, if enabled { SIDEREAL_RATE } else { RadPerSec(0.0) });
+        self.motion(Axis::Primary, if enabled { self.tracking_rate } else { RadPerSec(0.0) });
+        Ok(())
+    }
+
+    fn set_tracking_rate(&mut self, rate: RadPerSec) -> Result<(), Box<dyn Error>> {
+        self.tracking_rate = rate;
+        if self.tracking { self.motion(Axis::Primary, rate); }
         Ok(())
     }
 
     fn guide(&mut self, axis1_speed: RadPerSec, axis2_speed: RadPerSec) -> Result<(), Box<dyn Error>> {
         if !self.tracking { return Err("cannot guide when tracking disabled".into()); }
 
-        self.motion(Axis::Primary, SIDEREAL_RATE + axis1_speed);
+        self.motion(Axis::Primary, self.tracking_rate + axis1_speed);
         self.motion(Axis::Secondary, axis2_speed);
 
         Ok(())
@@ -72,10 +86,10 @@ impl Mount for Simulator {
     fn slew(&mut self, axis: Axis, speed: SlewSpeed) -> Result<(), Box<dyn Error>> {
         let speed = match speed {
             SlewSpeed::Specific(s) => s,
-            SlewSpeed::Max(dir) => (if dir { 512.0 } else { -512.0 }) * SIDEREAL_RATE
+            SlewSpeed::Max(dir) => if dir { MAX_SPEED } else { -MAX_SPEED }
         };
         match axis {
-            Axis::Primary => self.motion(axis, speed + if self.tracking { SIDEREAL_RATE } else { RadPerSec(0.0) }),
+            Axis::Primary => self.motion(axis, speed + if self.tracking { self.tracking_rate } else { RadPerSec(0.0) }),
             Axis::Secondary => self.motion(axis, speed)
         }
 
@@ -86,10 +100,26 @@ impl Mount for Simulator {
         true
     }
 
+    fn max_variable_slewing_speed(&self) -> Option<RadPerSec> {
+        Some(MAX_SPEED)
+    }
+
     fn stop(&mut self) -> Result<(), Box<dyn Error>> {
         self.tracking = false;
         self.data.primary_axis_speed.store(0.0, Ordering::Release);
         self.data.secondary_axis_speed.store(0.0, Ordering::Release);
         Ok(())
     }
+
+    fn status(&mut self) -> Result<Option<MountStatus>, Box<dyn Error>> {
+        let tracking_speed = if self.tracking {
+            (self.tracking_rate.0 / SIDEREAL_RATE.0 * self.data.sky_rotation_speed_pix_per_sec() as f64) as f32
+        } else {
+            0.0
+        };
+        let slewing = self.data.secondary_axis_speed.load(Ordering::Acquire) != 0.0
+            || self.data.primary_axis_speed.load(Ordering::Acquire) != tracking_speed;
+
+        Ok(Some(MountStatus{ tracking: Some(self.tracking), slewing: Some(slewing), error: None }))
+    }
 }
@@ -14,7 +14,8 @@
 //!
 
 use std::{error::Error, f64::consts::PI};
-use crate::mount::{Axis, Mount, SlewSpeed, RadPerSec, SIDEREAL_RATE};
+use crate::devices::SerialSettings;
+use crate::mount::{Axis, Mount, MountStatus, SlewSpeed, RadPerSec, SIDEREAL_RATE};
 
 const AXIS_STOP_MOTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
@@ -64,6 +65,7 @@ enum Opcode {
     GetTimerIntFreq,
     GetAxisStatus,
     GetHiSpeedRatio,
+    GetMotorBoardVersion,
     GetPecPeriod
 }
 
@@ -79,6 +81,7 @@ impl Opcode {
             Opcode::GetTimerIntFreq => 'b',
             Opcode::GetAxisStatus =>   'f',
             Opcode::GetHiSpeedRatio => 'g',
+            Opcode::GetMotorBoardVersion => 'e',
             Opcode::GetPecPeriod =>    's'
         }
     }
@@ -98,11 +101,14 @@ mod motion {
 
 pub struct SkyWatcher {
     device: String,
+    /// Mount model and motor controller firmware version.
+    firmware_info: String,
     serial_port: Box<dyn serialport::SerialPort>,
     rad_rate_to_int: [f64; 2],
     hi_speed_ratio: [u32; 2],
     current_slewing_speed: [RadPerSec; 2],
-    tracking: bool
+    tracking: bool,
+    tracking_rate: RadPerSec
 }
 
 impl SkyWatcher {
@@ -112,15 +118,16 @@ impl SkyWatcher {
     ///
     /// * `device` - System device name to use for connecting to the mount,
     ///     e.g., "COM3" on Windows or "/dev/ttyUSB0" on Linux.
+    /// * `serial_settings` - Baud rate and timeout.
     ///
     #[must_use]
-    pub fn new(device: &str) -> Result<SkyWatcher, Box<dyn Error>> {
-        let mut serial_port = serialport::new(device, 9600)
+    pub fn new(device: &str, serial_settings: SerialSettings) -> Result<SkyWatcher, Box<dyn Error>> {
+        let mut serial_port = serialport::new(device, serial_settings.baud_rate)
             .data_bits(serialport::DataBits::Eight)
             .flow_control(serialport::FlowControl::None)
             .parity(serialport::Parity::None)
             .stop_bits(serialport::StopBits::One)
-            .timeout(std::time::Duration::from_millis(50))
+            .timeout(serial_settings.timeout)
             .open()?;
 
         let mut rad_to_step = [0.0; 2];
@@ -151,12 +158,20 @@ impl SkyWatcher {
         let response = send_cmd_and_get_reply(&mut serial_port, Axis::Secondary, Opcode::GetHiSpeedRatio, "")?;
         hi_speed_ratio[Axis::Secondary.as_index()] = skywatcher_hex_str_to_u32(&extract_hex_number(&response))?;
 
+        let response = send_cmd_and_get_reply(&mut serial_port, Axis::Primary, Opcode::GetMotorBoardVersion, "")?;
+        let firmware_info = motor_board_version_to_string(
+            skywatcher_hex_str_to_u32(&extract_hex_number(&response))?
+        );
+        log::info!("Sky-Watcher motor controller: {}", firmware_info);
+
         send_cmd_and_get_reply(&mut serial_port, Axis::Primary, Opcode::InitMotorCtrl, "")?;
         send_cmd_and_get_reply(&mut serial_port, Axis::Secondary, Opcode::InitMotorCtrl, "")?;
 
         Ok(SkyWatcher{
             device: device.to_string(),
+            firmware_info,
             tracking: false,
+            tracking_rate: SIDEREAL_RATE,
             serial_port,
             rad_rate_to_int,
             hi_speed_ratio,
@@ -174,6 +189,23 @@ impl SkyWatcher {
         }
     }
 
+    /// Returns (running, blocked, initialized).
+    #[must_use]
+    fn axis_status(&mut self, axis: Axis) -> Result<(bool, bool, bool), Box<dyn Error>> {
+        let response = send_cmd_and_get_reply(&mut self.serial_port, axis, Opcode::GetAxisStatus, "")?;
+        if response.len() < 5 { return Err("invalid response".into()); }
+
+        let digit = |idx: usize| -> Result<u32, Box<dyn Error>> {
+            (response[idx] as char).to_digit(16).ok_or("invalid response".into())
+        };
+
+        let running = digit(2)? & 0x01 != 0;
+        let blocked = digit(2)? & 0x02 != 0;
+        let initialized = digit(3)? & 0x01 != 0;
+
+        Ok((running, blocked, initialized))
+    }
+
     #[must_use]
     fn update_step_period(&mut self, axis: Axis, mut speed: RadPerSec) -> Result<(), Box<dyn Error>> {
         if speed > MAX_SPEED {
@@ -255,18 +287,27 @@ impl SkyWatcher {
 
 impl Mount for SkyWatcher {
     fn get_info(&self) -> String {
-        format!("Sky-Watcher on {}", self.device)
+        format!("Sky-Watcher on {} ({})", self.device, self.firmware_info)
     }
 
     fn set_tracking(&mut self, enabled: bool) -> Result<(), Box<dyn Error>> {
         self.tracking = enabled;
-        self.set_motion(Axis::Primary, if enabled { SIDEREAL_RATE } else { RadPerSec(0.0) })
+        self.set_motion(Axis::Primary, if enabled { self.tracking_rate } else { RadPerSec(0.0) })
+    }
+
+    fn set_tracking_rate(&mut self, rate: RadPerSec) -> Result<(), Box<dyn Error>> {
+        self.tracking_rate = rate;
+        if self.tracking {
+            self.set_motion(Axis::Primary, rate)
+        } else {
+            Ok(())
+        }
     }
 
     fn guide(&mut self, axis1_speed: RadPerSec, axis2_speed: RadPerSec) -> Result<(), Box<dyn Error>> {
         if !self.tracking { return Err("cannot guide when tracking disabled".into()); }
 
-        self.set_motion(Axis::Primary, axis1_speed + if self.tracking { SIDEREAL_RATE } else { RadPerSec(0.0) })?;
+        self.set_motion(Axis::Primary, axis1_speed + if self.tracking { self.tracking_rate } else { RadPerSec(0.0) })?;
         self.set_motion(Axis::Secondary, axis2_speed)?;
 
         Ok(())
@@ -279,7 +320,7 @@ impl Mount for SkyWatcher {
         };
 
         match axis {
-            Axis::Primary => self.set_motion(axis, speed + if self.tracking { SIDEREAL_RATE } else { RadPerSec(0.0) })?,
+            Axis::Primary => self.set_motion(axis, speed + if self.tracking { self.tracking_rate } else { RadPerSec(0.0) })?,
             Axis::Secondary => self.set_motion(axis, speed)?
         }
 
@@ -290,10 +331,35 @@ impl Mount for SkyWatcher {
         speed <= MAX_SPEED
     }
 
+    fn max_variable_slewing_speed(&self) -> Option<RadPerSec> {
+        // crossing the threshold requires stopping the axis first (see `set_motion`)
+        Some(LOW_SPEED_THRESHOLD)
+    }
+
     fn stop(&mut self) -> Result<(), Box<dyn Error>> {
         self.stop_motion(Axis::Primary)?;
         self.stop_motion(Axis::Secondary)
     }
+
+    fn status(&mut self) -> Result<Option<MountStatus>, Box<dyn Error>> {
+        let (primary_running, primary_blocked, primary_init) = self.axis_status(Axis::Primary)?;
+        let (secondary_running, secondary_blocked, secondary_init) = self.axis_status(Axis::Secondary)?;
+
+        let tracking = self.tracking && primary_running;
+        let primary_expected_speed = if self.tracking { self.tracking_rate } else { RadPerSec(0.0) };
+        let slewing = secondary_running
+            || primary_running && self.current_slewing_speed[Axis::Primary.as_index()] != primary_expected_speed;
+
+        let error = if primary_blocked || secondary_blocked {
+            Some("axis blocked".to_string())
+        } else if !primary_init || !secondary_init {
+            Some("motor controller not initialized".to_string())
+        } else {
+            None
+        };
+
+        Ok(Some(MountStatus{ tracking: Some(tracking), slewing: Some(slewing), error }))
+    }
 }
 
 impl Drop for SkyWatcher {
@@ -317,6 +383,29 @@ fn skywatcher_hex_str_to_u32(s: &[u8]) -> Result<u32, Box<dyn Error>> {
     Ok (result)
 }
 
+/// Formats the (decoded) reply to `Opcode::GetMotorBoardVersion`.
+fn motor_board_version_to_string(version: u32) -> String {
+    let major = version & 0xFF;
+    let minor = (version >> 8) & 0xFF;
+    let mount_code = (version >> 16) & 0xFF;
+
+    let model = match mount_code {
+        0x00 => "EQ6".to_string(),
+        0x01 => "HEQ5".to_string(),
+        0x02 => "EQ5".to_string(),
+        0x03 => "EQ3".to_string(),
+        0x04 => "EQ8".to_string(),
+        0x05 => "AZ-EQ6".to_string(),
+        0x06 => "AZ-EQ5".to_string(),
+        0x80..=0x8F => "AZ GoTo".to_string(),
+        0x90..=0x9F => "Dobsonian".to_string(),
+        0xA5 => "AZ-GTi".to_string(),
+        _ => format!("mount code {:02X}h", mount_code)
+    };
+
+    format!("{}, firmware {}.{:02}", model, major, minor)
+}
+
 fn u32_to_skywatcher_hex_str(i: u32) -> String {
     format!("{:02X}{:02X}{:02X}", i & 0xFF, (i >> 8) & 0xFF, (i >> 16) & 0xFF)
 }
@@ -382,6 +471,14 @@ mod tests {
         assert!(skywatcher_hex_str_to_u32(b"12%6").is_err());
     }
 
+    #[test]
+    fn given_motor_board_version_format() {
+        assert_eq!(
+            "HEQ5, firmware 3.39",
+            motor_board_version_to_string(skywatcher_hex_str_to_u32(b"032701").unwrap())
+        );
+    }
+
     #[test]
     fn given_u32_format() {
         assert_eq!("CDAB12", u32_to_skywatcher_hex_str(0x12ABCD));
@@ -13,6 +13,7 @@
 //! Tested with AM3 and AM5
 //!
 
+use crate::devices::SerialSettings;
 use crate::mount::{Axis, Mount, SlewSpeed, RadPerSec, SIDEREAL_RATE};
 use std::error::Error;
 use std::sync::atomic::Ordering;
@@ -71,13 +72,13 @@ fn choose_slewing_speed(requested: &SlewSpeed) -> Option<&'static SupportedSlewi
 
 impl ZWO {
     #[must_use]
-    pub fn new(device: &str) -> Result<ZWO, Box<dyn Error>> {
-        let mut serial_port = serialport::new(device, 9600)
+    pub fn new(device: &str, serial_settings: SerialSettings) -> Result<ZWO, Box<dyn Error>> {
+        let mut serial_port = serialport::new(device, serial_settings.baud_rate)
             .data_bits(serialport::DataBits::Eight)
             .flow_control(serialport::FlowControl::None)
             .parity(serialport::Parity::None)
             .stop_bits(serialport::StopBits::One)
-            .timeout(std::time::Duration::from_millis(50))
+            .timeout(serial_settings.timeout)
             .open()?;
 
 
@@ -0,0 +1,619 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Recording output: uncompressed AVI video.
+//!
+//! 8-bit mono (and raw color) frames are stored as palettized 8-bit DIBs, RGB8 frames as 24-bit BGR DIBs.
+//! 16-bit frames are stored uncompressed with the FourCCs `Y16 ` (mono, little-endian) and `b48r` (RGB,
+//! big-endian), as there is no standard 16-bit-per-channel DIB.
+//!
+//! Recordings exceeding `MAX_RIFF_SIZE` are continued in `AVIX` RIFF lists (the OpenDML extension); the legacy
+//! `idx1` index covers only the frames in the first RIFF list.
+//!
+
+use crate::output::OutputWriter;
+use ga_image::{ImageView, PixelFormat};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+
+/// Maximum size of a single RIFF list; keeps the first one readable by players without OpenDML support.
+const MAX_RIFF_SIZE: u64 = 1 << 30;
+
+/// Maximum number of RIFF lists (and so standard indexes) referenced by the super index.
+const MAX_NUM_SEGMENTS: usize = 256;
+
+/// Frame interval stored if it cannot be determined from timestamps (i.e., for a single frame).
+const DEFAULT_FRAME_INTERVAL_US: u32 = 40_000;
+
+const AVIF_HASINDEX: u32 = 0x10;
+const AVIIF_KEYFRAME: u32 = 0x10;
+const AVI_INDEX_OF_INDEXES: u8 = 0x00;
+const AVI_INDEX_OF_CHUNKS: u8 = 0x01;
+
+const FRAME_CHUNK_ID: &[u8; 4] = b"00db";
+
+/// Size of the `dmlh` chunk's contents.
+const DMLH_SIZE: usize = 248;
+
+/// Size of the `avih` and `strh` chunks' contents.
+const AVI_HEADER_SIZE: usize = 56;
+
+const SUPER_INDEX_HEADER_SIZE: usize = 24;
+const SUPER_INDEX_ENTRY_SIZE: usize = 16;
+const STD_INDEX_HEADER_SIZE: usize = 24;
+const STD_INDEX_ENTRY_SIZE: usize = 8;
+const IDX1_ENTRY_SIZE: usize = 16;
+
+/// How frames are stored in the file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum AviFrameLayout {
+    /// Palettized grayscale, bottom-up rows padded to 4 bytes.
+    Mono8,
+    /// BGR, bottom-up rows padded to 4 bytes.
+    Bgr24,
+    /// FourCC `Y16 `: little-endian, top-down rows.
+    Mono16,
+    /// FourCC `b48r`: big-endian RGB, top-down rows.
+    Rgb48
+}
+
+impl AviFrameLayout {
+    fn from_pix_fmt(pix_fmt: PixelFormat) -> Result<AviFrameLayout, String> {
+        match pix_fmt {
+            PixelFormat::Mono8
+            | PixelFormat::CfaRGGB8 | PixelFormat::CfaGRBG8 | PixelFormat::CfaGBRG8 | PixelFormat::CfaBGGR8
+                => Ok(AviFrameLayout::Mono8),
+
+            PixelFormat::RGB8 => Ok(AviFrameLayout::Bgr24),
+
+            PixelFormat::Mono16
+            | PixelFormat::CfaRGGB16 | PixelFormat::CfaGRBG16 | PixelFormat::CfaGBRG16 | PixelFormat::CfaBGGR16
+                => Ok(AviFrameLayout::Mono16),
+
+            PixelFormat::RGB16 => Ok(AviFrameLayout::Rgb48),
+
+            other => Err(format!("recording {:?} as AVI video not supported", other))
+        }
+    }
+
+    pub(crate) fn bits_per_pixel(&self) -> u16 {
+        match self {
+            AviFrameLayout::Mono8 => 8,
+            AviFrameLayout::Bgr24 => 24,
+            AviFrameLayout::Mono16 => 16,
+            AviFrameLayout::Rgb48 => 48
+        }
+    }
+
+    /// Value of `biCompression`.
+    pub(crate) fn compression(&self) -> [u8; 4] {
+        match self {
+            AviFrameLayout::Mono8 | AviFrameLayout::Bgr24 => [0; 4], // BI_RGB
+            AviFrameLayout::Mono16 => *b"Y16 ",
+            AviFrameLayout::Rgb48 => *b"b48r"
+        }
+    }
+
+    pub(crate) fn is_bottom_up(&self) -> bool {
+        match self {
+            AviFrameLayout::Mono8 | AviFrameLayout::Bgr24 => true,
+            AviFrameLayout::Mono16 | AviFrameLayout::Rgb48 => false
+        }
+    }
+
+    /// Returns length (in bytes) of a stored row of pixels.
+    pub(crate) fn stride(&self, width: u32) -> usize {
+        let len = width as usize * self.bits_per_pixel() as usize / 8;
+        if self.is_bottom_up() { (len + 3) / 4 * 4 } else { len }
+    }
+}
+
+/// Positions (in the file) of header fields updated when recording ends.
+#[derive(Debug)]
+struct HeaderPositions {
+    avih: u64,
+    strh: u64,
+    indx: u64,
+    dmlh: u64
+}
+
+/// A RIFF list containing frames (`AVI ` for the first one, `AVIX` for subsequent ones).
+#[derive(Debug)]
+struct RiffSegment {
+    /// Position of the RIFF list.
+    start: u64,
+    /// Position of the `movi` list type (start of the `movi` contents).
+    movi: u64,
+    /// Positions of frame chunks.
+    frames: Vec<u64>
+}
+
+/// Super index entry.
+#[derive(Debug)]
+struct SegmentIndex {
+    /// Position of the segment's `ix00` chunk.
+    position: u64,
+    /// Size of the `ix00` chunk (incl. its header).
+    size: u32,
+    num_frames: u32
+}
+
+#[derive(Debug)]
+pub struct AviVideo {
+    writer: BufWriter<std::fs::File>,
+    /// Path of the file written (used for verification).
+    path: std::path::PathBuf,
+    /// Frame width, height, pixel format.
+    frame_format: Option<(u32, u32, PixelFormat)>,
+    layout: Option<AviFrameLayout>,
+    header: Option<HeaderPositions>,
+    /// Position at which the next chunk will be written.
+    position: u64,
+    max_riff_size: u64,
+    segment: Option<RiffSegment>,
+    /// Indexes of finished RIFF lists.
+    segments: Vec<SegmentIndex>,
+    frame_count: u32,
+    first_timestamp: Option<std::time::SystemTime>,
+    last_timestamp: Option<std::time::SystemTime>,
+    /// Frame conversion buffer.
+    buffer: Vec<u8>
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) { out.extend_from_slice(&value.to_le_bytes()); }
+
+fn put_u32(out: &mut Vec<u8>, value: u32) { out.extend_from_slice(&value.to_le_bytes()); }
+
+fn put_u64(out: &mut Vec<u8>, value: u64) { out.extend_from_slice(&value.to_le_bytes()); }
+
+/// Writes "RIFF"/"LIST" and a size placeholder; returns position of the size field in `out`.
+fn begin_list(out: &mut Vec<u8>, fourcc: &[u8; 4], list_type: &[u8; 4]) -> usize {
+    out.extend_from_slice(fourcc);
+    let size_pos = out.len();
+    put_u32(out, 0);
+    out.extend_from_slice(list_type);
+    size_pos
+}
+
+fn end_list(out: &mut Vec<u8>, size_pos: usize) {
+    let size = (out.len() - size_pos - 4) as u32;
+    out[size_pos..size_pos + 4].copy_from_slice(&size.to_le_bytes());
+}
+
+fn put_chunk(out: &mut Vec<u8>, fourcc: &[u8; 4], contents: &[u8]) {
+    out.extend_from_slice(fourcc);
+    put_u32(out, contents.len() as u32);
+    out.extend_from_slice(contents);
+    if contents.len() % 2 != 0 { out.push(0); }
+}
+
+impl AviVideo {
+    pub fn new(file: std::fs::File, path: &std::path::Path) -> AviVideo {
+        AviVideo{
+            writer: BufWriter::new(file),
+            path: path.into(),
+            frame_format: None,
+            layout: None,
+            header: None,
+            position: 0,
+            max_riff_size: MAX_RIFF_SIZE,
+            segment: None,
+            segments: vec![],
+            frame_count: 0,
+            first_timestamp: None,
+            last_timestamp: None,
+            buffer: vec![]
+        }
+    }
+
+    fn frame_size(&self) -> u32 {
+        let (width, height, _) = self.frame_format.unwrap();
+        (self.layout.unwrap().stride(width) * height as usize) as u32
+    }
+
+    fn frame_interval_us(&self) -> u32 {
+        match (self.first_timestamp, self.last_timestamp) {
+            (Some(first), Some(last)) if self.frame_count > 1 => match last.duration_since(first) {
+                Ok(duration) => ((duration.as_micros() / (self.frame_count - 1) as u128) as u32).max(1),
+                Err(_) => DEFAULT_FRAME_INTERVAL_US
+            },
+            _ => DEFAULT_FRAME_INTERVAL_US
+        }
+    }
+
+    fn main_header(&self) -> Vec<u8> {
+        let (width, height, _) = self.frame_format.unwrap();
+        let frame_interval_us = self.frame_interval_us();
+        let frames_in_first_riff = match self.segments.first() {
+            Some(segment) => segment.num_frames,
+            None => self.frame_count
+        };
+
+        let mut out = Vec::with_capacity(AVI_HEADER_SIZE);
+        put_u32(&mut out, frame_interval_us);
+        put_u32(&mut out, (self.frame_size() as u64 * 1_000_000 / frame_interval_us as u64).min(u32::MAX as u64) as u32);
+        put_u32(&mut out, 0); // padding granularity
+        put_u32(&mut out, AVIF_HASINDEX);
+        put_u32(&mut out, frames_in_first_riff);
+        put_u32(&mut out, 0); // initial frames
+        put_u32(&mut out, 1); // number of streams
+        put_u32(&mut out, self.frame_size() + 8);
+        put_u32(&mut out, width);
+        put_u32(&mut out, height);
+        out.extend_from_slice(&[0; 16]); // reserved
+        out
+    }
+
+    fn stream_header(&self) -> Vec<u8> {
+        let (width, height, _) = self.frame_format.unwrap();
+
+        let mut out = Vec::with_capacity(AVI_HEADER_SIZE);
+        out.extend_from_slice(b"vids");
+        out.extend_from_slice(&[0; 4]); // handler
+        put_u32(&mut out, 0); // flags
+        put_u16(&mut out, 0); // priority
+        put_u16(&mut out, 0); // language
+        put_u32(&mut out, 0); // initial frames
+        put_u32(&mut out, self.frame_interval_us()); // scale
+        put_u32(&mut out, 1_000_000); // rate
+        put_u32(&mut out, 0); // start
+        put_u32(&mut out, self.frame_count); // length
+        put_u32(&mut out, self.frame_size() + 8);
+        put_u32(&mut out, u32::MAX); // quality (default)
+        put_u32(&mut out, 0); // sample size
+        for value in [0, 0, width as u16, height as u16] { put_u16(&mut out, value); } // frame rectangle
+        out
+    }
+
+    fn stream_format(&self) -> Vec<u8> {
+        let (width, height, _) = self.frame_format.unwrap();
+        let layout = self.layout.unwrap();
+
+        let mut out = vec![];
+        put_u32(&mut out, 40); // size of BITMAPINFOHEADER
+        put_u32(&mut out, width);
+        put_u32(&mut out, height);
+        put_u16(&mut out, 1); // planes
+        put_u16(&mut out, layout.bits_per_pixel());
+        out.extend_from_slice(&layout.compression());
+        put_u32(&mut out, self.frame_size());
+        put_u32(&mut out, 0); // horizontal resolution
+        put_u32(&mut out, 0); // vertical resolution
+        let num_colors = if layout == AviFrameLayout::Mono8 { 256 } else { 0 };
+        put_u32(&mut out, num_colors);
+        put_u32(&mut out, 0); // important colors
+        for i in 0..num_colors {
+            out.extend_from_slice(&[i as u8, i as u8, i as u8, 0]);
+        }
+        out
+    }
+
+    fn super_index(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(SUPER_INDEX_HEADER_SIZE + MAX_NUM_SEGMENTS * SUPER_INDEX_ENTRY_SIZE);
+        put_u16(&mut out, (SUPER_INDEX_ENTRY_SIZE / 4) as u16);
+        out.push(0); // index subtype
+        out.push(AVI_INDEX_OF_INDEXES);
+        put_u32(&mut out, self.segments.len() as u32);
+        out.extend_from_slice(FRAME_CHUNK_ID);
+        out.extend_from_slice(&[0; 12]); // reserved
+        for segment in &self.segments {
+            put_u64(&mut out, segment.position);
+            put_u32(&mut out, segment.size);
+            put_u32(&mut out, segment.num_frames);
+        }
+        out.resize(SUPER_INDEX_HEADER_SIZE + MAX_NUM_SEGMENTS * SUPER_INDEX_ENTRY_SIZE, 0);
+        out
+    }
+
+    fn odml_header(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(DMLH_SIZE);
+        put_u32(&mut out, self.frame_count);
+        out.resize(DMLH_SIZE, 0);
+        out
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.writer.write_all(bytes).map_err(|err| format!("I/O error: {}", err))?;
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Overwrites `bytes` at `position`, then returns to the end of file.
+    fn patch(&mut self, position: u64, bytes: &[u8]) -> Result<(), String> {
+        self.writer.seek(SeekFrom::Start(position)).map_err(|err| format!("I/O error: {}", err))?;
+        self.writer.write_all(bytes).map_err(|err| format!("I/O error: {}", err))?;
+        self.writer.seek(SeekFrom::Start(self.position)).map_err(|err| format!("I/O error: {}", err))?;
+        Ok(())
+    }
+
+    fn write_file_header(&mut self) -> Result<(), String> {
+        let mut out = vec![];
+
+        // sizes of the RIFF list and the `movi` list are filled in by `end_segment`
+        begin_list(&mut out, b"RIFF", b"AVI ");
+        let hdrl_size_pos = begin_list(&mut out, b"LIST", b"hdrl");
+
+        let avih = out.len() as u64 + 8;
+        put_chunk(&mut out, b"avih", &self.main_header());
+
+        let strl_size_pos = begin_list(&mut out, b"LIST", b"strl");
+        let strh = out.len() as u64 + 8;
+        put_chunk(&mut out, b"strh", &self.stream_header());
+        put_chunk(&mut out, b"strf", &self.stream_format());
+        let indx = out.len() as u64 + 8;
+        put_chunk(&mut out, b"indx", &self.super_index());
+        end_list(&mut out, strl_size_pos);
+
+        let odml_size_pos = begin_list(&mut out, b"LIST", b"odml");
+        let dmlh = out.len() as u64 + 8;
+        put_chunk(&mut out, b"dmlh", &self.odml_header());
+        end_list(&mut out, odml_size_pos);
+
+        end_list(&mut out, hdrl_size_pos);
+
+        self.header = Some(HeaderPositions{ avih, strh, indx, dmlh });
+
+        begin_list(&mut out, b"LIST", b"movi");
+        self.segment = Some(RiffSegment{ start: 0, movi: out.len() as u64 - 4, frames: vec![] });
+
+        self.write_bytes(&out)
+    }
+
+    fn begin_segment(&mut self) -> Result<(), String> {
+        if self.segments.len() == MAX_NUM_SEGMENTS {
+            return Err(format!("maximum recording size ({} GiB) exceeded", (MAX_NUM_SEGMENTS as u64 * self.max_riff_size) >> 30));
+        }
+
+        let mut out = vec![];
+        begin_list(&mut out, b"RIFF", b"AVIX");
+        begin_list(&mut out, b"LIST", b"movi");
+        let start = self.position;
+        self.segment = Some(RiffSegment{ start, movi: start + out.len() as u64 - 4, frames: vec![] });
+
+        self.write_bytes(&out)
+    }
+
+    /// Returns the size of indexes written at the end of the current RIFF list if it contains `num_frames` frames.
+    fn segment_indexes_size(&self, num_frames: usize) -> u64 {
+        let std_index = 8 + STD_INDEX_HEADER_SIZE + num_frames * STD_INDEX_ENTRY_SIZE;
+        let idx1 = if self.segments.is_empty() { 8 + num_frames * IDX1_ENTRY_SIZE } else { 0 };
+        (std_index + idx1) as u64
+    }
+
+    /// Writes indexes of the current RIFF list and updates its size.
+    fn end_segment(&mut self) -> Result<(), String> {
+        let segment = self.segment.take().unwrap();
+        let frame_size = self.frame_size();
+
+        let mut std_index = vec![];
+        put_u16(&mut std_index, (STD_INDEX_ENTRY_SIZE / 4) as u16);
+        std_index.push(0); // index subtype
+        std_index.push(AVI_INDEX_OF_CHUNKS);
+        put_u32(&mut std_index, segment.frames.len() as u32);
+        std_index.extend_from_slice(FRAME_CHUNK_ID);
+        put_u64(&mut std_index, segment.movi);
+        put_u32(&mut std_index, 0); // reserved
+        for frame in &segment.frames {
+            // offset of frame data (not the chunk)
+            put_u32(&mut std_index, (frame + 8 - segment.movi) as u32);
+            put_u32(&mut std_index, frame_size);
+        }
+
+        let mut out = vec![];
+        put_chunk(&mut out, b"ix00", &std_index);
+        self.segments.push(SegmentIndex{
+            position: self.position,
+            size: out.len() as u32,
+            num_frames: segment.frames.len() as u32
+        });
+        self.write_bytes(&out)?;
+
+        let movi_size = (self.position - segment.movi) as u32;
+        self.patch(segment.movi - 4, &movi_size.to_le_bytes())?;
+
+        if self.segments.len() == 1 {
+            let mut idx1 = vec![];
+            for frame in &segment.frames {
+                idx1.extend_from_slice(FRAME_CHUNK_ID);
+                put_u32(&mut idx1, AVIIF_KEYFRAME);
+                put_u32(&mut idx1, (frame - segment.movi) as u32);
+                put_u32(&mut idx1, frame_size);
+            }
+            let mut out = vec![];
+            put_chunk(&mut out, b"idx1", &idx1);
+            self.write_bytes(&out)?;
+        }
+
+        let riff_size = (self.position - segment.start - 8) as u32;
+        self.patch(segment.start + 4, &riff_size.to_le_bytes())
+    }
+
+    /// Converts `image` to the stored frame layout in `self.buffer`.
+    fn convert_frame(&mut self, image: &ImageView) {
+        let layout = self.layout.unwrap();
+        let stride = layout.stride(image.width());
+        let height = image.height();
+
+        self.buffer.clear();
+        self.buffer.resize(stride * height as usize, 0);
+
+        for y in 0..height {
+            let dest_y = if layout.is_bottom_up() { height - 1 - y } else { y };
+            let dest = &mut self.buffer[dest_y as usize * stride..(dest_y as usize + 1) * stride];
+            match layout {
+                AviFrameLayout::Mono8 => {
+                    let line = image.line_raw(y);
+                    dest[..line.len()].copy_from_slice(line);
+                },
+
+                AviFrameLayout::Bgr24 => for (dest, src) in dest.chunks_exact_mut(3).zip(image.line::<u8>(y).chunks_exact(3)) {
+                    dest.copy_from_slice(&[src[2], src[1], src[0]]);
+                },
+
+                AviFrameLayout::Mono16 => for (dest, src) in dest.chunks_exact_mut(2).zip(image.line::<u16>(y).iter()) {
+                    dest.copy_from_slice(&src.to_le_bytes());
+                },
+
+                AviFrameLayout::Rgb48 => for (dest, src) in dest.chunks_exact_mut(2).zip(image.line::<u16>(y).iter()) {
+                    dest.copy_from_slice(&src.to_be_bytes());
+                }
+            }
+        }
+    }
+}
+
+impl OutputWriter for AviVideo {
+    fn write(&mut self, image: &ImageView, timestamp: std::time::SystemTime) -> Result<(), String> {
+        match self.frame_format {
+            None => {
+                self.layout = Some(AviFrameLayout::from_pix_fmt(image.pixel_format())?);
+                self.frame_format = Some((image.width(), image.height(), image.pixel_format()));
+                self.write_file_header()?;
+            },
+
+            Some(f) => if image.width() != f.0 ||
+                          image.height() != f.1 ||
+                          image.pixel_format() != f.2 {
+                return Err(format!("unexpected frame: {}x{}, {:?} (expected {}x{}, {:?})",
+                    image.width(), image.height(), image.pixel_format(),
+                    f.0, f.1, f.2)
+                );
+            }
+        }
+
+        let chunk_size = 8 + self.frame_size() as u64;
+        let segment = self.segment.as_ref().unwrap();
+        let num_frames = segment.frames.len();
+        if num_frames > 0
+            && self.position + chunk_size + self.segment_indexes_size(num_frames + 1) - segment.start > self.max_riff_size {
+
+            self.end_segment()?;
+            self.begin_segment()?;
+        }
+
+        self.convert_frame(image);
+        let mut chunk_header = vec![];
+        chunk_header.extend_from_slice(FRAME_CHUNK_ID);
+        put_u32(&mut chunk_header, self.buffer.len() as u32);
+
+        let position = self.position;
+        self.write_bytes(&chunk_header)?;
+        let buffer = std::mem::take(&mut self.buffer);
+        let result = self.write_bytes(&buffer);
+        self.buffer = buffer;
+        result?;
+
+        if let Err(err) = self.writer.flush() {
+            return Err(format!("error writing frame: {}", err));
+        }
+
+        self.segment.as_mut().unwrap().frames.push(position);
+        self.frame_count += 1;
+        if self.first_timestamp.is_none() { self.first_timestamp = Some(timestamp); }
+        self.last_timestamp = Some(timestamp);
+
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<(), String> {
+        if self.segment.is_none() { return Ok(()); } // nothing has been written
+
+        self.end_segment()?;
+
+        let header = self.header.as_ref().unwrap();
+        let (avih, strh, indx, dmlh) = (header.avih, header.strh, header.indx, header.dmlh);
+        self.patch(avih, &self.main_header())?;
+        self.patch(strh, &self.stream_header())?;
+        self.patch(indx, &self.super_index())?;
+        self.patch(dmlh, &self.odml_header())?;
+
+        self.writer.flush().map_err(|err| format!("I/O error: {}", err))
+    }
+
+    fn verify(&mut self) -> Result<(), String> {
+        if self.frame_format.is_none() { return Ok(()); } // nothing has been written
+        self.writer.flush().map_err(|err| format!("I/O error: {}", err))?;
+
+        let actual_size = std::fs::metadata(&self.path).map_err(|err| format!("cannot access file: {}", err))?.len();
+        if actual_size != self.position {
+            return Err(format!("file size is {} bytes, expected {}", actual_size, self.position));
+        }
+
+        let mut video = crate::input::open_avi_video(&self.path)
+            .map_err(|err| format!("cannot open file: {}", err))?;
+        if video.num_images() != self.frame_count as usize {
+            return Err(format!("file contains {} frames, expected {}", video.num_images(), self.frame_count));
+        }
+        for index in [0, video.num_images() - 1] {
+            video.get_image(index).map_err(|err| format!("cannot read frame {}: {}", index, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the number of RIFF lists written.
+    fn check_round_trip(path: &std::path::Path, frames: &[ga_image::Image], max_riff_size: u64) -> usize {
+        let mut video = AviVideo::new(std::fs::File::create(path).unwrap(), path);
+        video.max_riff_size = max_riff_size;
+        for frame in frames {
+            video.write(&frame.view(), std::time::SystemTime::now()).unwrap();
+        }
+        video.finalize().unwrap();
+        video.verify().unwrap();
+        let num_segments = video.segments.len();
+        drop(video);
+
+        let mut recorded = crate::input::open_avi_video(path).unwrap();
+        assert_eq!(frames.len(), recorded.num_images());
+        for (index, frame) in frames.iter().enumerate() {
+            let image = recorded.get_image(index).unwrap();
+            assert_eq!(frame.pixel_format(), image.pixel_format());
+            assert_eq!((frame.width(), frame.height()), (image.width(), image.height()));
+            for y in 0..frame.height() {
+                assert_eq!(frame.view().line_raw(y), image.view().line_raw(y));
+            }
+        }
+
+        num_segments
+    }
+
+    #[test]
+    fn recorded_test_pattern_is_read_back_unchanged() {
+        use crate::camera::drivers::simulator;
+
+        let path = std::env::temp_dir().join(format!("vidoxide_test_pattern_{}.avi", std::process::id()));
+        let mono8 = simulator::test_pattern_rgb8().convert_pix_fmt(PixelFormat::Mono8, None);
+        let rgb16 = simulator::test_pattern_rgb8().convert_pix_fmt(PixelFormat::RGB16, None);
+        for pattern in [simulator::test_pattern_mono16(), simulator::test_pattern_rgb8(), mono8, rgb16] {
+            assert_eq!(1, check_round_trip(&path, &[pattern], MAX_RIFF_SIZE));
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn frames_exceeding_riff_size_limit_are_continued_in_avix_lists() {
+        use crate::camera::drivers::simulator;
+
+        let path = std::env::temp_dir().join(format!("vidoxide_test_odml_{}.avi", std::process::id()));
+        let frames: Vec<ga_image::Image> = (0..5).map(|i| {
+            let mut frame = simulator::test_pattern_rgb8();
+            frame.line_mut::<u8>(0)[0] = i;
+            frame
+        }).collect();
+        // room for 2 frames (and the headers) per RIFF list
+        let chunk_size = 8 + AviFrameLayout::Bgr24.stride(frames[0].width()) as u64 * frames[0].height() as u64;
+        let max_riff_size = 2 * chunk_size + 8192;
+        assert_eq!(3, check_round_trip(&path, &frames, max_riff_size));
+        let _ = std::fs::remove_file(&path);
+    }
+}
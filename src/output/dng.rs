@@ -0,0 +1,147 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Recording output: DNG file sequence.
+//!
+
+use crate::output::{OutputWriter, tiff, tiff::TagValue};
+use ga_image::{ImageView, PixelFormat};
+use std::path::{Path, PathBuf};
+
+mod tags {
+    pub const NEW_SUBFILE_TYPE: u16 = 254;
+    pub const MODEL: u16 = 272;
+    pub const ORIENTATION: u16 = 274;
+    pub const SOFTWARE: u16 = 305;
+    pub const CFA_REPEAT_PATTERN_DIM: u16 = 33421;
+    pub const CFA_PATTERN: u16 = 33422;
+    pub const DNG_VERSION: u16 = 50706;
+    pub const DNG_BACKWARD_VERSION: u16 = 50707;
+    pub const UNIQUE_CAMERA_MODEL: u16 = 50708;
+    pub const CFA_PLANE_COLOR: u16 = 50710;
+    pub const CFA_LAYOUT: u16 = 50711;
+    pub const BLACK_LEVEL: u16 = 50714;
+    pub const WHITE_LEVEL: u16 = 50717;
+    pub const COLOR_MATRIX_1: u16 = 50721;
+    pub const AS_SHOT_NEUTRAL: u16 = 50728;
+    pub const CALIBRATION_ILLUMINANT_1: u16 = 50778;
+}
+
+mod photometric {
+    pub const CFA: u16 = 32803;
+    pub const LINEAR_RAW: u16 = 34892;
+}
+
+/// XYZ-to-camera matrix (×10000); the camera is assumed to have sRGB primaries.
+const COLOR_MATRIX: [i32; 9] = [
+    32406, -15372, -4986,
+    -9689,  18758,   415,
+      557,  -2040, 10570
+];
+
+const COLOR_MATRIX_DENOMINATOR: i32 = 10000;
+
+const D65_ILLUMINANT: u16 = 21;
+
+/// Returns the CFA pattern (0 = red, 1 = green, 2 = blue), or `None` if `pix_fmt` is not a CFA format.
+fn cfa_pattern(pix_fmt: PixelFormat) -> Option<[u8; 4]> {
+    match pix_fmt {
+        PixelFormat::CfaRGGB8 | PixelFormat::CfaRGGB16 => Some([0, 1, 1, 2]),
+        PixelFormat::CfaGRBG8 | PixelFormat::CfaGRBG16 => Some([1, 0, 2, 1]),
+        PixelFormat::CfaGBRG8 | PixelFormat::CfaGBRG16 => Some([1, 2, 0, 1]),
+        PixelFormat::CfaBGGR8 | PixelFormat::CfaBGGR16 => Some([2, 1, 1, 0]),
+        _ => None
+    }
+}
+
+/// Writes each frame as a DNG file. Supports raw color (CFA) and mono 8- and 16-bit images.
+///
+/// Black level is written as 0 and white level as the maximum value of the pixel format.
+#[derive(Debug)]
+pub struct DngSequence {
+    output_dir: PathBuf,
+    file_name_prefix: String,
+    counter: usize,
+    camera_model: String
+}
+
+impl DngSequence {
+    pub fn new(output_dir: &Path, file_name_prefix: &str, camera_model: &str) -> DngSequence {
+        DngSequence{
+            output_dir: output_dir.into(),
+            file_name_prefix: file_name_prefix.to_string(),
+            counter: 0,
+            camera_model: camera_model.to_string()
+        }
+    }
+
+    fn dng_tags(&self, pix_fmt: PixelFormat) -> Result<Vec<(u16, TagValue)>, String> {
+        let bits_per_sample = match pix_fmt {
+            PixelFormat::Mono8 | PixelFormat::Mono16 => pix_fmt.bytes_per_channel() * 8,
+            _ if cfa_pattern(pix_fmt).is_some() => pix_fmt.bytes_per_channel() * 8,
+            other => return Err(format!("saving {:?} as DNG not supported", other))
+        };
+
+        let mut dng_tags = vec![
+            (tags::NEW_SUBFILE_TYPE, TagValue::Long(vec![0])),
+            (tags::MODEL, TagValue::Ascii(self.camera_model.clone())),
+            (tags::ORIENTATION, TagValue::Short(vec![1])),
+            (tags::SOFTWARE, TagValue::Ascii("Vidoxide".to_string())),
+            (tags::DNG_VERSION, TagValue::Byte(vec![1, 4, 0, 0])),
+            (tags::DNG_BACKWARD_VERSION, TagValue::Byte(vec![1, 1, 0, 0])),
+            (tags::UNIQUE_CAMERA_MODEL, TagValue::Ascii(self.camera_model.clone())),
+            (tags::BLACK_LEVEL, TagValue::Long(vec![0])),
+            (tags::WHITE_LEVEL, TagValue::Long(vec![((1u64 << bits_per_sample) - 1) as u32]))
+        ];
+
+        if let Some(pattern) = cfa_pattern(pix_fmt) {
+            dng_tags.extend(vec![
+                (tiff::tags::PHOTOMETRIC_INTERPRETATION, TagValue::Short(vec![photometric::CFA])),
+                (tags::CFA_REPEAT_PATTERN_DIM, TagValue::Short(vec![2, 2])),
+                (tags::CFA_PATTERN, TagValue::Byte(pattern.to_vec())),
+                (tags::CFA_PLANE_COLOR, TagValue::Byte(vec![0, 1, 2])),
+                (tags::CFA_LAYOUT, TagValue::Short(vec![1])),
+                (tags::COLOR_MATRIX_1, TagValue::SRational(
+                    COLOR_MATRIX.iter().map(|value| (*value, COLOR_MATRIX_DENOMINATOR)).collect()
+                )),
+                (tags::AS_SHOT_NEUTRAL, TagValue::Rational(vec![(1, 1); 3])),
+                (tags::CALIBRATION_ILLUMINANT_1, TagValue::Short(vec![D65_ILLUMINANT]))
+            ]);
+        } else {
+            dng_tags.push((tiff::tags::PHOTOMETRIC_INTERPRETATION, TagValue::Short(vec![photometric::LINEAR_RAW])));
+        }
+
+        Ok(dng_tags)
+    }
+}
+
+impl OutputWriter for DngSequence {
+    fn write(&mut self, image: &ImageView, _timestamp: std::time::SystemTime) -> Result<(), String> {
+        let dng_tags = self.dng_tags(image.pixel_format())?;
+
+        let path = self.output_dir.join(format!("{}_{:05}.dng", self.file_name_prefix, self.counter));
+        // DNG allows only lossless JPEG compression of integer data
+        tiff::save(&path, image, tiff::Compression::None, dng_tags)?;
+
+        self.counter += 1;
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn verify(&mut self) -> Result<(), String> {
+        // `ga_image` cannot load DNG files, so only their presence is checked
+        super::verify_file_sequence(
+            (0..self.counter).map(|i| self.output_dir.join(format!("{}_{:05}.dng", self.file_name_prefix, i))),
+            None
+        )
+    }
+}
@@ -10,7 +10,7 @@
 //! Recording output: image file sequence.
 //!
 
-use crate::output::OutputWriter;
+use crate::output::{OutputWriter, tiff};
 use ga_image::{FileType, ImageView};
 use std::path::{Path, PathBuf};
 
@@ -19,42 +19,63 @@ pub struct FileSequence {
     output_dir: PathBuf,
     file_name_prefix: String,
     counter: usize,
-    file_type: FileType
+    file_type: FileType,
+    /// Used if `file_type` is `FileType::Tiff`.
+    tiff_compression: tiff::Compression
 }
 
 impl FileSequence {
-    pub fn new(output_dir: &Path, file_name_prefix: &str, file_type: FileType) -> FileSequence {
+    fn file_path(&self, index: usize) -> PathBuf {
+        let file_ext = match self.file_type {
+            FileType::Bmp => "bmp",
+            FileType::Tiff => "tif",
+            _ => unreachable!()
+        };
+
+        Path::new(&self.output_dir).join(format!("{}_{:05}.{}", self.file_name_prefix, index, file_ext))
+    }
+
+    pub fn new(
+        output_dir: &Path,
+        file_name_prefix: &str,
+        file_type: FileType,
+        tiff_compression: tiff::Compression
+    ) -> FileSequence {
         FileSequence{
             output_dir: output_dir.into(),
             file_name_prefix: file_name_prefix.to_string(),
             counter: 0,
-            file_type
+            file_type,
+            tiff_compression
         }
     }
 }
 
 impl OutputWriter for FileSequence {
-    fn write(&mut self, image: &ImageView) -> Result<(), String> {
-        let file_ext = match self.file_type {
-            FileType::Bmp => "bmp",
-            FileType::Tiff => "tif",
-            _ => unreachable!()
-        };
-
-        let result = image.save(
-            &Path::new(&self.output_dir)
-                .join(format!("{}_{:05}.{}", self.file_name_prefix, self.counter, file_ext))
-                .to_str().unwrap().to_string(),
-            self.file_type
-        );
+    fn write(&mut self, image: &ImageView, _timestamp: std::time::SystemTime) -> Result<(), String> {
+        let path = self.file_path(self.counter);
 
-        match result {
-            Err(err) => Err(format!("{:?}", err)),
-            Ok(()) => { self.counter += 1; Ok(()) }
+        // `ga_image` does not support TIFF compression
+        if matches!(self.file_type, FileType::Tiff) && self.tiff_compression != tiff::Compression::None {
+            tiff::save(&path, image, self.tiff_compression, vec![])?;
+        } else {
+            image.save(&path.to_str().unwrap().to_string(), self.file_type).map_err(|err| format!("{:?}", err))?;
         }
+
+        self.counter += 1;
+        Ok(())
     }
 
     fn finalize(&mut self) -> Result<(), String> {
         Ok(())
     }
+
+    fn verify(&mut self) -> Result<(), String> {
+        // `ga_image` does not support TIFF compression, so compressed files are only checked for presence
+        let loadable = !matches!(self.file_type, FileType::Tiff) || self.tiff_compression == tiff::Compression::None;
+        super::verify_file_sequence(
+            (0..self.counter).map(|i| self.file_path(i)),
+            if loadable { Some(self.file_type) } else { None }
+        )
+    }
 }
@@ -10,17 +10,71 @@
 //! Recording output module.
 //!
 
+pub mod avi;
+pub mod dng;
 pub mod file_seq;
+pub mod quality_log;
 pub mod ser;
+pub mod tiff;
 
 use ga_image::ImageView;
+use std::path::PathBuf;
 
 pub trait OutputWriter: std::fmt::Debug + Send {
+    /// Writes `image`; `timestamp` is the frame's capture time.
     #[must_use]
-    fn write(&mut self, image: &ImageView) -> Result<(), String>;
+    fn write(&mut self, image: &ImageView, timestamp: std::time::SystemTime) -> Result<(), String>;
 
     #[must_use]
     fn finalize(&mut self) -> Result<(), String>;
+
+    /// Checks the finalized output (by re-opening it) for missing or unreadable frames; returns a description
+    /// of the discrepancy found.
+    #[must_use]
+    fn verify(&mut self) -> Result<(), String>;
+}
+
+/// Max. number of unreadable files listed by `verify_file_sequence`.
+const MAX_REPORTED_UNREADABLE_FILES: usize = 5;
+
+/// Checks that all files of a recorded image sequence exist and are not empty; if `file_type` is specified,
+/// also checks that they can be loaded.
+fn verify_file_sequence<I: Iterator<Item = PathBuf>>(
+    paths: I,
+    file_type: Option<ga_image::FileType>
+) -> Result<(), String> {
+    let mut unreadable = vec![];
+    let mut num_unreadable = 0;
+    for path in paths {
+        let error = match std::fs::metadata(&path) {
+            Ok(metadata) => if metadata.len() == 0 {
+                Some("empty".to_string())
+            } else if let Some(file_type) = file_type {
+                ga_image::Image::load(&path.to_string_lossy(), file_type).err().map(|e| format!("{:?}", e))
+            } else {
+                None
+            },
+            Err(e) => Some(e.to_string())
+        };
+
+        if let Some(error) = error {
+            num_unreadable += 1;
+            if unreadable.len() < MAX_REPORTED_UNREADABLE_FILES {
+                unreadable.push(format!("{} ({})", path.to_string_lossy(), error));
+            }
+        }
+    }
+
+    if num_unreadable == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} unreadable file(s):\n{}{}",
+            num_unreadable,
+            unreadable.join("\n"),
+            if num_unreadable > unreadable.len() { "\n..." } else { "" }
+        ))
+    }
 }
 
 #[derive(Debug, PartialEq, strum_macros::EnumIter)]
@@ -28,7 +82,8 @@ pub enum OutputFormat {
     SerVideo,
     AviVideo,
     BmpSequence,
-    TiffSequence
+    TiffSequence,
+    DngSequence
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -37,7 +92,8 @@ impl std::fmt::Display for OutputFormat {
             OutputFormat::SerVideo => "SER video",
             OutputFormat::AviVideo => "AVI video",
             OutputFormat::BmpSequence => "Image sequence (BMP)",
-            OutputFormat::TiffSequence => "Image sequence (TIFF)"
+            OutputFormat::TiffSequence => "Image sequence (TIFF)",
+            OutputFormat::DngSequence => "Image sequence (DNG)"
         })
     }
 }
@@ -48,7 +104,7 @@ impl OutputFormat {
             OutputFormat::BmpSequence => ga_image::FileType::Bmp,
             OutputFormat::TiffSequence => ga_image::FileType::Tiff,
 
-            _ => panic!("Not an image sequence: {:?}", self)
+            _ => panic!("Not an image sequence supported by ga_image: {:?}", self)
         }
     }
 
@@ -57,7 +113,19 @@ impl OutputFormat {
             OutputFormat::SerVideo => false,
             OutputFormat::AviVideo => false,
             OutputFormat::BmpSequence => true,
-            OutputFormat::TiffSequence => true
+            OutputFormat::TiffSequence => true,
+            OutputFormat::DngSequence => true
+        }
+    }
+
+    /// Name used for configuration keys.
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            OutputFormat::SerVideo => "SER",
+            OutputFormat::AviVideo => "AVI",
+            OutputFormat::BmpSequence => "BMP",
+            OutputFormat::TiffSequence => "TIFF",
+            OutputFormat::DngSequence => "DNG"
         }
     }
 }
@@ -0,0 +1,57 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Recording output: per-frame quality list.
+//!
+//! A CSV file with lines "<frame index>,<quality>" (after a header line); frame indices correspond
+//! to the frames of the recorded video or image sequence. See `quality::frame_quality`.
+//!
+
+use std::io::{BufWriter, Write};
+
+#[derive(Debug)]
+pub struct QualityLog {
+    writer: BufWriter<std::fs::File>,
+    num_frames: usize
+}
+
+impl QualityLog {
+    pub fn create(path: &std::path::Path) -> Result<QualityLog, std::io::Error> {
+        let mut writer = BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "frame,quality")?;
+        Ok(QualityLog{ writer, num_frames: 0 })
+    }
+
+    /// Adds the quality of the next recorded frame.
+    pub fn add(&mut self, quality: f64) -> Result<(), std::io::Error> {
+        write_entry(&mut self.writer, self.num_frames, quality)?;
+        self.num_frames += 1;
+        Ok(())
+    }
+
+    pub fn finalize(&mut self) -> Result<(), std::io::Error> {
+        self.writer.flush()
+    }
+}
+
+fn write_entry<W: Write>(writer: &mut W, frame_index: usize, quality: f64) -> Result<(), std::io::Error> {
+    writeln!(writer, "{},{:.6e}", frame_index, quality)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_contains_frame_index_and_quality() {
+        let mut output = vec![];
+        write_entry(&mut output, 12, 0.0123).unwrap();
+        assert_eq!("12,1.230000e-2\n", String::from_utf8(output).unwrap());
+    }
+}
@@ -16,7 +16,7 @@ use ga_image::utils;
 use ga_image;
 use std::io::{BufWriter, Seek, SeekFrom, Write};
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
 enum SerColorFormat {
     Mono      = 0,
     BayerRGGB = 8,
@@ -31,6 +31,67 @@ enum SerColorFormat {
     BGR       = 101
 }
 
+/// Color ID to write in the SER header.
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumIter)]
+pub enum ColorId {
+    /// Determined by the pixel format.
+    Automatic,
+    /// Raw color data is marked as mono (players will not debayer it).
+    Mono,
+    BayerRGGB,
+    BayerGRBG,
+    BayerGBRG,
+    BayerBGGR
+}
+
+impl std::fmt::Display for ColorId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ColorId::Automatic => "automatic",
+            ColorId::Mono => "mono (no debayering)",
+            ColorId::BayerRGGB => "raw color RGGB",
+            ColorId::BayerGRBG => "raw color GRBG",
+            ColorId::BayerGBRG => "raw color GBRG",
+            ColorId::BayerBGGR => "raw color BGGR"
+        })
+    }
+}
+
+/// Returns the SER color format of frames with pixel format `pix_fmt`. Unless `color_id` is `Automatic`,
+/// it overrides the format of single-channel (mono or raw color) data; RGB data is always marked as such.
+fn ser_color_format(pix_fmt: ga_image::PixelFormat, color_id: ColorId) -> SerColorFormat {
+    use ga_image::PixelFormat;
+
+    let automatic = match pix_fmt {
+        PixelFormat::Mono8 | PixelFormat::Mono16 => SerColorFormat::Mono,
+        PixelFormat::RGB8 | PixelFormat::RGB16 => SerColorFormat::RGB,
+        PixelFormat::CfaRGGB8 | PixelFormat::CfaRGGB16 => SerColorFormat::BayerRGGB,
+        PixelFormat::CfaGRBG8 | PixelFormat::CfaGRBG16 => SerColorFormat::BayerGRBG,
+        PixelFormat::CfaGBRG8 | PixelFormat::CfaGBRG16 => SerColorFormat::BayerGBRG,
+        PixelFormat::CfaBGGR8 | PixelFormat::CfaBGGR16 => SerColorFormat::BayerBGGR,
+        other => panic!("Recording {:?} as SER video not implemented yet.", other)
+    };
+
+    if automatic == SerColorFormat::RGB {
+        if color_id != ColorId::Automatic {
+            log::warn!("SER color ID \"{}\" not applicable to {:?}; ignoring", color_id, pix_fmt);
+        }
+        return automatic;
+    }
+
+    match color_id {
+        ColorId::Automatic => automatic,
+        ColorId::Mono => SerColorFormat::Mono,
+        ColorId::BayerRGGB => SerColorFormat::BayerRGGB,
+        ColorId::BayerGRBG => SerColorFormat::BayerGRBG,
+        ColorId::BayerGBRG => SerColorFormat::BayerGBRG,
+        ColorId::BayerBGGR => SerColorFormat::BayerBGGR
+    }
+}
+
+/// Number of 100-ns ticks between 0001-01-01 and the Unix epoch (SER timestamps count from the former).
+const SER_TICKS_AT_UNIX_EPOCH: u64 = 621_355_968_000_000_000;
+
 // see comment for `SerHeader::little_endian`
 const SER_LITTLE_ENDIAN: u32 = 0;
 const SER_BIG_ENDIAN: u32 = 1;
@@ -70,37 +131,55 @@ struct SerHeader {
 #[derive(Debug)]
 pub struct SerVideo {
     writer: std::io::BufWriter<std::fs::File>,
+    /// Path of the file written (used for verification).
+    path: std::path::PathBuf,
+    color_id: ColorId,
     /// Frame width, height, pixel format.
     frame_format: Option<(u32, u32, ga_image::PixelFormat)>,
-    frame_count: u32
+    frame_count: u32,
+    /// Frame timestamps (written in the file trailer).
+    timestamps: Vec<u64>
 }
 
 impl SerVideo {
-    pub fn new(file: std::fs::File) -> SerVideo {
-        SerVideo{ writer: BufWriter::new(file), frame_format: None, frame_count: 0 }
+    pub fn new(file: std::fs::File, path: &std::path::Path, color_id: ColorId) -> SerVideo {
+        SerVideo{
+            writer: BufWriter::new(file),
+            path: path.into(),
+            color_id,
+            frame_format: None,
+            frame_count: 0,
+            timestamps: vec![]
+        }
     }
 }
 
+/// Converts `time` to a SER timestamp (in 100-ns ticks since 0001-01-01); `utc_offset` is added.
+fn ser_timestamp(time: std::time::SystemTime, utc_offset: chrono::Duration) -> u64 {
+    let since_epoch = match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(d) => d,
+        Err(_) => std::time::Duration::ZERO
+    };
+    let ticks = SER_TICKS_AT_UNIX_EPOCH + (since_epoch.as_nanos() / 100) as u64;
+    (ticks as i64 + utc_offset.num_microseconds().unwrap_or(0) * 10) as u64
+}
+
 impl OutputWriter for SerVideo {
-    fn write(&mut self, image: &ImageView) -> Result<(), String> {
+    fn write(&mut self, image: &ImageView, timestamp: std::time::SystemTime) -> Result<(), String> {
         match self.frame_format {
             None => {
                 self.frame_format = Some((image.width(), image.height(), image.pixel_format()));
 
+                let local_utc_offset = chrono::Duration::seconds(
+                    chrono::DateTime::<chrono::Local>::from(timestamp).offset().local_minus_utc() as i64
+                );
+
                 let is_machine_big_endian = 0x1122u16.to_be() == 0x1122;
 
                 let ser_header = SerHeader{
                     signature: str_as_byte_array!("Vidoxide", 14),
                     camera_series_id: 0,
-                    color_id: match image.pixel_format() {
-                        ga_image::PixelFormat::Mono8 | ga_image::PixelFormat::Mono16 => (SerColorFormat::Mono as u32).to_le(),
-                        ga_image::PixelFormat::RGB8 | ga_image::PixelFormat::RGB16 => (SerColorFormat::RGB as u32).to_le(),
-                        ga_image::PixelFormat::CfaRGGB8 | ga_image::PixelFormat::CfaRGGB16 => (SerColorFormat::BayerRGGB as u32).to_le(),
-                        ga_image::PixelFormat::CfaGRBG8 | ga_image::PixelFormat::CfaGRBG16 => (SerColorFormat::BayerGRBG as u32).to_le(),
-                        ga_image::PixelFormat::CfaGBRG8 | ga_image::PixelFormat::CfaGBRG16 => (SerColorFormat::BayerGBRG as u32).to_le(),
-                        ga_image::PixelFormat::CfaBGGR8 | ga_image::PixelFormat::CfaBGGR16 => (SerColorFormat::BayerBGGR as u32).to_le(),
-                        other => panic!("Recording {:?} as SER video not implemented yet.", other)
-                    },
+                    color_id: (ser_color_format(image.pixel_format(), self.color_id) as u32).to_le(),
                     little_endian: if is_machine_big_endian { SER_BIG_ENDIAN.to_le() } else { SER_LITTLE_ENDIAN.to_le() },
                     img_width: image.width().to_le(),
                     img_height: image.height().to_le(),
@@ -109,8 +188,8 @@ impl OutputWriter for SerVideo {
                     observer: [0; 40],   //
                     instrument: [0; 40], // TODO: set something here
                     telescope: [0; 40],  //
-                    date_time: 0, // TODO: support the timestamp and timestamp file trailer
-                    date_time_utc: 0
+                    date_time: (ser_timestamp(timestamp, local_utc_offset) as i64).to_le(),
+                    date_time_utc: (ser_timestamp(timestamp, chrono::Duration::zero()) as i64).to_le()
                 };
                 match utils::write_struct(&ser_header, &mut self.writer) {
                     Ok(_) => (),
@@ -130,19 +209,29 @@ impl OutputWriter for SerVideo {
         for y in 0..image.height() {
             let line = image.line_raw(y);
             match self.writer.write_all(line) {
-                Err(err) => return Err(format!("{:?}", err)),
+                Err(err) => return Err(format!("error writing frame: {}", err)),
                 Ok(()) => ()
             }
         }
 
-        self.writer.flush().unwrap();
+        if let Err(err) = self.writer.flush() {
+            return Err(format!("error writing frame: {}", err));
+        }
 
         self.frame_count += 1;
+        self.timestamps.push(ser_timestamp(timestamp, chrono::Duration::zero()));
 
         Ok(())
     }
 
     fn finalize(&mut self) -> Result<(), String> {
+        // trailer; the writer is positioned after the last frame
+        for timestamp in &self.timestamps {
+            if let Err(err) = utils::write_struct(&timestamp.to_le(), &mut self.writer) {
+                return Err(format!("I/O error: {:?}", err));
+            }
+        }
+
         match self.writer.seek(SeekFrom::Start(38 /* offset of `frame_count` */)) {
             Err(err) => { return Err(format!("I/O error: {:?}", err)); },
             _ => ()
@@ -154,4 +243,86 @@ impl OutputWriter for SerVideo {
 
         Ok(())
     }
+
+    fn verify(&mut self) -> Result<(), String> {
+        let (width, height, pix_fmt) = match self.frame_format {
+            Some(frame_format) => frame_format,
+            None => return Ok(()) // nothing has been written
+        };
+        self.writer.flush().map_err(|err| format!("I/O error: {}", err))?;
+
+        let frame_size = (width * height) as u64 * pix_fmt.bytes_per_pixel() as u64;
+        let expected_size = std::mem::size_of::<SerHeader>() as u64
+            + self.frame_count as u64 * (frame_size + std::mem::size_of::<u64>() as u64);
+        let actual_size = std::fs::metadata(&self.path).map_err(|err| format!("cannot access file: {}", err))?.len();
+        if actual_size != expected_size {
+            return Err(format!("file size is {} bytes, expected {}", actual_size, expected_size));
+        }
+
+        let mut video = crate::input::open_ser_video(&self.path)
+            .map_err(|err| format!("cannot open file: {}", err))?;
+        if video.num_images() != self.frame_count as usize {
+            return Err(format!("file contains {} frames, expected {}", video.num_images(), self.frame_count));
+        }
+        for index in [0, video.num_images() - 1] {
+            video.get_image(index).map_err(|err| format!("cannot read frame {}: {}", index, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_system_time_ser_timestamp_counts_ticks_since_year_1() {
+        assert_eq!(SER_TICKS_AT_UNIX_EPOCH, ser_timestamp(std::time::UNIX_EPOCH, chrono::Duration::zero()));
+
+        let t = std::time::UNIX_EPOCH + std::time::Duration::from_millis(1500);
+        assert_eq!(SER_TICKS_AT_UNIX_EPOCH + 15_000_000, ser_timestamp(t, chrono::Duration::zero()));
+        assert_eq!(
+            SER_TICKS_AT_UNIX_EPOCH + 15_000_000 + 36_000_000_000,
+            ser_timestamp(t, chrono::Duration::hours(1))
+        );
+    }
+
+    #[test]
+    fn ser_color_format_follows_pixel_format_unless_overridden() {
+        use ga_image::PixelFormat;
+
+        assert_eq!(SerColorFormat::BayerGBRG, ser_color_format(PixelFormat::CfaGBRG8, ColorId::Automatic));
+        assert_eq!(SerColorFormat::BayerRGGB, ser_color_format(PixelFormat::CfaRGGB16, ColorId::Automatic));
+        assert_eq!(SerColorFormat::Mono, ser_color_format(PixelFormat::Mono16, ColorId::Automatic));
+        assert_eq!(SerColorFormat::RGB, ser_color_format(PixelFormat::RGB8, ColorId::Automatic));
+
+        assert_eq!(SerColorFormat::Mono, ser_color_format(PixelFormat::CfaGBRG8, ColorId::Mono));
+        assert_eq!(SerColorFormat::BayerBGGR, ser_color_format(PixelFormat::Mono8, ColorId::BayerBGGR));
+        assert_eq!(SerColorFormat::RGB, ser_color_format(PixelFormat::RGB16, ColorId::BayerGRBG));
+    }
+
+    #[test]
+    fn recorded_test_pattern_is_read_back_unchanged() {
+        use crate::camera::drivers::simulator;
+
+        let path = std::env::temp_dir().join(format!("vidoxide_test_pattern_{}.ser", std::process::id()));
+        for pattern in [simulator::test_pattern_mono16(), simulator::test_pattern_rgb8()] {
+            let mut video = SerVideo::new(std::fs::File::create(&path).unwrap(), &path, ColorId::Automatic);
+            video.write(&pattern.view(), std::time::SystemTime::now()).unwrap();
+            video.finalize().unwrap();
+            video.verify().unwrap();
+            drop(video);
+
+            let mut recorded = crate::input::open_ser_video(&path).unwrap();
+            assert_eq!(1, recorded.num_images());
+            let image = recorded.get_image(0).unwrap();
+            assert_eq!(pattern.pixel_format(), image.pixel_format());
+            assert_eq!((pattern.width(), pattern.height()), (image.width(), image.height()));
+            for y in 0..pattern.height() {
+                assert_eq!(pattern.view().line_raw(y), image.view().line_raw(y));
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,369 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! TIFF writer supporting compression and custom tags.
+//!
+
+use ga_image::{ImageView, PixelFormat};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Approximate size of uncompressed strip in bytes.
+const STRIP_SIZE: usize = 64 * 1024;
+
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumIter)]
+pub enum Compression {
+    None,
+    Lzw,
+    Deflate
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Compression::None => "none",
+            Compression::Lzw => "LZW",
+            Compression::Deflate => "Deflate"
+        })
+    }
+}
+
+pub mod tags {
+    pub const IMAGE_WIDTH: u16 = 256;
+    pub const IMAGE_LENGTH: u16 = 257;
+    pub const BITS_PER_SAMPLE: u16 = 258;
+    pub const COMPRESSION: u16 = 259;
+    pub const PHOTOMETRIC_INTERPRETATION: u16 = 262;
+    pub const STRIP_OFFSETS: u16 = 273;
+    pub const SAMPLES_PER_PIXEL: u16 = 277;
+    pub const ROWS_PER_STRIP: u16 = 278;
+    pub const STRIP_BYTE_COUNTS: u16 = 279;
+    pub const PLANAR_CONFIGURATION: u16 = 284;
+    pub const SAMPLE_FORMAT: u16 = 339;
+}
+
+pub mod photometric {
+    pub const BLACK_IS_ZERO: u16 = 1;
+    pub const RGB: u16 = 2;
+}
+
+mod compression_tag {
+    pub const NONE: u16 = 1;
+    pub const LZW: u16 = 5;
+    pub const ADOBE_DEFLATE: u16 = 8;
+}
+
+mod sample_format {
+    pub const UINT: u16 = 1;
+    pub const IEEE_FP: u16 = 3;
+}
+
+pub enum TagValue {
+    Byte(Vec<u8>),
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(Vec<(u32, u32)>),
+    SRational(Vec<(i32, i32)>)
+}
+
+impl TagValue {
+    fn field_type(&self) -> u16 {
+        match self {
+            TagValue::Byte(_) => 1,
+            TagValue::Ascii(_) => 2,
+            TagValue::Short(_) => 3,
+            TagValue::Long(_) => 4,
+            TagValue::Rational(_) => 5,
+            TagValue::SRational(_) => 10
+        }
+    }
+
+    fn count(&self) -> u32 {
+        (match self {
+            TagValue::Byte(v) => v.len(),
+            TagValue::Ascii(s) => s.len() + 1,
+            TagValue::Short(v) => v.len(),
+            TagValue::Long(v) => v.len(),
+            TagValue::Rational(v) => v.len(),
+            TagValue::SRational(v) => v.len()
+        }) as u32
+    }
+
+    /// Returns value in native byte order.
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            TagValue::Byte(v) => v.clone(),
+            TagValue::Ascii(s) => s.bytes().chain(std::iter::once(0)).collect(),
+            TagValue::Short(v) => v.iter().flat_map(|x| x.to_ne_bytes()).collect(),
+            TagValue::Long(v) => v.iter().flat_map(|x| x.to_ne_bytes()).collect(),
+            TagValue::Rational(v) => v.iter().flat_map(|(n, d)| n.to_ne_bytes().iter().chain(d.to_ne_bytes().iter()).copied().collect::<Vec<u8>>()).collect(),
+            TagValue::SRational(v) => v.iter().flat_map(|(n, d)| n.to_ne_bytes().iter().chain(d.to_ne_bytes().iter()).copied().collect::<Vec<u8>>()).collect()
+        }
+    }
+}
+
+/// Saves `image` as TIFF (in native byte order). `extra_tags` are added to (or replace) the baseline tags.
+pub fn save(path: &Path, image: &ImageView, compression: Compression, extra_tags: Vec<(u16, TagValue)>) -> Result<(), String> {
+    let pix_fmt = image.pixel_format();
+    let (photometric, sample_format) = match pix_fmt {
+        PixelFormat::Mono8 | PixelFormat::Mono16
+        | PixelFormat::CfaRGGB8 | PixelFormat::CfaGRBG8 | PixelFormat::CfaGBRG8 | PixelFormat::CfaBGGR8
+        | PixelFormat::CfaRGGB16 | PixelFormat::CfaGRBG16 | PixelFormat::CfaGBRG16 | PixelFormat::CfaBGGR16
+            => (photometric::BLACK_IS_ZERO, sample_format::UINT),
+
+        PixelFormat::RGB8 | PixelFormat::RGB16 => (photometric::RGB, sample_format::UINT),
+
+        PixelFormat::Mono32f | PixelFormat::Mono64f => (photometric::BLACK_IS_ZERO, sample_format::IEEE_FP),
+
+        PixelFormat::RGB32f | PixelFormat::RGB64f => (photometric::RGB, sample_format::IEEE_FP),
+
+        other => return Err(format!("saving {:?} as TIFF not supported", other))
+    };
+
+    let num_channels = pix_fmt.num_channels();
+    let line_len = image.width() as usize * pix_fmt.bytes_per_pixel();
+    let rows_per_strip = (STRIP_SIZE / line_len).max(1) as u32;
+
+    let mut out = vec![];
+    out.extend_from_slice(if ga_image::utils::is_machine_big_endian() { b"MM" } else { b"II" });
+    out.extend_from_slice(&42u16.to_ne_bytes());
+    out.extend_from_slice(&0u32.to_ne_bytes()); // offset of IFD; filled in below
+
+    let mut strip_offsets = vec![];
+    let mut strip_byte_counts = vec![];
+    let mut y = 0;
+    while y < image.height() {
+        let mut strip = Vec::with_capacity(rows_per_strip as usize * line_len);
+        for row in y..(y + rows_per_strip).min(image.height()) {
+            strip.extend_from_slice(image.line_raw(row));
+        }
+        let strip = match compression {
+            Compression::None => strip,
+            Compression::Lzw => lzw_compress(&strip),
+            Compression::Deflate => miniz_oxide::deflate::compress_to_vec_zlib(&strip, 6)
+        };
+
+        strip_offsets.push(out.len() as u32);
+        strip_byte_counts.push(strip.len() as u32);
+        out.extend_from_slice(&strip);
+        if out.len() % 2 != 0 { out.push(0); }
+
+        y += rows_per_strip;
+    }
+
+    let mut ifd_tags = BTreeMap::new();
+    ifd_tags.insert(tags::IMAGE_WIDTH, TagValue::Long(vec![image.width()]));
+    ifd_tags.insert(tags::IMAGE_LENGTH, TagValue::Long(vec![image.height()]));
+    ifd_tags.insert(tags::BITS_PER_SAMPLE, TagValue::Short(vec![pix_fmt.bytes_per_channel() as u16 * 8; num_channels]));
+    ifd_tags.insert(tags::COMPRESSION, TagValue::Short(vec![match compression {
+        Compression::None => compression_tag::NONE,
+        Compression::Lzw => compression_tag::LZW,
+        Compression::Deflate => compression_tag::ADOBE_DEFLATE
+    }]));
+    ifd_tags.insert(tags::PHOTOMETRIC_INTERPRETATION, TagValue::Short(vec![photometric]));
+    ifd_tags.insert(tags::STRIP_OFFSETS, TagValue::Long(strip_offsets));
+    ifd_tags.insert(tags::SAMPLES_PER_PIXEL, TagValue::Short(vec![num_channels as u16]));
+    ifd_tags.insert(tags::ROWS_PER_STRIP, TagValue::Long(vec![rows_per_strip]));
+    ifd_tags.insert(tags::STRIP_BYTE_COUNTS, TagValue::Long(strip_byte_counts));
+    ifd_tags.insert(tags::PLANAR_CONFIGURATION, TagValue::Short(vec![1]));
+    ifd_tags.insert(tags::SAMPLE_FORMAT, TagValue::Short(vec![sample_format; num_channels]));
+    for (tag, value) in extra_tags {
+        ifd_tags.insert(tag, value);
+    }
+
+    let ifd_offset = out.len();
+    out[4..8].copy_from_slice(&(ifd_offset as u32).to_ne_bytes());
+
+    // values not fitting in 4 bytes are stored after the IFD
+    let values_offset = ifd_offset + 2 + 12 * ifd_tags.len() + 4;
+    let mut values = vec![];
+
+    out.extend_from_slice(&(ifd_tags.len() as u16).to_ne_bytes());
+    for (tag, value) in &ifd_tags {
+        out.extend_from_slice(&tag.to_ne_bytes());
+        out.extend_from_slice(&value.field_type().to_ne_bytes());
+        out.extend_from_slice(&value.count().to_ne_bytes());
+
+        let bytes = value.to_bytes();
+        if bytes.len() <= 4 {
+            let mut inline_value = [0u8; 4];
+            inline_value[..bytes.len()].copy_from_slice(&bytes);
+            out.extend_from_slice(&inline_value);
+        } else {
+            out.extend_from_slice(&((values_offset + values.len()) as u32).to_ne_bytes());
+            values.extend_from_slice(&bytes);
+            if values.len() % 2 != 0 { values.push(0); }
+        }
+    }
+    out.extend_from_slice(&0u32.to_ne_bytes()); // no more IFDs
+    out.extend_from_slice(&values);
+
+    std::fs::write(path, &out).map_err(|e| format!("{:?}", e))
+}
+
+/// Writes variable-length codes, most significant bit first.
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u32,
+    num_bits: u32
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter{ output: vec![], buffer: 0, num_bits: 0 }
+    }
+
+    fn write(&mut self, code: u16, width: u32) {
+        self.buffer = (self.buffer << width) | code as u32;
+        self.num_bits += width;
+        while self.num_bits >= 8 {
+            self.num_bits -= 8;
+            self.output.push((self.buffer >> self.num_bits) as u8);
+        }
+        self.buffer &= (1 << self.num_bits) - 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.num_bits > 0 {
+            self.output.push((self.buffer << (8 - self.num_bits)) as u8);
+        }
+        self.output
+    }
+}
+
+/// Compresses `data` with the TIFF variant of LZW (with "early change" of code width).
+fn lzw_compress(data: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END_OF_INFORMATION: u16 = 257;
+    const FIRST_CODE: u16 = 258;
+    const TABLE_FULL: u16 = 4094;
+    const MIN_WIDTH: u32 = 9;
+
+    let mut writer = BitWriter::new();
+    let mut width = MIN_WIDTH;
+    let mut next_code = FIRST_CODE;
+    // maps (prefix code, next byte) to code
+    let mut table = HashMap::<(u16, u8), u16>::new();
+
+    writer.write(CLEAR, width);
+
+    let mut current = match data.first() {
+        Some(byte) => *byte as u16,
+        None => {
+            writer.write(END_OF_INFORMATION, width);
+            return writer.finish();
+        }
+    };
+
+    // called after a code has been emitted; the decoder adds a new table entry at this point
+    let on_code_written = |writer: &mut BitWriter, width: &mut u32, next_code: &mut u16| -> bool {
+        *next_code += 1;
+        if *next_code == TABLE_FULL {
+            writer.write(CLEAR, *width);
+            *width = MIN_WIDTH;
+            *next_code = FIRST_CODE;
+            true
+        } else {
+            if *next_code as u32 > (1 << *width) - 1 { *width += 1; }
+            false
+        }
+    };
+
+    for &byte in &data[1..] {
+        if let Some(code) = table.get(&(current, byte)) {
+            current = *code;
+            continue;
+        }
+
+        writer.write(current, width);
+        table.insert((current, byte), next_code);
+        if on_code_written(&mut writer, &mut width, &mut next_code) {
+            table.clear();
+        }
+        current = byte as u16;
+    }
+
+    writer.write(current, width);
+    on_code_written(&mut writer, &mut width, &mut next_code);
+    writer.write(END_OF_INFORMATION, width);
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decompresses TIFF LZW data.
+    fn lzw_decompress(data: &[u8]) -> Vec<u8> {
+        let mut output = vec![];
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut width = 9;
+        let mut bit_pos = 0;
+        let mut prev: Option<Vec<u8>> = None;
+
+        loop {
+            let mut code = 0u32;
+            for _ in 0..width {
+                let bit = (data[bit_pos / 8] >> (7 - bit_pos % 8)) & 1;
+                code = (code << 1) | bit as u32;
+                bit_pos += 1;
+            }
+
+            if code == 256 {
+                table = (0..=255u8).map(|b| vec![b]).collect();
+                table.push(vec![]);
+                table.push(vec![]);
+                width = 9;
+                prev = None;
+                continue;
+            }
+            if code == 257 { break; }
+
+            let entry = if (code as usize) < table.len() {
+                table[code as usize].clone()
+            } else {
+                let mut entry = prev.clone().unwrap();
+                entry.push(entry[0]);
+                entry
+            };
+            output.extend_from_slice(&entry);
+            if let Some(mut prev) = prev {
+                prev.push(entry[0]);
+                table.push(prev);
+            }
+            prev = Some(entry);
+
+            // "early change": the decoder switches code width one entry earlier than the encoder
+            if table.len() + 1 >= (1 << width) && width < 12 { width += 1; }
+        }
+
+        output
+    }
+
+    #[test]
+    fn given_data_lzw_roundtrip_is_lossless() {
+        let mut data: Vec<u8> = (0..100_000u32).map(|i| ((i * 7919) % 251) as u8).collect();
+        data.extend(std::iter::repeat(0).take(50_000));
+        data.extend((0..100_000u32).map(|i| (i / 300) as u8));
+
+        assert_eq!(data, lzw_decompress(&lzw_compress(&data)));
+        assert_eq!(Vec::<u8>::new(), lzw_decompress(&lzw_compress(&[])));
+    }
+
+    #[test]
+    fn given_sparse_data_lzw_reduces_size() {
+        let mut data = vec![0u8; 100_000];
+        data[5000] = 200;
+        data[70000] = 100;
+
+        assert!(lzw_compress(&data).len() < data.len() / 10);
+    }
+}
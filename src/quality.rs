@@ -0,0 +1,95 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Frame quality estimation (for selecting the sharpest frames in lucky imaging).
+//!
+
+use ga_image::ImageView;
+
+/// Returns the sharpness of `image`: the sum of squared brightness differences between neighboring pixels
+/// divided by the sum of squared brightness values.
+///
+/// The value does not depend on the overall brightness, but only frames of the same scene can be compared.
+/// Neighbors are pixels of the same channel (for raw color images: of the same color).
+pub fn frame_quality(image: &ImageView) -> f64 {
+    let pix_fmt = image.pixel_format();
+    let num_channels = pix_fmt.num_channels();
+    // distance (in pixels) between neighbors
+    let step = if pix_fmt.is_cfa() { 2 } else { 1 };
+
+    match pix_fmt.bytes_per_channel() {
+        1 => gradient_energy_ratio::<u8>(image, num_channels, step),
+        2 => gradient_energy_ratio::<u16>(image, num_channels, step),
+        _ => 0.0
+    }
+}
+
+fn gradient_energy_ratio<T: 'static + Copy + Default + Into<f64>>(
+    image: &ImageView,
+    num_channels: usize,
+    step: usize
+) -> f64 {
+    let dx = step * num_channels; // distance between horizontal neighbors in a line (in values)
+    let mut sum_sq_gradients = 0.0;
+    let mut sum_sq_values = 0.0;
+
+    for y in 0..image.height().saturating_sub(step as u32) {
+        let line = image.line::<T>(y);
+        let next_line = image.line::<T>(y + step as u32);
+        for i in 0..line.len().saturating_sub(dx) {
+            let value: f64 = line[i].into();
+            let grad_x = line[i + dx].into() - value;
+            let grad_y = next_line[i].into() - value;
+            sum_sq_gradients += grad_x * grad_x + grad_y * grad_y;
+            sum_sq_values += value * value;
+        }
+    }
+
+    if sum_sq_values == 0.0 { 0.0 } else { sum_sq_gradients / sum_sq_values }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ga_image::{Image, PixelFormat};
+
+    /// Returns a `Mono8` image with vertical stripes, blurred over `blur` pixels.
+    fn stripes(brightness: u8, blur: u32) -> Image {
+        const SIZE: u32 = 32;
+        let mut image = Image::new(SIZE, SIZE, None, PixelFormat::Mono8, None, true);
+        for y in 0..SIZE {
+            for (x, value) in image.line_mut::<u8>(y).iter_mut().enumerate() {
+                let mut sum = 0u32;
+                for dx in 0..=blur {
+                    sum += if ((x as u32 + dx) / 4) % 2 == 0 { brightness as u32 } else { brightness as u32 / 4 };
+                }
+                *value = (sum / (blur + 1)) as u8;
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn sharper_frame_has_higher_quality() {
+        assert!(frame_quality(&stripes(200, 0).view()) > frame_quality(&stripes(200, 3).view()));
+    }
+
+    #[test]
+    fn quality_does_not_depend_on_brightness() {
+        let bright = frame_quality(&stripes(200, 0).view());
+        let dim = frame_quality(&stripes(100, 0).view());
+        assert!((bright - dim).abs() < 1.0e-9 * bright);
+    }
+
+    #[test]
+    fn uniform_frame_has_zero_quality() {
+        let image = Image::new(8, 8, None, PixelFormat::Mono16, None, true);
+        assert_eq!(0.0, frame_quality(&image.view()));
+    }
+}
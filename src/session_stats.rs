@@ -0,0 +1,78 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Program session statistics.
+//!
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Statistics of the current program session; updated by the main, capture and recording threads.
+#[derive(Default)]
+pub struct SessionStats {
+    pub frames_captured: AtomicU64,
+    pub recordings: AtomicU64,
+    pub frames_recorded: AtomicU64,
+    pub bytes_written: AtomicU64
+}
+
+impl SessionStats {
+    pub fn add_captured_frame(&self) {
+        self.frames_captured.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_recording(&self) {
+        self.recordings.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_recorded_frame(&self, num_bytes: u64) {
+        self.frames_recorded.fetch_add(1, Ordering::Relaxed);
+        self.bytes_written.fetch_add(num_bytes, Ordering::Relaxed);
+    }
+
+    pub fn summary(&self, duration: std::time::Duration) -> String {
+        format_summary(
+            duration,
+            self.frames_captured.load(Ordering::Relaxed),
+            self.recordings.load(Ordering::Relaxed),
+            self.frames_recorded.load(Ordering::Relaxed),
+            self.bytes_written.load(Ordering::Relaxed)
+        )
+    }
+}
+
+fn format_summary(
+    duration: std::time::Duration,
+    frames_captured: u64,
+    recordings: u64,
+    frames_recorded: u64,
+    bytes_written: u64
+) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "duration: {}:{:02}:{:02}; frames captured: {}; recordings: {} ({} frames, {:.2} GiB written)",
+        secs / 3600, (secs / 60) % 60, secs % 60,
+        frames_captured,
+        recordings,
+        frames_recorded,
+        bytes_written as f64 / (1u64 << 30) as f64
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_session_stats_summary_is_formatted() {
+        assert_eq!(
+            "duration: 1:02:05; frames captured: 12000; recordings: 3 (4500 frames, 1.50 GiB written)",
+            format_summary(std::time::Duration::from_secs(3725), 12000, 3, 4500, 3 << 29)
+        );
+    }
+}
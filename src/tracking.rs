@@ -16,13 +16,25 @@ use ga_image::{Image, ImageView, PixelFormat, Rect};
 const ANCHOR_SEARCH_RADIUS: i32 = 20;
 const REF_BLOCK_SIZE: u32 = 64;
 const MIN_REL_BRIGHTNESS_FOR_CENTROID: f32 = 30.0 / 255.0;
+/// Min. width and height of the centroid tracking area.
+const MIN_CENTROID_AREA_SIZE: u32 = 8;
+/// Number of consecutive frames with star's SNR below the minimum after which the star is considered lost.
+const MAX_LOW_SNR_FRAMES: usize = 5;
 
 enum State {
     Disabled,
     Centroid {
         area: Rect,
         /// Desired position of the `area`s centroid relative to `area`'s origin.
-        offset: Vector2<i32>
+        offset: Vector2<i32>,
+        /// Most recently found centroid position (image coordinates).
+        subpixel_pos: Point2<f64>,
+        /// Signal-to-noise ratio of the star in `area` (see `star_snr`).
+        snr: f64,
+        /// If the star's SNR stays below this value for `MAX_LOW_SNR_FRAMES`, it is considered lost.
+        min_snr: Option<f64>,
+        /// Number of consecutive frames with SNR below `min_snr`.
+        num_low_snr_frames: usize
     },
     Anchor {
         pos: Point2<i32>,
@@ -35,10 +47,26 @@ pub struct ImageTracker {
 }
 
 impl ImageTracker {
-    pub fn new_with_centroid(area: Rect, image: &Image) -> ImageTracker {
+    /// Creates a centroid tracker.
+    ///
+    /// # Parameters
+    ///
+    /// * `area` - Tracking area (must be contained in `image`).
+    /// * `image` - Image to start tracking in.
+    /// * `min_snr` - If set, tracking fails once the star's SNR stays below this value for several frames.
+    ///
+    pub fn new_with_centroid(area: Rect, image: &Image, min_snr: Option<f64>) -> ImageTracker {
         let centroid = image.centroid(Some(area));
+        let frag8 = image.convert_pix_fmt_of_subimage(PixelFormat::Mono8, area.pos(), area.width, area.height, None);
         ImageTracker{
-            state: State::Centroid{ area, offset: Vector2::from(centroid).cast::<i32>().unwrap() }
+            state: State::Centroid{
+                area,
+                offset: Vector2::from(centroid).cast::<i32>().unwrap(),
+                subpixel_pos: Point2::from(area.pos()).cast::<f64>().unwrap() + Vector2::from(centroid).cast::<f64>().unwrap(),
+                snr: star_snr(&frag8),
+                min_snr,
+                num_low_snr_frames: 0
+            }
         }
     }
 
@@ -61,14 +89,45 @@ impl ImageTracker {
     pub fn position(&self) -> Option<Point2<i32>> {
         match &self.state {
             State::Disabled => None,
-            State::Centroid{ area, offset } => Some(Point2::from(area.pos()) + offset),
+            State::Centroid{ area, offset, .. } => Some(Point2::from(area.pos()) + offset),
             State::Anchor{ pos, ref_block: _ } => Some(*pos)
         }
     }
 
+    /// Returns tracked position with subpixel precision (if available in the current mode).
+    pub fn subpixel_position(&self) -> Option<Point2<f64>> {
+        match &self.state {
+            State::Disabled => None,
+            State::Centroid{ subpixel_pos, .. } => Some(*subpixel_pos),
+            State::Anchor{ pos, ref_block: _ } => pos.cast::<f64>()
+        }
+    }
+
+    /// Returns signal-to-noise ratio of the tracked star (in centroid mode).
+    pub fn snr(&self) -> Option<f64> {
+        match &self.state {
+            State::Centroid{ snr, .. } => Some(*snr),
+            _ => None
+        }
+    }
+
+    /// Enlarges (or shrinks, if `delta` is negative) the centroid tracking area by `delta` pixels on each side
+    /// (as long as the result fits in `image` and is not smaller than `MIN_CENTROID_AREA_SIZE`).
+    pub fn resize_centroid_area(&mut self, delta: i32, image: &Image) {
+        if let State::Centroid{ area, min_snr, .. } = &self.state {
+            let new_area = resized_area(area, delta);
+            if new_area.width >= MIN_CENTROID_AREA_SIZE
+                && new_area.height >= MIN_CENTROID_AREA_SIZE
+                && image.img_rect().contains_rect(&new_area) {
+
+                *self = ImageTracker::new_with_centroid(new_area, image, *min_snr);
+            }
+        }
+    }
+
     pub fn centroid_area(&self) -> Option<Rect> {
         match &self.state {
-            State::Centroid{ area, offset: _ } => Some(*area),
+            State::Centroid{ area, .. } => Some(*area),
             _ => None
         }
     }
@@ -84,7 +143,7 @@ impl ImageTracker {
     #[must_use]
     pub fn update(&mut self, image: &Image, offset: Vector2<i32>) -> Result<(), ()> { //TODO: handle `offset`
         match &mut self.state {
-            State::Centroid{ area, offset } => {
+            State::Centroid{ area, offset, subpixel_pos, snr, min_snr, num_low_snr_frames } => {
                 if !image.img_rect().contains_rect(area) {
                     return Err(());
                 }
@@ -97,6 +156,20 @@ impl ImageTracker {
                     None
                 );
 
+                *snr = star_snr(&frag8);
+                if let Some(min_snr) = min_snr {
+                    if *snr < *min_snr {
+                        *num_low_snr_frames += 1;
+                        if *num_low_snr_frames >= MAX_LOW_SNR_FRAMES {
+                            log::warn!("guide star lost (SNR {:.1} below {:.1})", snr, min_snr);
+                            self.state = State::Disabled;
+                            return Err(());
+                        }
+                    } else {
+                        *num_low_snr_frames = 0;
+                    }
+                }
+
                 let w = frag8.width();
                 let h = frag8.height();
                 for y in 0..h {
@@ -105,7 +178,8 @@ impl ImageTracker {
                         if line[x as usize] < (MIN_REL_BRIGHTNESS_FOR_CENTROID * 255.0) as u8 { line[x as usize] = 0; }
                     }
                 }
-                let new_c = Point2::from(frag8.centroid(None)).cast::<i32>().unwrap();
+                let new_c_subpixel = Point2::from(frag8.centroid(None)).cast::<f64>().unwrap();
+                let new_c = new_c_subpixel.cast::<i32>().unwrap();
 
                 // TODO: make it configurable
                 // ignore a sudden jump which seems implausibly large; it's likely due to an image artifact
@@ -117,6 +191,7 @@ impl ImageTracker {
                     return Ok(());
                 }
 
+                *subpixel_pos = Point2::from(area.pos()).cast::<f64>().unwrap() + Vector2::new(new_c_subpixel.x, new_c_subpixel.y);
                 area.x += new_c.x - offset.x;
                 area.y += new_c.y - offset.y;
 
@@ -135,6 +210,48 @@ impl ImageTracker {
     }
 }
 
+/// Estimates signal-to-noise ratio of a star in `area` (must be `Mono8`).
+///
+/// The background level and noise are determined from the pixels on the border of `area`; the signal is the peak
+/// brightness above the background. Quantization noise is included, so that the result is finite also
+/// for a perfectly uniform background.
+fn star_snr(area: &Image) -> f64 {
+    assert!(area.pixel_format() == PixelFormat::Mono8);
+
+    let w = area.width() as usize;
+    let h = area.height();
+    if w == 0 || h == 0 { return 0.0; }
+
+    let mut peak = 0u8;
+    let mut border = vec![];
+    for y in 0..h {
+        let line = area.line::<u8>(y);
+        peak = peak.max(*line.iter().max().unwrap());
+        if y == 0 || y == h - 1 {
+            border.extend(line.iter().map(|v| *v as f64));
+        } else {
+            border.push(line[0] as f64);
+            border.push(line[w - 1] as f64);
+        }
+    }
+
+    let mean = border.iter().sum::<f64>() / border.len() as f64;
+    let variance = border.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / border.len() as f64;
+    let noise = (variance + 1.0 / 12.0).sqrt();
+
+    (peak as f64 - mean).max(0.0) / noise
+}
+
+/// Returns `area` enlarged by `delta` pixels on each side.
+fn resized_area(area: &Rect, delta: i32) -> Rect {
+    Rect{
+        x: area.x - delta,
+        y: area.y - delta,
+        width: (area.width as i32 + 2 * delta).max(0) as u32,
+        height: (area.height as i32 + 2 * delta).max(0) as u32
+    }
+}
+
 /// Updates anchor.
 ///
 /// # Parameters
@@ -235,3 +352,36 @@ fn calc_sum_of_abs_diffs(img1: &ImageView, img2: &ImageView) -> u64 {
 
     sum_diffs
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a `Mono8` image with a star of the given brightness above a noisy background.
+    fn star_image(star_brightness: u8) -> Image {
+        const SIZE: u32 = 32;
+        let mut image = Image::new(SIZE, SIZE, None, PixelFormat::Mono8, None, true);
+        let mut noise_state = 12345u32;
+        for y in 0..SIZE {
+            let line = image.line_mut::<u8>(y);
+            for x in 0..SIZE {
+                noise_state = noise_state.wrapping_mul(1103515245).wrapping_add(12345);
+                let noise = ((noise_state >> 16) % 9) as u8;
+                let dist_sq = (x as i32 - SIZE as i32 / 2).pow(2) + (y as i32 - SIZE as i32 / 2).pow(2);
+                let star = (star_brightness as f64 * (-(dist_sq as f64) / 8.0).exp()) as u8;
+                line[x as usize] = 20u8.saturating_add(noise).saturating_add(star);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn given_dim_star_snr_is_low() {
+        assert!(star_snr(&star_image(10)) < 10.0);
+    }
+
+    #[test]
+    fn given_bright_star_snr_is_high() {
+        assert!(star_snr(&star_image(200)) > 50.0);
+    }
+}
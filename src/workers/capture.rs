@@ -13,7 +13,9 @@
 use cgmath::{Point2, Vector2};
 use crate::camera::CameraError;
 use crate::camera::FrameCapturer;
+use crate::session_stats::SessionStats;
 use crate::tracking::ImageTracker;
+use crate::workers::frame_feed;
 use crate::workers::recording;
 use crate::{TrackingData, TrackingMode};
 use ga_image::Image;
@@ -26,13 +28,15 @@ use std::sync::mpsc::TryRecvError;
 #[derive(Debug)]
 pub struct Info {
     pub recording_info: Option<String>,
-    pub capture_fps: f64
+    pub capture_fps: f64,
+    /// Camera-provided frame counter and timestamp (if supported by the camera).
+    pub frame_info: Option<String>
 }
 
 #[derive(Debug)]
 pub enum CaptureToMainThreadMsg {
-    /// Includes tracking position if tracking is enabled.
-    PreviewImageReady((Arc<Image>, Option<Point2<i32>>)),
+    /// Includes tracking position if tracking is enabled, and the capture time.
+    PreviewImageReady((Arc<Image>, Option<Point2<i32>>, std::time::Instant)),
     Paused,
     CaptureError(CameraError),
     RecordingFinished,
@@ -49,20 +53,76 @@ pub enum MainToCaptureThreadMsg {
     Finish,
     Pause,
     Resume,
-    /// Contains a sender accepting frames + capture timestamps.
-    StartRecording((RecordingSender, recording::Limit)),
+    /// Contains a sender accepting frames + capture timestamps, recording limit, the "use camera timestamps" flag
+    /// and the maximum recording frame rate (excess frames are not recorded).
+    StartRecording((RecordingSender, recording::Limit, bool, Option<f64>)),
     StopRecording,
-    EnableCentroidTracking(Rect),
+    /// Stops sending frames to the recording thread without finishing the recording.
+    PauseRecording,
+    ResumeRecording,
+    /// Contains the tracking area and the minimum SNR of the star (see `ImageTracker::new_with_centroid`).
+    EnableCentroidTracking((Rect, Option<f64>)),
     EnableAnchorTracking(Point2<i32>),
+    /// Enlarges (or shrinks, if negative) the centroid tracking area by the given number of pixels on each side.
+    ResizeCentroidArea(i32),
     EnableRecordingCrop(Rect),
-    DisableRecordingCrop
+    DisableRecordingCrop,
+    /// Starts (or stops, if `None`) sending captured frames to the live frame feed.
+    SetFrameFeed(Option<frame_feed::FrameSender>)
 }
 
 struct RecData {
     sender: RecordingSender,
     limit: recording::Limit,
     tstart: std::time::Instant,
-    frame_counter: usize
+    frame_counter: usize,
+    /// If `Some`, the recording is paused since the given instant.
+    paused_since: Option<std::time::Instant>,
+    /// Total duration of the previous pauses.
+    paused_total: std::time::Duration,
+    /// If true, frame timestamps are based on the camera's clock (if available).
+    camera_timestamps: bool,
+    /// Host time and camera timestamp of the first frame with a camera timestamp.
+    camera_clock_origin: Option<(std::time::SystemTime, std::time::Duration)>,
+    frame_rate_limiter: Option<FrameRateLimiter>
+}
+
+impl RecData {
+    /// Returns recording duration so far, excluding pauses.
+    fn elapsed(&self) -> std::time::Duration {
+        let paused = self.paused_total + match self.paused_since {
+            Some(t) => t.elapsed(),
+            None => std::time::Duration::ZERO
+        };
+        self.tstart.elapsed().saturating_sub(paused)
+    }
+
+    /// Returns the timestamp of a frame captured just now.
+    fn frame_timestamp(&mut self, camera_timestamp: Option<std::time::Duration>) -> std::time::SystemTime {
+        let now = std::time::SystemTime::now();
+        if !self.camera_timestamps { return now; }
+
+        match camera_timestamp {
+            Some(camera_timestamp) => camera_clock_timestamp(&mut self.camera_clock_origin, now, camera_timestamp),
+
+            None => {
+                if self.frame_counter == 0 {
+                    log::warn!("camera does not provide frame timestamps; using host clock");
+                }
+                now
+            }
+        }
+    }
+}
+
+/// Converts `camera_timestamp` to host time, so that the first converted timestamp corresponds to `now`.
+fn camera_clock_timestamp(
+    origin: &mut Option<(std::time::SystemTime, std::time::Duration)>,
+    now: std::time::SystemTime,
+    camera_timestamp: std::time::Duration
+) -> std::time::SystemTime {
+    let (host_time0, camera_timestamp0) = *origin.get_or_insert((now, camera_timestamp));
+    host_time0 + camera_timestamp.saturating_sub(camera_timestamp0)
 }
 
 /// Specifies live cropping parameters to use for recording.
@@ -75,12 +135,63 @@ struct CropData {
 #[derive(PartialEq)]
 struct RecordingStillRunning(bool);
 
+/// Selects frames to record so that the recording does not exceed the maximum frame rate.
+struct FrameRateLimiter {
+    /// Minimum interval between recorded frames.
+    period: std::time::Duration,
+    /// Earliest time at which the next frame may be recorded.
+    next_due: Option<std::time::Instant>
+}
+
+impl FrameRateLimiter {
+    fn new(max_fps: f64) -> FrameRateLimiter {
+        FrameRateLimiter{ period: std::time::Duration::from_secs_f64(1.0 / max_fps), next_due: None }
+    }
+
+    /// Returns true if a frame captured at `t` is to be recorded.
+    fn accept(&mut self, t: std::time::Instant) -> bool {
+        let base = match self.next_due {
+            Some(next_due) if t < next_due => return false,
+            // keep the average rate by scheduling relative to the previous due time, unless lagging behind
+            // by more than a period (e.g., after a pause or when the camera is slower than the limit)
+            Some(next_due) if t < next_due + self.period => next_due,
+            _ => t
+        };
+        self.next_due = Some(base + self.period);
+        true
+    }
+}
+
+/// Detects gaps in the camera's frame counter values (i.e., frames dropped by the camera or transport layer).
+#[derive(Default)]
+struct FrameIdTracker {
+    last_id: Option<u64>,
+    num_skipped: u64
+}
+
+impl FrameIdTracker {
+    fn update(&mut self, frame_id: u64) {
+        if let Some(last_id) = self.last_id {
+            // if the counter went backwards, the camera must have reset it; ignore
+            if frame_id > last_id + 1 {
+                self.num_skipped += frame_id - last_id - 1;
+            }
+        }
+        self.last_id = Some(frame_id);
+    }
+}
+
 pub fn capture_thread(
     mut camera: Box<dyn FrameCapturer + Send>,
     sender: glib::Sender<CaptureToMainThreadMsg>,
     receiver: std::sync::mpsc::Receiver<MainToCaptureThreadMsg>,
     buffered_kib: Arc<AtomicIsize>,
-    new_preview_wanted: Arc<AtomicBool>
+    max_buffered_kib: Arc<AtomicIsize>,
+    new_preview_wanted: Arc<AtomicBool>,
+    session_stats: Arc<SessionStats>,
+    mut frame_feed: Option<frame_feed::FrameSender>,
+    /// If `Some`, capture fails after this many consecutive `CameraError::FrameUnavailable` results.
+    max_frame_unavailable_retries: Option<u32>
 ) {
     // To avoid unneccessary allocations, we (the capture thread) have two `Arc`-wrapped capture buffers.
     // One is provided to the main thread for preview, the other to the recording thread (if recording is in progress).
@@ -90,6 +201,7 @@ pub fn capture_thread(
     // If we are the sole owner of one of the buffers, it is used as the capture destination. If both are being shared,
     // it means the main thread and the recording thread still hold their copies and we must allocate a new one
     // to capture into. We send a buffer to the main thread only if it has already released the previously sent buffer.
+    // The live frame feed (if active) receives copies instead, so that it does not hold on to the buffers.
     //
     // Initially create dummy 1x1 images; they will be resized and set up correctly by `capture_frame`
     // (also after each video mode/ROI size/pixel format change).
@@ -116,6 +228,10 @@ pub fn capture_thread(
 
     let mut fps_counter: i32 = 0;
 
+    let mut frame_id_tracker = FrameIdTracker::default();
+
+    let mut num_frame_unavailable: u32 = 0;
+
     // for indoor testing; if `Some`, captured images will drift with the given (signed) speed in X and Y
     const DRIFT_PIX_PER_S: Option<[f64; 2]> = None;
 
@@ -125,7 +241,7 @@ pub fn capture_thread(
         let recording_finished = match rec_data {
             Some(ref data) => {
                 match data.limit {
-                    recording::Limit::Duration(duration) => data.tstart.elapsed() >= duration,
+                    recording::Limit::Duration(duration) => data.elapsed() >= duration,
                     recording::Limit::FrameCount(count) => data.frame_counter == count,
                     recording::Limit::Forever => false
                 }
@@ -134,7 +250,8 @@ pub fn capture_thread(
         };
 
         if recording_finished {
-            rec_data.take().unwrap().sender.send(recording::CaptureToRecordingThreadMsg::Finished).unwrap();
+            // the recording thread may have already ended the job due to a write error
+            let _ = rec_data.take().unwrap().sender.send(recording::CaptureToRecordingThreadMsg::Finished);
             sender.send(CaptureToMainThreadMsg::RecordingFinished).unwrap();
             num_dropped_frames = 0;
         }
@@ -156,6 +273,8 @@ pub fn capture_thread(
 
                 (current_buf_idx, camera.capture_frame(dest_img))
             };
+            let t_captured = std::time::Instant::now();
+            let t_captured_sys = std::time::SystemTime::now();
 
             fps_counter += 1;
 
@@ -163,7 +282,7 @@ pub fn capture_thread(
 
             let mut info: Option<Info> = None;
             if t_last_info.elapsed() >= std::time::Duration::from_secs(1) {
-                info = Some(Info{ capture_fps: fps_counter as f64, recording_info: None });
+                info = Some(Info{ capture_fps: fps_counter as f64, recording_info: None, frame_info: None });
                 fps_counter = 0;
                 t_last_info = std::time::Instant::now();
             }
@@ -171,13 +290,36 @@ pub fn capture_thread(
 
             match capture_result {
                 Err(err) => match err {
-                    CameraError::FrameUnavailable => (/* no error, do nothing */),
+                    CameraError::FrameUnavailable => {
+                        num_frame_unavailable += 1;
+                        if let Some(max_retries) = max_frame_unavailable_retries {
+                            if num_frame_unavailable > max_retries {
+                                log::error!("frame unavailable {} times in a row; giving up", num_frame_unavailable);
+                                sender.send(CaptureToMainThreadMsg::CaptureError(CameraError::FrameUnavailable)).unwrap();
+                                break;
+                            }
+                        }
+                        if num_frame_unavailable == 1 || num_frame_unavailable % 100 == 0 {
+                            log::warn!("frame unavailable ({} time(s) in a row); retrying", num_frame_unavailable);
+                        }
+                    },
                     other_err => {
                         sender.send(CaptureToMainThreadMsg::CaptureError(other_err)).unwrap();
                         break;
                     }
                 },
                 Ok(()) => {
+                    num_frame_unavailable = 0;
+                    session_stats.add_captured_frame();
+
+                    let frame_id = camera.last_frame_id();
+                    if let Some(frame_id) = frame_id {
+                        frame_id_tracker.update(frame_id);
+                    }
+                    if let Some(info) = info.as_mut() {
+                        info.frame_info = frame_info(frame_id, &frame_id_tracker, camera.last_frame_timestamp());
+                    }
+
                     if let Some([dx, dy]) = DRIFT_PIX_PER_S {
                         let img = &mut capture_buf[current_buf_idx];
                         let dt = t_start.elapsed();
@@ -189,16 +331,30 @@ pub fn capture_thread(
                     }
 
                     if let Some(ref mut tracker) = tracking {
-                        if on_tracking(tracker, &capture_buf[current_buf_idx], &sender, &mut crop_data).is_err() {
+                        if on_tracking(
+                            tracker, &capture_buf[current_buf_idx], t_captured_sys, &sender, &mut crop_data
+                        ).is_err() {
                             tracking = None;
                         }
                     }
 
                     if let Some(ref mut rec_data_contents) = rec_data {
-                        if RecordingStillRunning(false) == on_recording(
+                        let camera_timestamp = camera.last_frame_timestamp();
+                        if rec_data_contents.paused_since.is_some() {
+                            if let Some(info) = info.as_mut() {
+                                info.recording_info = Some(format!("Paused. {}", recording_progress(
+                                    &rec_data_contents.limit,
+                                    rec_data_contents.frame_counter,
+                                    num_dropped_frames,
+                                    rec_data_contents.elapsed()
+                                )));
+                            }
+                        } else if RecordingStillRunning(false) == on_recording(
                             rec_data_contents,
                             &capture_buf[current_buf_idx],
+                            camera_timestamp,
                             &buffered_kib,
+                            max_buffered_kib.load(Ordering::Relaxed),
                             info.as_mut(),
                             &mut num_dropped_frames,
                             &crop_data
@@ -207,11 +363,19 @@ pub fn capture_thread(
                         }
                     }
 
+                    if let Some(frame_feed) = &frame_feed {
+                        // if the feed is still busy with the previous frame, this one is skipped (without copying)
+                        if frame_feed.is_empty() {
+                            let _ = frame_feed.try_send(((*capture_buf[current_buf_idx]).clone(), t_captured_sys));
+                        }
+                    }
+
                     if new_preview_wanted.swap(false, Ordering::Relaxed) == true {
                         match sender.send(
                             CaptureToMainThreadMsg::PreviewImageReady((
                                 Arc::clone(&capture_buf[current_buf_idx]),
-                                if let Some(tracking) = &tracking { tracking.position() } else { None }
+                                if let Some(tracking) = &tracking { tracking.position() } else { None },
+                                t_captured
                             ))
                         ) {
                             Ok(()) => (),
@@ -244,19 +408,47 @@ pub fn capture_thread(
                     paused = false;
                 },
 
-                MainToCaptureThreadMsg::StartRecording((sender, limit)) => {
-                    rec_data = Some(RecData{ sender, limit, tstart: std::time::Instant::now(), frame_counter: 0 });
+                MainToCaptureThreadMsg::StartRecording((sender, limit, camera_timestamps, max_fps)) => {
+                    rec_data = Some(RecData{
+                        sender,
+                        limit,
+                        tstart: std::time::Instant::now(),
+                        frame_counter: 0,
+                        paused_since: None,
+                        paused_total: std::time::Duration::ZERO,
+                        camera_timestamps,
+                        camera_clock_origin: None,
+                        frame_rate_limiter: max_fps.map(|fps| FrameRateLimiter::new(fps))
+                    });
                 },
 
                 MainToCaptureThreadMsg::StopRecording => {
                     if rec_data.is_some() {
-                        rec_data.take().unwrap().sender.send(recording::CaptureToRecordingThreadMsg::Finished).unwrap();
+                        let _ = rec_data.take().unwrap().sender.send(recording::CaptureToRecordingThreadMsg::Finished);
+                    }
+                },
+
+                MainToCaptureThreadMsg::PauseRecording => if let Some(data) = rec_data.as_mut() {
+                    if data.paused_since.is_none() {
+                        data.paused_since = Some(std::time::Instant::now());
+                    }
+                },
+
+                MainToCaptureThreadMsg::ResumeRecording => if let Some(data) = rec_data.as_mut() {
+                    if let Some(t) = data.paused_since.take() {
+                        data.paused_total += t.elapsed();
                     }
                 },
 
-                MainToCaptureThreadMsg::EnableCentroidTracking(rect) => {
+                MainToCaptureThreadMsg::EnableCentroidTracking((rect, min_snr)) => {
                     if let Some(idx) = most_recently_captured_buf_idx {
-                        tracking = Some(ImageTracker::new_with_centroid(rect, &capture_buf[idx]));
+                        tracking = Some(ImageTracker::new_with_centroid(rect, &capture_buf[idx], min_snr));
+                    }
+                },
+
+                MainToCaptureThreadMsg::ResizeCentroidArea(delta) => {
+                    if let (Some(tracker), Some(idx)) = (tracking.as_mut(), most_recently_captured_buf_idx) {
+                        tracker.resize_centroid_area(delta, &capture_buf[idx]);
                     }
                 },
 
@@ -275,19 +467,22 @@ pub fn capture_thread(
                     crop_data = Some(CropData{ tracking_pos_offset, area });
                 },
 
-                MainToCaptureThreadMsg::DisableRecordingCrop => crop_data = None
+                MainToCaptureThreadMsg::DisableRecordingCrop => crop_data = None,
+
+                MainToCaptureThreadMsg::SetFrameFeed(sender) => frame_feed = sender
             }
         }
     }
 
     if let Some(data) = rec_data {
-        data.sender.send(recording::CaptureToRecordingThreadMsg::Finished).unwrap();
+        let _ = data.sender.send(recording::CaptureToRecordingThreadMsg::Finished);
     }
 }
 
 fn on_tracking(
     tracking: &mut ImageTracker,
     image: &Arc<Image>,
+    captured: std::time::SystemTime,
     sender: &glib::Sender<CaptureToMainThreadMsg>,
     crop_data: &mut Option<CropData>
 ) -> Result<(), ()> {
@@ -321,7 +516,13 @@ fn on_tracking(
         }
 
         sender.send(CaptureToMainThreadMsg::TrackingUpdate((
-            TrackingData{ pos: tracking_pos, mode },
+            TrackingData{
+                pos: tracking_pos,
+                subpixel_pos: tracking.subpixel_position().unwrap(),
+                snr: tracking.snr(),
+                mode,
+                captured
+            },
             match crop_data { Some(crop_data) => Some(crop_data.area), None => None }
         ))).unwrap();
 
@@ -333,7 +534,9 @@ fn on_tracking(
 fn on_recording(
     rec_data: &mut RecData,
     image: &Arc<Image>,
+    camera_timestamp: Option<std::time::Duration>,
     buffered_kib: &Arc<AtomicIsize>,
+    max_buffered_kib: isize,
     info: Option<&mut Info>,
     num_dropped_frames: &mut usize,
     crop_data: &Option<CropData>
@@ -345,50 +548,164 @@ fn on_recording(
         num_img_pixels
     };
     let frame_kib_amount = num_frag_pixels * image.num_pixel_bytes_without_padding() / num_img_pixels / 1024;
-    if buffered_kib.load(Ordering::Relaxed) <= recording::MAX_BUFFERED_KIB {
-        if rec_data.sender.send(recording::CaptureToRecordingThreadMsg::Captured((
-            Arc::clone(image),
-            if let Some(crop_data) = crop_data { crop_data.area } else { image.img_rect() },
-            std::time::SystemTime::now()
-        ))).is_err() {
-            return RecordingStillRunning(false);
-        }
-        rec_data.frame_counter += 1;
-        buffered_kib.fetch_add(frame_kib_amount as isize, Ordering::Relaxed);
-    } else {
-        match rec_data.limit {
-            recording::Limit::Duration(_) => *num_dropped_frames += 1,
-            _ => ()
+    // frames exceeding the frame rate limit are skipped on purpose and not counted as dropped
+    let within_frame_rate_limit = match rec_data.frame_rate_limiter.as_mut() {
+        Some(limiter) => limiter.accept(std::time::Instant::now()),
+        None => true
+    };
+    if within_frame_rate_limit {
+        if buffered_kib.load(Ordering::Relaxed) <= max_buffered_kib {
+            let timestamp = rec_data.frame_timestamp(camera_timestamp);
+            if rec_data.sender.send(recording::CaptureToRecordingThreadMsg::Captured((
+                Arc::clone(image),
+                if let Some(crop_data) = crop_data { crop_data.area } else { image.img_rect() },
+                timestamp
+            ))).is_err() {
+                return RecordingStillRunning(false);
+            }
+            rec_data.frame_counter += 1;
+            buffered_kib.fetch_add(frame_kib_amount as isize, Ordering::Relaxed);
+        } else {
+            match rec_data.limit {
+                recording::Limit::Duration(_) => *num_dropped_frames += 1,
+                _ => ()
+            }
         }
     }
 
     if let Some(info) = info {
-        info.recording_info = Some(match rec_data.limit {
-            recording::Limit::FrameCount(count) => {
-                format!("Recorded {}/{} frames", rec_data.frame_counter, count)
-            },
-            recording::Limit::Duration(duration) => {
-                let total_secs_left = {
-                    let elapsed = rec_data.tstart.elapsed();
-                    if duration > elapsed {
-                        (duration - elapsed).as_secs()
-                    } else {
-                        0
-                    }
-                };
-                let hh = total_secs_left / 3600;
-                let mm = (total_secs_left % 3600) / 60;
-                let ss = ((total_secs_left % 3600) % 60) % 60;
-                format!(
-                    "Recorded {} frames ({} dropped), time left: {:02}:{:02}:{:02}",
-                    rec_data.frame_counter, num_dropped_frames, hh, mm, ss
-                )
-            },
-            recording::Limit::Forever => {
-                format!("Recorded {} frames", rec_data.frame_counter)
-            }
-        });
+        info.recording_info = Some(recording_progress(
+            &rec_data.limit,
+            rec_data.frame_counter,
+            *num_dropped_frames,
+            rec_data.elapsed()
+        ));
     }
 
     RecordingStillRunning(true)
 }
+
+fn frame_info(
+    frame_id: Option<u64>,
+    frame_id_tracker: &FrameIdTracker,
+    camera_timestamp: Option<std::time::Duration>
+) -> Option<String> {
+    let mut items = vec![];
+    if let Some(frame_id) = frame_id {
+        items.push(format!("frame ID: {} (skipped: {})", frame_id, frame_id_tracker.num_skipped));
+    }
+    if let Some(timestamp) = camera_timestamp {
+        items.push(format!("camera time: {:.3} s", timestamp.as_secs_f64()));
+    }
+
+    if items.is_empty() { None } else { Some(items.join(", ")) }
+}
+
+fn format_hh_mm_ss(total_secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", total_secs / 3600, (total_secs % 3600) / 60, total_secs % 60)
+}
+
+/// Returns a description of the recording's progress towards its limit.
+fn recording_progress(
+    limit: &recording::Limit,
+    frame_counter: usize,
+    num_dropped_frames: usize,
+    elapsed: std::time::Duration
+) -> String {
+    match limit {
+        recording::Limit::FrameCount(count) => {
+            let percent = if *count > 0 { 100 * frame_counter / count } else { 100 };
+            // estimated from the average recording frame rate so far
+            let time_left = if frame_counter > 0 {
+                let secs_left = elapsed.as_secs_f64() * (count.saturating_sub(frame_counter)) as f64 / frame_counter as f64;
+                format!(", time left: {}", format_hh_mm_ss(secs_left.ceil() as u64))
+            } else {
+                "".to_string()
+            };
+            format!("Recorded {}/{} frames ({}%){}", frame_counter, count, percent, time_left)
+        },
+
+        recording::Limit::Duration(duration) => {
+            let total_secs_left = duration.saturating_sub(elapsed).as_secs();
+            let percent = if duration.as_secs_f64() > 0.0 {
+                (100.0 * elapsed.as_secs_f64() / duration.as_secs_f64()).min(100.0) as u32
+            } else {
+                100
+            };
+            format!(
+                "Recorded {} frames ({} dropped), {}%, time left: {}",
+                frame_counter, num_dropped_frames, percent, format_hh_mm_ss(total_secs_left)
+            )
+        },
+
+        // the elapsed time is added by the main thread (so that it runs also between frames)
+        recording::Limit::Forever => format!("Recorded {} frames", frame_counter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn given_frame_count_limit_progress_includes_estimated_time_left() {
+        assert_eq!(
+            "Recorded 320/1000 frames (32%), time left: 00:00:17",
+            recording_progress(&recording::Limit::FrameCount(1000), 320, 0, Duration::from_secs(8))
+        );
+        assert_eq!(
+            "Recorded 0/1000 frames (0%)",
+            recording_progress(&recording::Limit::FrameCount(1000), 0, 0, Duration::from_secs(0))
+        );
+    }
+
+    #[test]
+    fn given_frame_counter_gap_skipped_frames_are_counted() {
+        let mut tracker = FrameIdTracker::default();
+        for id in [10, 11, 14, 15, 17] {
+            tracker.update(id);
+        }
+        assert_eq!(3, tracker.num_skipped);
+
+        // counter reset
+        tracker.update(0);
+        tracker.update(1);
+        assert_eq!(3, tracker.num_skipped);
+    }
+
+    #[test]
+    fn given_camera_timestamps_they_are_relative_to_first_frame_host_time() {
+        let mut origin = None;
+        let t0 = std::time::UNIX_EPOCH + Duration::from_secs(1000);
+        assert_eq!(t0, camera_clock_timestamp(&mut origin, t0, Duration::from_millis(5000)));
+        assert_eq!(
+            t0 + Duration::from_millis(20),
+            camera_clock_timestamp(&mut origin, t0 + Duration::from_millis(25), Duration::from_millis(5020))
+        );
+    }
+
+    #[test]
+    fn given_frame_rate_limit_excess_frames_are_skipped() {
+        let mut limiter = FrameRateLimiter::new(30.0);
+        let t0 = std::time::Instant::now();
+        // 100 fps for 10 s
+        let num_accepted = (0..1000).filter(|i| limiter.accept(t0 + Duration::from_millis(i * 10))).count();
+        assert_eq!(300, num_accepted);
+    }
+
+    #[test]
+    fn given_frame_rate_limit_above_capture_rate_all_frames_are_recorded() {
+        let mut limiter = FrameRateLimiter::new(30.0);
+        let t0 = std::time::Instant::now();
+        assert!((0..100).all(|i| limiter.accept(t0 + Duration::from_millis(i * 50))));
+    }
+
+    #[test]
+    fn given_duration_limit_progress_includes_time_left() {
+        assert_eq!(
+            "Recorded 320 frames (2 dropped), 25%, time left: 00:01:30",
+            recording_progress(&recording::Limit::Duration(Duration::from_secs(120)), 320, 2, Duration::from_secs(30))
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Live frame feed thread.
+//!
+//! Serves captured frames over TCP to external consumers (e.g., EAA live stacking tools). Each frame is sent
+//! as a header followed by pixel data (rows without padding); all header values are little-endian:
+//!
+//!   - magic: "VDXF" (4 bytes)
+//!   - format version: u32 (`FORMAT_VERSION`)
+//!   - width: u32
+//!   - height: u32
+//!   - bytes per pixel: u32
+//!   - pixel data little-endian: u32 (1 or 0; relevant for more than 1 byte per channel)
+//!   - capture time: u64 (microseconds since the Unix epoch)
+//!   - pixel format name length: u32
+//!   - pixel format name (ASCII, e.g. "Mono8", "RGB16", "CfaRGGB16")
+//!
+//! Frames captured while the previous one is still being sent are skipped.
+//!
+
+use crossbeam::channel;
+use ga_image::Image;
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+
+const MAGIC: &[u8; 4] = b"VDXF";
+
+const FORMAT_VERSION: u32 = 1;
+
+/// Interval of checking for new clients (and for the finish request) while no frames arrive.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Clients not accepting a frame within this time are disconnected.
+const CLIENT_WRITE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Accepts captured frames (copies owned by the feed, so that capture buffers can be reused) and their capture times.
+pub type FrameSender = channel::Sender<(Image, std::time::SystemTime)>;
+
+pub struct FrameFeed {
+    sender: FrameSender,
+    finish: Arc<AtomicBool>,
+    address: SocketAddr
+}
+
+impl FrameFeed {
+    /// Starts serving frames at `address` (e.g. "127.0.0.1:5210").
+    pub fn start(address: &str) -> Result<FrameFeed, std::io::Error> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let (sender, receiver) = channel::bounded(1);
+        let finish = Arc::new(AtomicBool::new(false));
+        let finish_clone = Arc::clone(&finish);
+        // the thread is detached: it may be sending a frame to a slow client, so joining it from the main thread
+        // (when stopping the feed) could block it for up to `CLIENT_WRITE_TIMEOUT` per client
+        std::thread::spawn(move || feed_thread(listener, receiver, finish_clone));
+
+        log::info!("live frame feed started at {}", address);
+
+        Ok(FrameFeed{ sender, finish, address })
+    }
+
+    pub fn sender(&self) -> FrameSender { self.sender.clone() }
+
+    pub fn address(&self) -> SocketAddr { self.address }
+}
+
+impl Drop for FrameFeed {
+    fn drop(&mut self) {
+        // the feed thread ends after sending the current frame (if any)
+        self.finish.store(true, Ordering::Relaxed);
+        log::info!("live frame feed at {} stopped", self.address);
+    }
+}
+
+fn feed_thread(
+    listener: TcpListener,
+    receiver: channel::Receiver<(Image, std::time::SystemTime)>,
+    finish: Arc<AtomicBool>
+) {
+    let mut clients: Vec<(TcpStream, SocketAddr)> = vec![];
+
+    while !finish.load(Ordering::Relaxed) {
+        loop {
+            match listener.accept() {
+                Ok((stream, client_addr)) => match configure_client(&stream) {
+                    Ok(()) => {
+                        log::info!("live frame feed: client {} connected", client_addr);
+                        clients.push((stream, client_addr));
+                    },
+                    Err(e) => log::warn!("live frame feed: failed to set up connection with {}: {}", client_addr, e)
+                },
+
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+
+                Err(e) => { log::error!("live frame feed: failed to accept connection: {}", e); break; }
+            }
+        }
+
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok((image, captured)) => clients.retain(|(stream, client_addr)| {
+                let mut stream: &TcpStream = stream;
+                match write_frame(&mut stream, &image, captured) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        log::info!("live frame feed: client {} disconnected ({})", client_addr, e);
+                        false
+                    }
+                }
+            }),
+
+            Err(channel::RecvTimeoutError::Timeout) => (),
+
+            Err(channel::RecvTimeoutError::Disconnected) => break
+        }
+    }
+}
+
+fn configure_client(stream: &TcpStream) -> Result<(), std::io::Error> {
+    stream.set_nonblocking(false)?;
+    stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+    stream.set_nodelay(true)
+}
+
+/// Writes frame header and pixel data (see the module description).
+fn write_frame<W: Write>(writer: &mut W, image: &Image, captured: std::time::SystemTime) -> Result<(), std::io::Error> {
+    let pix_fmt_name = format!("{:?}", image.pixel_format());
+    let captured_us = captured.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+    let little_endian = !ga_image::utils::is_machine_big_endian();
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&image.width().to_le_bytes())?;
+    writer.write_all(&image.height().to_le_bytes())?;
+    writer.write_all(&(image.pixel_format().bytes_per_pixel() as u32).to_le_bytes())?;
+    writer.write_all(&(little_endian as u32).to_le_bytes())?;
+    writer.write_all(&captured_us.to_le_bytes())?;
+    writer.write_all(&(pix_fmt_name.len() as u32).to_le_bytes())?;
+    writer.write_all(pix_fmt_name.as_bytes())?;
+
+    let view = image.view();
+    for y in 0..image.height() {
+        writer.write_all(view.line_raw(y))?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ga_image::PixelFormat;
+    use std::convert::TryInto;
+
+    #[test]
+    fn frame_consists_of_header_and_unpadded_pixel_data() {
+        let mut image = Image::new(3, 2, None, PixelFormat::Mono8, None, true);
+        for y in 0..2 {
+            for (x, value) in image.line_mut::<u8>(y).iter_mut().enumerate() { *value = (10 * y as usize + x) as u8; }
+        }
+        let captured = std::time::UNIX_EPOCH + std::time::Duration::from_micros(1_000_002);
+
+        let mut output = vec![];
+        write_frame(&mut output, &image, captured).unwrap();
+
+        let u32_at = |offset: usize| u32::from_le_bytes(output[offset..offset + 4].try_into().unwrap());
+        assert_eq!(b"VDXF", &output[0..4]);
+        assert_eq!(FORMAT_VERSION, u32_at(4));
+        assert_eq!(3, u32_at(8));
+        assert_eq!(2, u32_at(12));
+        assert_eq!(1, u32_at(16));
+        assert_eq!(1_000_002, u64::from_le_bytes(output[24..32].try_into().unwrap()));
+        assert_eq!(5, u32_at(32));
+        assert_eq!(b"Mono8", &output[36..41]);
+        assert_eq!(&[0, 1, 2, 10, 11, 12], &output[41..]);
+    }
+}
@@ -16,7 +16,11 @@ use ga_image::Rect;
 #[derive(Debug)]
 pub struct HistogramRequest {
     pub image: Image,
-    pub fragment: Option<Rect>
+    pub fragment: Option<Rect>,
+    /// Capture time of `image`.
+    pub captured: std::time::Instant,
+    /// Requested number of bins (one of `NUM_BINS_CHOICES`); limited to the image's number of distinct values.
+    pub num_bins: usize
 }
 
 #[derive(Debug)]
@@ -24,21 +28,127 @@ pub enum MainToHistogramThreadMsg {
     CalculateHistogram(HistogramRequest)
 }
 
+/// Histogram bin counts offered to the user.
+pub const NUM_BINS_CHOICES: [usize; 3] = [256, 512, 1024];
+
+pub const DEFAULT_NUM_BINS: usize = 256;
+
 const RED: usize = 0;
 const GREEN: usize = 1;
 const BLUE: usize = 2;
 
+/// Fractions of pixel values at the ends of the full range.
+#[derive(Copy, Clone, Debug)]
+pub struct Clipping {
+    /// Fraction of values equal to 0.
+    pub black: f64,
+    /// Fraction of values equal to the maximum.
+    pub white: f64
+}
+
+/// Statistics of a channel's values (as fractions of the full range).
+#[derive(Copy, Clone, Debug)]
+pub struct ChannelStats {
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64
+}
+
 pub struct Histogram {
     /// True if the histogram was calculated from color or raw color (CFA) image.
     is_rgb: bool,
     /// Each element contains 3 values for each of RGB channels; for mono images, R=G=B.
-    values: [[usize; 3]; 256]
+    values: Vec<[usize; 3]>,
+    /// Capture time of the image the histogram was calculated from.
+    captured: Option<std::time::Instant>,
+    /// Calculated from all channels; `None` if not available for the image's pixel format.
+    clipping: Option<Clipping>
 }
 
 impl Histogram {
-    pub fn new() -> Histogram { Histogram{ is_rgb: false, values: [[0usize; 3]; 256] } }
+    pub fn new() -> Histogram {
+        Histogram{ is_rgb: false, values: vec![[0usize; 3]; DEFAULT_NUM_BINS], captured: None, clipping: None }
+    }
+
     pub fn values(&self) -> &[[usize; 3]] { &self.values }
     pub fn is_rgb(&self) -> bool { self.is_rgb }
+    pub fn captured(&self) -> Option<std::time::Instant> { self.captured }
+    pub fn clipping(&self) -> Option<Clipping> { self.clipping }
+
+    /// Returns mean value (of all channels) as fraction of the full range; `None` if the histogram is empty.
+    pub fn mean(&self) -> Option<f64> {
+        let mut count = 0;
+        let mut sum = 0;
+        for (value, counts) in self.values.iter().enumerate() {
+            let num_values: usize = counts.iter().sum();
+            count += num_values;
+            sum += value * num_values;
+        }
+
+        if count == 0 { None } else { Some(sum as f64 / count as f64 / (self.values.len() - 1) as f64) }
+    }
+
+    /// Returns statistics of `channel` (0-2; for mono images all channels are the same) based on the bin values;
+    /// `None` if the histogram is empty.
+    pub fn channel_stats(&self, channel: usize) -> Option<ChannelStats> {
+        let total: usize = self.values.iter().map(|counts| counts[channel]).sum();
+        if total == 0 { return None; }
+
+        let max_value = (self.values.len() - 1).max(1) as f64;
+
+        let mean = self.values.iter().enumerate()
+            .map(|(value, counts)| value as f64 * counts[channel] as f64)
+            .sum::<f64>() / total as f64;
+
+        let variance = self.values.iter().enumerate()
+            .map(|(value, counts)| (value as f64 - mean).powi(2) * counts[channel] as f64)
+            .sum::<f64>() / total as f64;
+
+        let mut cumulative = 0;
+        let mut median = self.values.len() - 1;
+        for (value, counts) in self.values.iter().enumerate() {
+            cumulative += counts[channel];
+            if 2 * cumulative >= total {
+                median = value;
+                break;
+            }
+        }
+
+        Some(ChannelStats{ mean: mean / max_value, median: median as f64 / max_value, std_dev: variance.sqrt() / max_value })
+    }
+
+    /// Returns the bin counts as CSV (one row per bin; the value is the bin's lower bound as fraction
+    /// of the full range).
+    pub fn to_csv(&self) -> String {
+        let max_value = (self.values.len() - 1).max(1) as f64;
+        let mut csv = if self.is_rgb { "bin,value,red,green,blue\n" } else { "bin,value,count\n" }.to_string();
+        for (bin, counts) in self.values.iter().enumerate() {
+            let value = bin as f64 / max_value;
+            if self.is_rgb {
+                csv += &format!("{},{:.6},{},{},{}\n", bin, value, counts[RED], counts[GREEN], counts[BLUE]);
+            } else {
+                csv += &format!("{},{:.6},{}\n", bin, value, counts[0]);
+            }
+        }
+
+        csv
+    }
+
+    /// Returns the value (as fraction of the full range) not exceeded by 99.9% of values (so that hot pixels
+    /// are ignored); `None` if the histogram is empty.
+    pub fn peak(&self) -> Option<f64> {
+        let counts: Vec<usize> = self.values.iter().map(|counts| counts.iter().sum()).collect();
+        let total: usize = counts.iter().sum();
+        if total == 0 { return None; }
+
+        let mut cumulative = 0;
+        for (value, count) in counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative as f64 >= 0.999 * total as f64 { return Some(value as f64 / (counts.len() - 1) as f64); }
+        }
+
+        Some(1.0)
+    }
 }
 
 pub fn histogram_thread(
@@ -63,8 +173,12 @@ pub fn histogram_thread(
 fn calculate_histogram(hist_request: HistogramRequest) -> Histogram {
     let img_view = ImageView::new(&hist_request.image, hist_request.fragment);
 
-    let mut values = [[0usize; 3]; 256];
     let is_rgb = !img_view.pixel_format().is_mono();
+    let bits_per_channel = 8 * img_view.pixel_format().bytes_per_channel() as u32;
+    let bin_bits = hist_request.num_bins.max(1).next_power_of_two().trailing_zeros().min(bits_per_channel.min(16));
+    // values are shifted right by this many bits to obtain the bin index
+    let shift = bits_per_channel - bin_bits;
+    let mut values = vec![[0usize; 3]; 1 << bin_bits];
 
     match img_view.pixel_format() {
         PixelFormat::Mono8 => {
@@ -72,7 +186,7 @@ fn calculate_histogram(hist_request: HistogramRequest) -> Histogram {
                 let line = img_view.line::<u8>(y);
                 for p in line {
                     for i in 0..3 {
-                        unsafe { values.get_unchecked_mut(*p as usize)[i] += 1; }
+                        unsafe { values.get_unchecked_mut((*p >> shift) as usize)[i] += 1; }
                     }
                 }
             }
@@ -83,7 +197,7 @@ fn calculate_histogram(hist_request: HistogramRequest) -> Histogram {
                 let line = img_view.line::<u16>(y);
                 for p in line {
                     for i in 0..3 {
-                        unsafe { values.get_unchecked_mut((*p >> 8) as usize)[i] += 1; }
+                        unsafe { values.get_unchecked_mut((*p >> shift) as usize)[i] += 1; }
                     }
                 }
             }
@@ -94,9 +208,9 @@ fn calculate_histogram(hist_request: HistogramRequest) -> Histogram {
                 let line = img_view.line::<u8>(y);
                 for i in (0..line.len()).step_by(3) {
                     unsafe {
-                        values.get_unchecked_mut(*line.get_unchecked(i    ) as usize)[RED] += 1;
-                        values.get_unchecked_mut(*line.get_unchecked(i + 1) as usize)[GREEN] += 1;
-                        values.get_unchecked_mut(*line.get_unchecked(i + 2) as usize)[BLUE] += 1;
+                        values.get_unchecked_mut((*line.get_unchecked(i    ) >> shift) as usize)[RED] += 1;
+                        values.get_unchecked_mut((*line.get_unchecked(i + 1) >> shift) as usize)[GREEN] += 1;
+                        values.get_unchecked_mut((*line.get_unchecked(i + 2) >> shift) as usize)[BLUE] += 1;
                     }
                 }
             }
@@ -104,20 +218,54 @@ fn calculate_histogram(hist_request: HistogramRequest) -> Histogram {
 
         _ => if img_view.pixel_format().is_cfa() {
             if img_view.pixel_format().bytes_per_channel() == 1 {
-                count_cfa_values::<u8>(&mut values, &img_view);
+                count_cfa_values::<u8>(&mut values, &img_view, shift);
             } else if img_view.pixel_format().bytes_per_channel() == 2 {
-                //FIXME: need to scale down values to 0-255 before increasing the counters
-                //count_cfa_values::<u16>(&mut values, &img_view);
+                count_cfa_values::<u16>(&mut values, &img_view, shift);
             }
         }
 
         //TODO: implement for other formats
     }
 
-    Histogram{ is_rgb, values }
+    let clipping = match img_view.pixel_format().bytes_per_channel() {
+        1 => calculate_clipping::<u8>(&img_view, u8::MAX),
+        2 => calculate_clipping::<u16>(&img_view, u16::MAX),
+        _ => None
+    };
+
+    Histogram{ is_rgb, values, captured: Some(hist_request.captured), clipping }
 }
 
-fn count_cfa_values<T: 'static + Copy + Default + Into<usize>>(values: &mut [[usize; 3]; 256], img_view: &ImageView) {
+/// Returns `None` if `img_view` is empty.
+fn calculate_clipping<T: 'static + Copy + Default + PartialEq>(img_view: &ImageView, max: T) -> Option<Clipping> {
+    let mut num_black = 0;
+    let mut num_white = 0;
+    let mut total = 0;
+    for y in 0..img_view.height() {
+        let line = img_view.line::<T>(y);
+        for value in line {
+            if *value == T::default() {
+                num_black += 1;
+            } else if *value == max {
+                num_white += 1;
+            }
+        }
+        total += line.len();
+    }
+
+    if total == 0 {
+        None
+    } else {
+        Some(Clipping{ black: num_black as f64 / total as f64, white: num_white as f64 / total as f64 })
+    }
+}
+
+/// Values are shifted right by `shift` bits to fit in `values`.
+fn count_cfa_values<T: 'static + Copy + Default + Into<usize>>(
+    values: &mut [[usize; 3]],
+    img_view: &ImageView,
+    shift: u32
+) {
     let cfa = img_view.pixel_format().cfa_pattern();
     let dx_r = cfa.red_col_ofs() as usize;
     let dy_r = cfa.red_row_ofs() as usize;
@@ -128,21 +276,91 @@ fn count_cfa_values<T: 'static + Copy + Default + Into<usize>>(values: &mut [[us
         if (y + dy_r as u32) < img_view.height() {
             let line_red = img_view.line::<T>(y + dy_r as u32);
             for red in line_red.iter().skip(dx_r).step_by(2) {
-                unsafe { values.get_unchecked_mut(Into::<usize>::into(*red))[RED] += 1; }
+                unsafe { values.get_unchecked_mut(Into::<usize>::into(*red) >> shift)[RED] += 1; }
             }
             for green in line_red.iter().skip(dx_r ^ 1).step_by(2) {
-                unsafe { values.get_unchecked_mut(Into::<usize>::into(*green))[GREEN] += 1; }
+                unsafe { values.get_unchecked_mut(Into::<usize>::into(*green) >> shift)[GREEN] += 1; }
             }
         }
 
         if (y + dy_b as u32) < img_view.height() {
             let line_blue = img_view.line::<T>(y + dy_b as u32);
             for blue in line_blue.iter().skip(dx_b).step_by(2) {
-                unsafe { values.get_unchecked_mut(Into::<usize>::into(*blue))[BLUE] += 1; }
+                unsafe { values.get_unchecked_mut(Into::<usize>::into(*blue) >> shift)[BLUE] += 1; }
             }
             for green in line_blue.iter().skip(dx_b ^ 1).step_by(2) {
-                unsafe { values.get_unchecked_mut(Into::<usize>::into(*green))[GREEN] += 1; }
+                unsafe { values.get_unchecked_mut(Into::<usize>::into(*green) >> shift)[GREEN] += 1; }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mono16_histogram(pixels: &[u16], num_bins: usize) -> Histogram {
+        let mut image = Image::new(pixels.len() as u32, 1, None, PixelFormat::Mono16, None, true);
+        image.line_mut::<u16>(0).copy_from_slice(pixels);
+        calculate_histogram(HistogramRequest{ image, fragment: None, captured: std::time::Instant::now(), num_bins })
+    }
+
+    #[test]
+    fn given_more_bins_close_16_bit_values_are_separated() {
+        let pixels = [0x8000, 0x8040, 0x8080, 0x80C0];
+
+        let coarse = mono16_histogram(&pixels, 256);
+        assert_eq!(256, coarse.values().len());
+        assert_eq!(4, coarse.values()[0x80][0]);
+
+        let fine = mono16_histogram(&pixels, 1024);
+        assert_eq!(1024, fine.values().len());
+        for bin in 0x200..0x204 {
+            assert_eq!(1, fine.values()[bin][0]);
+        }
+    }
+
+    #[test]
+    fn channel_stats_are_calculated_from_bins() {
+        let mut histogram = Histogram::new();
+        histogram.values[51] = [1, 0, 0];
+        histogram.values[102] = [2, 0, 0];
+        histogram.values[153] = [1, 0, 0];
+
+        let stats = histogram.channel_stats(RED).unwrap();
+        assert!((stats.mean - 0.4).abs() < 1.0e-9);
+        assert!((stats.median - 0.4).abs() < 1.0e-9);
+        assert!((stats.std_dev - (0.5f64).sqrt() * 0.2).abs() < 1.0e-9);
+
+        assert!(histogram.channel_stats(GREEN).is_none());
+    }
+
+    #[test]
+    fn given_mono_histogram_csv_has_one_count_column() {
+        let histogram = mono16_histogram(&[0, 0, 0xFFFF], 256);
+        let csv = histogram.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(257, lines.len());
+        assert_eq!("bin,value,count", lines[0]);
+        assert_eq!("0,0.000000,2", lines[1]);
+        assert_eq!("255,1.000000,1", lines[256]);
+    }
+
+    #[test]
+    fn clipping_counts_values_at_range_ends() {
+        let histogram = mono16_histogram(&[0, 0, 100, 0xFFFF, 0xFFFF, 0xFFFF, 0xFFFE, 200], 256);
+        let clipping = histogram.clipping().unwrap();
+        assert_eq!(0.25, clipping.black);
+        assert_eq!(0.375, clipping.white);
+    }
+
+    #[test]
+    fn given_8_bit_image_bin_count_is_limited_to_256() {
+        let image = Image::new(4, 1, None, PixelFormat::Mono8, None, true);
+        let histogram = calculate_histogram(HistogramRequest{
+            image, fragment: None, captured: std::time::Instant::now(), num_bins: 1024
+        });
+        assert_eq!(256, histogram.values().len());
+        assert_eq!(4, histogram.values()[0][0]);
+    }
+}
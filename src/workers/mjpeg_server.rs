@@ -0,0 +1,203 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! MJPEG preview server thread.
+//!
+//! Serves the preview (encoded as JPEG by the main thread) over HTTP as a `multipart/x-mixed-replace` stream,
+//! which web browsers show as live video. Every request receives the stream, regardless of its path.
+//!
+
+use crossbeam::channel;
+use std::cell::Cell;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, atomic::{AtomicBool, AtomicUsize, Ordering}};
+
+const BOUNDARY: &str = "vidoxide-preview";
+
+/// Max. rate of frames sent to clients.
+const MAX_FPS: f64 = 5.0;
+
+/// Interval of checking for new clients (and for the finish request) while no frames arrive.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Max. time of receiving the request and sending a frame; slower clients are disconnected.
+const CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Max. accepted size of request headers.
+const MAX_REQUEST_SIZE: usize = 8192;
+
+pub struct MjpegServer {
+    sender: channel::Sender<Arc<Vec<u8>>>,
+    num_clients: Arc<AtomicUsize>,
+    finish: Arc<AtomicBool>,
+    address: SocketAddr,
+    t_last_frame: Cell<Option<std::time::Instant>>
+}
+
+impl MjpegServer {
+    /// Starts serving at `address` (e.g. "127.0.0.1:8090").
+    pub fn start(address: &str) -> Result<MjpegServer, std::io::Error> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let (sender, receiver) = channel::bounded(1);
+        let num_clients = Arc::new(AtomicUsize::new(0));
+        let finish = Arc::new(AtomicBool::new(false));
+        {
+            let num_clients = Arc::clone(&num_clients);
+            let finish = Arc::clone(&finish);
+            // the thread is detached: it may be sending a frame to a slow client, so joining it from the main thread
+            // (when stopping the server) could block it for up to `CLIENT_TIMEOUT` per client
+            std::thread::spawn(move || server_thread(listener, receiver, num_clients, finish));
+        }
+
+        log::info!("MJPEG preview server started at {}", address);
+
+        Ok(MjpegServer{
+            sender,
+            num_clients,
+            finish,
+            address,
+            t_last_frame: Cell::new(None)
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr { self.address }
+
+    pub fn has_clients(&self) -> bool { self.num_clients.load(Ordering::Relaxed) > 0 }
+
+    /// Returns true if a new frame should be encoded and sent (there are clients and `MAX_FPS` is not exceeded).
+    pub fn frame_wanted(&self) -> bool {
+        self.has_clients() && match self.t_last_frame.get() {
+            Some(t) => t.elapsed().as_secs_f64() >= 1.0 / MAX_FPS,
+            None => true
+        }
+    }
+
+    /// Sends a JPEG-encoded frame to clients (skipped if the previous one is still being sent).
+    pub fn send_frame(&self, jpeg: Vec<u8>) {
+        self.t_last_frame.set(Some(std::time::Instant::now()));
+        let _ = self.sender.try_send(Arc::new(jpeg));
+    }
+}
+
+impl Drop for MjpegServer {
+    fn drop(&mut self) {
+        // the server thread ends after sending the current frame (if any)
+        self.finish.store(true, Ordering::Relaxed);
+        log::info!("MJPEG preview server at {} stopped", self.address);
+    }
+}
+
+fn server_thread(
+    listener: TcpListener,
+    receiver: channel::Receiver<Arc<Vec<u8>>>,
+    num_clients: Arc<AtomicUsize>,
+    finish: Arc<AtomicBool>
+) {
+    let mut clients: Vec<(TcpStream, SocketAddr)> = vec![];
+
+    while !finish.load(Ordering::Relaxed) {
+        loop {
+            match listener.accept() {
+                Ok((stream, client_addr)) => match start_stream(&stream) {
+                    Ok(()) => {
+                        log::info!("MJPEG preview server: client {} connected", client_addr);
+                        clients.push((stream, client_addr));
+                    },
+                    Err(e) => log::warn!("MJPEG preview server: failed to respond to {}: {}", client_addr, e)
+                },
+
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+
+                Err(e) => { log::error!("MJPEG preview server: failed to accept connection: {}", e); break; }
+            }
+        }
+        num_clients.store(clients.len(), Ordering::Relaxed);
+
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(jpeg) => {
+                let part = multipart_frame(&jpeg);
+                clients.retain(|(stream, client_addr)| {
+                    let mut stream: &TcpStream = stream;
+                    match stream.write_all(&part).and_then(|_| stream.flush()) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            log::info!("MJPEG preview server: client {} disconnected ({})", client_addr, e);
+                            false
+                        }
+                    }
+                });
+                num_clients.store(clients.len(), Ordering::Relaxed);
+            },
+
+            Err(channel::RecvTimeoutError::Timeout) => (),
+
+            Err(channel::RecvTimeoutError::Disconnected) => break
+        }
+    }
+}
+
+/// Receives the client's request and responds with the stream's headers.
+fn start_stream(stream: &TcpStream) -> Result<(), std::io::Error> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_nodelay(true)?;
+
+    let mut stream: &TcpStream = stream;
+    let mut request = vec![];
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let num_read = stream.read(&mut buf)?;
+        if num_read == 0 || request.len() + num_read > MAX_REQUEST_SIZE {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "incomplete or too long request"));
+        }
+        request.extend_from_slice(&buf[..num_read]);
+    }
+
+    stream.write_all(stream_response_header().as_bytes())?;
+    stream.flush()
+}
+
+fn stream_response_header() -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\n\
+        Content-Type: multipart/x-mixed-replace; boundary={}\r\n\
+        Cache-Control: no-cache, no-store\r\n\
+        Connection: close\r\n\r\n",
+        BOUNDARY
+    )
+}
+
+/// Returns a part of the multipart stream containing a single JPEG frame.
+fn multipart_frame(jpeg: &[u8]) -> Vec<u8> {
+    let mut part = format!(
+        "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n", BOUNDARY, jpeg.len()
+    ).into_bytes();
+    part.extend_from_slice(jpeg);
+    part.extend_from_slice(b"\r\n");
+    part
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_part_contains_boundary_headers_and_data() {
+        let part = multipart_frame(&[0xFF, 0xD8, 0xFF, 0xD9]);
+        let expected_header = format!("--{}\r\nContent-Type: image/jpeg\r\nContent-Length: 4\r\n\r\n", BOUNDARY);
+        assert!(part.starts_with(expected_header.as_bytes()));
+        assert!(part.ends_with(&[0xFF, 0xD8, 0xFF, 0xD9, b'\r', b'\n']));
+        assert!(stream_response_header().contains(&format!("boundary={}", BOUNDARY)));
+    }
+}
@@ -13,5 +13,8 @@
 pub mod capture;
 #[cfg(feature = "controller")]
 pub mod controller;
+pub mod frame_feed;
 pub mod histogram;
+pub mod mjpeg_server;
 pub mod recording;
+pub mod remote_control;
@@ -10,14 +10,23 @@
 //! Recording thread.
 //!
 
-use crate::output::OutputWriter;
+use crate::output::{OutputWriter, quality_log::QualityLog};
+use crate::quality;
+use crate::session_stats::SessionStats;
 use crossbeam;
 use ga_image::Rect;
 use ga_image::{Image, ImageView};
+use std::collections::BinaryHeap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::atomic::{AtomicIsize, AtomicU32, Ordering};
 
-pub const MAX_BUFFERED_KIB: isize = 2 * 1024 * 1024;
+/// Default maximum amount of captured image data waiting to be recorded; if exceeded, new frames are dropped.
+pub const DEFAULT_MAX_BUFFERED_MIB: i32 = 2 * 1024;
+
+/// Default interval of decreasing the buffered data amount by what has been written (and of reporting the write rate).
+pub const DEFAULT_UPDATE_INTERVAL_MS: u32 = 1000;
+
+pub const MIN_UPDATE_INTERVAL_MS: u32 = 50;
 
 #[derive(Debug)]
 pub enum Limit {
@@ -26,6 +35,17 @@ pub enum Limit {
     Forever
 }
 
+/// Selects the recorded frames which are written to the output.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FrameSelection {
+    All,
+    /// Only frames whose quality (see `quality::frame_quality`) is at least the given value.
+    QualityAbove(f64),
+    /// Only the given percentage of the sharpest frames; the candidates are kept in memory until the job ends
+    /// (and count towards the maximum amount of buffered data).
+    BestPercent(f64)
+}
+
 #[derive(Debug)]
 struct CfaState {
     crop_fragment_even_x_offset: bool,
@@ -36,15 +56,165 @@ struct CfaState {
 pub struct Job {
     receiver: crossbeam::channel::Receiver<CaptureToRecordingThreadMsg>,
     writer: Box<dyn OutputWriter>,
-    cfa_state: Option<CfaState>
+    cfa_state: Option<CfaState>,
+    /// If true, the output is verified after finalizing (see `OutputWriter::verify`).
+    verify_output: bool,
+    /// Receives the quality of each written frame (if enabled).
+    quality_log: Option<QualityLog>,
+    frame_selection: FrameSelection,
+    /// Frames waiting to be ranked (for `FrameSelection::BestPercent`).
+    best_frames: Option<BestFrames>
+}
+
+#[derive(Debug)]
+struct BufferedFrame {
+    image: Image,
+    timestamp: std::time::SystemTime,
+    quality: f64,
+    /// Position in capture order.
+    index: usize,
+    /// Amount of memory occupied.
+    kib: usize
+}
+
+// ordered so that the top of a `BinaryHeap` is the frame of the lowest quality
+impl PartialEq for BufferedFrame {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == std::cmp::Ordering::Equal }
+}
+
+impl Eq for BufferedFrame {}
+
+impl PartialOrd for BufferedFrame {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for BufferedFrame {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.quality.partial_cmp(&self.quality).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// Keeps the candidates for `FrameSelection::BestPercent`.
+#[derive(Debug)]
+struct BestFrames {
+    percent: f64,
+    /// Maximum number of kept frames (known if the number of recorded frames is limited).
+    max_kept: Option<usize>,
+    /// The top frame has the lowest quality.
+    frames: BinaryHeap<BufferedFrame>,
+    /// Number of frames added so far.
+    num_added: usize,
+    kept_kib: usize
+}
+
+impl BestFrames {
+    fn new(percent: f64, frame_limit: Option<usize>) -> BestFrames {
+        BestFrames{
+            percent,
+            max_kept: frame_limit.map(|limit| num_best_frames(limit, percent)),
+            frames: BinaryHeap::new(),
+            num_added: 0,
+            kept_kib: 0
+        }
+    }
+
+    /// Adds a frame; returns the amount of memory (in KiB) released by discarding the worst frame (if any).
+    fn add(&mut self, image: Image, timestamp: std::time::SystemTime, quality: f64, kib: usize) -> usize {
+        self.frames.push(BufferedFrame{ image, timestamp, quality, index: self.num_added, kib });
+        self.num_added += 1;
+        self.kept_kib += kib;
+
+        match self.max_kept {
+            Some(max_kept) if self.frames.len() > max_kept => {
+                let discarded = self.frames.pop().unwrap();
+                self.kept_kib -= discarded.kib;
+                discarded.kib
+            },
+            _ => 0
+        }
+    }
+
+    /// Removes all frames; returns the best ones in capture order.
+    fn take_best(&mut self) -> Vec<BufferedFrame> {
+        let count = num_best_frames(self.num_added, self.percent);
+        while self.frames.len() > count { self.frames.pop(); }
+        let mut best = std::mem::take(&mut self.frames).into_vec();
+        best.sort_by_key(|frame| frame.index);
+        self.num_added = 0;
+        self.kept_kib = 0;
+        best
+    }
 }
 
 impl Job {
     pub fn new(
         receiver: crossbeam::channel::Receiver<CaptureToRecordingThreadMsg>,
-        writer: Box<dyn OutputWriter>
+        writer: Box<dyn OutputWriter>,
+        verify_output: bool,
+        quality_log: Option<QualityLog>,
+        frame_selection: FrameSelection,
+        // number of frames to be recorded (if limited)
+        frame_limit: Option<usize>
     ) -> Job {
-        Job{ receiver, writer, cfa_state: None }
+        let best_frames = match frame_selection {
+            FrameSelection::BestPercent(percent) => Some(BestFrames::new(percent, frame_limit)),
+            _ => None
+        };
+        Job{ receiver, writer, cfa_state: None, verify_output, quality_log, frame_selection, best_frames }
+    }
+
+    /// Returns the amount of memory (in KiB) occupied by frames waiting to be ranked.
+    fn kept_kib(&self) -> usize {
+        self.best_frames.as_ref().map_or(0, |best_frames| best_frames.kept_kib)
+    }
+
+    fn needs_quality(&self) -> bool {
+        self.quality_log.is_some() || self.frame_selection != FrameSelection::All
+    }
+
+    /// Writes `frame` and adds its quality (if known) to the quality log.
+    fn write_frame(
+        &mut self,
+        frame: &ImageView,
+        timestamp: std::time::SystemTime,
+        quality: Option<f64>
+    ) -> Result<(), String> {
+        self.writer.write(frame, timestamp)?;
+        if let Some(quality) = quality { self.log_quality(quality); }
+        Ok(())
+    }
+
+    /// Writes the best of the buffered frames (in capture order); does nothing unless `FrameSelection::BestPercent`
+    /// is used.
+    fn write_best_frames(&mut self, session_stats: &SessionStats) -> Result<(), String> {
+        let (num_frames, best) = match &mut self.best_frames {
+            Some(best_frames) => (best_frames.num_added, best_frames.take_best()),
+            None => return Ok(())
+        };
+        log::info!("writing {} best of {} frames", best.len(), num_frames);
+        for frame in &best {
+            self.write_frame(&frame.image.view(), frame.timestamp, Some(frame.quality))?;
+            session_stats.add_recorded_frame(frame.image.num_pixel_bytes_without_padding() as u64);
+        }
+        Ok(())
+    }
+
+    /// Adds a written frame's quality to the quality log (if enabled); the log is disabled after an error.
+    fn log_quality(&mut self, quality: f64) {
+        if let Some(quality_log) = &mut self.quality_log {
+            if let Err(e) = quality_log.add(quality) {
+                log::error!("failed to write frame quality, disabling quality log: {}", e);
+                self.quality_log = None;
+            }
+        }
+    }
+
+    fn finalize_quality_log(&mut self) {
+        if let Some(quality_log) = &mut self.quality_log {
+            if let Err(e) = quality_log.finalize() {
+                log::error!("failed to finalize quality log: {}", e);
+            }
+        }
     }
 }
 
@@ -57,6 +227,8 @@ pub enum CaptureToRecordingThreadMsg {
 pub enum RecordingToMainThreadMsg {
     Info(String),
     Error(String),
+    /// The finished job's output is incomplete or unreadable.
+    VerificationFailed(String),
     CaptureThreadEnded
 }
 
@@ -65,16 +237,34 @@ pub enum MainToRecordingThreadMsg {
     Finish
 }
 
+/// Returns the number of frames making up `percent`% of `num_frames` (at least one frame, if there are any).
+fn num_best_frames(num_frames: usize, percent: f64) -> usize {
+    ((num_frames as f64 * percent / 100.0).ceil() as usize).max(1).min(num_frames)
+}
+
+/// Returns the number of bytes of `fragment` of `image` to be written.
+fn fragment_bytes(image: &Image, fragment: &Rect) -> usize {
+    let num_img_pixels = (image.width() * image.height()) as usize;
+    let num_frag_pixels = (fragment.width * fragment.height) as usize;
+    num_frag_pixels * image.num_pixel_bytes_without_padding() / num_img_pixels
+}
+
 pub fn recording_thread(
     jobs: Arc<crossbeam::queue::SegQueue<Job>>,
     sender: glib::Sender<RecordingToMainThreadMsg>,
     receiver_main: crossbeam::channel::Receiver<MainToRecordingThreadMsg>,
-    buffered_kib: Arc<AtomicIsize>
+    buffered_kib: Arc<AtomicIsize>,
+    max_buffered_kib: Arc<AtomicIsize>,
+    update_interval_ms: Arc<AtomicU32>,
+    session_stats: Arc<SessionStats>
 ) {
     const RECEIVED_FROM_MAIN_THREAD: usize = 0;
     const RECEIVED_FROM_CAPTURE_THREAD: usize = 1;
 
     let mut job: Option<Job> = None;
+    // set when the main thread requests finishing while a job is active; the job's remaining frames are written
+    // and its output is finalized before the thread ends
+    let mut finishing = false;
 
     let mut t_last_info_sent = std::time::Instant::now();
     let mut last_kib_written = 0;
@@ -83,10 +273,47 @@ pub fn recording_thread(
 
     macro_rules! end_job { () => {
         log::info!("recording job ends");
-        match job.as_mut().unwrap().writer.finalize() {
+        let ended_job = job.as_mut().unwrap();
+        written_kib_since_update += ended_job.kept_kib();
+        if let Err(err) = ended_job.write_best_frames(&session_stats) {
+            sender.send(RecordingToMainThreadMsg::Error(err)).unwrap();
+        }
+        ended_job.finalize_quality_log();
+        match ended_job.writer.finalize() {
             Err(err) => sender.send(RecordingToMainThreadMsg::Error(err)).unwrap(),
-            _ => ()
+            Ok(()) => if ended_job.verify_output {
+                if let Err(err) = ended_job.writer.verify() {
+                    log::error!("recorded output verification failed: {}", err);
+                    sender.send(RecordingToMainThreadMsg::VerificationFailed(err)).unwrap();
+                }
+            }
+        }
+
+        job = jobs.pop();
+        if job.is_some() {
+            log::info!("starting new recording job");
+        }
+    }}
+
+    // Ends the current job after an error; the job's output is finalized (as far as possible), and the frames still
+    // waiting to be written are discarded.
+    macro_rules! fail_job { ($err:expr) => {
+        let err: String = $err;
+        log::error!("recording job failed: {}", err);
+        {
+            let job = job.as_mut().unwrap();
+            written_kib_since_update += job.kept_kib();
+            job.finalize_quality_log();
+            if let Err(finalize_err) = job.writer.finalize() {
+                log::error!("failed to finalize output after error: {}", finalize_err);
+            }
+            for msg in job.receiver.try_iter() {
+                if let CaptureToRecordingThreadMsg::Captured((image, fragment, _)) = msg {
+                    written_kib_since_update += fragment_bytes(&image, &fragment) / 1024;
+                }
+            }
         }
+        sender.send(RecordingToMainThreadMsg::Error(err)).unwrap();
 
         job = jobs.pop();
         if job.is_some() {
@@ -95,6 +322,8 @@ pub fn recording_thread(
     }}
 
     loop {
+        if finishing && job.is_none() { break; }
+
         let mut sel = crossbeam::channel::Select::new();
         sel.recv(&receiver_main);
         if job.is_some() { sel.recv(&job.as_ref().unwrap().receiver); }
@@ -109,12 +338,17 @@ pub fn recording_thread(
                     }
                 },
 
-                MainToRecordingThreadMsg::Finish => break
+                MainToRecordingThreadMsg::Finish => if job.is_none() {
+                    break;
+                } else {
+                    log::info!("finishing the current recording job before exiting");
+                    finishing = true;
+                }
             },
 
             RECEIVED_FROM_CAPTURE_THREAD => match sel_result.recv(&job.as_ref().unwrap().receiver) {
                 Ok(msg) => match msg {
-                    CaptureToRecordingThreadMsg::Captured((image, mut fragment, _timestamp)) => {
+                    CaptureToRecordingThreadMsg::Captured((image, mut fragment, timestamp)) => {
                         let job = job.as_mut().unwrap();
 
                         let mut diag_error = None; //TODO: remove once diagnosed
@@ -158,29 +392,60 @@ pub fn recording_thread(
 
                         //TODO: remove once diagnosed
                         if let Some(e) = diag_error {
-                            sender.send(RecordingToMainThreadMsg::Error(e)).unwrap();
-                            end_job!();
+                            fail_job!(e);
                         } else {
                         //END TODO
 
                             if !(*image).img_rect().contains_rect(&fragment) {
-                                sender.send(RecordingToMainThreadMsg::Error(
-                                    "attempted to record fragment outside image bounds".into()
-                                )).unwrap();
-                                end_job!();
+                                fail_job!("attempted to record fragment outside image bounds".into());
                             } else {
-                                match job.writer.write(&ImageView::new(&*image, Some(fragment))) {
-                                    Err(err) => {
-                                        sender.send(RecordingToMainThreadMsg::Error(err)).unwrap();
-                                        end_job!();
+                                let frame = ImageView::new(&*image, Some(fragment));
+                                let quality = if job.needs_quality() { Some(quality::frame_quality(&frame)) } else { None };
+                                match job.frame_selection {
+                                    FrameSelection::BestPercent(_) => {
+                                        // the kept copy stays counted as buffered until it is written or discarded
+                                        let kib = fragment_bytes(&image, &fragment) / 1024;
+                                        let max_kib = max_buffered_kib.load(Ordering::Relaxed).max(0) as usize;
+                                        if job.kept_kib() + kib > max_kib {
+                                            written_kib_since_update += kib + job.kept_kib();
+                                            let result = job.write_best_frames(&session_stats);
+                                            fail_job!(match result {
+                                                Err(err) => err,
+                                                Ok(()) => format!(
+                                                    "frames kept for best frame selection exceeded the maximum \
+                                                    amount of buffered data ({} MiB); recording stopped",
+                                                    max_kib / 1024
+                                                )
+                                            });
+                                        } else {
+                                            written_kib_since_update += job.best_frames.as_mut().unwrap().add(
+                                                image.fragment_copy(
+                                                    &[fragment.x, fragment.y], fragment.width, fragment.height, false
+                                                ),
+                                                timestamp,
+                                                quality.unwrap(),
+                                                kib
+                                            );
+                                        }
+                                    },
+
+                                    FrameSelection::QualityAbove(min_quality) if quality.unwrap() < min_quality => {
+                                        written_kib_since_update += fragment_bytes(&image, &fragment) / 1024;
                                     },
 
-                                    Ok(()) => {
-                                        let num_img_pixels = (image.width() * image.height()) as usize;
-                                        let num_frag_pixels = (fragment.width * fragment.height) as usize;
-                                        let kib_written = num_frag_pixels * image.num_pixel_bytes_without_padding() / num_img_pixels / 1024;
-                                        total_kib_written += kib_written;
-                                        written_kib_since_update += kib_written;
+                                    _ => match job.write_frame(&frame, timestamp, quality) {
+                                        Err(err) => {
+                                            written_kib_since_update += fragment_bytes(&image, &fragment) / 1024;
+                                            fail_job!(err);
+                                        },
+
+                                        Ok(()) => {
+                                            let bytes_written = fragment_bytes(&image, &fragment);
+                                            session_stats.add_recorded_frame(bytes_written as u64);
+                                            let kib_written = bytes_written / 1024;
+                                            total_kib_written += kib_written;
+                                            written_kib_since_update += kib_written;
+                                        }
                                     }
                                 }
                             }
@@ -194,6 +459,11 @@ pub fn recording_thread(
                     println!("WARNING: Capture thread ended with error; current recording job ends.");
                     sender.send(RecordingToMainThreadMsg::CaptureThreadEnded).unwrap();
 
+                    written_kib_since_update += job.as_ref().unwrap().kept_kib();
+                    if let Err(err) = job.as_mut().unwrap().write_best_frames(&session_stats) {
+                        sender.send(RecordingToMainThreadMsg::Error(err)).unwrap();
+                    }
+                    job.as_mut().unwrap().finalize_quality_log();
                     match job.as_mut().unwrap().writer.finalize() {
                         Err(err) => sender.send(RecordingToMainThreadMsg::Error(err)).unwrap(),
                         _ => ()
@@ -210,7 +480,8 @@ pub fn recording_thread(
         }
 
         let t_elapsed = t_last_info_sent.elapsed();
-        if t_elapsed >= std::time::Duration::from_secs(1) {
+        let update_interval = std::time::Duration::from_millis(update_interval_ms.load(Ordering::Relaxed) as u64);
+        if t_elapsed >= update_interval {
             let num_jobs = jobs.len() + if job.is_some() { 1 } else { 0 };
             let write_rate = (total_kib_written - last_kib_written) as f64 / 1024.0 / t_elapsed.as_secs_f64();
             let current_buffered = buffered_kib.load(Ordering::Relaxed);
@@ -238,3 +509,51 @@ pub fn recording_thread(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns capture order indices of the best frames selected from frames of `qualities`.
+    fn best_frame_indices(qualities: &[f64], percent: f64, frame_limit: Option<usize>) -> Vec<usize> {
+        let mut best_frames = BestFrames::new(percent, frame_limit);
+        for quality in qualities {
+            let image = Image::new(1, 1, None, ga_image::PixelFormat::Mono8, None, true);
+            best_frames.add(image, std::time::SystemTime::now(), *quality, 1);
+        }
+        best_frames.take_best().iter().map(|frame| frame.index).collect()
+    }
+
+    #[test]
+    fn best_frames_are_selected_in_capture_order() {
+        let qualities = [0.5, 0.9, 0.1, 0.3, 0.8, 0.2, 0.4, 0.6];
+        assert_eq!(vec![1, 4], best_frame_indices(&qualities, 25.0, None));
+        assert_eq!(vec![1, 4], best_frame_indices(&qualities, 25.0, Some(qualities.len())));
+    }
+
+    #[test]
+    fn at_least_one_frame_is_selected() {
+        assert_eq!(vec![1], best_frame_indices(&[0.1, 0.2, 0.1], 1.0, None));
+        assert!(best_frame_indices(&[], 25.0, None).is_empty());
+    }
+
+    #[test]
+    fn with_frame_limit_only_best_frames_are_kept_in_memory() {
+        const NUM_FRAMES: usize = 100;
+        let mut best_frames = BestFrames::new(10.0, Some(NUM_FRAMES));
+        for i in 0..NUM_FRAMES {
+            let image = Image::new(1, 1, None, ga_image::PixelFormat::Mono8, None, true);
+            let quality = ((i * 37) % NUM_FRAMES) as f64;
+            let released_kib = best_frames.add(image, std::time::SystemTime::now(), quality, 1);
+            assert_eq!(if i < 10 { 0 } else { 1 }, released_kib);
+            assert!(best_frames.frames.len() <= 10);
+            assert_eq!(best_frames.frames.len(), best_frames.kept_kib);
+        }
+
+        let best = best_frames.take_best();
+        assert_eq!(10, best.len());
+        assert!(best.iter().all(|frame| frame.quality >= 90.0));
+        assert!(best.windows(2).all(|w| w[0].index < w[1].index));
+        assert_eq!(0, best_frames.kept_kib);
+    }
+}
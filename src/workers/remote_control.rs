@@ -0,0 +1,245 @@
+//
+// Vidoxide - Image acquisition for amateur astronomy
+// Copyright (c) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This project is licensed under the terms of the MIT license
+// (see the LICENSE file for details).
+//
+
+//!
+//! Remote control HTTP API thread.
+//!
+//! Accepts HTTP requests and forwards them as commands to the main thread, which performs them the same way
+//! as the corresponding GUI actions. Endpoints (responses are JSON objects):
+//!
+//!   - `GET /status`: camera, capture and recording status
+//!   - `POST /recording/start`: start recording (with the settings currently set in the GUI)
+//!   - `POST /recording/stop`: stop recording
+//!   - `POST /snapshot`: take a snapshot
+//!
+//! Example: `curl -X POST http://127.0.0.1:8091/recording/start`.
+//!
+
+use crossbeam::channel;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+
+/// Interval of checking for new connections (and for the finish request).
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Max. time of receiving a request and sending the response.
+const CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Max. time of waiting for the main thread to perform a command.
+const COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Max. accepted size of request headers.
+const MAX_REQUEST_SIZE: usize = 8192;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Command {
+    Status,
+    StartRecording,
+    StopRecording,
+    TakeSnapshot
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ResponseStatus {
+    Ok,
+    /// Command cannot be performed in the current state (e.g., there is no recording to stop).
+    Conflict,
+    NotFound,
+    MethodNotAllowed,
+    BadRequest,
+    /// The main thread did not respond in time.
+    ServiceUnavailable
+}
+
+impl ResponseStatus {
+    fn status_line(&self) -> &'static str {
+        match self {
+            ResponseStatus::Ok => "200 OK",
+            ResponseStatus::Conflict => "409 Conflict",
+            ResponseStatus::NotFound => "404 Not Found",
+            ResponseStatus::MethodNotAllowed => "405 Method Not Allowed",
+            ResponseStatus::BadRequest => "400 Bad Request",
+            ResponseStatus::ServiceUnavailable => "503 Service Unavailable"
+        }
+    }
+}
+
+pub struct Response {
+    pub status: ResponseStatus,
+    /// JSON object.
+    pub body: String
+}
+
+impl Response {
+    pub fn error(status: ResponseStatus, message: &str) -> Response {
+        Response{ status, body: format!("{{\"error\":\"{}\"}}", json_escaped(message)) }
+    }
+}
+
+pub struct RemoteToMainThreadMsg {
+    pub command: Command,
+    pub reply: channel::Sender<Response>
+}
+
+pub struct RemoteControl {
+    finish: Arc<AtomicBool>,
+    address: SocketAddr
+}
+
+impl RemoteControl {
+    /// Starts serving the API at `address` (e.g. "127.0.0.1:8091"); commands are sent via `sender`.
+    pub fn start(address: &str, sender: glib::Sender<RemoteToMainThreadMsg>) -> Result<RemoteControl, std::io::Error> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let finish = Arc::new(AtomicBool::new(false));
+        let finish_clone = Arc::clone(&finish);
+        // the thread is detached: it may be waiting for the main thread to perform a command, so joining it
+        // from the main thread (when stopping the API) could block it for up to `COMMAND_TIMEOUT`
+        std::thread::spawn(move || server_thread(listener, sender, finish_clone));
+
+        log::info!("remote control API started at {}", address);
+
+        Ok(RemoteControl{ finish, address })
+    }
+
+    pub fn address(&self) -> SocketAddr { self.address }
+}
+
+impl Drop for RemoteControl {
+    fn drop(&mut self) {
+        // the server thread ends after finishing the current request (if any)
+        self.finish.store(true, Ordering::Relaxed);
+        log::info!("remote control API at {} stopped", self.address);
+    }
+}
+
+/// Returns a string which can be placed between quotes in JSON.
+pub fn json_escaped(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c)
+        }
+    }
+    result
+}
+
+fn server_thread(listener: TcpListener, sender: glib::Sender<RemoteToMainThreadMsg>, finish: Arc<AtomicBool>) {
+    while !finish.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, client_addr)) => if let Err(e) = handle_connection(&stream, &sender) {
+                log::warn!("remote control API: failed to handle request from {}: {}", client_addr, e);
+            },
+
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+
+            Err(e) => {
+                log::error!("remote control API: failed to accept connection: {}", e);
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+fn handle_connection(stream: &TcpStream, sender: &glib::Sender<RemoteToMainThreadMsg>) -> Result<(), std::io::Error> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let mut stream: &TcpStream = stream;
+    let mut request = vec![];
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let num_read = stream.read(&mut buf)?;
+        if num_read == 0 || request.len() + num_read > MAX_REQUEST_SIZE {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "incomplete or too long request"));
+        }
+        request.extend_from_slice(&buf[..num_read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let response = match parse_request_line(request.lines().next().unwrap_or("")) {
+        Ok(command) => {
+            let (reply_sender, reply_receiver) = channel::bounded(1);
+            if sender.send(RemoteToMainThreadMsg{ command, reply: reply_sender }).is_err() {
+                Response::error(ResponseStatus::ServiceUnavailable, "program is shutting down")
+            } else {
+                match reply_receiver.recv_timeout(COMMAND_TIMEOUT) {
+                    Ok(response) => response,
+                    Err(_) => Response::error(ResponseStatus::ServiceUnavailable, "no response from program")
+                }
+            }
+        },
+
+        Err(status) => Response::error(status, status.status_line())
+    };
+
+    write_response(&mut stream, &response)
+}
+
+/// Returns the command requested by an HTTP request line (e.g. "POST /recording/start HTTP/1.1").
+fn parse_request_line(line: &str) -> Result<Command, ResponseStatus> {
+    let mut parts = line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method, target),
+        _ => return Err(ResponseStatus::BadRequest)
+    };
+    let path = target.split('?').next().unwrap().trim_end_matches('/');
+
+    let (expected_method, command) = match path {
+        "/status" => ("GET", Command::Status),
+        "/recording/start" => ("POST", Command::StartRecording),
+        "/recording/stop" => ("POST", Command::StopRecording),
+        "/snapshot" => ("POST", Command::TakeSnapshot),
+        _ => return Err(ResponseStatus::NotFound)
+    };
+
+    if method == expected_method { Ok(command) } else { Err(ResponseStatus::MethodNotAllowed) }
+}
+
+fn write_response<W: Write>(writer: &mut W, response: &Response) -> Result<(), std::io::Error> {
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status.status_line(),
+        response.body.len(),
+        response.body
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_lines_are_mapped_to_commands() {
+        assert_eq!(Ok(Command::StartRecording), parse_request_line("POST /recording/start HTTP/1.1"));
+        assert_eq!(Ok(Command::StopRecording), parse_request_line("POST /recording/stop/ HTTP/1.1"));
+        assert_eq!(Ok(Command::Status), parse_request_line("GET /status?x=1 HTTP/1.1"));
+        assert_eq!(Err(ResponseStatus::MethodNotAllowed), parse_request_line("GET /snapshot HTTP/1.1"));
+        assert_eq!(Err(ResponseStatus::NotFound), parse_request_line("POST /focuser HTTP/1.1"));
+        assert_eq!(Err(ResponseStatus::BadRequest), parse_request_line(""));
+    }
+
+    #[test]
+    fn response_has_json_body_and_length() {
+        let mut output = vec![];
+        write_response(&mut output, &Response::error(ResponseStatus::Conflict, "not \"recording\"")).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("HTTP/1.1 409 Conflict\r\n"));
+        assert!(output.contains("Content-Length: 29\r\n"));
+        assert!(output.ends_with("\r\n\r\n{\"error\":\"not \\\"recording\\\"\"}"));
+    }
+}